@@ -0,0 +1,14 @@
+#![no_main]
+
+use enigo::keymap::Keymap;
+use libfuzzer_sys::fuzz_target;
+
+// Compositors and EIS implementations send their keymap as text. A malformed
+// keymap has to result in an error and never in a panic
+fuzz_target!(|data: &str| {
+    if let Ok(keymap) = Keymap::from_string(data) {
+        let _ = keymap.layout_names();
+        let _ = keymap.unused_keycodes();
+        let _ = keymap.keycode_for_char('a', 0);
+    }
+});
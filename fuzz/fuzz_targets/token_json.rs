@@ -0,0 +1,13 @@
+#![no_main]
+
+use enigo::agent::Token;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(tokens) = serde_json::from_slice::<Vec<Token>>(data) {
+        // Whatever was accepted has to survive a round trip
+        let serialized = serde_json::to_vec(&tokens).unwrap();
+        let deserialized: Vec<Token> = serde_json::from_slice(&serialized).unwrap();
+        assert_eq!(tokens, deserialized);
+    }
+});
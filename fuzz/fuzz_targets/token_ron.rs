@@ -0,0 +1,13 @@
+#![no_main]
+
+use enigo::agent::Token;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    if let Ok(tokens) = ron::from_str::<Vec<Token>>(data) {
+        // Whatever was accepted has to survive a round trip
+        let serialized = ron::to_string(&tokens).unwrap();
+        let deserialized: Vec<Token> = ron::from_str(&serialized).unwrap();
+        assert_eq!(tokens, deserialized);
+    }
+});
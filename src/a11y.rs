@@ -0,0 +1,42 @@
+//! Query the focused element of the accessibility tree
+//!
+//! Assistive tools built on enigo can check where the input will go before
+//! they type (e.g. if the caret is in a text field). UI Automation is used on
+//! Windows, the `AXUIElement` API on macOS and AT-SPI on Linux. On macOS the
+//! application needs the accessibility permission, which is also needed to
+//! simulate input.
+//!
+//! ```no_run
+//! use enigo::{a11y, Enigo, Keyboard, Settings};
+//!
+//! let mut enigo = Enigo::new(&Settings::default()).unwrap();
+//! let element = a11y::focused_element().unwrap();
+//! if element.editable {
+//!     enigo.text("Hello World").unwrap();
+//! } else {
+//!     println!("the caret is not in a text field: {element:?}");
+//! }
+//! ```
+
+use crate::{os, InputResult};
+
+/// Element of the user interface that has the keyboard focus
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FocusedElement {
+    /// Role of the element as it is named by the platform (e.g. `Edit` on
+    /// Windows, `AXTextField` on macOS and `text` on Linux)
+    pub role: String,
+    /// Accessible name of the element (e.g. the label of a text field)
+    pub name: Option<String>,
+    /// The element accepts text input
+    pub editable: bool,
+}
+
+/// Returns the element that has the keyboard focus
+///
+/// # Errors
+/// Returns an error if the accessibility API is not available or no element
+/// has the focus
+pub fn focused_element() -> InputResult<FocusedElement> {
+    os::a11y::focused_element()
+}
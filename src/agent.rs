@@ -1,19 +1,32 @@
-use crate::{Axis, Button, Coordinate, Direction, Enigo, InputResult, Key, Keyboard, Mouse};
+use std::{
+    collections::VecDeque,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    clock::{Clock, SystemClock},
+    round_coordinates, Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard,
+    Mouse,
+};
 
 use log::error;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Token {
     /// Call the [`Keyboard::text`] fn with the string as text
     #[cfg_attr(feature = "serde", serde(alias = "T"))]
     #[cfg_attr(feature = "serde", serde(alias = "t"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Text"))]
     Text(String),
     /// Call the [`Keyboard::key`] fn with the given key and direction
     #[cfg_attr(feature = "serde", serde(alias = "K"))]
     #[cfg_attr(feature = "serde", serde(alias = "k"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Key"))]
     Key(
         Key,
         #[cfg_attr(feature = "serde", serde(default))] Direction,
@@ -21,6 +34,7 @@ pub enum Token {
     /// Call the [`Keyboard::raw`] fn with the given keycode and direction
     #[cfg_attr(feature = "serde", serde(alias = "R"))]
     #[cfg_attr(feature = "serde", serde(alias = "r"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Raw"))]
     Raw(
         u16,
         #[cfg_attr(feature = "serde", serde(default))] Direction,
@@ -28,6 +42,7 @@ pub enum Token {
     /// Call the [`Mouse::button`] fn with the given mouse button and direction
     #[cfg_attr(feature = "serde", serde(alias = "B"))]
     #[cfg_attr(feature = "serde", serde(alias = "b"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Button"))]
     Button(
         Button,
         #[cfg_attr(feature = "serde", serde(default))] Direction,
@@ -38,6 +53,7 @@ pub enum Token {
     /// the current position of the mouse.
     #[cfg_attr(feature = "serde", serde(alias = "M"))]
     #[cfg_attr(feature = "serde", serde(alias = "m"))]
+    #[cfg_attr(feature = "serde", serde(rename = "MoveMouse"))]
     MoveMouse(
         i32,
         i32,
@@ -46,26 +62,112 @@ pub enum Token {
     /// Call the [`Mouse::scroll`] fn.
     #[cfg_attr(feature = "serde", serde(alias = "S"))]
     #[cfg_attr(feature = "serde", serde(alias = "s"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Scroll"))]
     Scroll(i32, #[cfg_attr(feature = "serde", serde(default))] Axis),
     /// Call the [`Mouse::location`] fn and compare the return values with
     /// the values of this enum. Log an error if they are not equal.
     /// This variant contains the EXPECTED location of the mouse
     #[cfg_attr(feature = "serde", serde(alias = "L"))]
     #[cfg_attr(feature = "serde", serde(alias = "l"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Location"))]
     Location(i32, i32),
     /// Call the [`Mouse::main_display`] fn and compare the return values with
     /// the values of this enum. Log an error if they are not equal.
     /// This variant contains the EXPECTED size of the main display
     #[cfg_attr(feature = "serde", serde(alias = "D"))]
     #[cfg_attr(feature = "serde", serde(alias = "d"))]
+    #[cfg_attr(feature = "serde", serde(rename = "MainDisplay"))]
     MainDisplay(i32, i32),
+    /// Wait for the number of milliseconds before executing the next token
+    #[cfg_attr(feature = "serde", serde(alias = "W"))]
+    #[cfg_attr(feature = "serde", serde(alias = "w"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Sleep"))]
+    Sleep(u64),
+    /// Press the modifiers, execute the tokens of the body and release the
+    /// modifiers in reverse order. The modifiers are released even if a token
+    /// of the body returned an error, so they are never left pressed
+    #[cfg_attr(feature = "serde", serde(rename = "WithModifiers"))]
+    WithModifiers {
+        /// Keys that are held while the body is executed
+        #[cfg_attr(feature = "serde", serde(rename = "modifiers"))]
+        modifiers: Vec<Key>,
+        /// Tokens that are executed while the modifiers are held
+        #[cfg_attr(feature = "serde", serde(rename = "body"))]
+        body: Vec<Token>,
+    },
 }
 
+/// Executes [`Token`]s
+///
+/// The trait is implemented for everything that implements [`Keyboard`] and
+/// [`Mouse`]. If you wrap [`crate::Enigo`] in your own struct (e.g. to log or
+/// rate limit the input) and implement the two traits for it, you can execute
+/// the tokens with it without any additional code. It also works for `&mut T`
+/// and for `dyn Agent` trait objects.
+///
+/// ```no_run
+/// use enigo::{
+///     agent::{Agent, Token},
+///     Axis, Button, Coordinate, Direction, Enigo, InputResult, Key, Keyboard, Mouse, Settings,
+/// };
+///
+/// struct Logger(Enigo);
+///
+/// impl Keyboard for Logger {
+///     fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+///         println!("text: {text}");
+///         self.0.fast_text(text)
+///     }
+///     fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+///         println!("key: {key:?} {direction:?}");
+///         self.0.key(key, direction)
+///     }
+///     fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+///         self.0.raw(keycode, direction)
+///     }
+/// }
+///
+/// impl Mouse for Logger {
+///     fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+///         self.0.button(button, direction)
+///     }
+///     fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+///         self.0.move_mouse(x, y, coordinate)
+///     }
+///     fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+///         self.0.scroll(length, axis)
+///     }
+///     fn main_display(&self) -> InputResult<(i32, i32)> {
+///         self.0.main_display()
+///     }
+///     fn location(&self) -> InputResult<(i32, i32)> {
+///         self.0.location()
+///     }
+/// }
+///
+/// let mut logger = Logger(Enigo::new(&Settings::default()).unwrap());
+/// logger.execute(&Token::Text("Hello".to_string())).unwrap();
+/// let agent: &mut dyn Agent = &mut logger;
+/// agent.execute(&Token::Key(Key::Return, Direction::Click)).unwrap();
+/// ```
 pub trait Agent
 where
     Self: Keyboard,
     Self: Mouse,
 {
+    /// Check if the policy allows the token and execute it if it does. Use it
+    /// to restrict what clients may do if you receive the tokens over a
+    /// network or IPC
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InputError::PermissionDenied`] if the policy does not allow
+    /// the token. Otherwise the same as [`Agent::execute`]
+    fn execute_with_policy(&mut self, token: &Token, policy: &mut Policy) -> InputResult<()> {
+        policy.check(token, || self.location())?;
+        self.execute(token)
+    }
+
     /// Execute the action associated with the token. A [`Token::Text`] will
     /// enter text, a [`Token::Scroll`] will scroll and so forth. Have a look at
     /// the documentation of the [`Token`] enum for more information.
@@ -75,6 +177,59 @@ where
     /// Same as the individual functions. Have a look at [`InputResult`] for a
     /// list of possible errors
     fn execute(&mut self, token: &Token) -> InputResult<()> {
+        self.execute_with_clock(token, &SystemClock)
+    }
+
+    /// Same as [`Agent::execute`], but the coordinates of the token are
+    /// re-scaled from the size of the display they were recorded on to the
+    /// current size of the [`Mouse::main_display`]. Store the size of the
+    /// main display together with the recording so it can still be replayed
+    /// after the resolution changed. Have a look at [`rescale`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InputError::InvalidInput`] if one of the displays has no
+    /// area. Otherwise the same as [`Agent::execute`]
+    fn execute_rescaled(&mut self, token: &Token, recorded_display: (i32, i32)) -> InputResult<()> {
+        let token = rescale(token, recorded_display, self.main_display()?)?;
+        self.execute(&token)
+    }
+
+    /// Execute the recorded tokens (e.g. from
+    /// [`crate::middleware::Recorded`]) one after another. If the recording
+    /// starts with a [`Token::MainDisplay`], it is the size of the display the
+    /// tokens were recorded on. The coordinates of the other tokens are then
+    /// re-scaled to the current size of the [`Mouse::main_display`] like
+    /// [`Agent::execute_rescaled`] does. Otherwise the tokens are executed
+    /// unchanged
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InputError::InvalidInput`] if one of the displays has no
+    /// area. Otherwise the same as [`Agent::execute`]. No further tokens are
+    /// executed after an error
+    fn replay(&mut self, tokens: &[Token]) -> InputResult<()> {
+        if let [Token::MainDisplay(width, height), tokens @ ..] = tokens {
+            let current_display = self.main_display()?;
+            for token in tokens {
+                self.execute(&rescale(token, (*width, *height), current_display)?)?;
+            }
+        } else {
+            for token in tokens {
+                self.execute(token)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Same as [`Agent::execute`], but a [`Token::Sleep`] sleeps with the
+    /// clock. Inject a [`crate::clock::MockClock`] to execute tokens in tests
+    /// without waiting
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Agent::execute`]
+    fn execute_with_clock(&mut self, token: &Token, clock: &dyn Clock) -> InputResult<()> {
         match token {
             Token::Text(text) => self.text(text),
             Token::Key(key, direction) => self.key(*key, *direction),
@@ -106,8 +261,492 @@ where
                     Err(e)
                 }
             },
+            Token::Sleep(ms) => {
+                clock.sleep(Duration::from_millis(*ms));
+                Ok(())
+            }
+            Token::WithModifiers { modifiers, body } => {
+                let mut pressed = Vec::with_capacity(modifiers.len());
+                let mut res = Ok(());
+                for &modifier in modifiers {
+                    res = self.key(modifier, Direction::Press);
+                    if res.is_err() {
+                        break;
+                    }
+                    pressed.push(modifier);
+                }
+                if res.is_ok() {
+                    res = body
+                        .iter()
+                        .try_for_each(|token| self.execute_with_clock(token, clock));
+                }
+                // Release the modifiers even if there was an error
+                for &modifier in pressed.iter().rev() {
+                    if let Err(e) = self.key(modifier, Direction::Release) {
+                        error!("unable to release {modifier:?}");
+                        if res.is_ok() {
+                            res = Err(e);
+                        }
+                    }
+                }
+                res
+            }
         }
     }
 }
 
-impl Agent for Enigo {}
+impl<T> Agent for T where T: Keyboard + Mouse + ?Sized {}
+
+/// Restricts which [`Token`]s may be executed with
+/// [`Agent::execute_with_policy`]
+///
+/// The default policy allows everything. Restrict it by changing the fields.
+/// Tokens that only compare values ([`Token::Location`] and
+/// [`Token::MainDisplay`]) are always allowed.
+///
+/// ```no_run
+/// use enigo::{
+///     agent::{Agent, Policy, RateLimit, Token},
+///     Button, Direction, Enigo, Key, Settings,
+/// };
+///
+/// let mut policy = Policy {
+///     allow_raw: false,
+///     allowed_keys: Some(vec![Key::Return, Key::Backspace]),
+///     allowed_buttons: Some(vec![Button::Left]),
+///     bounds: Some((0, 0, 1920, 1080)),
+///     rate_limit: Some(RateLimit::new(100)),
+///     ..Default::default()
+/// };
+/// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+/// enigo
+///     .execute_with_policy(&Token::Key(Key::Return, Direction::Click), &mut policy)
+///     .unwrap();
+/// assert!(enigo
+///     .execute_with_policy(&Token::Key(Key::Meta, Direction::Click), &mut policy)
+///     .is_err());
+/// ```
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone)]
+pub struct Policy {
+    /// Allow [`Token::Text`]
+    pub allow_text: bool,
+    /// Allow [`Token::Raw`]
+    pub allow_raw: bool,
+    /// Keys that are allowed in [`Token::Key`]. All keys are allowed if it is
+    /// `None`
+    pub allowed_keys: Option<Vec<Key>>,
+    /// Mouse buttons that are allowed in [`Token::Button`]. All buttons are
+    /// allowed if it is `None`
+    pub allowed_buttons: Option<Vec<Button>>,
+    /// Allow [`Token::MoveMouse`]
+    pub allow_mouse_move: bool,
+    /// Allow [`Token::Scroll`]
+    pub allow_scroll: bool,
+    /// The mouse may only be moved within this rectangle (x, y, width,
+    /// height). Relative moves are checked with the current location of the
+    /// mouse. In the body of a [`Token::WithModifiers`], they are checked with
+    /// the destination of the previous move
+    pub bounds: Option<(i32, i32, i32, i32)>,
+    /// Longest duration of a [`Token::Sleep`]
+    pub max_sleep: Option<Duration>,
+    /// Maximum rate of tokens that may be executed
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            allow_text: true,
+            allow_raw: true,
+            allowed_keys: None,
+            allowed_buttons: None,
+            allow_mouse_move: true,
+            allow_scroll: true,
+            bounds: None,
+            max_sleep: None,
+            rate_limit: None,
+        }
+    }
+}
+
+impl Policy {
+    /// Check if the token is allowed. `location` is only called for relative
+    /// mouse moves if there are bounds. The modifiers and the body of a
+    /// [`Token::WithModifiers`] are checked like individual tokens and each of
+    /// them counts for the rate limit
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InputError::PermissionDenied`] if the token is not allowed
+    /// or the error of `location`
+    pub fn check(
+        &mut self,
+        token: &Token,
+        mut location: impl FnMut() -> InputResult<(i32, i32)>,
+    ) -> InputResult<()> {
+        let count = self.check_token(token, &mut location, &mut None)?;
+
+        if let Some(rate_limit) = self.rate_limit.as_mut() {
+            if !rate_limit.allow(count) {
+                return Err(InputError::PermissionDenied(
+                    "the maximum rate of tokens was exceeded",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check if the token is allowed without counting it for the rate limit.
+    /// `position` is the destination of the previous mouse move within the
+    /// same token. Returns the number of tokens it counts as for the rate
+    /// limit
+    fn check_token(
+        &self,
+        token: &Token,
+        location: &mut dyn FnMut() -> InputResult<(i32, i32)>,
+        position: &mut Option<(i32, i32)>,
+    ) -> InputResult<usize> {
+        match token {
+            Token::Text(_) if !self.allow_text => {
+                return Err(InputError::PermissionDenied("entering text is not allowed"));
+            }
+            Token::Raw(..) if !self.allow_raw => {
+                return Err(InputError::PermissionDenied("raw keycodes are not allowed"));
+            }
+            Token::Key(key, _)
+                if self
+                    .allowed_keys
+                    .as_ref()
+                    .is_some_and(|keys| !keys.contains(key)) =>
+            {
+                return Err(InputError::PermissionDenied("the key is not allowed"));
+            }
+            Token::Button(button, _)
+                if self
+                    .allowed_buttons
+                    .as_ref()
+                    .is_some_and(|buttons| !buttons.contains(button)) =>
+            {
+                return Err(InputError::PermissionDenied(
+                    "the mouse button is not allowed",
+                ));
+            }
+            Token::MoveMouse(..) if !self.allow_mouse_move => {
+                return Err(InputError::PermissionDenied(
+                    "moving the mouse is not allowed",
+                ));
+            }
+            Token::MoveMouse(x, y, coordinate) => {
+                if let Some((bx, by, width, height)) = self.bounds {
+                    let (x, y) = if *coordinate == Coordinate::Rel {
+                        let (current_x, current_y) = match *position {
+                            Some(position) => position,
+                            None => location()?,
+                        };
+                        (current_x.saturating_add(*x), current_y.saturating_add(*y))
+                    } else {
+                        (*x, *y)
+                    };
+                    if x < bx
+                        || y < by
+                        || x >= bx.saturating_add(width)
+                        || y >= by.saturating_add(height)
+                    {
+                        return Err(InputError::PermissionDenied(
+                            "the destination is out of bounds",
+                        ));
+                    }
+                    *position = Some((x, y));
+                }
+            }
+            Token::Scroll(..) if !self.allow_scroll => {
+                return Err(InputError::PermissionDenied("scrolling is not allowed"));
+            }
+            Token::Sleep(ms)
+                if self
+                    .max_sleep
+                    .is_some_and(|max| Duration::from_millis(*ms) > max) =>
+            {
+                return Err(InputError::PermissionDenied("the sleep is too long"));
+            }
+            Token::WithModifiers { modifiers, body } => {
+                let mut count = 0;
+                for &modifier in modifiers {
+                    count += self.check_token(
+                        &Token::Key(modifier, Direction::Press),
+                        location,
+                        position,
+                    )?;
+                }
+                for token in body {
+                    count += self.check_token(token, location, position)?;
+                }
+                return Ok(count);
+            }
+            _ => {}
+        }
+        Ok(1)
+    }
+}
+
+/// Limits the number of tokens a [`Policy`] allows within one second
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    max_per_second: u32,
+    executed: VecDeque<Instant>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RateLimit {
+    /// Allow at most `max_per_second` tokens within any second
+    #[must_use]
+    pub fn new(max_per_second: u32) -> Self {
+        Self::with_clock(max_per_second, Arc::new(SystemClock))
+    }
+
+    /// Same as [`RateLimit::new`], but the time is measured with the clock
+    #[must_use]
+    pub fn with_clock(max_per_second: u32, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            max_per_second,
+            executed: VecDeque::new(),
+            clock,
+        }
+    }
+
+    /// Returns true and counts the tokens if they do not exceed the limit
+    fn allow(&mut self, count: usize) -> bool {
+        let now = self.clock.now();
+        while self
+            .executed
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(1))
+        {
+            self.executed.pop_front();
+        }
+        if self.executed.len() + count > self.max_per_second as usize {
+            return false;
+        }
+        self.executed.extend(std::iter::repeat_n(now, count));
+        true
+    }
+}
+
+/// Largest distance in pixels a point of a mouse path may be away from the
+/// simplified path when using [`optimize`]
+pub const PATH_TOLERANCE: f64 = 1.0;
+
+/// Shrink recorded tokens (e.g. from [`crate::middleware::Recorded`]) without
+/// changing what they do when they are executed, so replays are faster and
+/// recordings smaller. Same as [`optimize_with_tolerance`] with the
+/// [`PATH_TOLERANCE`]
+#[must_use]
+pub fn optimize(tokens: &[Token]) -> Vec<Token> {
+    optimize_with_tolerance(tokens, PATH_TOLERANCE)
+}
+
+/// Shrink recorded tokens. This
+///
+/// - merges consecutive relative mouse moves and drops the ones that don't
+///   move at all
+/// - simplifies paths of consecutive absolute mouse moves with the
+///   Ramer–Douglas–Peucker algorithm. No point of the original path is more
+///   than `tolerance` pixels away from the simplified one
+/// - replaces a press that is directly followed by the release of the same
+///   key, keycode or button with a click
+/// - collapses clicked characters and consecutive texts into a single
+///   [`Token::Text`] as long as no key is held, so shortcuts keep working
+///
+/// The bodies of [`Token::WithModifiers`] are optimized as well, but their
+/// keys are never turned into text.
+#[must_use]
+pub fn optimize_with_tolerance(tokens: &[Token], tolerance: f64) -> Vec<Token> {
+    optimize_tokens(tokens, tolerance, true)
+}
+
+fn optimize_tokens(tokens: &[Token], tolerance: f64, allow_text: bool) -> Vec<Token> {
+    let mut optimized: Vec<Token> = Vec::with_capacity(tokens.len());
+    let mut held_keys = Vec::new();
+    let mut held_raw = Vec::new();
+    let mut path = Vec::new();
+
+    for token in tokens {
+        if let Token::MoveMouse(x, y, Coordinate::Abs) = token {
+            path.push((*x, *y));
+            continue;
+        }
+        flush_path(&mut optimized, &mut path, tolerance);
+
+        let token = match token {
+            Token::Key(key, direction) => {
+                track_held(&mut held_keys, *key, *direction);
+                match (optimized.last(), direction) {
+                    (Some(Token::Key(pressed, Direction::Press)), Direction::Release)
+                        if pressed == key =>
+                    {
+                        optimized.pop();
+                        Token::Key(*key, Direction::Click)
+                    }
+                    _ => token.clone(),
+                }
+            }
+            Token::Raw(keycode, direction) => {
+                track_held(&mut held_raw, *keycode, *direction);
+                match (optimized.last(), direction) {
+                    (Some(Token::Raw(pressed, Direction::Press)), Direction::Release)
+                        if pressed == keycode =>
+                    {
+                        optimized.pop();
+                        Token::Raw(*keycode, Direction::Click)
+                    }
+                    _ => token.clone(),
+                }
+            }
+            Token::Button(button, Direction::Release) => match optimized.last() {
+                Some(Token::Button(pressed, Direction::Press)) if pressed == button => {
+                    optimized.pop();
+                    Token::Button(*button, Direction::Click)
+                }
+                _ => token.clone(),
+            },
+            Token::MoveMouse(x, y, Coordinate::Rel) => {
+                let (mut x, mut y) = (*x, *y);
+                if let Some(Token::MoveMouse(prev_x, prev_y, Coordinate::Rel)) = optimized.last() {
+                    x = x.saturating_add(*prev_x);
+                    y = y.saturating_add(*prev_y);
+                    optimized.pop();
+                }
+                if x == 0 && y == 0 {
+                    continue;
+                }
+                Token::MoveMouse(x, y, Coordinate::Rel)
+            }
+            Token::WithModifiers { modifiers, body } => Token::WithModifiers {
+                modifiers: modifiers.clone(),
+                body: optimize_tokens(body, tolerance, false),
+            },
+            _ => token.clone(),
+        };
+
+        let token = match token {
+            Token::Key(Key::Unicode(c), Direction::Click)
+                if allow_text && !c.is_control() && held_keys.is_empty() && held_raw.is_empty() =>
+            {
+                Token::Text(c.to_string())
+            }
+            token => token,
+        };
+        match (optimized.last_mut(), token) {
+            (Some(Token::Text(text)), Token::Text(next)) => text.push_str(&next),
+            (_, token) => optimized.push(token),
+        }
+    }
+    flush_path(&mut optimized, &mut path, tolerance);
+    optimized
+}
+
+/// Remember which keys are held so clicked characters are only turned into
+/// text if no modifier is pressed
+fn track_held<T: PartialEq>(held: &mut Vec<T>, key: T, direction: Direction) {
+    match direction {
+        Direction::Press => held.push(key),
+        Direction::Release => held.retain(|k| *k != key),
+        Direction::Click => {}
+    }
+}
+
+/// Append the simplified path of absolute mouse moves to the tokens
+fn flush_path(optimized: &mut Vec<Token>, path: &mut Vec<(i32, i32)>, tolerance: f64) {
+    if path.is_empty() {
+        return;
+    }
+    path.dedup();
+    let mut keep = vec![false; path.len()];
+    keep[0] = true;
+    keep[path.len() - 1] = true;
+
+    // Iterative Ramer–Douglas–Peucker so long paths can't overflow the stack
+    let mut segments = vec![(0, path.len() - 1)];
+    while let Some((start, end)) = segments.pop() {
+        let farthest = (start + 1..end)
+            .map(|i| (i, segment_distance(path[i], path[start], path[end])))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                segments.push((start, i));
+                segments.push((i, end));
+            }
+        }
+    }
+
+    optimized.extend(
+        path.drain(..)
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|((x, y), _)| Token::MoveMouse(x, y, Coordinate::Abs)),
+    );
+}
+
+/// Distance of the point to the line segment from `start` to `end`
+fn segment_distance(point: (i32, i32), start: (i32, i32), end: (i32, i32)) -> f64 {
+    let (px, py) = (f64::from(point.0), f64::from(point.1));
+    let (sx, sy) = (f64::from(start.0), f64::from(start.1));
+    let (dx, dy) = (f64::from(end.0) - sx, f64::from(end.1) - sy);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((px - sx) * dx + (py - sy) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    (px - (sx + t * dx)).hypot(py - (sy + t * dy))
+}
+
+/// Re-scale the coordinates of the token from a display with the size `from`
+/// to one with the size `to` (width, height). The coordinates are normalized
+/// to the size of the display (0.0 to 1.0) and multiplied with the new size,
+/// so a token that moved the mouse to the center of the old display moves it
+/// to the center of the new one. Mouse moves, [`Token::Location`] and
+/// [`Token::MainDisplay`] are re-scaled, including the ones in the body of a
+/// [`Token::WithModifiers`]. All other tokens are returned unchanged
+///
+/// # Errors
+///
+/// Returns an [`InputError::InvalidInput`] if one of the displays has no area
+/// or a re-scaled coordinate does not fit in an i32
+pub fn rescale(token: &Token, from: (i32, i32), to: (i32, i32)) -> InputResult<Token> {
+    if from.0 <= 0 || from.1 <= 0 || to.0 <= 0 || to.1 <= 0 {
+        return Err(InputError::InvalidInput(
+            "the width and height of the displays have to be positive",
+        ));
+    }
+    let scale = |x: i32, y: i32| {
+        round_coordinates(
+            f64::from(x) / f64::from(from.0) * f64::from(to.0),
+            f64::from(y) / f64::from(from.1) * f64::from(to.1),
+        )
+    };
+    Ok(match token {
+        Token::MoveMouse(x, y, coordinate) => {
+            let (x, y) = scale(*x, *y)?;
+            Token::MoveMouse(x, y, *coordinate)
+        }
+        Token::Location(x, y) => {
+            let (x, y) = scale(*x, *y)?;
+            Token::Location(x, y)
+        }
+        Token::MainDisplay(width, height) => {
+            let (width, height) = scale(*width, *height)?;
+            Token::MainDisplay(width, height)
+        }
+        Token::WithModifiers { modifiers, body } => Token::WithModifiers {
+            modifiers: modifiers.clone(),
+            body: body
+                .iter()
+                .map(|token| rescale(token, from, to))
+                .collect::<InputResult<_>>()?,
+        },
+        _ => token.clone(),
+    })
+}
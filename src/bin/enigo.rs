@@ -0,0 +1,127 @@
+//! Simulate keyboard and mouse input from the command line
+//!
+//! Run `enigo help` for the list of commands. Set the `RUST_LOG` environment
+//! variable (e.g. to `debug`) to see what enigo does.
+
+use std::{fs, process::ExitCode};
+
+use enigo::{
+    agent::{Agent, Token},
+    dsl, Axis, Coordinate, Enigo, Mouse, Settings,
+};
+
+const USAGE: &str = "Usage: enigo <COMMAND>
+
+Commands:
+  key <KEYS> [press|release]    Click (or press or release) a key or chord (e.g. ctrl+c)
+  type <TEXT>                   Enter the text
+  button [BUTTON] [press|release]
+                                Click (or press or release) a mouse button (default: left)
+  move <X> <Y> [--rel]          Move the mouse to the coordinates or relative to its location
+  scroll <LENGTH> [--horizontal]
+                                Scroll vertically or horizontally
+  run <SCRIPT>                  Execute a script in the syntax of the dsl module
+  play <FILE>                   Execute the tokens of a .ron file or a script in any other file
+  location                      Print the location of the mouse
+  display                       Print the size of the main display
+  help                          Print this help";
+
+fn main() -> ExitCode {
+    env_logger::try_init().ok();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[&str]) -> Result<(), String> {
+    let tokens = match args {
+        ["key", keys] => parse_script(&format!("{{{keys}}}"))?,
+        ["key", keys, direction] => {
+            parse_script(&format!("{{{}{keys}}}", direction_prefix(direction)?))?
+        }
+        ["type", text] => vec![Token::Text((*text).to_string())],
+        ["button"] => parse_script("{button left}")?,
+        ["button", button] => parse_script(&format!("{{button {button}}}"))?,
+        ["button", button, direction] => parse_script(&format!(
+            "{{{}button {button}}}",
+            direction_prefix(direction)?
+        ))?,
+        ["move", x, y] => vec![Token::MoveMouse(
+            parse_number(x)?,
+            parse_number(y)?,
+            Coordinate::Abs,
+        )],
+        ["move", x, y, "--rel"] => vec![Token::MoveMouse(
+            parse_number(x)?,
+            parse_number(y)?,
+            Coordinate::Rel,
+        )],
+        ["scroll", length] => vec![Token::Scroll(parse_number(length)?, Axis::Vertical)],
+        ["scroll", length, "--horizontal"] => {
+            vec![Token::Scroll(parse_number(length)?, Axis::Horizontal)]
+        }
+        ["run", script] => parse_script(script)?,
+        ["play", file] => {
+            let content =
+                fs::read_to_string(file).map_err(|e| format!("unable to read {file}: {e}"))?;
+            if file.ends_with(".ron") {
+                ron::from_str(&content).map_err(|e| format!("invalid tokens in {file}: {e}"))?
+            } else {
+                parse_script(&content)?
+            }
+        }
+        ["location"] => {
+            let (x, y) = new_enigo()?.location().map_err(|e| e.to_string())?;
+            println!("{x} {y}");
+            return Ok(());
+        }
+        ["display"] => {
+            let (width, height) = new_enigo()?.main_display().map_err(|e| e.to_string())?;
+            println!("{width} {height}");
+            return Ok(());
+        }
+        ["help" | "--help" | "-h"] => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+        _ => return Err(format!("invalid arguments\n\n{USAGE}")),
+    };
+
+    let mut enigo = new_enigo()?;
+    for token in &tokens {
+        enigo
+            .execute(token)
+            .map_err(|e| format!("unable to execute {token:?}: {e}"))?;
+    }
+    Ok(())
+}
+
+fn new_enigo() -> Result<Enigo, String> {
+    Enigo::new(&Settings::default()).map_err(|e| e.to_string())
+}
+
+fn parse_script(script: &str) -> Result<Vec<Token>, String> {
+    dsl::parse(script).map_err(|e| e.to_string())
+}
+
+fn parse_number(number: &str) -> Result<i32, String> {
+    number
+        .parse()
+        .map_err(|_| format!("invalid number: {number}"))
+}
+
+fn direction_prefix(direction: &str) -> Result<&'static str, String> {
+    match direction {
+        "press" => Ok("+"),
+        "release" => Ok("-"),
+        "click" => Ok(""),
+        _ => Err(format!("invalid direction: {direction}")),
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::InputError;
+
+/// Signals a long running function (e.g. [`Keyboard::text_cancellable`]) to
+/// stop. Clones share the state, so a clone can be moved to another thread
+/// (e.g. the one handling a "stop typing" button) and be cancelled from there
+///
+/// [`Keyboard::text_cancellable`]: crate::Keyboard::text_cancellable
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token and all of its clones
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns true if the token or one of its clones was cancelled
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Error of [`Keyboard::text_cancellable`]. Some of the text might have been
+/// entered before the error occurred
+///
+/// [`Keyboard::text_cancellable`]: crate::Keyboard::text_cancellable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialTextError {
+    /// Number of characters that were entered before the error occurred
+    pub entered: usize,
+    /// The error that stopped entering the text
+    pub error: InputError,
+}
+
+impl Display for PartialTextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} after entering {} characters",
+            self.error, self.entered
+        )
+    }
+}
+
+impl Error for PartialTextError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<PartialTextError> for InputError {
+    fn from(err: PartialTextError) -> Self {
+        err.error
+    }
+}
@@ -0,0 +1,303 @@
+#[cfg(unix)]
+use std::sync::atomic::AtomicI32;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+use log::{debug, warn};
+
+use crate::{Button, Direction, Enigo, InputResult, Key, Keyboard, Mouse, Settings};
+
+/// Set once the cleanup handlers were installed. Held input is only tracked
+/// after that
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Input that is held by any Enigo struct of the process
+static HELD: Mutex<Held> = Mutex::new(Held {
+    keys: Vec::new(),
+    raw: Vec::new(),
+    buttons: Vec::new(),
+});
+
+#[derive(Debug, Default)]
+struct Held {
+    keys: Vec<Key>,
+    raw: Vec<u16>,
+    buttons: Vec<Button>,
+}
+
+fn update<T: PartialEq>(held: &mut Vec<T>, item: T, direction: Direction) {
+    match direction {
+        Direction::Press if !held.contains(&item) => held.push(item),
+        Direction::Release => held.retain(|i| *i != item),
+        _ => {}
+    }
+}
+
+/// Track the key so it can be released on abnormal termination
+pub(crate) fn key(key: Key, direction: Direction) {
+    if INSTALLED.load(Ordering::Relaxed) {
+        if let Ok(mut held) = HELD.lock() {
+            update(&mut held.keys, key, direction);
+        }
+    }
+}
+
+/// Track the keycode so it can be released on abnormal termination
+pub(crate) fn raw(keycode: u16, direction: Direction) {
+    if INSTALLED.load(Ordering::Relaxed) {
+        if let Ok(mut held) = HELD.lock() {
+            update(&mut held.raw, keycode, direction);
+        }
+    }
+}
+
+/// Track the mouse button so it can be released on abnormal termination
+pub(crate) fn button(button: Button, direction: Direction) {
+    if INSTALLED.load(Ordering::Relaxed) {
+        if let Ok(mut held) = HELD.lock() {
+            update(&mut held.buttons, button, direction);
+        }
+    }
+}
+
+/// Set while the system cursors are replaced with a transparent one
+#[cfg(target_os = "windows")]
+static CURSOR_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Track whether the cursor is hidden so it can be shown again on abnormal
+/// termination. The system cursors stay replaced after the process exited
+#[cfg(target_os = "windows")]
+pub(crate) fn cursor_hidden(hidden: bool) {
+    if INSTALLED.load(Ordering::Relaxed) {
+        CURSOR_HIDDEN.store(hidden, Ordering::Relaxed);
+    }
+}
+
+/// Settings of the Enigo structs that release the held input
+fn cleanup_settings() -> Settings {
+    Settings {
+        release_keys_when_dropped: false,
+        open_prompt_to_get_permissions: false,
+        ..Default::default()
+    }
+}
+
+/// Release all tracked input and show the cursor again if it was hidden. A
+/// new Enigo struct is created if `enigo` is `None`. Must not be called from a
+/// signal handler
+fn release_all(enigo: Option<&mut Enigo>) {
+    let held = match HELD.lock() {
+        Ok(mut held) => std::mem::take(&mut *held),
+        Err(poisoned) => std::mem::take(&mut *poisoned.into_inner()),
+    };
+    #[cfg(target_os = "windows")]
+    let cursor_hidden = CURSOR_HIDDEN.swap(false, Ordering::Relaxed);
+    #[cfg(not(target_os = "windows"))]
+    let cursor_hidden = false;
+    if held.keys.is_empty() && held.raw.is_empty() && held.buttons.is_empty() && !cursor_hidden {
+        return;
+    }
+    debug!("releasing the held input: {held:?}");
+    let mut new_enigo;
+    let enigo = if let Some(enigo) = enigo {
+        enigo
+    } else {
+        new_enigo = Enigo::new(&cleanup_settings());
+        let Ok(enigo) = new_enigo.as_mut() else {
+            warn!("unable to release the held input");
+            return;
+        };
+        enigo
+    };
+    if cursor_hidden {
+        debug!("showing the cursor again");
+        let _ = enigo.show_cursor(true);
+    }
+    for button in held.buttons {
+        let _ = enigo.button(button, Direction::Release);
+    }
+    for key in held.keys.into_iter().rev() {
+        let _ = enigo.key(key, Direction::Release);
+    }
+    for keycode in held.raw.into_iter().rev() {
+        let _ = enigo.raw(keycode, Direction::Release);
+    }
+}
+
+extern "C" {
+    fn atexit(callback: extern "C" fn()) -> std::os::raw::c_int;
+}
+
+extern "C" fn on_exit() {
+    release_all(None);
+}
+
+/// Write end of the pipe the signal handler sends the signal number to
+#[cfg(unix)]
+static SIGNAL_PIPE: AtomicI32 = AtomicI32::new(-1);
+
+/// Only forwards the signal to the cleanup thread, because nothing else that
+/// is needed to release the input is async-signal-safe
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    let fd = SIGNAL_PIPE.load(Ordering::Relaxed);
+    // SAFETY: write is async-signal-safe and the buffer outlives the call. The
+    // write end is non-blocking, so the handler can't block if the pipe is
+    // full (the cleanup thread only needs the first signal anyway)
+    unsafe {
+        libc::write(
+            fd,
+            std::ptr::addr_of!(signal).cast(),
+            std::mem::size_of::<libc::c_int>(),
+        );
+    }
+}
+
+/// Spawn the thread that releases the held input once a signal arrived and
+/// then terminates the process with the signal
+#[cfg(unix)]
+fn spawn_signal_thread() -> InputResult<()> {
+    let mut fds = [0; 2];
+    // SAFETY: fds has room for the two file descriptors pipe writes
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+        return Err(crate::InputError::Simulate(
+            "unable to create the pipe for the signal handler",
+        ));
+    }
+    let [read_fd, write_fd] = fds;
+    // SAFETY: write_fd is a file descriptor that was just created
+    if unsafe { libc::fcntl(write_fd, libc::F_SETFL, libc::O_NONBLOCK) } != 0 {
+        return Err(crate::InputError::Simulate(
+            "unable to make the pipe for the signal handler non-blocking",
+        ));
+    }
+    SIGNAL_PIPE.store(write_fd, Ordering::SeqCst);
+
+    std::thread::Builder::new()
+        .name("enigo-cleanup".to_string())
+        .spawn(move || {
+            // Connect up front so no connection has to be established while
+            // the process is shutting down
+            let mut enigo = Enigo::new(&cleanup_settings())
+                .map_err(|e| warn!("unable to connect for the cleanup: {e}"))
+                .ok();
+            let mut signal: libc::c_int = 0;
+            loop {
+                // SAFETY: read_fd is the read end of the pipe and the buffer
+                // has room for the c_int that is read
+                let read = unsafe {
+                    libc::read(
+                        read_fd,
+                        std::ptr::addr_of_mut!(signal).cast(),
+                        std::mem::size_of::<libc::c_int>(),
+                    )
+                };
+                if usize::try_from(read).ok() == Some(std::mem::size_of::<libc::c_int>()) {
+                    break;
+                }
+                if read < 0
+                    && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted
+                {
+                    continue;
+                }
+                warn!("unable to read from the pipe of the signal handler");
+                return;
+            }
+            release_all(enigo.as_mut());
+            // Terminate the process like the default handler would
+            // SAFETY: Restoring the default disposition and raising a signal
+            // has no preconditions
+            unsafe {
+                libc::signal(signal, libc::SIG_DFL);
+                libc::raise(signal);
+            }
+        })
+        .map_err(|_| crate::InputError::Simulate("unable to spawn the cleanup thread"))?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn on_console_ctrl(_ctrl_type: u32) -> windows::Win32::Foundation::BOOL {
+    // The handler runs on its own thread, not in a signal context
+    release_all(None);
+    // Let the next handler (by default the one that exits the process) handle
+    // the event
+    false.into()
+}
+
+impl Enigo {
+    /// Release all keys and mouse buttons that are held by any Enigo struct
+    /// of the process when it terminates abnormally. On Windows the cursor is
+    /// shown again as well if it was hidden with `Mouse::show_cursor`.
+    /// Dropping the Enigo struct releases the held keys, but that is skipped if the process
+    /// panics with `panic = "abort"`, calls `std::process::exit` or gets
+    /// terminated by a signal.
+    ///
+    /// An `atexit` handler and handlers for SIGTERM, SIGINT, SIGHUP and SIGQUIT
+    /// (Linux and macOS) or the console control events (Windows) are
+    /// installed. Signals that already have a handler are left alone. The
+    /// signal handler only wakes up a cleanup thread that connected when this
+    /// function was called. It releases the input and then terminates the
+    /// process with the signal. If the crate is compiled with `panic =
+    /// "abort"`, a panic hook is installed as well. Panics that unwind release
+    /// the keys when the Enigo struct is dropped, so nothing is released behind
+    /// the back of code that catches them. This is best effort: With some
+    /// protocols (e.g. the virtual keyboard on Wayland) only the connection
+    /// that pressed a key can release it.
+    ///
+    /// Calling it more than once has no effect. Only input that is simulated
+    /// after it was called is tracked.
+    ///
+    /// # Errors
+    /// Returns an error if a handler could not be installed
+    pub fn install_cleanup_handler() -> InputResult<()> {
+        if INSTALLED.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        debug!("\x1b[93minstall_cleanup_handler()\x1b[0m");
+
+        // With unwinding, the panic might be caught and the held input is
+        // released when the Enigo struct is dropped
+        #[cfg(panic = "abort")]
+        {
+            let previous_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                release_all(None);
+                previous_hook(info);
+            }));
+        }
+
+        if unsafe { atexit(on_exit) } != 0 {
+            return Err(crate::InputError::Simulate(
+                "unable to register the atexit handler",
+            ));
+        }
+
+        #[cfg(unix)]
+        spawn_signal_thread()?;
+        #[cfg(unix)]
+        for signal in [libc::SIGTERM, libc::SIGINT, libc::SIGHUP, libc::SIGQUIT] {
+            let handler = on_signal as extern "C" fn(libc::c_int);
+            let previous = unsafe { libc::signal(signal, handler as libc::sighandler_t) };
+            if previous != libc::SIG_DFL {
+                // Do not replace the handler of the application
+                unsafe { libc::signal(signal, previous) };
+            }
+        }
+
+        #[cfg(target_os = "windows")]
+        if unsafe {
+            windows::Win32::System::Console::SetConsoleCtrlHandler(Some(on_console_ctrl), true)
+        }
+        .is_err()
+        {
+            return Err(crate::InputError::Simulate(
+                "unable to set the console control handler",
+            ));
+        }
+
+        Ok(())
+    }
+}
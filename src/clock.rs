@@ -0,0 +1,104 @@
+//! Source of time for everything in enigo that waits or measures intervals
+//!
+//! By default the [`SystemClock`] is used. Tests can inject a [`MockClock`]
+//! instead to check timing behavior (e.g. double click detection or the
+//! duration of a [`Token::Sleep`](crate::agent::Token::Sleep)) without
+//! actually sleeping. The clock is used by the token player
+//! ([`crate::agent::Agent::execute_with_clock`]), the
+//! [`RateLimit`](crate::agent::RateLimit) of a policy and on macOS by the
+//! double click tracking and the waiting for the OS to handle the posted
+//! events (see `Enigo::set_clock`).
+//!
+//! ```
+//! use enigo::clock::{Clock, MockClock};
+//! use std::time::Duration;
+//!
+//! let clock = MockClock::new();
+//! let start = clock.now();
+//! clock.sleep(Duration::from_millis(500));
+//! assert_eq!(clock.elapsed(start), Duration::from_millis(500));
+//! ```
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Source of time
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current point in time
+    fn now(&self) -> Instant;
+
+    /// Blocks for the duration
+    fn sleep(&self, duration: Duration);
+
+    /// Time that passed since the point in time. It is zero if the point in
+    /// time is in the future
+    fn elapsed(&self, since: Instant) -> Duration {
+        self.now().saturating_duration_since(since)
+    }
+}
+
+/// The clock of the OS. It actually sleeps
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Clock that only advances when it is told to. Sleeping advances the time
+/// instantly instead of blocking
+///
+/// Clones share the same time, so you can keep a clone to advance the clock
+/// after handing it to enigo.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl MockClock {
+    /// Create a clock that starts at the current point in time
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Move the time forward
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self
+            .now
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        *now += duration;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self
+            .now
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
@@ -0,0 +1,163 @@
+//! Snapshot of the environment to attach to bug reports
+//!
+//! Whether simulated input works depends a lot on the environment: the
+//! backend that is used, the desktop environment, the permissions of the
+//! application and the keyboard layout. [`Enigo::diagnostics`](crate::Enigo)
+//! collects all of it in a [`DiagnosticsReport`]. Its `Display` output can be
+//! pasted into an issue and with the `serde` feature it can be serialized.
+//!
+//! The settings are included in a redacted form: the names of the displays,
+//! seats and devices are replaced, because they can contain user names or
+//! paths.
+//!
+//! ```no_run
+//! use enigo::{Enigo, Settings};
+//!
+//! let enigo = Enigo::new(&Settings::default()).unwrap();
+//! println!("{}", enigo.diagnostics());
+//! ```
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    health::{health_check, HealthReport},
+    system, Settings,
+};
+
+/// Placeholder for the values that were removed from the settings
+pub const REDACTED: &str = "<redacted>";
+
+/// Information about the environment enigo runs in
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagnosticsReport {
+    /// Version of enigo
+    pub enigo_version: String,
+    /// Operating system (e.g. "linux")
+    pub os: String,
+    /// Architecture of the CPU (e.g. "`x86_64`")
+    pub arch: String,
+    /// The features enigo was compiled with
+    pub features: Vec<String>,
+    /// The backends that are connected (e.g. "x11rb" and "libei")
+    pub backends: Vec<String>,
+    /// Desktop environment (`XDG_CURRENT_DESKTOP` on Linux)
+    pub desktop: Option<String>,
+    /// Type of the session (`XDG_SESSION_TYPE` on Linux, e.g. "wayland")
+    pub session_type: Option<String>,
+    /// Whether the application has the permission to simulate input. It is
+    /// `None` on platforms that don't need a permission
+    pub has_permission: Option<bool>,
+    /// Name of the active keyboard layout
+    pub keyboard_layout: Option<String>,
+    /// Dots per inch of the main display
+    pub screen_dpi: Option<f64>,
+    /// Size of the main display in pixels
+    pub main_display: Option<(i32, i32)>,
+    /// Reasons why the OS would discard the simulated input
+    pub health: HealthReport,
+    /// The redacted settings the Enigo struct was created with
+    pub settings: String,
+}
+
+impl fmt::Display for DiagnosticsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Format an optional value or "unknown"
+        fn or_unknown<T: fmt::Debug>(value: Option<&T>) -> String {
+            value.map_or_else(|| "unknown".to_string(), |v| format!("{v:?}"))
+        }
+
+        writeln!(f, "enigo version: {}", self.enigo_version)?;
+        writeln!(f, "os: {} ({})", self.os, self.arch)?;
+        writeln!(f, "features: {}", self.features.join(", "))?;
+        writeln!(f, "backends: {}", self.backends.join(", "))?;
+        writeln!(f, "desktop: {}", or_unknown(self.desktop.as_ref()))?;
+        writeln!(
+            f,
+            "session type: {}",
+            or_unknown(self.session_type.as_ref())
+        )?;
+        writeln!(
+            f,
+            "permission: {}",
+            or_unknown(self.has_permission.as_ref())
+        )?;
+        writeln!(
+            f,
+            "keyboard layout: {}",
+            or_unknown(self.keyboard_layout.as_ref())
+        )?;
+        writeln!(f, "screen DPI: {}", or_unknown(self.screen_dpi.as_ref()))?;
+        writeln!(
+            f,
+            "main display: {}",
+            or_unknown(self.main_display.as_ref())
+        )?;
+        writeln!(f, "health: {}", self.health)?;
+        write!(f, "settings: {}", self.settings)
+    }
+}
+
+/// The features enigo was compiled with
+fn features() -> Vec<String> {
+    [
+        ("a11y", cfg!(feature = "a11y")),
+        ("arbitrary", cfg!(feature = "arbitrary")),
+        ("cli", cfg!(feature = "cli")),
+        ("dsl", cfg!(feature = "dsl")),
+        ("libei", cfg!(feature = "libei")),
+        ("schema", cfg!(feature = "schema")),
+        ("serde", cfg!(feature = "serde")),
+        ("test-harness", cfg!(feature = "test-harness")),
+        ("testing", cfg!(feature = "testing")),
+        ("tracing", cfg!(feature = "tracing")),
+        ("wayland", cfg!(feature = "wayland")),
+        ("webdriver", cfg!(feature = "webdriver")),
+        ("x11rb", cfg!(feature = "x11rb")),
+        ("xdo", cfg!(feature = "xdo")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+/// Copy of the settings without the values that could identify the user
+pub(crate) fn redact(settings: &Settings) -> Settings {
+    let redacted = |value: &Option<String>| value.as_ref().map(|_| REDACTED.to_string());
+    Settings {
+        x11_display: redacted(&settings.x11_display),
+        wayland_display: redacted(&settings.wayland_display),
+        libei_seat: redacted(&settings.libei_seat),
+        libei_device: redacted(&settings.libei_device),
+        ..settings.clone()
+    }
+}
+
+/// Collect the parts of the report that don't depend on the platform
+pub(crate) fn report(
+    backends: Vec<String>,
+    has_permission: Option<bool>,
+    keyboard_layout: Option<String>,
+    main_display: Option<(i32, i32)>,
+    settings: &Settings,
+) -> DiagnosticsReport {
+    DiagnosticsReport {
+        enigo_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        features: features(),
+        backends,
+        desktop: std::env::var("XDG_CURRENT_DESKTOP").ok(),
+        session_type: std::env::var("XDG_SESSION_TYPE").ok(),
+        has_permission,
+        keyboard_layout,
+        screen_dpi: system::screen_dpi().ok(),
+        main_display,
+        health: health_check(),
+        settings: format!("{settings:?}"),
+    }
+}
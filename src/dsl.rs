@@ -0,0 +1,234 @@
+//! A small text syntax for scripts that is compiled into [`Token`]s
+//!
+//! Text outside of braces is entered as it is. Commands are written in braces:
+//!
+//! | Command            | Meaning                                                  |
+//! |--------------------|----------------------------------------------------------|
+//! | `{Return}`         | Click a key. Single characters are unicode keys (`{a}`)  |
+//! | `{+Shift}`         | Press a key                                              |
+//! | `{-Shift}`         | Release a key                                            |
+//! | `{ctrl+shift+t}`   | Click a chord (press all keys, release in reverse order) |
+//! | `{raw 38}`         | Click a raw keycode (`+` and `-` work as for keys)       |
+//! | `{button left}`    | Click a mouse button (`+` and `-` work as for keys)      |
+//! | `{move 100 200}`   | Move the mouse to the absolute coordinates               |
+//! | `{move_rel 10 -5}` | Move the mouse relative to its current location          |
+//! | `{scroll 3}`       | Scroll vertically                                        |
+//! | `{hscroll -2}`     | Scroll horizontally                                      |
+//! | `{sleep 500}`      | Wait for the number of milliseconds                      |
+//! | `{{` and `}}`      | Enter a literal `{` or `}`                               |
+//!
+//! Key and button names are the names of the variants of [`crate::Key`] and
+//! [`crate::Button`] (e.g. `PageUp`) or their serde aliases (e.g. `ctrl`). The
+//! first letter is not case sensitive (`{return}` works as well).
+//!
+//! ```no_run
+//! use enigo::{agent::Agent, dsl, Enigo, Settings};
+//!
+//! let tokens = dsl::parse("{ctrl+a}Hello {+shift}world{-shift}!{Return}{sleep 100}{button left}")
+//!     .unwrap();
+//! let mut enigo = Enigo::new(&Settings::default()).unwrap();
+//! for token in &tokens {
+//!     enigo.execute(token).unwrap();
+//! }
+//! ```
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use serde::de::{DeserializeOwned, IntoDeserializer};
+
+use crate::{agent::Token, Axis, Coordinate, Direction, Key};
+
+/// Error when parsing a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The `{` at the byte offset was never closed
+    UnclosedBrace(usize),
+    /// There was a `}` at the byte offset that was not opened. Use `}}` to
+    /// enter it as text
+    UnexpectedBrace(usize),
+    /// The command is not known or has the wrong number of arguments
+    UnknownCommand(String),
+    /// There is no key with the name
+    UnknownKey(String),
+    /// There is no mouse button with the name
+    UnknownButton(String),
+    /// The argument is not a valid number
+    InvalidNumber(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnclosedBrace(idx) => write!(f, "the brace at {idx} was never closed"),
+            ParseError::UnexpectedBrace(idx) => {
+                write!(f, "unexpected closing brace at {idx}. Use }}}} to enter it")
+            }
+            ParseError::UnknownCommand(command) => write!(f, "unknown command: {{{command}}}"),
+            ParseError::UnknownKey(key) => write!(f, "unknown key: {key}"),
+            ParseError::UnknownButton(button) => write!(f, "unknown mouse button: {button}"),
+            ParseError::InvalidNumber(number) => write!(f, "invalid number: {number}"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Compile the script into tokens
+///
+/// # Errors
+/// Returns an error if the script is not valid. Have a look at the
+/// documentation of the module for the syntax
+pub fn parse(script: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = vec![];
+    let mut text = String::new();
+    let mut chars = script.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '{' if chars.next_if(|&(_, c)| c == '{').is_some() => text.push('{'),
+            '}' if chars.next_if(|&(_, c)| c == '}').is_some() => text.push('}'),
+            '{' => {
+                let mut command = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '}')) => break,
+                        Some((_, c)) => command.push(c),
+                        None => return Err(ParseError::UnclosedBrace(idx)),
+                    }
+                }
+                if !text.is_empty() {
+                    tokens.push(Token::Text(std::mem::take(&mut text)));
+                }
+                parse_command(&command, &mut tokens)?;
+            }
+            '}' => return Err(ParseError::UnexpectedBrace(idx)),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    Ok(tokens)
+}
+
+/// Parse the content of the braces and add the resulting tokens
+fn parse_command(command: &str, tokens: &mut Vec<Token>) -> Result<(), ParseError> {
+    let unknown = || ParseError::UnknownCommand(command.to_string());
+
+    // A single "+" or "-" is the key and not a direction
+    let (direction, rest) = match command.trim() {
+        c if c.len() > 1 && c.starts_with('+') => (Direction::Press, &c[1..]),
+        c if c.len() > 1 && c.starts_with('-') => (Direction::Release, &c[1..]),
+        c => (Direction::Click, c),
+    };
+    let mut words = rest.split_whitespace();
+    let name = words.next().ok_or_else(unknown)?;
+    let args: Vec<&str> = words.collect();
+
+    match (name, args.as_slice()) {
+        ("sleep", [ms]) if direction == Direction::Click => {
+            tokens.push(Token::Sleep(parse_number(ms)?));
+        }
+        ("move", [x, y]) if direction == Direction::Click => {
+            tokens.push(Token::MoveMouse(
+                parse_number(x)?,
+                parse_number(y)?,
+                Coordinate::Abs,
+            ));
+        }
+        ("move_rel", [x, y]) if direction == Direction::Click => {
+            tokens.push(Token::MoveMouse(
+                parse_number(x)?,
+                parse_number(y)?,
+                Coordinate::Rel,
+            ));
+        }
+        ("scroll", [length]) if direction == Direction::Click => {
+            tokens.push(Token::Scroll(parse_number(length)?, Axis::Vertical));
+        }
+        ("hscroll", [length]) if direction == Direction::Click => {
+            tokens.push(Token::Scroll(parse_number(length)?, Axis::Horizontal));
+        }
+        ("raw", [keycode]) => tokens.push(Token::Raw(parse_number(keycode)?, direction)),
+        ("button", [button]) => {
+            let button = parse_name(button)
+                .ok_or_else(|| ParseError::UnknownButton((*button).to_string()))?;
+            tokens.push(Token::Button(button, direction));
+        }
+        (chord, []) => {
+            // A "+" at the end of the chord is the key itself (e.g. "ctrl++")
+            let mut names: Vec<&str> = match chord.strip_suffix("++") {
+                Some(modifiers) => modifiers.split('+').chain(["+"]).collect(),
+                None if chord == "+" => vec!["+"],
+                None => chord.split('+').collect(),
+            };
+            names.retain(|name| !name.is_empty());
+            let keys = names
+                .iter()
+                .map(|name| parse_key(name))
+                .collect::<Result<Vec<_>, _>>()?;
+            let Some((&last, modifiers)) = keys.split_last() else {
+                return Err(unknown());
+            };
+            match direction {
+                Direction::Press => {
+                    tokens.extend(keys.iter().map(|&key| Token::Key(key, Direction::Press)));
+                }
+                Direction::Release => tokens.extend(
+                    keys.iter()
+                        .rev()
+                        .map(|&key| Token::Key(key, Direction::Release)),
+                ),
+                Direction::Click => {
+                    tokens.extend(
+                        modifiers
+                            .iter()
+                            .map(|&key| Token::Key(key, Direction::Press)),
+                    );
+                    tokens.push(Token::Key(last, Direction::Click));
+                    tokens.extend(
+                        modifiers
+                            .iter()
+                            .rev()
+                            .map(|&key| Token::Key(key, Direction::Release)),
+                    );
+                }
+            }
+        }
+        _ => return Err(unknown()),
+    }
+    Ok(())
+}
+
+fn parse_number<T: std::str::FromStr>(number: &str) -> Result<T, ParseError> {
+    number
+        .parse()
+        .map_err(|_| ParseError::InvalidNumber(number.to_string()))
+}
+
+fn parse_key(name: &str) -> Result<Key, ParseError> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(Key::Unicode(c));
+    }
+    parse_name(name).ok_or_else(|| ParseError::UnknownKey(name.to_string()))
+}
+
+/// Look up the variant of the enum by its name or serde alias. If there is
+/// none, try again with the first letter in upper case
+fn parse_name<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let lookup = |name: &str| {
+        T::deserialize(
+            IntoDeserializer::<serde::de::value::Error>::into_deserializer(name.to_string()),
+        )
+        .ok()
+    };
+    lookup(name).or_else(|| {
+        let mut chars = name.chars();
+        let first = chars.next()?;
+        lookup(&first.to_uppercase().chain(chars).collect::<String>())
+    })
+}
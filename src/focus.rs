@@ -0,0 +1,41 @@
+use log::{debug, warn};
+
+use crate::{
+    agent::{Agent, Token},
+    Enigo, InputError, InputResult,
+};
+
+impl Enigo {
+    /// Execute the tokens one after another and stop at the first error.
+    ///
+    /// If `Settings::focus_guard` is enabled, the active window is recorded
+    /// before the first token is executed. The remaining tokens are not
+    /// executed if a different window became active, so they are not received
+    /// by the wrong application.
+    ///
+    /// # Errors
+    /// Returns an [`InputError::WrongForegroundWindow`] if the focus guard
+    /// detected a different active window, an error if the active window
+    /// could not be determined or the error of the token that failed
+    pub fn execute_sequence(&mut self, tokens: &[Token]) -> InputResult<()> {
+        debug!("\x1b[93mexecute_sequence(tokens: {tokens:?})\x1b[0m");
+        let expected = if self.focus_guard {
+            let window = self.active_window()?;
+            debug!("guarding the focus of the window {window}");
+            Some(window)
+        } else {
+            None
+        };
+        for token in tokens {
+            if let Some(expected) = expected {
+                let active = self.active_window()?;
+                if active != expected {
+                    warn!("the window {active} became active instead of {expected}. Aborting");
+                    return Err(InputError::WrongForegroundWindow);
+                }
+            }
+            self.execute(token)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender, SyncSender},
+    thread,
+};
+
+use log::{debug, error};
+
+use crate::{
+    agent::{Agent, Token},
+    Axis, Button, Coordinate, Direction, Enigo, InputError, InputResult, Key, Keyboard, Mouse,
+    NewConError, Settings,
+};
+
+/// Instructions for the thread that owns the [`Enigo`] struct
+enum Command {
+    Execute(Token, Option<SyncSender<InputResult<()>>>),
+    MainDisplay(SyncSender<InputResult<(i32, i32)>>),
+    Location(SyncSender<InputResult<(i32, i32)>>),
+    ScrollWithInertia(f64, Axis),
+}
+
+/// A cheap to clone handle to an [`Enigo`] struct that lives on its own thread
+///
+/// The handle is `Send` and `Sync`, so you can simulate input from any thread
+/// (e.g. from the callbacks of a GUI). All input is simulated in the order it
+/// was sent. The thread is stopped and the [`Enigo`] struct is dropped once all
+/// handles were dropped.
+///
+/// ```no_run
+/// use enigo::{agent::Token, EnigoHandle, Keyboard, Settings};
+///
+/// let handle = EnigoHandle::new(&Settings::default()).unwrap();
+/// let mut handle2 = handle.clone();
+/// std::thread::spawn(move || handle2.text("Hello from another thread").unwrap());
+/// handle.queue(Token::Text("Hello".to_string())).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnigoHandle {
+    sender: Sender<Command>,
+}
+
+impl EnigoHandle {
+    /// Spawn a new thread and create the [`Enigo`] struct on it with the
+    /// specified settings
+    ///
+    /// # Errors
+    /// Have a look at the documentation of `NewConError` to see under which
+    /// conditions an error will be returned.
+    pub fn new(settings: &Settings) -> Result<Self, NewConError> {
+        let (sender, receiver) = mpsc::channel();
+        let (init_sender, init_receiver) = mpsc::sync_channel(1);
+        let settings = settings.clone();
+
+        thread::Builder::new()
+            .name("enigo".to_string())
+            .spawn(move || match Enigo::new(&settings) {
+                Ok(enigo) => {
+                    let _ = init_sender.send(Ok(()));
+                    Self::run(enigo, &receiver);
+                }
+                Err(e) => {
+                    let _ = init_sender.send(Err(e));
+                }
+            })
+            .map_err(|_| NewConError::EstablishCon("unable to spawn the thread for enigo"))?;
+
+        init_receiver
+            .recv()
+            .map_err(|_| NewConError::EstablishCon("the thread for enigo stopped"))??;
+        debug!("started the thread for enigo");
+        Ok(Self { sender })
+    }
+
+    /// Handle the commands until all handles were dropped
+    fn run(mut enigo: Enigo, receiver: &Receiver<Command>) {
+        for command in receiver {
+            match command {
+                Command::Execute(token, reply) => {
+                    let res = enigo.execute(&token);
+                    if let Some(reply) = reply {
+                        let _ = reply.send(res);
+                    } else if let Err(e) = res {
+                        error!("unable to execute {token:?}: {e}");
+                    }
+                }
+                Command::MainDisplay(reply) => {
+                    let _ = reply.send(enigo.main_display());
+                }
+                Command::Location(reply) => {
+                    let _ = reply.send(enigo.location());
+                }
+                Command::ScrollWithInertia(velocity, axis) => {
+                    if let Err(e) = enigo.scroll_with_inertia(velocity, axis) {
+                        error!("unable to scroll with inertia: {e}");
+                    }
+                }
+            }
+        }
+        debug!("all handles were dropped. Stopping the thread for enigo");
+    }
+
+    /// Send the command and wait for the reply
+    fn request<T>(
+        &self,
+        command: impl FnOnce(SyncSender<InputResult<T>>) -> Command,
+    ) -> InputResult<T> {
+        let (reply_sender, reply_receiver) = mpsc::sync_channel(1);
+        self.sender
+            .send(command(reply_sender))
+            .map_err(|_| InputError::Simulate("the thread for enigo stopped"))?;
+        reply_receiver
+            .recv()
+            .map_err(|_| InputError::Simulate("the thread for enigo stopped"))?
+    }
+
+    /// Simulate the token and wait until it was simulated
+    ///
+    /// # Errors
+    /// Same as [`Agent::execute`] or if the thread for enigo stopped
+    pub fn simulate(&self, token: Token) -> InputResult<()> {
+        self.request(|reply| Command::Execute(token, Some(reply)))
+    }
+
+    /// Add the token to the queue of input that gets simulated and return
+    /// immediately. Errors when simulating the token are only logged
+    ///
+    /// # Errors
+    /// Returns an error if the thread for enigo stopped
+    pub fn queue(&self, token: Token) -> InputResult<()> {
+        self.sender
+            .send(Command::Execute(token, None))
+            .map_err(|_| InputError::Simulate("the thread for enigo stopped"))
+    }
+}
+
+impl Keyboard for EnigoHandle {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        self.simulate(Token::Text(text.to_string())).map(Some)
+    }
+
+    fn text(&mut self, text: &str) -> InputResult<()> {
+        self.simulate(Token::Text(text.to_string()))
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        self.simulate(Token::Key(key, direction))
+    }
+
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        self.simulate(Token::Raw(keycode, direction))
+    }
+}
+
+impl Mouse for EnigoHandle {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        self.simulate(Token::Button(button, direction))
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        self.simulate(Token::MoveMouse(x, y, coordinate))
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        self.simulate(Token::Scroll(length, axis))
+    }
+
+    /// Start the fling on the thread of the [`Enigo`] struct and return
+    /// immediately. Input that is sent afterwards is simulated once the fling
+    /// came to rest. Errors while scrolling are only logged
+    fn scroll_with_inertia(&mut self, velocity: f64, axis: Axis) -> InputResult<()> {
+        if !velocity.is_finite() {
+            return Err(InputError::InvalidInput("the velocity has to be finite"));
+        }
+        self.sender
+            .send(Command::ScrollWithInertia(velocity, axis))
+            .map_err(|_| InputError::Simulate("the thread for enigo stopped"))
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        self.request(Command::MainDisplay)
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        self.request(Command::Location)
+    }
+}
@@ -0,0 +1,133 @@
+//! Check if the OS is likely to discard the simulated input
+//!
+//! The OS drops simulated input without reporting an error in several
+//! situations: the session is locked, the secure desktop (lock screen or UAC
+//! prompt) is shown or there is no display the input could go to.
+//! [`health_check`] inspects the state of the session and returns a
+//! [`HealthReport`] with all the reasons it found, so e.g. a remote agent can
+//! tell its user why the input isn't arriving.
+//!
+//! | Issue                                 | Windows | macOS | Linux |
+//! |---------------------------------------|---------|-------|-------|
+//! | [`HealthIssue::SessionLocked`]        | ✅      | ✅    | ✅    |
+//! | [`HealthIssue::SessionInactive`]      | ✅      | ✅    | ✅    |
+//! | [`HealthIssue::SecureDesktop`]        | ✅      | ❌    | ❌    |
+//! | [`HealthIssue::NoDisplay`]            | ❌      | ✅    | ✅    |
+//! | [`HealthIssue::BlockedByUipi`]        | ✅      | ❌    | ❌    |
+//! | [`HealthIssue::PermissionMissing`]    | ❌      | ✅    | ❌    |
+//!
+//! The checks are a best effort. If the state could not be determined, no
+//! issue is reported.
+//!
+//! ```no_run
+//! let report = enigo::health::health_check();
+//! for issue in &report.issues {
+//!     println!("the input will probably not arrive: {issue}");
+//! }
+//! ```
+
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::os;
+
+/// Reason why the OS likely discards the simulated input
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthIssue {
+    /// The screen is locked
+    #[cfg_attr(feature = "serde", serde(rename = "SessionLocked"))]
+    SessionLocked,
+    /// The session is not the active one (e.g. another user is logged in on
+    /// the console or the session is remote)
+    #[cfg_attr(feature = "serde", serde(rename = "SessionInactive"))]
+    SessionInactive,
+    /// The secure desktop (the lock screen, the login screen, a UAC prompt or
+    /// the screen saver) has the input focus. No application can send input
+    /// to it
+    #[cfg_attr(feature = "serde", serde(rename = "SecureDesktop"))]
+    SecureDesktop,
+    /// There is no display server or window server session to send the input
+    /// to (e.g. in a container or a SSH session)
+    #[cfg_attr(feature = "serde", serde(rename = "NoDisplay"))]
+    NoDisplay,
+    /// The foreground window runs with a higher integrity level (e.g.
+    /// elevated), so UIPI discards the input. Have a look at
+    /// [`InputError::BlockedByUipi`](crate::InputError::BlockedByUipi)
+    #[cfg_attr(feature = "serde", serde(rename = "BlockedByUipi"))]
+    BlockedByUipi,
+    /// The application is not allowed to simulate input (e.g. the
+    /// accessibility permission is missing on macOS)
+    #[cfg_attr(feature = "serde", serde(rename = "PermissionMissing"))]
+    PermissionMissing,
+}
+
+impl fmt::Display for HealthIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let string = match self {
+            HealthIssue::SessionLocked => "the screen is locked",
+            HealthIssue::SessionInactive => "the session is not the active session",
+            HealthIssue::SecureDesktop => {
+                "the secure desktop (lock screen, login screen, UAC prompt or screen saver) is shown"
+            }
+            HealthIssue::NoDisplay => "there is no display to send the input to",
+            HealthIssue::BlockedByUipi => {
+                "the foreground window runs elevated. run the program as administrator or with uiAccess"
+            }
+            HealthIssue::PermissionMissing => {
+                "the application does not have the permission to simulate input"
+            }
+        };
+        write!(f, "{string}")
+    }
+}
+
+/// Result of a [`health_check`]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HealthReport {
+    /// Everything that was found to prevent the simulated input from arriving
+    #[cfg_attr(feature = "serde", serde(rename = "issues"))]
+    pub issues: Vec<HealthIssue>,
+}
+
+impl HealthReport {
+    /// Returns true if no issue was found
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns true if the issue was found
+    #[must_use]
+    pub fn has(&self, issue: HealthIssue) -> bool {
+        self.issues.contains(&issue)
+    }
+}
+
+impl fmt::Display for HealthReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_healthy() {
+            return write!(f, "no issues found");
+        }
+        let issues: Vec<String> = self.issues.iter().map(ToString::to_string).collect();
+        write!(f, "{}", issues.join(", "))
+    }
+}
+
+/// Inspect the state of the session to find reasons why the OS would discard
+/// simulated input
+///
+/// It does not simulate any input and does not need an [`Enigo`](crate::Enigo)
+/// struct. On Linux the display is looked up in the `DISPLAY` and
+/// `WAYLAND_DISPLAY` environment variables and the state of the session is
+/// asked from logind over D-Bus. That needs the `wayland`, `libei` or `a11y`
+/// feature. Otherwise the state of the session is not checked.
+#[must_use]
+pub fn health_check() -> HealthReport {
+    HealthReport {
+        issues: os::health::issues(),
+    }
+}
@@ -0,0 +1,50 @@
+//! Keep the screen on and the system awake during long playbacks
+//!
+//! Unattended playback of a long macro gets interrupted when the screen saver
+//! starts, the screen gets locked or the system goes to sleep, because the
+//! simulated input does not always count as activity. Hold a [`KeepAwake`]
+//! for as long as the input is simulated to prevent that.
+//!
+//! | Platform | Mechanism                                                                     |
+//! |----------|-------------------------------------------------------------------------------|
+//! | Windows  | `SetThreadExecutionState`                                                     |
+//! | macOS    | An `IOPMAssertion` that prevents the display from sleeping                    |
+//! | Linux    | A logind inhibitor lock and the `org.freedesktop.ScreenSaver` D-Bus interface |
+//!
+//! On Linux the D-Bus interfaces are only used if enigo was compiled with the
+//! `a11y` or `libei` feature. Then the lock is a file descriptor that the kernel
+//! closes if the process dies. Otherwise `systemd-inhibit` is run to take the
+//! lock. It is killed once the thread that created the [`KeepAwake`] exits, so
+//! create it on a thread that lives as long as it is needed.
+//!
+//! ```no_run
+//! use enigo::keep_awake::KeepAwake;
+//!
+//! let _awake = KeepAwake::new("playing back a macro").unwrap();
+//! // Simulate the input here. The system is allowed to sleep again once
+//! // `_awake` is dropped
+//! ```
+
+use crate::{os, InputResult};
+
+/// Prevents the screen saver, the screen lock and the system sleep until it
+/// is dropped
+#[derive(Debug)]
+pub struct KeepAwake {
+    _inhibitor: os::keep_awake::Inhibitor,
+}
+
+impl KeepAwake {
+    /// Prevent the screen saver, the screen lock and the system sleep. The
+    /// reason is shown to the user by some platforms (e.g. in the power
+    /// settings)
+    ///
+    /// # Errors
+    /// Returns an `InputError::Simulate` if none of the mechanisms of the
+    /// platform could be used
+    pub fn new(reason: &str) -> InputResult<Self> {
+        Ok(Self {
+            _inhibitor: os::keep_awake::Inhibitor::new(reason)?,
+        })
+    }
+}
@@ -14,600 +14,902 @@ use strum_macros::EnumIter;
 /// available on a specific platform. Use conditional compilation to use them.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(test, derive(EnumIter))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Key {
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num0"))]
     Num0,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num1"))]
     Num1,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num2"))]
     Num2,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num3"))]
     Num3,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num4"))]
     Num4,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num5"))]
     Num5,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num6"))]
     Num6,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num7"))]
     Num7,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num8"))]
     Num8,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Num9"))]
     Num9,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "A"))]
     A,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "B"))]
     B,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "C"))]
     C,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "D"))]
     D,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "E"))]
     E,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "F"))]
     F,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "G"))]
     G,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "H"))]
     H,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "I"))]
     I,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "J"))]
     J,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "K"))]
     K,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "L"))]
     L,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "M"))]
     M,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "N"))]
     N,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "O"))]
     O,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "P"))]
     P,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Q"))]
     Q,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "R"))]
     R,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "S"))]
     S,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "T"))]
     T,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "U"))]
     U,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "V"))]
     V,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "W"))]
     W,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "X"))]
     X,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Y"))]
     Y,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Z"))]
     Z,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "AbntC1"))]
     AbntC1,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "AbntC2"))]
     AbntC2,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Accept"))]
     Accept,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Add"))]
     Add,
     /// alt key on Linux and Windows (option key on macOS)
+    #[cfg_attr(feature = "serde", serde(rename = "Alt"))]
     Alt,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Apps"))]
     Apps,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Attn"))]
     Attn,
     /// backspace key
+    #[cfg_attr(feature = "serde", serde(rename = "Backspace"))]
     Backspace,
-    #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    /// break key (Ctrl+Pause)
+    #[cfg_attr(feature = "serde", serde(rename = "Break"))]
     Break,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "Begin"))]
     Begin,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "BrightnessDown"))]
     BrightnessDown,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "BrightnessUp"))]
     BrightnessUp,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "BrowserBack"))]
     BrowserBack,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "BrowserFavorites"))]
     BrowserFavorites,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "BrowserForward"))]
     BrowserForward,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "BrowserHome"))]
     BrowserHome,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "BrowserRefresh"))]
     BrowserRefresh,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "BrowserSearch"))]
     BrowserSearch,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "BrowserStop"))]
     BrowserStop,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Cancel"))]
     Cancel,
     /// caps lock key
+    #[cfg_attr(feature = "serde", serde(rename = "CapsLock"))]
     CapsLock,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Clear"))]
     Clear,
     #[deprecated(since = "0.0.12", note = "now renamed to Meta")]
     /// command key on macOS (super key on Linux, windows key on Windows)
     #[cfg_attr(feature = "serde", serde(alias = "cmd"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Command"))]
     Command,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "ContrastUp"))]
     ContrastUp,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "ContrastDown"))]
     ContrastDown,
     /// control key
     #[cfg_attr(feature = "serde", serde(alias = "ctrl"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Control"))]
     Control,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Convert"))]
     Convert,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Crsel"))]
     Crsel,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEAlphanumeric"))]
     DBEAlphanumeric,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBECodeinput"))]
     DBECodeinput,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEDetermineString"))]
     DBEDetermineString,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEEnterDLGConversionMode"))]
     DBEEnterDLGConversionMode,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEEnterIMEConfigMode"))]
     DBEEnterIMEConfigMode,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEEnterWordRegisterMode"))]
     DBEEnterWordRegisterMode,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEFlushString"))]
     DBEFlushString,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEHiragana"))]
     DBEHiragana,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBEKatakana"))]
     DBEKatakana,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBENoCodepoint"))]
     DBENoCodepoint,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBENoRoman"))]
     DBENoRoman,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBERoman"))]
     DBERoman,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBESBCSChar"))]
     DBESBCSChar,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "DBESChar"))]
     DBESChar,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Decimal"))]
     Decimal,
     /// delete key
+    #[cfg_attr(feature = "serde", serde(rename = "Delete"))]
     Delete,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Divide"))]
     Divide,
     /// down arrow key
+    #[cfg_attr(feature = "serde", serde(rename = "DownArrow"))]
     DownArrow,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "Eject"))]
     Eject,
     /// end key
+    #[cfg_attr(feature = "serde", serde(rename = "End"))]
     End,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Ereof"))]
     Ereof,
     /// escape key (esc)
+    #[cfg_attr(feature = "serde", serde(rename = "Escape"))]
     Escape,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Execute"))]
     Execute,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Exsel"))]
     Exsel,
     /// F1 key
+    #[cfg_attr(feature = "serde", serde(rename = "F1"))]
     F1,
     /// F2 key
+    #[cfg_attr(feature = "serde", serde(rename = "F2"))]
     F2,
     /// F3 key
+    #[cfg_attr(feature = "serde", serde(rename = "F3"))]
     F3,
     /// F4 key
+    #[cfg_attr(feature = "serde", serde(rename = "F4"))]
     F4,
     /// F5 key
+    #[cfg_attr(feature = "serde", serde(rename = "F5"))]
     F5,
     /// F6 key
+    #[cfg_attr(feature = "serde", serde(rename = "F6"))]
     F6,
     /// F7 key
+    #[cfg_attr(feature = "serde", serde(rename = "F7"))]
     F7,
     /// F8 key
+    #[cfg_attr(feature = "serde", serde(rename = "F8"))]
     F8,
     /// F9 key
+    #[cfg_attr(feature = "serde", serde(rename = "F9"))]
     F9,
     /// F10 key
+    #[cfg_attr(feature = "serde", serde(rename = "F10"))]
     F10,
     /// F11 key
+    #[cfg_attr(feature = "serde", serde(rename = "F11"))]
     F11,
     /// F12 key
+    #[cfg_attr(feature = "serde", serde(rename = "F12"))]
     F12,
     /// F13 key
+    #[cfg_attr(feature = "serde", serde(rename = "F13"))]
     F13,
     /// F14 key
+    #[cfg_attr(feature = "serde", serde(rename = "F14"))]
     F14,
     /// F15 key
+    #[cfg_attr(feature = "serde", serde(rename = "F15"))]
     F15,
     /// F16 key
+    #[cfg_attr(feature = "serde", serde(rename = "F16"))]
     F16,
     /// F17 key
+    #[cfg_attr(feature = "serde", serde(rename = "F17"))]
     F17,
     /// F18 key
+    #[cfg_attr(feature = "serde", serde(rename = "F18"))]
     F18,
     /// F19 key
+    #[cfg_attr(feature = "serde", serde(rename = "F19"))]
     F19,
     /// F20 key
+    #[cfg_attr(feature = "serde", serde(rename = "F20"))]
     F20,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
     /// F21 key
+    #[cfg_attr(feature = "serde", serde(rename = "F21"))]
     F21,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
     /// F22 key
+    #[cfg_attr(feature = "serde", serde(rename = "F22"))]
     F22,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
     /// F23 key
+    #[cfg_attr(feature = "serde", serde(rename = "F23"))]
     F23,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
     /// F24 key
+    #[cfg_attr(feature = "serde", serde(rename = "F24"))]
     F24,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F25"))]
     F25,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F26"))]
     F26,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F27"))]
     F27,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F28"))]
     F28,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F29"))]
     F29,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F30"))]
     F30,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F31"))]
     F31,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F32"))]
     F32,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F33"))]
     F33,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F34"))]
     F34,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "F35"))]
     F35,
-    #[cfg(target_os = "macos")]
+    /// fn key. Most keyboards handle it in their firmware, so it cannot be
+    /// simulated on Windows. On Linux, it only has an effect if an application
+    /// listens for the `XF86Fn` keysym
+    #[cfg_attr(feature = "serde", serde(alias = "fn"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Function"))]
     Function,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Final"))]
     Final,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "Find"))]
     Find,
+    /// globe key on macOS (fn key on Linux and Windows)
+    #[cfg_attr(feature = "serde", serde(rename = "Globe"))]
+    Globe,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadA"))]
     GamepadA,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadB"))]
     GamepadB,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadDPadDown"))]
     GamepadDPadDown,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadDPadLeft"))]
     GamepadDPadLeft,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadDPadRight"))]
     GamepadDPadRight,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadDPadUp"))]
     GamepadDPadUp,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadLeftShoulder"))]
     GamepadLeftShoulder,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadLeftThumbstickButton"))]
     GamepadLeftThumbstickButton,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadLeftThumbstickDown"))]
     GamepadLeftThumbstickDown,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadLeftThumbstickLeft"))]
     GamepadLeftThumbstickLeft,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadLeftThumbstickRight"))]
     GamepadLeftThumbstickRight,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadLeftThumbstickUp"))]
     GamepadLeftThumbstickUp,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadLeftTrigger"))]
     GamepadLeftTrigger,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadMenu"))]
     GamepadMenu,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadRightShoulder"))]
     GamepadRightShoulder,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadRightThumbstickButton"))]
     GamepadRightThumbstickButton,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadRightThumbstickDown"))]
     GamepadRightThumbstickDown,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadRightThumbstickLeft"))]
     GamepadRightThumbstickLeft,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadRightThumbstickRight"))]
     GamepadRightThumbstickRight,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadRightThumbstickUp"))]
     GamepadRightThumbstickUp,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadRightTrigger"))]
     GamepadRightTrigger,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadView"))]
     GamepadView,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadX"))]
     GamepadX,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "GamepadY"))]
     GamepadY,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Hangeul"))]
     Hangeul,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Hangul"))]
     Hangul,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Hanja"))]
     Hanja,
+    #[cfg_attr(feature = "serde", serde(rename = "Help"))]
     Help,
     /// home key
+    #[cfg_attr(feature = "serde", serde(rename = "Home"))]
     Home,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Ico00"))]
     Ico00,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "IcoClear"))]
     IcoClear,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "IcoHelp"))]
     IcoHelp,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "IlluminationDown"))]
     IlluminationDown,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "IlluminationUp"))]
     IlluminationUp,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "IlluminationToggle"))]
     IlluminationToggle,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "IMEOff"))]
     IMEOff,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "IMEOn"))]
     IMEOn,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Insert"))]
     Insert,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Junja"))]
     Junja,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Kana"))]
     Kana,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Kanji"))]
     Kanji,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "LaunchApp1"))]
     LaunchApp1,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "LaunchApp2"))]
     LaunchApp2,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "LaunchMail"))]
     LaunchMail,
-    #[cfg(target_os = "windows")]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "LaunchMediaSelect"))]
     LaunchMediaSelect,
     #[cfg(target_os = "macos")]
     /// Opens launchpad
+    #[cfg_attr(feature = "serde", serde(rename = "Launchpad"))]
     Launchpad,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "LaunchPanel"))]
     LaunchPanel,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "LButton"))]
     LButton,
+    #[cfg_attr(feature = "serde", serde(rename = "LControl"))]
     LControl,
     /// left arrow key
+    #[cfg_attr(feature = "serde", serde(rename = "LeftArrow"))]
     LeftArrow,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "Linefeed"))]
     Linefeed,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "LMenu"))]
     LMenu,
+    #[cfg_attr(feature = "serde", serde(rename = "LShift"))]
     LShift,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "LWin"))]
     LWin,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "MButton"))]
     MButton,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "MediaFast"))]
     MediaFast,
+    #[cfg_attr(feature = "serde", serde(rename = "MediaNextTrack"))]
     MediaNextTrack,
+    #[cfg_attr(feature = "serde", serde(rename = "MediaPlayPause"))]
     MediaPlayPause,
+    #[cfg_attr(feature = "serde", serde(rename = "MediaPrevTrack"))]
     MediaPrevTrack,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "MediaRewind"))]
     MediaRewind,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "MediaStop"))]
     MediaStop,
     /// meta key (also known as "windows", "super", and "command")
+    #[cfg_attr(feature = "serde", serde(rename = "Meta"))]
     Meta,
     #[cfg(target_os = "macos")]
     /// Opens mission control
+    #[cfg_attr(feature = "serde", serde(rename = "MissionControl"))]
     MissionControl,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "ModeChange"))]
     ModeChange,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Multiply"))]
     Multiply,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationAccept"))]
     NavigationAccept,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationCancel"))]
     NavigationCancel,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationDown"))]
     NavigationDown,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationLeft"))]
     NavigationLeft,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationMenu"))]
     NavigationMenu,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationRight"))]
     NavigationRight,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationUp"))]
     NavigationUp,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NavigationView"))]
     NavigationView,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NoName"))]
     NoName,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "NonConvert"))]
     NonConvert,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "None"))]
     None,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Numlock"))]
     Numlock,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad0"))]
     Numpad0,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad1"))]
     Numpad1,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad2"))]
     Numpad2,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad3"))]
     Numpad3,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad4"))]
     Numpad4,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad5"))]
     Numpad5,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad6"))]
     Numpad6,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad7"))]
     Numpad7,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad8"))]
     Numpad8,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Numpad9"))]
     Numpad9,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM1"))]
     OEM1,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM102"))]
     OEM102,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM2"))]
     OEM2,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM3"))]
     OEM3,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM4"))]
     OEM4,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM5"))]
     OEM5,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM6"))]
     OEM6,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM7"))]
     OEM7,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEM8"))]
     OEM8,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMAttn"))]
     OEMAttn,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMAuto"))]
     OEMAuto,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMAx"))]
     OEMAx,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMBacktab"))]
     OEMBacktab,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMClear"))]
     OEMClear,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMComma"))]
     OEMComma,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMCopy"))]
     OEMCopy,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMCusel"))]
     OEMCusel,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMEnlw"))]
     OEMEnlw,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMFinish"))]
     OEMFinish,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMFJJisho"))]
     OEMFJJisho,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMFJLoya"))]
     OEMFJLoya,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMFJMasshou"))]
     OEMFJMasshou,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMFJRoya"))]
     OEMFJRoya,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMFJTouroku"))]
     OEMFJTouroku,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMJump"))]
     OEMJump,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMMinus"))]
     OEMMinus,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMNECEqual"))]
     OEMNECEqual,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMPA1"))]
     OEMPA1,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMPA2"))]
     OEMPA2,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMPA3"))]
     OEMPA3,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMPeriod"))]
     OEMPeriod,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMPlus"))]
     OEMPlus,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMReset"))]
     OEMReset,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "OEMWsctrl"))]
     OEMWsctrl,
     /// option key on macOS (alt key on Linux and Windows)
+    #[cfg_attr(feature = "serde", serde(rename = "Option"))]
     Option,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "PA1"))]
     PA1,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Packet"))]
     Packet,
     /// page down key
+    #[cfg_attr(feature = "serde", serde(rename = "PageDown"))]
     PageDown,
     /// page up key
+    #[cfg_attr(feature = "serde", serde(rename = "PageUp"))]
     PageUp,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Pause"))]
     Pause,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Play"))]
     Play,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "Power"))]
     Power,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
     #[deprecated(since = "0.2.2", note = "now renamed to PrintScr")]
+    #[cfg_attr(feature = "serde", serde(rename = "Print"))]
     Print,
     /// Take a screenshot
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
     #[doc(alias = "Print")]
     #[doc(alias = "Snapshot")]
+    #[cfg_attr(feature = "serde", serde(rename = "PrintScr"))]
     PrintScr,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Processkey"))]
     Processkey,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "RButton"))]
     RButton,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "RCommand"))]
     RCommand,
+    #[cfg_attr(feature = "serde", serde(rename = "RControl"))]
     RControl,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "Redo"))]
     Redo,
     /// return key
+    #[cfg_attr(feature = "serde", serde(rename = "Return"))]
     Return,
     /// right arrow key
+    #[cfg_attr(feature = "serde", serde(rename = "RightArrow"))]
     RightArrow,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "RMenu"))]
     RMenu,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "ROption"))]
     ROption,
+    #[cfg_attr(feature = "serde", serde(rename = "RShift"))]
     RShift,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "RWin"))]
     RWin,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Scroll"))]
     Scroll,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "ScrollLock"))]
     ScrollLock,
     #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    #[cfg_attr(feature = "serde", serde(rename = "Select"))]
     Select,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "ScriptSwitch"))]
     ScriptSwitch,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Separator"))]
     Separator,
     /// shift key
+    #[cfg_attr(feature = "serde", serde(rename = "Shift"))]
     Shift,
     #[cfg(all(unix, not(target_os = "macos")))]
     /// Lock shift key
+    #[cfg_attr(feature = "serde", serde(rename = "ShiftLock"))]
     ShiftLock,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Sleep"))]
     Sleep,
     #[cfg(target_os = "windows")]
     #[deprecated(since = "0.2.2", note = "now renamed to PrintScr")]
+    #[cfg_attr(feature = "serde", serde(rename = "Snapshot"))]
     Snapshot,
     /// space key
+    #[cfg_attr(feature = "serde", serde(rename = "Space"))]
     Space,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Subtract"))]
     Subtract,
     #[deprecated(since = "0.0.12", note = "now renamed to Meta")]
     /// super key on linux (command key on macOS, windows key on Windows)
+    #[cfg_attr(feature = "serde", serde(rename = "Super"))]
     Super,
-    #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+    /// system request key (Alt+PrintScr)
+    #[cfg_attr(feature = "serde", serde(rename = "SysReq"))]
     SysReq,
     /// tab key (tabulator)
+    #[cfg_attr(feature = "serde", serde(rename = "Tab"))]
     Tab,
     #[cfg(all(unix, not(target_os = "macos")))]
+    #[cfg_attr(feature = "serde", serde(rename = "Undo"))]
     Undo,
     /// up arrow key
+    #[cfg_attr(feature = "serde", serde(rename = "UpArrow"))]
     UpArrow,
     #[cfg(target_os = "macos")]
+    #[cfg_attr(feature = "serde", serde(rename = "VidMirror"))]
     VidMirror,
+    #[cfg_attr(feature = "serde", serde(rename = "VolumeDown"))]
     VolumeDown,
+    #[cfg_attr(feature = "serde", serde(rename = "VolumeMute"))]
     VolumeMute,
+    #[cfg_attr(feature = "serde", serde(rename = "VolumeUp"))]
     VolumeUp,
     #[cfg(all(unix, not(target_os = "macos")))]
     /// microphone mute toggle on linux
+    #[cfg_attr(feature = "serde", serde(rename = "MicMute"))]
     MicMute,
     #[deprecated(since = "0.0.12", note = "now renamed to Meta")]
     /// windows key on Windows (super key on Linux, command key on macOS)
+    #[cfg_attr(feature = "serde", serde(rename = "Windows"))]
     Windows,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "XButton1"))]
     XButton1,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "XButton2"))]
     XButton2,
     #[cfg(target_os = "windows")]
+    #[cfg_attr(feature = "serde", serde(rename = "Zoom"))]
     Zoom,
     /// Unicode character
     #[doc(alias = "Layout")]
@@ -615,6 +917,7 @@ pub enum Key {
     #[cfg_attr(feature = "serde", serde(alias = "Uni"))]
     #[cfg_attr(feature = "serde", serde(alias = "Char"))]
     #[cfg_attr(feature = "serde", serde(alias = "char"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Unicode"))]
     Unicode(char),
     /// Use this for keys that are not listed here that you know the
     /// value of. Let us know if you think the key should be listed so
@@ -622,9 +925,882 @@ pub enum Key {
     /// On Linux, this will result in a keysym,
     /// On Windows, this will result in a `Virtual_Key` and
     /// On macOS, this will yield a `KeyCode`
+    #[cfg_attr(feature = "serde", serde(rename = "Other"))]
     Other(u32),
 }
 
+/// Platforms on which a [`Key`] is available
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Platforms {
+    /// The key is available on Linux (and other Unix platforms except macOS)
+    pub linux: bool,
+    /// The key is available on Windows
+    pub windows: bool,
+    /// The key is available on macOS
+    pub macos: bool,
+}
+
+impl Platforms {
+    /// All platforms
+    pub const ALL: Self = Self {
+        linux: true,
+        windows: true,
+        macos: true,
+    };
+    /// Only Linux
+    pub const LINUX: Self = Self {
+        linux: true,
+        windows: false,
+        macos: false,
+    };
+    /// Only Windows
+    pub const WINDOWS: Self = Self {
+        linux: false,
+        windows: true,
+        macos: false,
+    };
+    /// Only macOS
+    pub const MACOS: Self = Self {
+        linux: false,
+        windows: false,
+        macos: true,
+    };
+    /// Windows and Linux
+    pub const WINDOWS_LINUX: Self = Self {
+        linux: true,
+        windows: true,
+        macos: false,
+    };
+}
+
+impl Key {
+    /// All keys that are available on the current platform. [`Key::Unicode`],
+    /// [`Key::Other`] and the deprecated keys are not included
+    pub const ALL: &'static [Key] = &[
+        #[cfg(target_os = "windows")]
+        Key::Num0,
+        #[cfg(target_os = "windows")]
+        Key::Num1,
+        #[cfg(target_os = "windows")]
+        Key::Num2,
+        #[cfg(target_os = "windows")]
+        Key::Num3,
+        #[cfg(target_os = "windows")]
+        Key::Num4,
+        #[cfg(target_os = "windows")]
+        Key::Num5,
+        #[cfg(target_os = "windows")]
+        Key::Num6,
+        #[cfg(target_os = "windows")]
+        Key::Num7,
+        #[cfg(target_os = "windows")]
+        Key::Num8,
+        #[cfg(target_os = "windows")]
+        Key::Num9,
+        #[cfg(target_os = "windows")]
+        Key::A,
+        #[cfg(target_os = "windows")]
+        Key::B,
+        #[cfg(target_os = "windows")]
+        Key::C,
+        #[cfg(target_os = "windows")]
+        Key::D,
+        #[cfg(target_os = "windows")]
+        Key::E,
+        #[cfg(target_os = "windows")]
+        Key::F,
+        #[cfg(target_os = "windows")]
+        Key::G,
+        #[cfg(target_os = "windows")]
+        Key::H,
+        #[cfg(target_os = "windows")]
+        Key::I,
+        #[cfg(target_os = "windows")]
+        Key::J,
+        #[cfg(target_os = "windows")]
+        Key::K,
+        #[cfg(target_os = "windows")]
+        Key::L,
+        #[cfg(target_os = "windows")]
+        Key::M,
+        #[cfg(target_os = "windows")]
+        Key::N,
+        #[cfg(target_os = "windows")]
+        Key::O,
+        #[cfg(target_os = "windows")]
+        Key::P,
+        #[cfg(target_os = "windows")]
+        Key::Q,
+        #[cfg(target_os = "windows")]
+        Key::R,
+        #[cfg(target_os = "windows")]
+        Key::S,
+        #[cfg(target_os = "windows")]
+        Key::T,
+        #[cfg(target_os = "windows")]
+        Key::U,
+        #[cfg(target_os = "windows")]
+        Key::V,
+        #[cfg(target_os = "windows")]
+        Key::W,
+        #[cfg(target_os = "windows")]
+        Key::X,
+        #[cfg(target_os = "windows")]
+        Key::Y,
+        #[cfg(target_os = "windows")]
+        Key::Z,
+        #[cfg(target_os = "windows")]
+        Key::AbntC1,
+        #[cfg(target_os = "windows")]
+        Key::AbntC2,
+        #[cfg(target_os = "windows")]
+        Key::Accept,
+        #[cfg(target_os = "windows")]
+        Key::Add,
+        Key::Alt,
+        #[cfg(target_os = "windows")]
+        Key::Apps,
+        #[cfg(target_os = "windows")]
+        Key::Attn,
+        Key::Backspace,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Break,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::Begin,
+        #[cfg(target_os = "macos")]
+        Key::BrightnessDown,
+        #[cfg(target_os = "macos")]
+        Key::BrightnessUp,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::BrowserBack,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::BrowserFavorites,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::BrowserForward,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::BrowserHome,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::BrowserRefresh,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::BrowserSearch,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::BrowserStop,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Cancel,
+        Key::CapsLock,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Clear,
+        #[cfg(target_os = "macos")]
+        Key::ContrastUp,
+        #[cfg(target_os = "macos")]
+        Key::ContrastDown,
+        Key::Control,
+        #[cfg(target_os = "windows")]
+        Key::Convert,
+        #[cfg(target_os = "windows")]
+        Key::Crsel,
+        #[cfg(target_os = "windows")]
+        Key::DBEAlphanumeric,
+        #[cfg(target_os = "windows")]
+        Key::DBECodeinput,
+        #[cfg(target_os = "windows")]
+        Key::DBEDetermineString,
+        #[cfg(target_os = "windows")]
+        Key::DBEEnterDLGConversionMode,
+        #[cfg(target_os = "windows")]
+        Key::DBEEnterIMEConfigMode,
+        #[cfg(target_os = "windows")]
+        Key::DBEEnterWordRegisterMode,
+        #[cfg(target_os = "windows")]
+        Key::DBEFlushString,
+        #[cfg(target_os = "windows")]
+        Key::DBEHiragana,
+        #[cfg(target_os = "windows")]
+        Key::DBEKatakana,
+        #[cfg(target_os = "windows")]
+        Key::DBENoCodepoint,
+        #[cfg(target_os = "windows")]
+        Key::DBENoRoman,
+        #[cfg(target_os = "windows")]
+        Key::DBERoman,
+        #[cfg(target_os = "windows")]
+        Key::DBESBCSChar,
+        #[cfg(target_os = "windows")]
+        Key::DBESChar,
+        #[cfg(target_os = "windows")]
+        Key::Decimal,
+        Key::Delete,
+        #[cfg(target_os = "windows")]
+        Key::Divide,
+        Key::DownArrow,
+        #[cfg(target_os = "macos")]
+        Key::Eject,
+        Key::End,
+        #[cfg(target_os = "windows")]
+        Key::Ereof,
+        Key::Escape,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Execute,
+        #[cfg(target_os = "windows")]
+        Key::Exsel,
+        Key::F1,
+        Key::F2,
+        Key::F3,
+        Key::F4,
+        Key::F5,
+        Key::F6,
+        Key::F7,
+        Key::F8,
+        Key::F9,
+        Key::F10,
+        Key::F11,
+        Key::F12,
+        Key::F13,
+        Key::F14,
+        Key::F15,
+        Key::F16,
+        Key::F17,
+        Key::F18,
+        Key::F19,
+        Key::F20,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::F21,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::F22,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::F23,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::F24,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F25,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F26,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F27,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F28,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F29,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F30,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F31,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F32,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F33,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F34,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::F35,
+        Key::Function,
+        #[cfg(target_os = "windows")]
+        Key::Final,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::Find,
+        Key::Globe,
+        #[cfg(target_os = "windows")]
+        Key::GamepadA,
+        #[cfg(target_os = "windows")]
+        Key::GamepadB,
+        #[cfg(target_os = "windows")]
+        Key::GamepadDPadDown,
+        #[cfg(target_os = "windows")]
+        Key::GamepadDPadLeft,
+        #[cfg(target_os = "windows")]
+        Key::GamepadDPadRight,
+        #[cfg(target_os = "windows")]
+        Key::GamepadDPadUp,
+        #[cfg(target_os = "windows")]
+        Key::GamepadLeftShoulder,
+        #[cfg(target_os = "windows")]
+        Key::GamepadLeftThumbstickButton,
+        #[cfg(target_os = "windows")]
+        Key::GamepadLeftThumbstickDown,
+        #[cfg(target_os = "windows")]
+        Key::GamepadLeftThumbstickLeft,
+        #[cfg(target_os = "windows")]
+        Key::GamepadLeftThumbstickRight,
+        #[cfg(target_os = "windows")]
+        Key::GamepadLeftThumbstickUp,
+        #[cfg(target_os = "windows")]
+        Key::GamepadLeftTrigger,
+        #[cfg(target_os = "windows")]
+        Key::GamepadMenu,
+        #[cfg(target_os = "windows")]
+        Key::GamepadRightShoulder,
+        #[cfg(target_os = "windows")]
+        Key::GamepadRightThumbstickButton,
+        #[cfg(target_os = "windows")]
+        Key::GamepadRightThumbstickDown,
+        #[cfg(target_os = "windows")]
+        Key::GamepadRightThumbstickLeft,
+        #[cfg(target_os = "windows")]
+        Key::GamepadRightThumbstickRight,
+        #[cfg(target_os = "windows")]
+        Key::GamepadRightThumbstickUp,
+        #[cfg(target_os = "windows")]
+        Key::GamepadRightTrigger,
+        #[cfg(target_os = "windows")]
+        Key::GamepadView,
+        #[cfg(target_os = "windows")]
+        Key::GamepadX,
+        #[cfg(target_os = "windows")]
+        Key::GamepadY,
+        #[cfg(target_os = "windows")]
+        Key::Hangeul,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Hangul,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Hanja,
+        Key::Help,
+        Key::Home,
+        #[cfg(target_os = "windows")]
+        Key::Ico00,
+        #[cfg(target_os = "windows")]
+        Key::IcoClear,
+        #[cfg(target_os = "windows")]
+        Key::IcoHelp,
+        #[cfg(target_os = "macos")]
+        Key::IlluminationDown,
+        #[cfg(target_os = "macos")]
+        Key::IlluminationUp,
+        #[cfg(target_os = "macos")]
+        Key::IlluminationToggle,
+        #[cfg(target_os = "windows")]
+        Key::IMEOff,
+        #[cfg(target_os = "windows")]
+        Key::IMEOn,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Insert,
+        #[cfg(target_os = "windows")]
+        Key::Junja,
+        #[cfg(target_os = "windows")]
+        Key::Kana,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Kanji,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::LaunchApp1,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::LaunchApp2,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::LaunchMail,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::LaunchMediaSelect,
+        #[cfg(target_os = "macos")]
+        Key::Launchpad,
+        #[cfg(target_os = "macos")]
+        Key::LaunchPanel,
+        #[cfg(target_os = "windows")]
+        Key::LButton,
+        Key::LControl,
+        Key::LeftArrow,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::Linefeed,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::LMenu,
+        Key::LShift,
+        #[cfg(target_os = "windows")]
+        Key::LWin,
+        #[cfg(target_os = "windows")]
+        Key::MButton,
+        #[cfg(target_os = "macos")]
+        Key::MediaFast,
+        Key::MediaNextTrack,
+        Key::MediaPlayPause,
+        Key::MediaPrevTrack,
+        #[cfg(target_os = "macos")]
+        Key::MediaRewind,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::MediaStop,
+        Key::Meta,
+        #[cfg(target_os = "macos")]
+        Key::MissionControl,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::ModeChange,
+        #[cfg(target_os = "windows")]
+        Key::Multiply,
+        #[cfg(target_os = "windows")]
+        Key::NavigationAccept,
+        #[cfg(target_os = "windows")]
+        Key::NavigationCancel,
+        #[cfg(target_os = "windows")]
+        Key::NavigationDown,
+        #[cfg(target_os = "windows")]
+        Key::NavigationLeft,
+        #[cfg(target_os = "windows")]
+        Key::NavigationMenu,
+        #[cfg(target_os = "windows")]
+        Key::NavigationRight,
+        #[cfg(target_os = "windows")]
+        Key::NavigationUp,
+        #[cfg(target_os = "windows")]
+        Key::NavigationView,
+        #[cfg(target_os = "windows")]
+        Key::NoName,
+        #[cfg(target_os = "windows")]
+        Key::NonConvert,
+        #[cfg(target_os = "windows")]
+        Key::None,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Numlock,
+        #[cfg(target_os = "windows")]
+        Key::Numpad0,
+        #[cfg(target_os = "windows")]
+        Key::Numpad1,
+        #[cfg(target_os = "windows")]
+        Key::Numpad2,
+        #[cfg(target_os = "windows")]
+        Key::Numpad3,
+        #[cfg(target_os = "windows")]
+        Key::Numpad4,
+        #[cfg(target_os = "windows")]
+        Key::Numpad5,
+        #[cfg(target_os = "windows")]
+        Key::Numpad6,
+        #[cfg(target_os = "windows")]
+        Key::Numpad7,
+        #[cfg(target_os = "windows")]
+        Key::Numpad8,
+        #[cfg(target_os = "windows")]
+        Key::Numpad9,
+        #[cfg(target_os = "windows")]
+        Key::OEM1,
+        #[cfg(target_os = "windows")]
+        Key::OEM102,
+        #[cfg(target_os = "windows")]
+        Key::OEM2,
+        #[cfg(target_os = "windows")]
+        Key::OEM3,
+        #[cfg(target_os = "windows")]
+        Key::OEM4,
+        #[cfg(target_os = "windows")]
+        Key::OEM5,
+        #[cfg(target_os = "windows")]
+        Key::OEM6,
+        #[cfg(target_os = "windows")]
+        Key::OEM7,
+        #[cfg(target_os = "windows")]
+        Key::OEM8,
+        #[cfg(target_os = "windows")]
+        Key::OEMAttn,
+        #[cfg(target_os = "windows")]
+        Key::OEMAuto,
+        #[cfg(target_os = "windows")]
+        Key::OEMAx,
+        #[cfg(target_os = "windows")]
+        Key::OEMBacktab,
+        #[cfg(target_os = "windows")]
+        Key::OEMClear,
+        #[cfg(target_os = "windows")]
+        Key::OEMComma,
+        #[cfg(target_os = "windows")]
+        Key::OEMCopy,
+        #[cfg(target_os = "windows")]
+        Key::OEMCusel,
+        #[cfg(target_os = "windows")]
+        Key::OEMEnlw,
+        #[cfg(target_os = "windows")]
+        Key::OEMFinish,
+        #[cfg(target_os = "windows")]
+        Key::OEMFJJisho,
+        #[cfg(target_os = "windows")]
+        Key::OEMFJLoya,
+        #[cfg(target_os = "windows")]
+        Key::OEMFJMasshou,
+        #[cfg(target_os = "windows")]
+        Key::OEMFJRoya,
+        #[cfg(target_os = "windows")]
+        Key::OEMFJTouroku,
+        #[cfg(target_os = "windows")]
+        Key::OEMJump,
+        #[cfg(target_os = "windows")]
+        Key::OEMMinus,
+        #[cfg(target_os = "windows")]
+        Key::OEMNECEqual,
+        #[cfg(target_os = "windows")]
+        Key::OEMPA1,
+        #[cfg(target_os = "windows")]
+        Key::OEMPA2,
+        #[cfg(target_os = "windows")]
+        Key::OEMPA3,
+        #[cfg(target_os = "windows")]
+        Key::OEMPeriod,
+        #[cfg(target_os = "windows")]
+        Key::OEMPlus,
+        #[cfg(target_os = "windows")]
+        Key::OEMReset,
+        #[cfg(target_os = "windows")]
+        Key::OEMWsctrl,
+        Key::Option,
+        #[cfg(target_os = "windows")]
+        Key::PA1,
+        #[cfg(target_os = "windows")]
+        Key::Packet,
+        Key::PageDown,
+        Key::PageUp,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Pause,
+        #[cfg(target_os = "windows")]
+        Key::Play,
+        #[cfg(target_os = "macos")]
+        Key::Power,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::PrintScr,
+        #[cfg(target_os = "windows")]
+        Key::Processkey,
+        #[cfg(target_os = "windows")]
+        Key::RButton,
+        #[cfg(target_os = "macos")]
+        Key::RCommand,
+        Key::RControl,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::Redo,
+        Key::Return,
+        Key::RightArrow,
+        #[cfg(target_os = "windows")]
+        Key::RMenu,
+        #[cfg(target_os = "macos")]
+        Key::ROption,
+        Key::RShift,
+        #[cfg(target_os = "windows")]
+        Key::RWin,
+        #[cfg(target_os = "windows")]
+        Key::Scroll,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::ScrollLock,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Select,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::ScriptSwitch,
+        #[cfg(target_os = "windows")]
+        Key::Separator,
+        Key::Shift,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::ShiftLock,
+        #[cfg(target_os = "windows")]
+        Key::Sleep,
+        Key::Space,
+        #[cfg(target_os = "windows")]
+        Key::Subtract,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::SysReq,
+        Key::Tab,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::Undo,
+        Key::UpArrow,
+        #[cfg(target_os = "macos")]
+        Key::VidMirror,
+        Key::VolumeDown,
+        Key::VolumeMute,
+        Key::VolumeUp,
+        #[cfg(all(unix, not(target_os = "macos")))]
+        Key::MicMute,
+        #[cfg(target_os = "windows")]
+        Key::XButton1,
+        #[cfg(target_os = "windows")]
+        Key::XButton2,
+        #[cfg(target_os = "windows")]
+        Key::Zoom,
+    ];
+
+    /// Returns true if the key is a modifier that can be latched or locked
+    /// (e.g. Shift or Control)
+    #[must_use]
+    pub fn is_modifier(self) -> bool {
+        match self {
+            Key::Shift
+            | Key::LShift
+            | Key::RShift
+            | Key::Control
+            | Key::LControl
+            | Key::RControl
+            | Key::Alt
+            | Key::Option
+            | Key::Meta
+            | Key::Super
+            | Key::Command
+            | Key::Windows => true,
+            #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+            Key::LMenu => true,
+            #[cfg(target_os = "windows")]
+            Key::LWin | Key::RWin | Key::RMenu => true,
+            #[cfg(target_os = "macos")]
+            Key::RCommand | Key::ROption => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the key toggles a lock (e.g. Caps Lock or Num Lock)
+    #[must_use]
+    pub fn is_lock(self) -> bool {
+        match self {
+            Key::CapsLock => true,
+            #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+            Key::Numlock => true,
+            #[cfg(target_os = "windows")]
+            Key::Scroll => true,
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Key::ScrollLock | Key::ShiftLock => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the platforms on which the key is available. Keys that are not
+    /// available on all platforms are only defined when compiling for one of
+    /// their platforms
+    #[must_use]
+    #[allow(clippy::too_many_lines)]
+    pub fn platforms(self) -> Platforms {
+        match self {
+            #[cfg(target_os = "windows")]
+            Key::Num0
+            | Key::Num1
+            | Key::Num2
+            | Key::Num3
+            | Key::Num4
+            | Key::Num5
+            | Key::Num6
+            | Key::Num7
+            | Key::Num8
+            | Key::Num9
+            | Key::A
+            | Key::B
+            | Key::C
+            | Key::D
+            | Key::E
+            | Key::F
+            | Key::G
+            | Key::H
+            | Key::I
+            | Key::J
+            | Key::K
+            | Key::L
+            | Key::M
+            | Key::N
+            | Key::O
+            | Key::P
+            | Key::Q
+            | Key::R
+            | Key::S
+            | Key::T
+            | Key::U
+            | Key::V
+            | Key::W
+            | Key::X
+            | Key::Y
+            | Key::Z
+            | Key::AbntC1
+            | Key::AbntC2
+            | Key::Accept
+            | Key::Add
+            | Key::Apps
+            | Key::Attn
+            | Key::Convert
+            | Key::Crsel
+            | Key::DBEAlphanumeric
+            | Key::DBECodeinput
+            | Key::DBEDetermineString
+            | Key::DBEEnterDLGConversionMode
+            | Key::DBEEnterIMEConfigMode
+            | Key::DBEEnterWordRegisterMode
+            | Key::DBEFlushString
+            | Key::DBEHiragana
+            | Key::DBEKatakana
+            | Key::DBENoCodepoint
+            | Key::DBENoRoman
+            | Key::DBERoman
+            | Key::DBESBCSChar
+            | Key::DBESChar
+            | Key::Decimal
+            | Key::Divide
+            | Key::Ereof
+            | Key::Exsel
+            | Key::Final
+            | Key::GamepadA
+            | Key::GamepadB
+            | Key::GamepadDPadDown
+            | Key::GamepadDPadLeft
+            | Key::GamepadDPadRight
+            | Key::GamepadDPadUp
+            | Key::GamepadLeftShoulder
+            | Key::GamepadLeftThumbstickButton
+            | Key::GamepadLeftThumbstickDown
+            | Key::GamepadLeftThumbstickLeft
+            | Key::GamepadLeftThumbstickRight
+            | Key::GamepadLeftThumbstickUp
+            | Key::GamepadLeftTrigger
+            | Key::GamepadMenu
+            | Key::GamepadRightShoulder
+            | Key::GamepadRightThumbstickButton
+            | Key::GamepadRightThumbstickDown
+            | Key::GamepadRightThumbstickLeft
+            | Key::GamepadRightThumbstickRight
+            | Key::GamepadRightThumbstickUp
+            | Key::GamepadRightTrigger
+            | Key::GamepadView
+            | Key::GamepadX
+            | Key::GamepadY
+            | Key::Hangeul
+            | Key::Ico00
+            | Key::IcoClear
+            | Key::IcoHelp
+            | Key::IMEOff
+            | Key::IMEOn
+            | Key::Junja
+            | Key::Kana
+            | Key::LButton
+            | Key::LWin
+            | Key::MButton
+            | Key::Multiply
+            | Key::NavigationAccept
+            | Key::NavigationCancel
+            | Key::NavigationDown
+            | Key::NavigationLeft
+            | Key::NavigationMenu
+            | Key::NavigationRight
+            | Key::NavigationUp
+            | Key::NavigationView
+            | Key::NoName
+            | Key::NonConvert
+            | Key::None
+            | Key::Numpad0
+            | Key::Numpad1
+            | Key::Numpad2
+            | Key::Numpad3
+            | Key::Numpad4
+            | Key::Numpad5
+            | Key::Numpad6
+            | Key::Numpad7
+            | Key::Numpad8
+            | Key::Numpad9
+            | Key::OEM1
+            | Key::OEM102
+            | Key::OEM2
+            | Key::OEM3
+            | Key::OEM4
+            | Key::OEM5
+            | Key::OEM6
+            | Key::OEM7
+            | Key::OEM8
+            | Key::OEMAttn
+            | Key::OEMAuto
+            | Key::OEMAx
+            | Key::OEMBacktab
+            | Key::OEMClear
+            | Key::OEMComma
+            | Key::OEMCopy
+            | Key::OEMCusel
+            | Key::OEMEnlw
+            | Key::OEMFinish
+            | Key::OEMFJJisho
+            | Key::OEMFJLoya
+            | Key::OEMFJMasshou
+            | Key::OEMFJRoya
+            | Key::OEMFJTouroku
+            | Key::OEMJump
+            | Key::OEMMinus
+            | Key::OEMNECEqual
+            | Key::OEMPA1
+            | Key::OEMPA2
+            | Key::OEMPA3
+            | Key::OEMPeriod
+            | Key::OEMPlus
+            | Key::OEMReset
+            | Key::OEMWsctrl
+            | Key::PA1
+            | Key::Packet
+            | Key::Play
+            | Key::Processkey
+            | Key::RButton
+            | Key::RMenu
+            | Key::RWin
+            | Key::Scroll
+            | Key::Separator
+            | Key::Sleep
+            | Key::Snapshot
+            | Key::Subtract
+            | Key::XButton1
+            | Key::XButton2
+            | Key::Zoom => Platforms::WINDOWS,
+            #[cfg(target_os = "macos")]
+            Key::BrightnessDown
+            | Key::BrightnessUp
+            | Key::ContrastUp
+            | Key::ContrastDown
+            | Key::Eject
+            | Key::IlluminationDown
+            | Key::IlluminationUp
+            | Key::IlluminationToggle
+            | Key::Launchpad
+            | Key::LaunchPanel
+            | Key::MediaFast
+            | Key::MediaRewind
+            | Key::MissionControl
+            | Key::Power
+            | Key::RCommand
+            | Key::ROption
+            | Key::VidMirror => Platforms::MACOS,
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Key::Begin
+            | Key::F25
+            | Key::F26
+            | Key::F27
+            | Key::F28
+            | Key::F29
+            | Key::F30
+            | Key::F31
+            | Key::F32
+            | Key::F33
+            | Key::F34
+            | Key::F35
+            | Key::Find
+            | Key::Linefeed
+            | Key::Redo
+            | Key::ScrollLock
+            | Key::ScriptSwitch
+            | Key::ShiftLock
+            | Key::Undo
+            | Key::MicMute => Platforms::LINUX,
+            #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+            Key::Break
+            | Key::BrowserBack
+            | Key::BrowserFavorites
+            | Key::BrowserForward
+            | Key::BrowserHome
+            | Key::BrowserRefresh
+            | Key::BrowserSearch
+            | Key::BrowserStop
+            | Key::Cancel
+            | Key::Clear
+            | Key::Execute
+            | Key::F21
+            | Key::F22
+            | Key::F23
+            | Key::F24
+            | Key::Hangul
+            | Key::Hanja
+            | Key::Insert
+            | Key::Kanji
+            | Key::LaunchApp1
+            | Key::LaunchApp2
+            | Key::LaunchMail
+            | Key::LaunchMediaSelect
+            | Key::LMenu
+            | Key::MediaStop
+            | Key::ModeChange
+            | Key::Numlock
+            | Key::Pause
+            | Key::Print
+            | Key::PrintScr
+            | Key::Select
+            | Key::SysReq => Platforms::WINDOWS_LINUX,
+            _ => Platforms::ALL,
+        }
+    }
+}
+
 #[cfg(all(unix, not(target_os = "macos")))]
 /// Converts a Key to a Keysym
 impl From<Key> for xkeysym::Keysym {
@@ -640,6 +1816,13 @@ impl From<Key> for xkeysym::Keysym {
             Key::Alt | Key::Option => Keysym::Alt_L,
             Key::Backspace => Keysym::BackSpace,
             Key::Begin => Keysym::Begin,
+            Key::BrowserBack => Keysym::XF86_Back,
+            Key::BrowserFavorites => Keysym::XF86_Favorites,
+            Key::BrowserForward => Keysym::XF86_Forward,
+            Key::BrowserHome => Keysym::XF86_HomePage,
+            Key::BrowserRefresh => Keysym::XF86_Refresh,
+            Key::BrowserSearch => Keysym::XF86_Search,
+            Key::BrowserStop => Keysym::XF86_Stop,
             Key::Break => Keysym::Break,
             Key::Cancel => Keysym::Cancel,
             Key::CapsLock => Keysym::Caps_Lock,
@@ -686,12 +1869,17 @@ impl From<Key> for xkeysym::Keysym {
             Key::F34 => Keysym::F34,
             Key::F35 => Keysym::F35,
             Key::Find => Keysym::Find,
+            Key::Function | Key::Globe => Keysym::XF86_Fn,
             Key::Hangul => Keysym::Hangul,
             Key::Hanja => Keysym::Hangul_Hanja,
             Key::Help => Keysym::Help,
             Key::Home => Keysym::Home,
             Key::Insert => Keysym::Insert,
             Key::Kanji => Keysym::Kanji,
+            Key::LaunchApp1 => Keysym::XF86_MyComputer,
+            Key::LaunchApp2 => Keysym::XF86_Calculator,
+            Key::LaunchMail => Keysym::XF86_Mail,
+            Key::LaunchMediaSelect => Keysym::XF86_AudioMedia,
             Key::LeftArrow => Keysym::Left,
             Key::Linefeed => Keysym::Linefeed,
             Key::LMenu => Keysym::Menu,
@@ -835,7 +2023,7 @@ impl TryFrom<Key> for windows::Win32::UI::Input::KeyboardAndMouse::VIRTUAL_KEY {
             Key::BrowserRefresh => VK_BROWSER_REFRESH,
             Key::BrowserSearch => VK_BROWSER_SEARCH,
             Key::BrowserStop => VK_BROWSER_STOP,
-            Key::Cancel => VK_CANCEL,
+            Key::Cancel | Key::Break => VK_CANCEL,
             Key::CapsLock => VK_CAPITAL,
             Key::Clear => VK_CLEAR,
             Key::Control => VK_CONTROL,
@@ -1006,7 +2194,7 @@ impl TryFrom<Key> for windows::Win32::UI::Input::KeyboardAndMouse::VIRTUAL_KEY {
             Key::Pause => VK_PAUSE,
             Key::Play => VK_PLAY,
             Key::Print => VK_PRINT,
-            Key::PrintScr | Key::Snapshot => VK_SNAPSHOT,
+            Key::PrintScr | Key::Snapshot | Key::SysReq => VK_SNAPSHOT,
             Key::Processkey => VK_PROCESSKEY,
             Key::RButton => VK_RBUTTON,
             Key::RControl => VK_RCONTROL,
@@ -1077,6 +2265,9 @@ impl TryFrom<Key> for windows::Win32::UI::Input::KeyboardAndMouse::VIRTUAL_KEY {
                 VIRTUAL_KEY(v)
             }
             Key::Super | Key::Command | Key::Windows | Key::Meta | Key::LWin => VK_LWIN,
+            Key::Function | Key::Globe => {
+                return Err("the fn key is handled by the keyboard and cannot be simulated");
+            }
         };
 
         trace!("virtual key: {vk:?})");
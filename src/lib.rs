@@ -19,6 +19,24 @@
 //!
 //! This crate previously included a simple DSL. This is no longer the case. In order to simplify the codebase and also allow serializing objects, you can now serialize and deserialize most enums and structs of this crate. You can use this instead of the DSL. This feature is hidden behind the `serde` feature. Have a look at the `serde` example to see how to use it to serialize Tokens in the [RON](https://crates.io/crates/ron) format.
 
+//! # Serialization compatibility
+//! The serialized names of the variants of [`agent::Token`], [`Key`],
+//! [`Button`], [`Direction`], [`Axis`] and [`Coordinate`] are pinned with
+//! explicit tags and do not follow renames in the Rust code. Within a major
+//! version, a tag is never removed or changed. If a variant gets a new name,
+//! the old tag keeps being accepted as an alias when deserializing. New
+//! variants and new aliases can be added in minor versions, so clients should
+//! not reject unknown tags they receive. The index of a variant is not part of
+//! the guarantee, so use a self-describing format like JSON or RON instead of
+//! formats that encode the variant index (e.g. bincode) if the payloads are
+//! exchanged between different versions of Enigo. With the `schema` feature,
+//! the `schema` module exports a JSON Schema of the tokens to validate
+//! payloads in non-Rust clients.
+//!
+//! The `arbitrary` feature implements [`Arbitrary`](https://docs.rs/arbitrary)
+//! for the tokens and the types they contain. Use it to property-test or fuzz
+//! your serializers, executors and middleware with generated tokens.
+//!
 //! # Examples
 //! ```no_run
 //! use enigo::{
@@ -46,19 +64,84 @@
 #![allow(clippy::cast_possible_wrap)]
 #![allow(clippy::cast_sign_loss)]
 #![allow(deprecated)]
+// The helpers of the platform implementations are unused by the stub
+#![cfg_attr(not(any(unix, target_os = "windows")), allow(dead_code, unused_macros))]
 
 use std::{
     error::Error,
     fmt::{self, Display, Formatter},
+    time::Duration,
 };
 
-use log::{debug, error};
+use log::{debug, error, warn};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 use strum_macros::EnumIter;
 
+/// Log the call of a function that simulates input and run it. With the
+/// `tracing` feature, it runs in a span instead that has the backend, the
+/// arguments and the result as fields. Afterwards the metrics hook of the
+/// Enigo struct gets notified
+macro_rules! traced {
+    ($fn:literal, $enigo:ident, [$($field:ident),*], $body:expr) => {{
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            $fn,
+            backend = %$enigo.backend(),
+            $($field = ?$field,)*
+            result = tracing::field::Empty,
+        )
+        .entered();
+        #[cfg(not(feature = "tracing"))]
+        if log::log_enabled!(log::Level::Debug) {
+            let args: &[String] = &[$(format!(concat!(stringify!($field), ": {:?}"), $field)),*];
+            debug!("\x1b[93m{}({})\x1b[0m", $fn, args.join(", "));
+        }
+        let start = std::time::Instant::now();
+        let res = crate::record_result($body);
+        if let Some(hook) = $enigo.metrics_hook.as_ref() {
+            let backend = $enigo.backend();
+            hook.record(&crate::metrics::InputMetric {
+                function: $fn,
+                backend: AsRef::<str>::as_ref(&backend),
+                latency: start.elapsed(),
+                error: res.as_ref().err(),
+            });
+        }
+        res
+    }};
+}
+
+/// Same as `traced!` but the call counts towards the maximum number of events
+/// per second of the Enigo struct
+macro_rules! throttled {
+    ($fn:literal, $enigo:ident, [$($field:ident),*], $body:expr) => {
+        traced!($fn, $enigo, [$($field),*], || {
+            if let Some(throttle) = $enigo.throttle.as_mut() {
+                throttle.acquire()?;
+            }
+            ($body)()
+        })
+    };
+}
+
+/// Run the function and record its result in the current span
+fn record_result<T>(f: impl FnOnce() -> InputResult<T>) -> InputResult<T> {
+    let res = f();
+    #[cfg(feature = "tracing")]
+    match &res {
+        Ok(_) => {
+            tracing::Span::current().record("result", "ok");
+        }
+        Err(e) => {
+            tracing::Span::current().record("result", tracing::field::display(e));
+        }
+    }
+    res
+}
+
 /// This crate contains the [`crate::agent::Token`] struct and the
 /// [`crate::agent::Agent`] trait. A token is an instruction for the [`Enigo`]
 /// struct to do something. If you want Enigo to simulate input, you then have
@@ -67,20 +150,81 @@ use strum_macros::EnumIter;
 /// works.
 pub mod agent;
 
+pub mod clock;
+
+pub mod diagnostics;
+
+#[cfg(feature = "dsl")]
+pub mod dsl;
+
+pub mod health;
+
+pub mod keep_awake;
+
+pub mod metrics;
+
+pub mod system;
+
+pub mod test_keyboard;
+
+#[cfg(feature = "a11y")]
+pub mod a11y;
+
+#[cfg(feature = "schema")]
+pub mod schema;
+
+#[cfg(feature = "test-harness")]
+pub mod test_harness;
+
+#[cfg(all(feature = "testing", unix, not(target_os = "macos")))]
+pub mod testing;
+
+#[cfg(feature = "webdriver")]
+pub mod webdriver;
+
+mod cleanup;
+
+mod focus;
+
+mod throttle;
+
+pub mod middleware;
+
+mod handle;
+pub use handle::EnigoHandle;
+
+mod cancel;
+pub use cancel::{CancellationToken, PartialTextError};
+
 #[cfg_attr(all(unix, not(target_os = "macos")), path = "linux/mod.rs")]
 #[cfg_attr(target_os = "macos", path = "macos/mod.rs")]
 #[cfg_attr(target_os = "windows", path = "win/mod.rs")]
-mod platform;
-pub use platform::Enigo;
+#[cfg_attr(not(any(unix, target_os = "windows")), path = "stub/mod.rs")]
+mod os;
+pub use os::Enigo;
 
 #[cfg(target_os = "windows")]
-pub use platform::set_dpi_awareness;
+pub use os::set_dpi_awareness;
+#[cfg(all(unix, not(target_os = "macos")))]
+pub use os::xkb_keymap as keymap;
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub use os::Tagged;
+#[cfg(target_os = "windows")]
+pub use os::EXT;
 #[cfg(target_os = "windows")]
-pub use platform::EXT;
+pub use os::{ForegroundTarget, MouseAcceleration};
+
+pub mod platform;
 
 mod keycodes;
 /// Contains the available keycodes
-pub use keycodes::Key;
+pub use keycodes::{Key, Platforms};
+
+pub mod scancode;
+pub use scancode::Scancode;
+
+pub mod settings;
+pub use settings::{SettingsBuilder, SettingsError};
 
 /// Arbitrary value to be able to distinguish events created by enigo
 pub const EVENT_MARKER: u32 = 100;
@@ -93,6 +237,7 @@ pub const EVENT_MARKER: u32 = 100;
 // struct that stores the nth click for each Button
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(test, derive(EnumIter))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[doc(alias = "MouseButton")]
 pub enum Button {
@@ -100,44 +245,53 @@ pub enum Button {
     #[cfg_attr(feature = "serde", serde(alias = "L"))]
     #[cfg_attr(feature = "serde", serde(alias = "l"))]
     #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "Left"))]
     Left,
     /// Middle mouse button
     #[cfg_attr(feature = "serde", serde(alias = "M"))]
     #[cfg_attr(feature = "serde", serde(alias = "m"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Middle"))]
     Middle,
     /// Right mouse button
     #[cfg_attr(feature = "serde", serde(alias = "R"))]
     #[cfg_attr(feature = "serde", serde(alias = "r"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Right"))]
     Right,
     /// 4th mouse button. Typically performs the same function as `Browser_Back`
     #[cfg_attr(feature = "serde", serde(alias = "B"))]
     #[cfg_attr(feature = "serde", serde(alias = "b"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Back"))]
     Back,
     /// 5th mouse button. Typically performs the same function as
     /// `Browser_Forward`
     #[cfg_attr(feature = "serde", serde(alias = "F"))]
     #[cfg_attr(feature = "serde", serde(alias = "f"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Forward"))]
     Forward,
 
     /// Scroll up button. It is better to use the
     /// [`Mouse::scroll`] method to scroll.
     #[cfg_attr(feature = "serde", serde(alias = "SU"))]
     #[cfg_attr(feature = "serde", serde(alias = "su"))]
+    #[cfg_attr(feature = "serde", serde(rename = "ScrollUp"))]
     ScrollUp,
     /// Scroll down button. It is better to use the
     /// [`Mouse::scroll`] method to scroll.
     #[cfg_attr(feature = "serde", serde(alias = "SD"))]
     #[cfg_attr(feature = "serde", serde(alias = "sd"))]
+    #[cfg_attr(feature = "serde", serde(rename = "ScrollDown"))]
     ScrollDown,
     /// Scroll left button. It is better to use the
     /// [`Mouse::scroll`] method to scroll.
     #[cfg_attr(feature = "serde", serde(alias = "SL"))]
     #[cfg_attr(feature = "serde", serde(alias = "sl"))]
+    #[cfg_attr(feature = "serde", serde(rename = "ScrollLeft"))]
     ScrollLeft,
     /// Scroll right button. It is better to use the
     /// [`Mouse::scroll`] method to scroll.
     #[cfg_attr(feature = "serde", serde(alias = "SR"))]
     #[cfg_attr(feature = "serde", serde(alias = "sr"))]
+    #[cfg_attr(feature = "serde", serde(rename = "ScrollRight"))]
     ScrollRight,
 }
 
@@ -149,17 +303,20 @@ impl fmt::Debug for Enigo {
 
 /// The direction of a key or button
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Direction {
     #[cfg_attr(feature = "serde", serde(alias = "P"))]
     #[cfg_attr(feature = "serde", serde(alias = "p"))]
     #[cfg_attr(feature = "serde", serde(alias = "Pressed"))]
     #[cfg_attr(feature = "serde", serde(alias = "pressed"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Press"))]
     Press,
     #[cfg_attr(feature = "serde", serde(alias = "R"))]
     #[cfg_attr(feature = "serde", serde(alias = "r"))]
     #[cfg_attr(feature = "serde", serde(alias = "Released"))]
     #[cfg_attr(feature = "serde", serde(alias = "released"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Release"))]
     Release,
     /// Equivalent to a press followed by a release
     #[cfg_attr(feature = "serde", serde(alias = "C"))]
@@ -167,34 +324,209 @@ pub enum Direction {
     #[cfg_attr(feature = "serde", serde(alias = "Clicked"))]
     #[cfg_attr(feature = "serde", serde(alias = "clicked"))]
     #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "Click"))]
     Click,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 /// Specifies the axis for scrolling
 pub enum Axis {
     #[cfg_attr(feature = "serde", serde(alias = "H"))]
     #[cfg_attr(feature = "serde", serde(alias = "h"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Horizontal"))]
     Horizontal,
     #[cfg_attr(feature = "serde", serde(alias = "V"))]
     #[cfg_attr(feature = "serde", serde(alias = "v"))]
     #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "Vertical"))]
     Vertical,
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Specifies the unit of the lengths for scrolling
+pub enum ScrollUnit {
+    /// Number of 15° (click) rotations of the mouse wheel. How many lines will
+    /// be scrolled depends on the current setting of the operating system
+    #[default]
+    Line,
+    /// Number of pixels like the ones a trackpad produces. This is only
+    /// supported on macOS and with the `wayland` and `libei` features
+    Pixel,
+}
+
+/// Number of lines most systems scroll for a notch of the mouse wheel
+pub(crate) const DEFAULT_WHEEL_SCROLL_LINES: i32 = 3;
+/// Approximate height of a line of text in pixels
+pub(crate) const LINE_HEIGHT: i32 = 20;
+
+/// Number of notches of the mouse wheel that scroll closest to `px` pixels if
+/// each of them scrolls `px_per_notch` pixels. It is at least one notch unless
+/// `px` is zero
+///
+/// # Errors
+/// Returns an `InputError::InvalidInput` if a notch does not scroll at least
+/// one pixel
+pub(crate) fn pixels_to_notches(px: i32, px_per_notch: i32) -> InputResult<i32> {
+    if px_per_notch <= 0 {
+        return Err(InputError::InvalidInput(
+            "a notch of the mouse wheel has to scroll at least one pixel",
+        ));
+    }
+    let notches = px
+        .unsigned_abs()
+        .saturating_add(px_per_notch.unsigned_abs() / 2)
+        / px_per_notch.unsigned_abs();
+    Ok(i32::try_from(notches.max(1)).unwrap_or(i32::MAX) * px.signum())
+}
+
+/// Round the coordinates to whole pixels
+pub(crate) fn round_coordinates(x: f64, y: f64) -> InputResult<(i32, i32)> {
+    let round = |value: f64| {
+        let value = value.round();
+        if (f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(&value) {
+            // The range was checked
+            #[allow(clippy::cast_possible_truncation)]
+            Ok(value as i32)
+        } else {
+            Err(InputError::InvalidInput(
+                "the coordinates have to be finite and fit in an i32",
+            ))
+        }
+    };
+    Ok((round(x)?, round(y)?))
+}
+
+/// Fractions of a pixel of the relative movements that were not simulated
+/// yet, because the platform can only move by whole pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Subpixel {
+    x: f64,
+    y: f64,
+}
+
+impl Subpixel {
+    /// Add the movement to the remainders and return the whole pixels to
+    /// move. At most half a pixel is left on each axis
+    pub(crate) fn take(&mut self, dx: f64, dy: f64) -> (i32, i32) {
+        let (x, y) = (self.x + dx, self.y + dy);
+        let (whole_x, whole_y) = (x.round(), y.round());
+        self.x = x - whole_x;
+        self.y = y - whole_y;
+        // Casting saturates, which is fine for such absurd distances
+        #[allow(clippy::cast_possible_truncation)]
+        (whole_x as i32, whole_y as i32)
+    }
+}
+
+/// Time between two of the scroll events of [`Mouse::scroll_with_inertia`]
+pub const INERTIA_FRAME: Duration = Duration::from_millis(16);
+/// Factor the velocity of [`Mouse::scroll_with_inertia`] is multiplied with
+/// after each frame. It is about the deceleration of a fling on a trackpad
+pub const INERTIA_DECAY: f64 = 0.95;
+/// Velocity in pixels per second below which [`Mouse::scroll_with_inertia`]
+/// comes to rest
+pub const INERTIA_MIN_VELOCITY: f64 = 10.0;
+
+/// Number of pixels to scroll in each frame of a fling with the initial
+/// `velocity` in pixels per second. The fractions of a pixel are carried over
+/// to the next frame, so the sum is the distance of the whole fling. Frames
+/// that would scroll less than a pixel are left out
+pub(crate) fn inertia_deltas(velocity: f64) -> Vec<i32> {
+    let mut deltas = Vec::new();
+    if !velocity.is_finite() {
+        return deltas;
+    }
+    let mut velocity = velocity;
+    let mut remainder = 0.0;
+    while velocity.abs() >= INERTIA_MIN_VELOCITY {
+        remainder += velocity * INERTIA_FRAME.as_secs_f64();
+        let delta = remainder.trunc();
+        if delta != 0.0 {
+            // The velocity is finite, so this only saturates for absurdly fast flings
+            #[allow(clippy::cast_possible_truncation)]
+            deltas.push(delta as i32);
+            remainder -= delta;
+        }
+        velocity *= INERTIA_DECAY;
+    }
+    deltas
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// Rectangle on the screen in pixels. The origin is the top left corner of
+/// the main display
+pub struct Rect {
+    /// Horizontal coordinate of the left edge
+    pub x: i32,
+    /// Vertical coordinate of the top edge
+    pub y: i32,
+    /// Width of the rectangle
+    pub width: i32,
+    /// Height of the rectangle
+    pub height: i32,
+}
+
+impl Rect {
+    /// Create a new rectangle from its top left corner and its size
+    #[must_use]
+    pub const fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns true if the point is inside of the rectangle
+    #[must_use]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && i64::from(x) < i64::from(self.x) + i64::from(self.width)
+            && i64::from(y) < i64::from(self.y) + i64::from(self.height)
+    }
+
+    /// Returns the closest point inside of the rectangle. An empty rectangle
+    /// is treated as if it was one pixel wide and high
+    #[must_use]
+    pub fn clamp(&self, x: i32, y: i32) -> (i32, i32) {
+        let right = self.x.saturating_add(self.width.max(1) - 1);
+        let bottom = self.y.saturating_add(self.height.max(1) - 1);
+        (x.clamp(self.x, right), y.clamp(self.y, bottom))
+    }
+
+    /// Returns an error if the rectangle has no area
+    pub(crate) fn check_not_empty(&self) -> InputResult<()> {
+        if self.width <= 0 || self.height <= 0 {
+            return Err(InputError::InvalidInput(
+                "the width and height of the rectangle must be positive",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 /// Specifies if a coordinate is relative or absolute
 pub enum Coordinate {
     #[doc(alias = "Absolute")]
     #[cfg_attr(feature = "serde", serde(alias = "A"))]
     #[cfg_attr(feature = "serde", serde(alias = "a"))]
     #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "Abs"))]
     Abs,
     #[doc(alias = "Relative")]
     #[cfg_attr(feature = "serde", serde(alias = "R"))]
     #[cfg_attr(feature = "serde", serde(alias = "r"))]
+    #[cfg_attr(feature = "serde", serde(rename = "Rel"))]
     Rel,
 }
 
@@ -238,25 +570,60 @@ pub trait Keyboard {
             return Ok(()); // Nothing to simulate.
         }
 
-        // Fall back to entering single keys if no fast text entry is available
-        let fast_text_res = self.fast_text(text);
-        match fast_text_res {
-            Ok(Some(())) => {
-                debug!("fast text entry was successful");
-                Ok(())
-            }
-            Ok(None) => {
-                debug!("fast text entry not available. Trying to enter individual letters now");
-                for c in text.chars() {
-                    self.key(Key::Unicode(c), Direction::Click)?;
-                }
-                Ok(())
+        text_with_policy(self, text, UnmappableCharPolicy::Abort)
+    }
+
+    /// Enter the text like [`Keyboard::text`], but check the token before
+    /// each chunk of [`TEXT_CHUNK_LEN`] characters and stop if it was
+    /// cancelled. Returns the number of characters that were entered, so the
+    /// rest of the text can be entered later (e.g. with
+    /// `text.chars().skip(entered)`)
+    ///
+    /// # Errors
+    /// Have a look at the documentation of [`InputError`] to see under which
+    /// conditions an error will be returned. The chunks before the one that
+    /// failed were entered and the returned [`PartialTextError`] contains
+    /// their number of characters
+    fn text_cancellable(
+        &mut self,
+        text: &str,
+        token: &CancellationToken,
+    ) -> Result<usize, PartialTextError> {
+        let mut entered = 0;
+        for chunk in text_chunks(text) {
+            if token.is_cancelled() {
+                debug!("entering the text was cancelled after {entered} characters");
+                return Ok(entered);
             }
-            Err(e) => {
-                error!("{e}");
-                Err(e)
+            self.text(chunk)
+                .map_err(|error| PartialTextError { entered, error })?;
+            entered += chunk.chars().count();
+        }
+        Ok(entered)
+    }
+
+    /// Enter the text of the chunks one after another without collecting them
+    /// in one large `String` first. The next chunk is only taken from the
+    /// iterator once the previous one was entered, so e.g. a file can be read
+    /// and typed in parallel. Chunks that are longer than [`TEXT_CHUNK_LEN`]
+    /// characters are split, which keeps the number of keycodes that need to
+    /// be mapped at the same time on Linux small
+    ///
+    /// # Errors
+    /// Have a look at the documentation of [`InputError`] to see under which
+    /// conditions an error will be returned. The chunks before the one that
+    /// failed were entered
+    fn text_stream<'a, I>(&mut self, chunks: I) -> InputResult<()>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = &'a str>,
+    {
+        for chunk in chunks {
+            for part in text_chunks(chunk) {
+                self.text(part)?;
             }
         }
+        Ok(())
     }
 
     /// Sends an individual key event. It will enter the keysym (virtual key).
@@ -278,13 +645,179 @@ pub trait Keyboard {
     /// if you just want to enter a specific key and don't want to worry about
     /// the layout/keymap. Windows only: If you want to enter the keycode
     /// (scancode) of an extended key, you need to set extra bits. You can
-    /// for example do: `enigo.raw(45 | EXT, Direction::Click)`
+    /// for example do: `enigo.raw(45 | EXT, Direction::Click)`. Use
+    /// [`Keyboard::physical`] to press a key at a physical position with the
+    /// same code on all platforms
     ///
     /// # Errors
     /// Have a look at the documentation of [`InputError`] to see under which
     /// conditions an error will be returned.
     #[doc(alias = "Key::Raw")]
     fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()>;
+
+    /// Press several keys down in the order of the slice and release them in
+    /// the reverse order, like a chord or a shortcut (e.g. `[Key::Control,
+    /// Key::Shift, Key::Unicode('t')]`). With [`Direction::Press`] the keys
+    /// are only pressed and with [`Direction::Release`] they are only released
+    /// (in reverse order). If a key can't be pressed, the keys that were
+    /// already pressed are released again, so no key is left held. On Windows
+    /// the events are sent with a single `SendInput` call and with libei all
+    /// presses are sent in one frame and all releases in the next one. All
+    /// other platforms send them one after another.
+    ///
+    /// # Errors
+    /// Have a look at the documentation of [`InputError`] to see under which
+    /// conditions an error will be returned. If releasing a key fails, the
+    /// other keys are still released and the first error is returned.
+    #[doc(alias = "chord", alias = "shortcut")]
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        key_sequence_by_key(self, keys, direction)
+    }
+
+    /// Sends the key at the physical position of the [`Scancode`], regardless
+    /// of the layout. Unlike [`Keyboard::raw`], the code is converted to the
+    /// scheme of the current platform, so the same scancode presses the same
+    /// key everywhere (e.g. `Scancode::from_hid_usage(0x1A)` is the key in
+    /// the position of W on a QWERTY layout)
+    ///
+    /// # Errors
+    /// Returns an [`InputError::InvalidInput`] if the scancode has no
+    /// equivalent on the current platform. Have a look at the documentation of
+    /// [`InputError`] to see under which other conditions an error will be
+    /// returned.
+    fn physical(&mut self, scancode: Scancode, direction: Direction) -> InputResult<()> {
+        self.raw(scancode.to_platform()?, direction)
+    }
+
+    /// Latch the modifier key (e.g. [`Key::Shift`]) like sticky keys do. It
+    /// is active for the next key that is entered and released afterwards.
+    /// This is useful for one-shot modifiers. On Linux the latched modifiers
+    /// of XKB are used. On Windows and macOS the modifier is pressed before
+    /// the next key that is not a modifier and released after it
+    ///
+    /// # Errors
+    /// Returns an error if the key is not a modifier or latching modifiers
+    /// is not supported. The default implementation always returns an error
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        error!("unable to latch {key:?}");
+        Err(InputError::Simulate("latching modifiers is not supported"))
+    }
+
+    /// Lock or unlock the modifier key (e.g. [`Key::Shift`]), so it stays
+    /// active until it gets unlocked. On Linux the locked modifiers of XKB
+    /// are used. The default implementation presses the key to lock it and
+    /// releases it to unlock it
+    ///
+    /// # Errors
+    /// Have a look at the documentation of [`InputError`] to see under which
+    /// conditions an error will be returned.
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        let direction = if locked {
+            Direction::Press
+        } else {
+            Direction::Release
+        };
+        self.key(key, direction)
+    }
+}
+
+/// Maximum number of characters that [`Keyboard::text_cancellable`] and
+/// [`Keyboard::text_stream`] enter at once
+pub const TEXT_CHUNK_LEN: usize = 32;
+
+/// Default implementation of [`Keyboard::text`]. If no fast text entry is
+/// available, the characters are entered key by key and the ones that can't
+/// be mapped are handled according to the policy
+pub(crate) fn text_with_policy<K: Keyboard + ?Sized>(
+    keyboard: &mut K,
+    text: &str,
+    policy: UnmappableCharPolicy,
+) -> InputResult<()> {
+    // Fall back to entering single keys if no fast text entry is available
+    match keyboard.fast_text(text) {
+        Ok(Some(())) => {
+            debug!("fast text entry was successful");
+            Ok(())
+        }
+        Ok(None) => {
+            debug!("fast text entry not available. Trying to enter individual letters now");
+            for c in text.chars() {
+                let e = match keyboard.key(Key::Unicode(c), Direction::Click) {
+                    Err(InputError::Mapping(e)) => e,
+                    res => {
+                        res?;
+                        continue;
+                    }
+                };
+                match policy {
+                    UnmappableCharPolicy::Abort => {
+                        error!("unable to map {c:?}: {e}");
+                        return Err(InputError::Mapping(e));
+                    }
+                    UnmappableCharPolicy::SkipUnmappable => {
+                        warn!("skipped {c:?} because it could not be mapped: {e}");
+                    }
+                    UnmappableCharPolicy::ReplaceWith(replacement) => {
+                        warn!("replaced {c:?} with {replacement:?} because it could not be mapped: {e}");
+                        keyboard.key(Key::Unicode(replacement), Direction::Click)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Err(e) => {
+            error!("{e}");
+            Err(e)
+        }
+    }
+}
+
+/// Split the text into parts of at most [`TEXT_CHUNK_LEN`] characters
+fn text_chunks(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest
+            .char_indices()
+            .nth(TEXT_CHUNK_LEN)
+            .map_or(rest.len(), |(i, _)| i);
+        let (chunk, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(chunk)
+    })
+}
+
+/// Default implementation of [`Keyboard::key_sequence`] that enters one key
+/// after another. The pressed keys are released in reverse order if a key
+/// can't be pressed
+pub(crate) fn key_sequence_by_key<K: Keyboard + ?Sized>(
+    keyboard: &mut K,
+    keys: &[Key],
+    direction: Direction,
+) -> InputResult<()> {
+    if direction != Direction::Release {
+        for (i, &key) in keys.iter().enumerate() {
+            if let Err(e) = keyboard.key(key, Direction::Press) {
+                for &pressed in keys[..i].iter().rev() {
+                    if let Err(e) = keyboard.key(pressed, Direction::Release) {
+                        warn!("unable to release {pressed:?} after the key sequence failed: {e}");
+                    }
+                }
+                return Err(e);
+            }
+        }
+    }
+    let mut res = Ok(());
+    if direction != Direction::Press {
+        for &key in keys.iter().rev() {
+            if let Err(e) = keyboard.key(key, Direction::Release) {
+                res = res.and(Err(e));
+            }
+        }
+    }
+    res
 }
 
 /// Contains functions to control the mouse and to get the size of the display.
@@ -305,6 +838,30 @@ pub trait Mouse {
     #[doc(alias = "mouse_down", alias = "mouse_up", alias = "mouse_click")]
     fn button(&mut self, button: Button, direction: Direction) -> InputResult<()>;
 
+    /// Sends several mouse button events at once, so applications see them as
+    /// simultaneous (e.g. pressing the left and right button together). All
+    /// presses are sent before all releases. On Windows the events are sent
+    /// with a single `SendInput` call and with libei in a single frame. All
+    /// other platforms send them one after another.
+    ///
+    /// # Errors
+    /// Have a look at the documentation of [`InputError`] to see under which
+    /// conditions an error will be returned.
+    #[doc(alias = "chord")]
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        for &(button, direction) in buttons {
+            if direction != Direction::Release {
+                self.button(button, Direction::Press)?;
+            }
+        }
+        for &(button, direction) in buttons {
+            if direction != Direction::Press {
+                self.button(button, Direction::Release)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Move the mouse cursor to the specified x and y coordinates.
     ///
     /// You can specify absolute coordinates or relative from the current
@@ -325,6 +882,56 @@ pub trait Mouse {
     #[doc(alias = "mouse_move_to", alias = "mouse_move_relative")]
     fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()>;
 
+    /// Move the mouse cursor like [`Mouse::move_mouse`] and return the
+    /// position it ended up at. Clamping to the displays or a confinement,
+    /// the mouse acceleration and the scaling of the display mean the cursor
+    /// doesn't always land exactly where it was requested, so callers can
+    /// continue their relative math from the returned position without
+    /// drifting.
+    ///
+    /// The position is the one [`Mouse::location`] reports after the
+    /// movement. If it can't be queried (e.g. with the `wayland` and `libei`
+    /// features), the requested position of an absolute movement is returned.
+    ///
+    /// # Errors
+    /// Returns the error of the movement or the error of
+    /// [`Mouse::location`] if the movement was relative. Have a look at the
+    /// documentation of [`InputError`] to see under which conditions an error
+    /// will be returned.
+    fn move_mouse_checked(
+        &mut self,
+        x: i32,
+        y: i32,
+        coordinate: Coordinate,
+    ) -> InputResult<(i32, i32)> {
+        self.move_mouse(x, y, coordinate)?;
+        match (self.location(), coordinate) {
+            (Err(_), Coordinate::Abs) => Ok((x, y)),
+            (res, _) => res,
+        }
+    }
+
+    /// Move the mouse cursor like [`Mouse::move_mouse`], but by fractions of
+    /// a pixel. This matters for absolute positioning on high-DPI displays
+    /// (e.g. the points of macOS or a fractional scale on Wayland).
+    ///
+    /// The fractions are kept for absolute movements on Windows and macOS,
+    /// for relative movements with the `wayland` feature and for all
+    /// movements with the `libei` feature. Otherwise the [`Enigo`] struct
+    /// rounds to whole pixels and carries the remainder of relative movements
+    /// over to the next one, so many small movements add up to the correct
+    /// distance. The default implementation only rounds.
+    ///
+    /// # Errors
+    /// Returns an [`InputError::InvalidInput`] if a coordinate is not finite
+    /// or does not fit in an `i32` when it is rounded. Have a look at the
+    /// documentation of [`InputError`] to see under which other conditions an
+    /// error will be returned.
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        let (x, y) = round_coordinates(x, y)?;
+        self.move_mouse(x, y, coordinate)
+    }
+
     /// Send a mouse scroll event
     ///
     /// # Arguments
@@ -343,6 +950,97 @@ pub trait Mouse {
     #[doc(alias = "mouse_scroll_x", alias = "mouse_scroll_y")]
     fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()>;
 
+    /// Scroll on both axes at once. Unlike two calls to [`Mouse::scroll`],
+    /// applications see a single diagonal scroll like the ones from a
+    /// trackpad. This is the case on macOS and with the `wayland` and `libei`
+    /// features. Windows sends both events with one `SendInput` call and all
+    /// other platforms send them one after another.
+    ///
+    /// A positive `dx` scrolls to the right and a positive `dy` scrolls down.
+    ///
+    /// # Errors
+    /// Returns an [`InputError::InvalidInput`] if the platform does not
+    /// support the unit. Have a look at the documentation of [`InputError`]
+    /// to see under which other conditions an error will be returned.
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        if unit != ScrollUnit::Line {
+            return Err(InputError::InvalidInput(
+                "scrolling by pixels is not supported on this platform",
+            ));
+        }
+        if dx != 0 {
+            self.scroll(dx, Axis::Horizontal)?;
+        }
+        if dy != 0 {
+            self.scroll(dy, Axis::Vertical)?;
+        }
+        Ok(())
+    }
+
+    /// Scroll by approximately `target_px` pixels on the axis. Platforms that
+    /// can scroll by pixels (see [`ScrollUnit::Pixel`]) scroll by exactly that
+    /// distance. All others scroll by the number of notches that comes closest
+    /// to it, but at least one. On Windows the distance of a notch is
+    /// calculated from the wheel settings of the system and elsewhere a notch
+    /// is assumed to scroll three lines.
+    ///
+    /// With [`Axis::Vertical`], a positive distance will result in scrolling
+    /// down and negative ones up. With [`Axis::Horizontal`], a positive
+    /// distance will result in scrolling to the right and negative ones to the
+    /// left
+    ///
+    /// # Errors
+    /// Have a look at the documentation of [`InputError`] to see under which
+    /// conditions an error will be returned.
+    fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+        let (dx, dy) = match axis {
+            Axis::Horizontal => (target_px, 0),
+            Axis::Vertical => (0, target_px),
+        };
+        match self.scroll_2d(dx, dy, ScrollUnit::Pixel) {
+            Err(InputError::InvalidInput(_)) => self.scroll(
+                pixels_to_notches(target_px, DEFAULT_WHEEL_SCROLL_LINES * LINE_HEIGHT)?,
+                axis,
+            ),
+            res => res,
+        }
+    }
+
+    /// Scroll like a fling on a trackpad that keeps moving after the fingers
+    /// were lifted. It starts with the `velocity` in pixels per second and
+    /// scrolls by pixels every [`INERTIA_FRAME`] while the velocity decays by
+    /// [`INERTIA_DECAY`] per frame. This is useful to test implementations of
+    /// kinetic scrolling.
+    ///
+    /// With [`Axis::Vertical`], a positive velocity will result in scrolling
+    /// down and negative ones up. With [`Axis::Horizontal`], a positive
+    /// velocity will result in scrolling to the right and negative ones to the
+    /// left.
+    ///
+    /// The function blocks until the fling came to rest. [`EnigoHandle`]
+    /// simulates the fling on its own thread and returns immediately.
+    ///
+    /// # Errors
+    /// Returns an [`InputError::InvalidInput`] if the velocity is not finite
+    /// or the platform can't scroll by pixels (see [`ScrollUnit::Pixel`]).
+    /// Have a look at the documentation of [`InputError`] to see under which
+    /// other conditions an error will be returned.
+    fn scroll_with_inertia(&mut self, velocity: f64, axis: Axis) -> InputResult<()> {
+        if !velocity.is_finite() {
+            return Err(InputError::InvalidInput("the velocity has to be finite"));
+        }
+        for (i, delta) in inertia_deltas(velocity).into_iter().enumerate() {
+            if i > 0 {
+                std::thread::sleep(INERTIA_FRAME);
+            }
+            match axis {
+                Axis::Horizontal => self.scroll_2d(delta, 0, ScrollUnit::Pixel)?,
+                Axis::Vertical => self.scroll_2d(0, delta, ScrollUnit::Pixel)?,
+            }
+        }
+        Ok(())
+    }
+
     /// Get the (width, height) of the main display in pixels. This currently
     /// only works on the main display
     ///
@@ -359,6 +1057,327 @@ pub trait Mouse {
     /// conditions an error will be returned.
     #[doc(alias = "mouse_location")]
     fn location(&self) -> InputResult<(i32, i32)>;
+
+    /// Show or hide the cursor on the whole screen, e.g. during scripted
+    /// demos. Simulated and physical input still move the hidden cursor.
+    ///
+    /// On Windows all system cursors are replaced with a transparent one until
+    /// the cursor is shown again or the Enigo struct is dropped. On macOS the
+    /// cursor is only hidden while the application is active. On Linux it is
+    /// only supported by the `x11rb` feature and lasts as long as the
+    /// connection
+    ///
+    /// # Warning
+    /// On Windows the cursors are replaced for the whole session, not just
+    /// for this process. If the process terminates without dropping the Enigo
+    /// struct (e.g. it aborts, calls `std::process::exit` or gets killed), the
+    /// cursor stays invisible until the user logs out or changes the cursor
+    /// scheme. Call [`Enigo::install_cleanup_handler`] to show it again in
+    /// most of these cases. Nothing can restore it if the process is killed
+    /// forcefully (e.g. with the Task Manager)
+    ///
+    /// # Errors
+    /// Returns an `InputError::Simulate` if it is not supported
+    fn show_cursor(&mut self, _visible: bool) -> InputResult<()> {
+        Err(InputError::Simulate(
+            "showing and hiding the cursor is not supported",
+        ))
+    }
+
+    /// Associate the cursor with the movement of the physical mouse or not.
+    /// If it is not associated, moving the physical mouse does not move the
+    /// cursor, so it is jailed at its location.
+    ///
+    /// On macOS simulated movements still move the cursor. On Windows the
+    /// cursor is confined to its current location with `ClipCursor`, so
+    /// simulated movements can't move it either. Windows lifts the confinement
+    /// when the foreground window changes. It is not supported on Linux
+    ///
+    /// # Errors
+    /// Returns an `InputError::Simulate` if it is not supported
+    fn associate_cursor(&mut self, _associated: bool) -> InputResult<()> {
+        Err(InputError::Simulate(
+            "disassociating the cursor from the mouse is not supported",
+        ))
+    }
+
+    /// Confine the cursor to the rectangle, so e.g. drag heavy automation
+    /// can't escape the area it is supposed to work in. The confinement lasts
+    /// until [`Mouse::release_confinement`] is called or the Enigo struct is
+    /// dropped.
+    ///
+    /// On Windows the cursor is confined with `ClipCursor`, which also
+    /// applies to the physical mouse. Windows lifts the confinement when the
+    /// foreground window changes. On macOS the cursor is disassociated from
+    /// the physical mouse and the simulated movements are clamped to the
+    /// rectangle. On Linux only the simulated movements are clamped, because
+    /// the compositors only allow a client to confine the pointer to its own
+    /// surfaces
+    ///
+    /// # Errors
+    /// Returns an `InputError::InvalidInput` if the rectangle is empty and an
+    /// `InputError::Simulate` if it is not supported
+    fn confine_to(&mut self, _rect: Rect) -> InputResult<()> {
+        Err(InputError::Simulate(
+            "confining the cursor is not supported",
+        ))
+    }
+
+    /// Stop confining the cursor to the rectangle of [`Mouse::confine_to`]
+    ///
+    /// # Errors
+    /// Returns an `InputError::Simulate` if it is not supported
+    fn release_confinement(&mut self) -> InputResult<()> {
+        Err(InputError::Simulate(
+            "confining the cursor is not supported",
+        ))
+    }
+}
+
+/// Simulates keyboard and mouse input. This is implemented for all types that
+/// implement [`Keyboard`] and [`Mouse`] and cannot be implemented manually.
+///
+/// Both traits are object safe, so you can store different implementations
+/// (e.g. a mock in the tests and [`Enigo`] otherwise) behind a `Box<dyn
+/// InputDevice>`. Have a look at [`Enigo::new_boxed`].
+pub trait InputDevice: Keyboard + Mouse + private::Sealed {}
+
+impl<T: Keyboard + Mouse + ?Sized> InputDevice for T {}
+
+mod private {
+    pub trait Sealed {}
+
+    impl<T: crate::Keyboard + crate::Mouse + ?Sized> Sealed for T {}
+}
+
+impl Enigo {
+    /// Create a new Enigo struct like [`Enigo::new`] and return it as a trait
+    /// object
+    ///
+    /// # Errors
+    /// Have a look at the documentation of `NewConError` to see under which
+    /// conditions an error will be returned.
+    pub fn new_boxed(settings: &Settings) -> Result<Box<dyn InputDevice + Send>, NewConError> {
+        Ok(Box::new(Self::new(settings)?))
+    }
+}
+
+impl<T: Keyboard + ?Sized> Keyboard for Box<T> {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        (**self).fast_text(text)
+    }
+
+    fn text(&mut self, text: &str) -> InputResult<()> {
+        (**self).text(text)
+    }
+
+    fn text_cancellable(
+        &mut self,
+        text: &str,
+        token: &CancellationToken,
+    ) -> Result<usize, PartialTextError> {
+        (**self).text_cancellable(text, token)
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        (**self).key(key, direction)
+    }
+
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        (**self).raw(keycode, direction)
+    }
+
+    fn physical(&mut self, scancode: Scancode, direction: Direction) -> InputResult<()> {
+        (**self).physical(scancode, direction)
+    }
+
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        (**self).key_sequence(keys, direction)
+    }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        (**self).latch_modifier(key)
+    }
+
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        (**self).lock_modifier(key, locked)
+    }
+}
+
+impl<T: Mouse + ?Sized> Mouse for Box<T> {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        (**self).button(button, direction)
+    }
+
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        (**self).buttons(buttons)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        (**self).move_mouse(x, y, coordinate)
+    }
+
+    fn move_mouse_checked(
+        &mut self,
+        x: i32,
+        y: i32,
+        coordinate: Coordinate,
+    ) -> InputResult<(i32, i32)> {
+        (**self).move_mouse_checked(x, y, coordinate)
+    }
+
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        (**self).move_mouse_f(x, y, coordinate)
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        (**self).scroll(length, axis)
+    }
+
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        (**self).scroll_2d(dx, dy, unit)
+    }
+
+    fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+        (**self).scroll_by_pixels_approx(target_px, axis)
+    }
+
+    fn scroll_with_inertia(&mut self, velocity: f64, axis: Axis) -> InputResult<()> {
+        (**self).scroll_with_inertia(velocity, axis)
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        (**self).main_display()
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        (**self).location()
+    }
+
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        (**self).show_cursor(visible)
+    }
+
+    fn associate_cursor(&mut self, associated: bool) -> InputResult<()> {
+        (**self).associate_cursor(associated)
+    }
+
+    fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+        (**self).confine_to(rect)
+    }
+
+    fn release_confinement(&mut self) -> InputResult<()> {
+        (**self).release_confinement()
+    }
+}
+
+impl<T: Keyboard + ?Sized> Keyboard for &mut T {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        (**self).fast_text(text)
+    }
+
+    fn text(&mut self, text: &str) -> InputResult<()> {
+        (**self).text(text)
+    }
+
+    fn text_cancellable(
+        &mut self,
+        text: &str,
+        token: &CancellationToken,
+    ) -> Result<usize, PartialTextError> {
+        (**self).text_cancellable(text, token)
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        (**self).key(key, direction)
+    }
+
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        (**self).raw(keycode, direction)
+    }
+
+    fn physical(&mut self, scancode: Scancode, direction: Direction) -> InputResult<()> {
+        (**self).physical(scancode, direction)
+    }
+
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        (**self).key_sequence(keys, direction)
+    }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        (**self).latch_modifier(key)
+    }
+
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        (**self).lock_modifier(key, locked)
+    }
+}
+
+impl<T: Mouse + ?Sized> Mouse for &mut T {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        (**self).button(button, direction)
+    }
+
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        (**self).buttons(buttons)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        (**self).move_mouse(x, y, coordinate)
+    }
+
+    fn move_mouse_checked(
+        &mut self,
+        x: i32,
+        y: i32,
+        coordinate: Coordinate,
+    ) -> InputResult<(i32, i32)> {
+        (**self).move_mouse_checked(x, y, coordinate)
+    }
+
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        (**self).move_mouse_f(x, y, coordinate)
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        (**self).scroll(length, axis)
+    }
+
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        (**self).scroll_2d(dx, dy, unit)
+    }
+
+    fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+        (**self).scroll_by_pixels_approx(target_px, axis)
+    }
+
+    fn scroll_with_inertia(&mut self, velocity: f64, axis: Axis) -> InputResult<()> {
+        (**self).scroll_with_inertia(velocity, axis)
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        (**self).main_display()
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        (**self).location()
+    }
+
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        (**self).show_cursor(visible)
+    }
+
+    fn associate_cursor(&mut self, associated: bool) -> InputResult<()> {
+        (**self).associate_cursor(associated)
+    }
+
+    fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+        (**self).confine_to(rect)
+    }
+
+    fn release_confinement(&mut self) -> InputResult<()> {
+        (**self).release_confinement()
+    }
 }
 
 pub type InputResult<T> = Result<T, InputError>;
@@ -378,6 +1397,34 @@ pub enum InputError {
     /// This happens for example if you want to enter text that contains NULL
     /// bytes (`\0`)
     InvalidInput(&'static str),
+    /// The session is locked or not active, so the simulated input would be
+    /// discarded
+    SessionLocked,
+    /// The [`agent::Policy`] does not allow the token
+    PermissionDenied(&'static str),
+    /// More events than `Settings::max_events_per_second` were simulated
+    RateLimited,
+    /// The expected window is not the foreground window, so the input would
+    /// be received by a different application
+    WrongForegroundWindow,
+    /// The foreground window belongs to a process with a higher integrity
+    /// level (e.g. it runs as administrator). Windows' User Interface
+    /// Privilege Isolation (UIPI) silently discards the input. Run your
+    /// program elevated as well or with `uiAccess` to simulate input in it
+    BlockedByUipi,
+    /// The connection could not be established when it was first used. This
+    /// only happens if `Settings::lazy_connect` is enabled
+    Connect(NewConError),
+    /// The backend lacks the capability to simulate the input (e.g. the
+    /// Wayland compositor does not offer the virtual pointer protocol) and no
+    /// other backend simulated it. It is also returned if the platform does
+    /// not have a setting of the `system` module
+    NotSupported {
+        /// The missing capability
+        capability: Capability,
+        /// Name of the backend (e.g. "wayland")
+        backend: &'static str,
+    },
 }
 
 impl Display for InputError {
@@ -390,6 +1437,24 @@ impl Display for InputError {
             }
             InputError::Simulate(e) => format!("simulating input failed: ({e})"),
             InputError::InvalidInput(e) => format!("you tried to simulate invalid input: ({e})"),
+            InputError::SessionLocked => {
+                "the session is locked or inactive so the input would be discarded".to_string()
+            }
+            InputError::PermissionDenied(e) => format!("the policy does not allow it: ({e})"),
+            InputError::RateLimited => {
+                "the maximum number of events per second was exceeded".to_string()
+            }
+            InputError::WrongForegroundWindow => {
+                "the expected window is not the foreground window".to_string()
+            }
+            InputError::BlockedByUipi => {
+                "the foreground window runs elevated so UIPI would discard the input. run the program as administrator or with uiAccess".to_string()
+            }
+            InputError::Connect(e) => format!("no connection could be established: {e}"),
+            InputError::NotSupported {
+                capability,
+                backend,
+            } => format!("the {backend} backend lacks the {capability}"),
         };
         write!(f, "{string}")
     }
@@ -397,6 +1462,49 @@ impl Display for InputError {
 
 impl Error for InputError {}
 
+/// Capability of a backend to simulate a kind of input or to read a setting
+/// of the system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Pressing and releasing keys (e.g. the virtual keyboard protocol on
+    /// Wayland)
+    Keyboard,
+    /// Moving the mouse, pressing its buttons and scrolling (e.g. the virtual
+    /// pointer protocol on Wayland)
+    Pointer,
+    /// Reading the speed of the mouse with `system::mouse_speed`
+    MouseSpeed,
+    /// Reading whether the mouse is accelerated with
+    /// `system::mouse_acceleration_enabled`
+    MouseAcceleration,
+    /// Reading the double click interval with `system::double_click_interval`
+    DoubleClickInterval,
+    /// Reading the DPI of the screen with `system::screen_dpi`
+    ScreenDpi,
+    /// Reading the time since the last input with `system::idle_time`
+    IdleTime,
+    /// Keeping the system awake with `keep_awake::KeepAwake`
+    KeepAwake,
+    /// Reading the focused element with the accessibility API
+    Accessibility,
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Capability::Keyboard => write!(f, "virtual keyboard"),
+            Capability::Pointer => write!(f, "virtual pointer"),
+            Capability::MouseSpeed => write!(f, "mouse speed setting"),
+            Capability::MouseAcceleration => write!(f, "mouse acceleration setting"),
+            Capability::DoubleClickInterval => write!(f, "double click interval setting"),
+            Capability::ScreenDpi => write!(f, "screen DPI"),
+            Capability::IdleTime => write!(f, "idle time"),
+            Capability::KeepAwake => write!(f, "inhibitor to keep the system awake"),
+            Capability::Accessibility => write!(f, "accessibility API"),
+        }
+    }
+}
+
 /// Error when establishing a new connection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum NewConError {
@@ -408,6 +1516,9 @@ pub enum NewConError {
     Reply,
     /// The keymap is full, so there was no space to map any keycodes to keysyms
     NoEmptyKeycodes,
+    /// The connection was not established within
+    /// `Settings::connection_timeout`
+    Timeout,
 }
 
 impl Display for NewConError {
@@ -424,6 +1535,7 @@ impl Display for NewConError {
             NewConError::NoEmptyKeycodes => {
                 "there were no empty keycodes that could be used".to_string()
             }
+            NewConError::Timeout => "the connection was not established in time".to_string(),
         };
         write!(f, "{string}")
     }
@@ -431,6 +1543,260 @@ impl Display for NewConError {
 
 impl Error for NewConError {}
 
+/// Information about the keyboard layout that is currently active. Have a look
+/// at `Enigo::keyboard_layout`
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LayoutInfo {
+    /// Human readable name of the layout (e.g. "German")
+    pub name: String,
+    /// Language of the layout as a BCP 47 tag (e.g. "de-DE") on Windows and
+    /// macOS and as the short xkb layout name (e.g. "de") on Linux
+    pub language: Option<String>,
+    /// Identifier of the variant of the layout. This is the KLID (e.g.
+    /// "00000407") on Windows, the input source ID (e.g.
+    /// "com.apple.keylayout.German") on macOS and the xkb variant (e.g.
+    /// "nodeadkeys") on Linux
+    pub variant: Option<String>,
+}
+
+/// How a `Key::Unicode` is simulated
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnicodeKeyMode {
+    /// The character is entered regardless of the active layout. If a key of
+    /// the layout produces the character, it is pressed. Otherwise the
+    /// character is entered as text (Windows, macOS) or a keycode is mapped to
+    /// it (Linux)
+    #[default]
+    Character,
+    /// The key that produces the character on the active layout is pressed
+    /// without any modifiers. An `InputError::Mapping` is returned if there is
+    /// no such key. Use this if the position of the key matters (e.g. for
+    /// keyboard shortcuts).
+    ///
+    /// On Linux, this is only supported by the `x11rb` and `libei` features.
+    /// `libei` always uses these semantics, `wayland` and `xdo` never do
+    Layout,
+}
+
+/// What happens if a character of a text can't be mapped to a key
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnmappableCharPolicy {
+    /// An `InputError::Mapping` is returned and the rest of the text is not
+    /// entered
+    #[default]
+    Abort,
+    /// The character is skipped and the rest of the text is entered
+    SkipUnmappable,
+    /// The character is replaced (e.g. with '?' or '\u{FFFD}') and the rest
+    /// of the text is entered. An error is returned if the replacement can't be
+    /// mapped either
+    ReplaceWith(char),
+}
+
+/// How characters that are not entered by pressing a key are entered on
+/// Windows
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextEntryMethod {
+    /// Enter the characters as unicode (`KEYEVENTF_UNICODE`)
+    #[default]
+    Unicode,
+    /// Enter the characters of the active ANSI codepage with Alt+Numpad codes
+    /// (e.g. Alt+0228 for 'ä'). Some legacy applications only accept
+    /// characters entered like this. All other characters are entered as
+    /// unicode
+    AltNumpad,
+}
+
+/// What happens if text is entered while an input method editor (IME) is
+/// active in the foreground window on Windows. The IME handles the simulated
+/// unicode input as keystrokes, so it can start a composition or enter
+/// different characters
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ImeStrategy {
+    /// The text is entered as usual
+    #[default]
+    Ignore,
+    /// The IME is closed while the text is entered and opened again afterwards
+    Disable,
+    /// The characters are posted to the focused window as `WM_IME_CHAR`
+    /// messages. They are not subject to the IME, but they bypass `SendInput`,
+    /// so only applications that handle the message receive them
+    ImeChar,
+    /// An `InputError::Simulate` is returned
+    Error,
+}
+
+/// Where the simulated events are posted on macOS
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TapLocation {
+    /// The events are posted where events from the HID system enter the window
+    /// server. They are handled like the input of physical devices
+    #[default]
+    Hid,
+    /// The events are posted where events from the HID system and remote
+    /// control sources enter a login session. Event taps of the window server
+    /// do not see them
+    Session,
+    /// The events are posted where session events are annotated and about to
+    /// be delivered to applications. Only event taps of applications see them
+    AnnotatedSession,
+}
+
+/// How relative mouse movements (`Coordinate::Rel`) are simulated
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RelativeMode {
+    /// The cursor is moved by exactly the given number of pixels. On Windows
+    /// this is done by moving it to the resulting absolute location, so
+    /// applications that read raw input (e.g. games that lock the cursor) do
+    /// not receive any motion
+    #[default]
+    Precise,
+    /// Relative motion with the un-accelerated deltas is injected, so
+    /// applications that read raw input receive exactly these deltas. The
+    /// operating system can still apply the mouse speed and acceleration to
+    /// the cursor. On Windows, use `Enigo::relative_moves_transformed` to check
+    /// if that is the case.
+    ///
+    /// On macOS and with the `wayland`, `libei` and `x11rb` features, relative
+    /// moves always include the raw deltas, so both modes behave the same
+    Raw,
+}
+
+/// Which mouse movements are simulated. Have a look at
+/// `Enigo::set_pointer_mode`
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PointerMode {
+    /// Absolute and relative movements are simulated
+    #[default]
+    Normal,
+    /// Only relative movements are simulated and they are always injected as
+    /// raw deltas (like `RelativeMode::Raw`), which is what games that lock
+    /// the pointer expect. Absolute movements return an
+    /// `InputError::InvalidInput`. With libei only the relative pointer is
+    /// used and never the absolute regions
+    RelativeOnly,
+}
+
+/// How absolute mouse movements (`Coordinate::Abs`) are simulated
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AbsoluteMode {
+    /// A motion event is simulated. Applications receive it like the motion
+    /// of a physical mouse, so e.g. hover effects are triggered
+    #[default]
+    Synthesized,
+    /// The cursor is warped to the location (`SetCursorPos` on Windows,
+    /// `CGWarpMouseCursorPosition` on macOS and `WarpPointer` on X11) without
+    /// simulating a motion event. Use this to move the cursor out of the way
+    /// without triggering hover effects. Windows still sends a `WM_MOUSEMOVE`
+    /// message to the window under the cursor.
+    ///
+    /// It is only supported by the `x11rb` feature on Linux. The other
+    /// connections return an `InputError::Simulate`
+    Warp,
+}
+
+/// Corner of the main display that is the origin of absolute coordinates
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Origin {
+    /// The y coordinate increases downwards. Windows, X11, Wayland and the
+    /// Quartz APIs of macOS use this origin
+    #[default]
+    TopLeft,
+    /// The y coordinate increases upwards. The `AppKit` APIs of macOS (e.g.
+    /// `NSEvent.mouseLocation` or the frames of an `NSWindow`) use this
+    /// origin
+    BottomLeft,
+}
+
+impl Origin {
+    /// Convert a point from this origin to the top left origin.
+    /// `main_display_height` is the height of the main display (see
+    /// [`Mouse::main_display`]). Flipping the y coordinate is its own inverse,
+    /// so this also converts from the top left origin to this one
+    #[must_use]
+    pub fn to_top_left(self, x: i32, y: i32, main_display_height: i32) -> (i32, i32) {
+        match self {
+            Origin::TopLeft => (x, y),
+            Origin::BottomLeft => (x, main_display_height.saturating_sub(y)),
+        }
+    }
+
+    /// Convert a point from the top left origin to this origin. Have a look
+    /// at [`Origin::to_top_left`]
+    #[must_use]
+    pub fn from_top_left(self, x: i32, y: i32, main_display_height: i32) -> (i32, i32) {
+        self.to_top_left(x, y, main_display_height)
+    }
+
+    /// Convert a rectangle from this origin to the top left origin. With the
+    /// bottom left origin, the position of a rectangle is its bottom left
+    /// corner (like the frame of an `NSWindow`), so the result is the top left
+    /// corner
+    #[must_use]
+    pub fn rect_to_top_left(self, rect: Rect, main_display_height: i32) -> Rect {
+        match self {
+            Origin::TopLeft => rect,
+            Origin::BottomLeft => Rect {
+                y: main_display_height
+                    .saturating_sub(rect.y)
+                    .saturating_sub(rect.height),
+                ..rect
+            },
+        }
+    }
+}
+
+/// Convert absolute coordinates in the `origin` to the top left origin
+pub(crate) fn origin_to_top_left<M: Mouse + ?Sized>(
+    mouse: &M,
+    origin: Origin,
+    x: i32,
+    y: i32,
+) -> InputResult<(i32, i32)> {
+    if origin == Origin::TopLeft {
+        return Ok((x, y));
+    }
+    let (_, height) = mouse.main_display()?;
+    Ok(origin.to_top_left(x, y, height))
+}
+
+/// Convert absolute coordinates with fractions of a pixel in the `origin` to
+/// the top left origin
+pub(crate) fn origin_to_top_left_f<M: Mouse + ?Sized>(
+    mouse: &M,
+    origin: Origin,
+    x: f64,
+    y: f64,
+) -> InputResult<(f64, f64)> {
+    if origin == Origin::TopLeft {
+        return Ok((x, y));
+    }
+    let (_, height) = mouse.main_display()?;
+    Ok((x, f64::from(height) - y))
+}
+
+/// What happens if more events than `Settings::max_events_per_second` are
+/// simulated
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RateLimitBehavior {
+    /// Wait until the event may be simulated
+    #[default]
+    Block,
+    /// Return an `InputError::RateLimited` without simulating the event
+    Error,
+}
+
 /// Settings for creating the Enigo struct and it's behavior
 #[allow(dead_code)] // It is not dead code on other platforms
 #[allow(clippy::struct_excessive_bools)]
@@ -443,6 +1809,12 @@ pub struct Settings {
     pub x11_display: Option<String>,
     /// Display name to connect to when using Linux Wayland
     pub wayland_display: Option<String>,
+    /// Name of the seat to use when using libei on Linux. If it is `None`,
+    /// all seats are used
+    pub libei_seat: Option<String>,
+    /// Name of the device to use when using libei on Linux. If it is `None`,
+    /// the first device that supports the input is used
+    pub libei_device: Option<String>,
     /// Arbitrary value to be able to distinguish events created by enigo
     /// All events will be marked with this value in the dwExtraInfo field
     pub windows_dw_extra_info: Option<usize>,
@@ -450,6 +1822,12 @@ pub struct Settings {
     /// All events will be marked with this value in the
     /// `EVENT_SOURCE_USER_DATA` field
     pub event_source_user_data: Option<i64>,
+    /// Emulate the auto-repeat of held keycodes (`Keyboard::raw`) on Linux
+    /// with Wayland and libei. The press events are repeated at the rate and
+    /// after the delay the compositor reported (or 25/s after 600 ms if it
+    /// did not report any) when `Enigo::repeat_held_keys` is called. X11
+    /// repeats held keys itself. The default is false.
+    pub linux_emulate_key_repeat: bool,
     /// Set this to true if you want all held keys to get released when Enigo
     /// gets dropped. The default is true.
     pub release_keys_when_dropped: bool,
@@ -461,6 +1839,9 @@ pub struct Settings {
     /// The default is true. If the Shift key for example is pressed,
     /// following simulated input will not be capitalized.
     pub independent_of_keyboard_state: bool,
+    /// Where the events are posted on macOS. Have a look at the documentation
+    /// of `TapLocation`. The default is `TapLocation::Hid`
+    pub macos_tap_location: TapLocation,
     /// If this is set to true, the relative mouse motion will be subject to the
     /// settings for mouse speed and acceleration level. An end user sets
     /// these values using the Mouse application in Control Panel. An
@@ -468,6 +1849,82 @@ pub struct Settings {
     /// `windows::Win32::UI::WindowsAndMessaging::SystemParametersInfoA`
     /// function. The default value is false.
     pub windows_subject_to_mouse_speed_and_acceleration_level: bool,
+    /// How a `Key::Unicode` is simulated. Have a look at the documentation of
+    /// `UnicodeKeyMode`. The default is `UnicodeKeyMode::Character`
+    pub unicode_key_mode: UnicodeKeyMode,
+    /// How text is entered on Windows. Have a look at the documentation of
+    /// `TextEntryMethod`. The default is `TextEntryMethod::Unicode`
+    pub windows_text_entry_method: TextEntryMethod,
+    /// KLID of the keyboard layout (e.g. "00000407" for German) that keys are
+    /// simulated with on Windows instead of the layout of the foreground
+    /// window. Have a look at `Enigo::with_keyboard_layout`. The default is
+    /// `None`
+    pub windows_keyboard_layout: Option<String>,
+    /// What happens if text is entered while an IME is active on Windows. Have
+    /// a look at the documentation of `ImeStrategy`. The default is
+    /// `ImeStrategy::Ignore`
+    pub windows_ime_strategy: ImeStrategy,
+    /// What happens if a character of a text can't be mapped to a key. This
+    /// only applies to the backends that enter text key by key (`x11rb` and
+    /// `libei` on Linux). The other backends enter every character. Have a
+    /// look at the documentation of `UnmappableCharPolicy`. The default is
+    /// `UnmappableCharPolicy::Abort`
+    pub unmappable_char_policy: UnmappableCharPolicy,
+    /// How relative mouse movements are simulated. Have a look at the
+    /// documentation of `RelativeMode`. The default is `RelativeMode::Precise`
+    pub relative_mode: RelativeMode,
+    /// How absolute mouse movements are simulated. Have a look at the
+    /// documentation of `AbsoluteMode`. The default is
+    /// `AbsoluteMode::Synthesized`
+    pub absolute_mode: AbsoluteMode,
+    /// Origin of the absolute coordinates of `Mouse::move_mouse`,
+    /// `Mouse::move_mouse_f`, `Mouse::move_mouse_checked` and
+    /// `Mouse::location`. The rectangle of `Mouse::confine_to` always has the
+    /// top left origin. Relative movements (`Coordinate::Rel`) are not
+    /// affected and always use the top left orientation, so a positive y
+    /// moves the mouse down even with `Origin::BottomLeft`. Have a look at the
+    /// documentation of `Origin`. The default is `Origin::TopLeft`
+    pub origin: Origin,
+    /// Maximum interval between two clicks of a mouse button so they count as
+    /// a double click. If it is `None`, the setting of the OS is used on
+    /// Windows and macOS and 400 ms (the default of GTK) on Linux. The default
+    /// is `None`
+    pub double_click_interval: Option<Duration>,
+    /// If this is true, `Button::Left` is the primary and `Button::Right` the
+    /// secondary button. They are swapped if the user configured the mouse for
+    /// left-handed use. If it is false, `Button::Left` is always the physical
+    /// left button. The swap is detected on Windows and macOS. On Linux the
+    /// setting is not detected, so it is always the physical button.
+    /// The default is true.
+    pub respect_button_swap: bool,
+    /// Maximum number of events that are simulated per second. Each call of a
+    /// function of the `Keyboard` and `Mouse` traits that simulates input
+    /// counts as one event. Short bursts of up to this number of events are
+    /// allowed. This protects the session from being flooded with input (e.g.
+    /// by a runaway client of a remote desktop server). If it is `None`, there
+    /// is no limit. The default is `None`
+    pub max_events_per_second: Option<u32>,
+    /// What happens if the limit of `max_events_per_second` is reached. The
+    /// default is `RateLimitBehavior::Block`
+    pub rate_limit_behavior: RateLimitBehavior,
+    /// Record the active window when `Enigo::execute_sequence` starts and
+    /// abort it if a different window becomes active, so the remaining tokens
+    /// are not received by the wrong application. This is not supported with
+    /// the `wayland` and `libei` features. The default is false.
+    pub focus_guard: bool,
+    /// Maximum time to wait for each connection to be established on Linux.
+    /// This includes the handshake with the XDG desktop portal when using
+    /// libei, which can block for a long time in misconfigured sessions. A
+    /// connection that did not finish in time is given up and
+    /// `NewConError::Timeout` is returned if no other connection was
+    /// established. If it is `None`, there is no limit. The default is `None`
+    pub connection_timeout: Option<Duration>,
+    /// Don't establish the connections on Linux in `Enigo::new`, but when
+    /// input is simulated for the first time (or `Enigo::connect` is called).
+    /// This avoids e.g. the dialog of the XDG desktop portal if no input is
+    /// ever simulated. Errors while connecting are then returned as
+    /// `InputError::Connect`. The default is false.
+    pub lazy_connect: bool,
 }
 
 impl Default for Settings {
@@ -477,12 +1934,31 @@ impl Default for Settings {
             linux_delay: 12,
             x11_display: None,
             wayland_display: None,
+            libei_seat: None,
+            libei_device: None,
             windows_dw_extra_info: None,
             event_source_user_data: None,
+            linux_emulate_key_repeat: false,
             release_keys_when_dropped: true,
             open_prompt_to_get_permissions: true,
             independent_of_keyboard_state: true,
+            macos_tap_location: TapLocation::Hid,
             windows_subject_to_mouse_speed_and_acceleration_level: false,
+            unicode_key_mode: UnicodeKeyMode::Character,
+            windows_text_entry_method: TextEntryMethod::Unicode,
+            windows_keyboard_layout: None,
+            windows_ime_strategy: ImeStrategy::Ignore,
+            unmappable_char_policy: UnmappableCharPolicy::Abort,
+            relative_mode: RelativeMode::Precise,
+            absolute_mode: AbsoluteMode::Synthesized,
+            origin: Origin::TopLeft,
+            double_click_interval: None,
+            respect_button_swap: true,
+            max_events_per_second: None,
+            rate_limit_behavior: RateLimitBehavior::Block,
+            focus_guard: false,
+            connection_timeout: None,
+            lazy_connect: false,
         }
     }
 }
@@ -0,0 +1,104 @@
+use log::{debug, error};
+use zbus::{
+    blocking::{connection::Builder, Connection, Proxy},
+    zvariant::OwnedObjectPath,
+};
+
+use crate::{a11y::FocusedElement, os::dbus::blocking, InputError, InputResult};
+
+/// Bus name of the registry of all accessible applications
+const REGISTRY: &str = "org.a11y.atspi.Registry";
+/// Path of the root of the accessibility tree
+const ROOT: &str = "/org/a11y/atspi/accessible/root";
+/// Interface of the elements of the accessibility tree
+const ACCESSIBLE: &str = "org.a11y.atspi.Accessible";
+
+// States of AT-SPI (`AtspiStateType`)
+const STATE_ACTIVE: u32 = 1;
+const STATE_EDITABLE: u32 = 7;
+const STATE_FOCUSED: u32 = 12;
+
+/// Maximum number of elements that are searched for the focused element
+const MAX_ELEMENTS: usize = 10_000;
+
+pub(crate) fn focused_element() -> InputResult<FocusedElement> {
+    blocking(|| Ok(find_focused_element())).map_err(|e| {
+        error!("{e}");
+        InputError::Simulate("unable to search for the focused element")
+    })?
+}
+
+/// Connect to the accessibility bus. Its address is provided on the session
+/// bus
+fn a11y_bus() -> zbus::Result<Connection> {
+    let session = Connection::session()?;
+    let address: String = Proxy::new(&session, "org.a11y.Bus", "/org/a11y/bus", "org.a11y.Bus")?
+        .call("GetAddress", &())?;
+    Builder::address(address.as_str())?.build()
+}
+
+/// Returns true if the state set contains the state
+fn has_state(states: &[u32], state: u32) -> bool {
+    states
+        .get((state / 32) as usize)
+        .is_some_and(|bits| bits & (1 << (state % 32)) != 0)
+}
+
+/// Search the accessibility tree for the focused element. Only the active
+/// windows of the applications are searched
+fn find_focused_element() -> InputResult<FocusedElement> {
+    let connection = a11y_bus().map_err(|e| {
+        error!("{e}");
+        InputError::Simulate("unable to connect to the accessibility bus")
+    })?;
+
+    let mut stack = vec![(
+        REGISTRY.to_string(),
+        OwnedObjectPath::try_from(ROOT).unwrap(),
+        0,
+    )];
+    let mut visited = 0;
+    while let Some((destination, path, depth)) = stack.pop() {
+        visited += 1;
+        if visited > MAX_ELEMENTS {
+            debug!("searched {MAX_ELEMENTS} elements without finding the focused one");
+            break;
+        }
+        let Ok(proxy) = Proxy::new(&connection, destination, path, ACCESSIBLE) else {
+            continue;
+        };
+        // The root and the applications have no states
+        if depth >= 2 {
+            let Ok(states) = proxy.call::<_, _, Vec<u32>>("GetState", &()) else {
+                continue;
+            };
+            if has_state(&states, STATE_FOCUSED) {
+                let role = proxy
+                    .call::<_, _, String>("GetRoleName", &())
+                    .map_err(|_| InputError::Simulate("unable to get the role"))?;
+                let name = proxy
+                    .get_property::<String>("Name")
+                    .ok()
+                    .filter(|name| !name.is_empty());
+                return Ok(FocusedElement {
+                    role,
+                    name,
+                    editable: has_state(&states, STATE_EDITABLE),
+                });
+            }
+            // Only the active window of an application can have the focus
+            if depth == 2 && !has_state(&states, STATE_ACTIVE) {
+                continue;
+            }
+        }
+        if let Ok(children) = proxy.call::<_, _, Vec<(String, OwnedObjectPath)>>("GetChildren", &())
+        {
+            stack.extend(
+                children
+                    .into_iter()
+                    .map(|(destination, path)| (destination, path, depth + 1)),
+            );
+        }
+    }
+    Err(InputError::Simulate("no element has the focus"))
+}
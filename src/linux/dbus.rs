@@ -0,0 +1,15 @@
+use std::thread;
+
+/// Run blocking D-Bus calls on a thread of their own. The blocking API of zbus
+/// drives the calls with a tokio runtime, which panics if it is started from
+/// within another runtime (e.g. if enigo is used in an async application)
+pub(crate) fn blocking<T: Send>(f: impl FnOnce() -> zbus::Result<T> + Send) -> zbus::Result<T> {
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .name("enigo-dbus".to_string())
+            .spawn_scoped(scope, f)
+            .map_err(|e| zbus::Error::Failure(format!("unable to spawn a thread: {e}")))?
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
@@ -0,0 +1,132 @@
+use std::{env, time::Duration};
+
+#[cfg(not(any(feature = "a11y", feature = "libei", feature = "wayland")))]
+use log::debug;
+
+use crate::health::HealthIssue;
+
+/// State of the session according to logind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SessionState {
+    pub(crate) locked: bool,
+    pub(crate) active: bool,
+}
+
+/// Ask logind if the current session is locked or inactive. Returns `None` if
+/// the state could not be determined (e.g. because there is no logind or enigo
+/// was compiled without D-Bus support)
+pub(crate) fn session_state() -> Option<SessionState> {
+    #[cfg(any(feature = "a11y", feature = "libei", feature = "wayland"))]
+    return logind::session_state();
+    #[cfg(not(any(feature = "a11y", feature = "libei", feature = "wayland")))]
+    {
+        debug!("the state of the session is unknown without D-Bus support");
+        None
+    }
+}
+
+/// Ask logind how long the session is idle. Returns `None` if it could not be
+/// determined
+pub(crate) fn idle_time() -> Option<Duration> {
+    #[cfg(any(feature = "a11y", feature = "libei", feature = "wayland"))]
+    return logind::idle_time();
+    #[cfg(not(any(feature = "a11y", feature = "libei", feature = "wayland")))]
+    {
+        debug!("the idle time of the session is unknown without D-Bus support");
+        None
+    }
+}
+
+#[cfg(any(feature = "a11y", feature = "libei", feature = "wayland"))]
+mod logind {
+    use std::{env, sync::OnceLock, time::Duration};
+
+    use log::{debug, trace};
+    use zbus::{
+        blocking::{proxy::Builder, Connection, Proxy},
+        proxy::CacheProperties,
+        zvariant::OwnedObjectPath,
+    };
+
+    use super::SessionState;
+    use crate::os::dbus::blocking;
+
+    const LOGIN1: &str = "org.freedesktop.login1";
+    const LOGIN1_PATH: &str = "/org/freedesktop/login1";
+    const MANAGER: &str = "org.freedesktop.login1.Manager";
+    const SESSION: &str = "org.freedesktop.login1.Session";
+
+    /// Proxy of the session of this process. Connected on first use. Stays
+    /// `None` if there is no logind or the process does not belong to a
+    /// session
+    static CURRENT_SESSION: OnceLock<Option<Proxy<'static>>> = OnceLock::new();
+
+    fn connect() -> Option<Proxy<'static>> {
+        blocking(|| {
+            let connection = Connection::system()?;
+            let manager = Proxy::new(&connection, LOGIN1, LOGIN1_PATH, MANAGER)?;
+            let path: OwnedObjectPath = if let Ok(id) = env::var("XDG_SESSION_ID") {
+                manager.call("GetSession", &(id,))?
+            } else {
+                manager.call("GetSessionByPID", &(std::process::id(),))?
+            };
+            trace!("the session of the process is {}", path.as_str());
+            // The properties are read on demand, because they are only needed
+            // occasionally
+            Builder::<Proxy<'static>>::new(&connection)
+                .destination(LOGIN1)?
+                .path(path)?
+                .interface(SESSION)?
+                .cache_properties(CacheProperties::No)
+                .build()
+        })
+        .inspect_err(|e| debug!("unable to find the session with logind: {e}"))
+        .ok()
+    }
+
+    pub(super) fn session_state() -> Option<SessionState> {
+        let session = CURRENT_SESSION.get_or_init(connect).as_ref()?;
+        blocking(|| {
+            let locked: bool = session.get_property("LockedHint")?;
+            let active: bool = session.get_property("Active")?;
+            Ok(SessionState { locked, active })
+        })
+        .inspect(|state| trace!("session state: {state:?}"))
+        .inspect_err(|e| debug!("unable to get the state of the session from logind: {e}"))
+        .ok()
+    }
+
+    pub(super) fn idle_time() -> Option<Duration> {
+        let session = CURRENT_SESSION.get_or_init(connect).as_ref()?;
+        let (idle, since) = blocking(|| {
+            let idle: bool = session.get_property("IdleHint")?;
+            let since: u64 = session.get_property("IdleSinceHintMonotonic")?;
+            Ok((idle, since))
+        })
+        .inspect_err(|e| debug!("unable to get the idle hint of the session from logind: {e}"))
+        .ok()?;
+        if !idle {
+            return Some(Duration::ZERO);
+        }
+        if since == 0 {
+            return None;
+        }
+        Some(crate::os::system::monotonic_now()?.saturating_sub(Duration::from_micros(since)))
+    }
+}
+
+pub(crate) fn issues() -> Vec<HealthIssue> {
+    let mut issues = Vec::new();
+    if env::var_os("DISPLAY").is_none() && env::var_os("WAYLAND_DISPLAY").is_none() {
+        issues.push(HealthIssue::NoDisplay);
+    }
+    if let Some(state) = session_state() {
+        if state.locked {
+            issues.push(HealthIssue::SessionLocked);
+        }
+        if !state.active {
+            issues.push(HealthIssue::SessionInactive);
+        }
+    }
+    issues
+}
@@ -0,0 +1,226 @@
+use std::{
+    os::unix::process::CommandExt,
+    process::{Child, Command, Stdio},
+};
+
+use log::{debug, error};
+
+use crate::{InputError, InputResult};
+
+/// Holds a logind inhibitor lock and an inhibition of the screen saver. Both
+/// are released when the process holding the lock exits or the D-Bus
+/// connection is closed
+#[derive(Debug)]
+pub(crate) struct Inhibitor {
+    /// File descriptor of the logind inhibitor lock. The kernel closes it when
+    /// the process dies, so the lock can't outlive it
+    #[cfg(any(feature = "a11y", feature = "libei"))]
+    _logind: Option<std::os::fd::OwnedFd>,
+    /// Fallback if logind can't be reached over D-Bus
+    systemd_inhibit: Option<Child>,
+    #[cfg(any(feature = "a11y", feature = "libei"))]
+    _screensaver: Option<screensaver::Inhibition>,
+}
+
+impl Inhibitor {
+    pub(crate) fn new(reason: &str) -> InputResult<Self> {
+        #[cfg(any(feature = "a11y", feature = "libei"))]
+        let logind = logind::inhibit(reason);
+        #[cfg(any(feature = "a11y", feature = "libei"))]
+        let systemd_inhibit = if logind.is_some() {
+            None
+        } else {
+            spawn_systemd_inhibit(reason)
+        };
+        #[cfg(not(any(feature = "a11y", feature = "libei")))]
+        let systemd_inhibit = spawn_systemd_inhibit(reason);
+
+        #[cfg(any(feature = "a11y", feature = "libei"))]
+        let screensaver = screensaver::Inhibition::new(reason);
+        let inhibited = systemd_inhibit.is_some();
+        #[cfg(any(feature = "a11y", feature = "libei"))]
+        let inhibited = inhibited || logind.is_some() || screensaver.is_some();
+
+        if !inhibited {
+            return Err(InputError::Simulate(
+                "unable to inhibit the screen saver and the system sleep",
+            ));
+        }
+        Ok(Self {
+            #[cfg(any(feature = "a11y", feature = "libei"))]
+            _logind: logind,
+            systemd_inhibit,
+            #[cfg(any(feature = "a11y", feature = "libei"))]
+            _screensaver: screensaver,
+        })
+    }
+}
+
+/// Run `systemd-inhibit` to hold the lock. The child gets killed when the
+/// thread that spawned it exits, so the lock is released even if `Drop` is
+/// never run (e.g. because the process got killed)
+fn spawn_systemd_inhibit(reason: &str) -> Option<Child> {
+    // SAFETY: getpid is always safe to call
+    let parent = unsafe { libc::getpid() };
+    let mut command = Command::new("systemd-inhibit");
+    command
+        .args([
+            "--what=idle:sleep",
+            "--who=enigo",
+            &format!("--why={reason}"),
+            "--mode=block",
+            "sleep",
+            "infinity",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // SAFETY: The closure runs in the child between fork and exec and only
+    // calls the async-signal-safe functions prctl and getppid
+    unsafe {
+        command.pre_exec(move || {
+            if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            // The parent died before the signal was set up
+            if libc::getppid() != parent {
+                return Err(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+            }
+            Ok(())
+        });
+    }
+    command
+        .spawn()
+        .inspect_err(|e| debug!("unable to run systemd-inhibit: {e}"))
+        .ok()
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.systemd_inhibit.take() {
+            if child.kill().is_err() {
+                error!("unable to stop systemd-inhibit");
+            }
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(any(feature = "a11y", feature = "libei"))]
+mod logind {
+    use std::os::fd::OwnedFd;
+
+    use log::debug;
+    use zbus::{Connection, Proxy};
+
+    const LOGIN1: &str = "org.freedesktop.login1";
+    const LOGIN1_PATH: &str = "/org/freedesktop/login1";
+    const MANAGER: &str = "org.freedesktop.login1.Manager";
+
+    /// Take an inhibitor lock from logind. The lock is held until the returned
+    /// file descriptor is closed
+    pub(super) fn inhibit(reason: &str) -> Option<OwnedFd> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .ok()?;
+        let fd = runtime.block_on(async {
+            let connection = Connection::system().await?;
+            let proxy = Proxy::new(&connection, LOGIN1, LOGIN1_PATH, MANAGER).await?;
+            let fd: zbus::zvariant::OwnedFd = proxy
+                .call("Inhibit", &("idle:sleep", "enigo", reason, "block"))
+                .await?;
+            zbus::Result::Ok(fd)
+        });
+        fd.map(OwnedFd::from)
+            .inspect_err(|e| debug!("unable to take the logind inhibitor lock: {e}"))
+            .ok()
+    }
+}
+
+#[cfg(any(feature = "a11y", feature = "libei"))]
+mod screensaver {
+    use std::{
+        sync::mpsc::{self, Sender},
+        thread::{self, JoinHandle},
+    };
+
+    use log::{debug, error};
+    use zbus::{Connection, Proxy};
+
+    const SCREENSAVER: &str = "org.freedesktop.ScreenSaver";
+    const SCREENSAVER_PATH: &str = "/org/freedesktop/ScreenSaver";
+
+    /// The inhibition lasts as long as the D-Bus connection, so a thread of
+    /// its own keeps it open until the inhibition is dropped
+    #[derive(Debug)]
+    pub(super) struct Inhibition {
+        stop: Option<Sender<()>>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Inhibition {
+        pub(super) fn new(reason: &str) -> Option<Self> {
+            let reason = reason.to_string();
+            let (stop_tx, stop_rx) = mpsc::channel::<()>();
+            let (started_tx, started_rx) = mpsc::channel();
+            let thread = thread::Builder::new()
+                .name("enigo-keep-awake".to_string())
+                .spawn(move || {
+                    let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                    else {
+                        let _ = started_tx.send(false);
+                        return;
+                    };
+                    let inhibited = runtime.block_on(async {
+                        let connection = Connection::session().await?;
+                        let proxy =
+                            Proxy::new(&connection, SCREENSAVER, SCREENSAVER_PATH, SCREENSAVER)
+                                .await?;
+                        let cookie: u32 = proxy.call("Inhibit", &("enigo", reason)).await?;
+                        zbus::Result::Ok((proxy, cookie))
+                    });
+                    let (proxy, cookie) = match inhibited {
+                        Ok(inhibited) => inhibited,
+                        Err(e) => {
+                            debug!("unable to inhibit the screen saver: {e}");
+                            let _ = started_tx.send(false);
+                            return;
+                        }
+                    };
+                    let _ = started_tx.send(true);
+                    // Returns an error once the sender was dropped
+                    let _ = stop_rx.recv();
+                    if runtime
+                        .block_on(proxy.call::<_, _, ()>("UnInhibit", &(cookie,)))
+                        .is_err()
+                    {
+                        error!("unable to stop inhibiting the screen saver");
+                    }
+                })
+                .ok()?;
+
+            if started_rx.recv() != Ok(true) {
+                let _ = thread.join();
+                return None;
+            }
+            Some(Self {
+                stop: Some(stop_tx),
+                thread: Some(thread),
+            })
+        }
+    }
+
+    impl Drop for Inhibition {
+        fn drop(&mut self) {
+            drop(self.stop.take());
+            if let Some(thread) = self.thread.take() {
+                if thread.join().is_err() {
+                    error!("the thread inhibiting the screen saver panicked");
+                }
+            }
+        }
+    }
+}
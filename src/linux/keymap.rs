@@ -26,6 +26,8 @@ pub struct KeyMap<Keycode> {
     #[cfg(feature = "wayland")]
     pub(super) file: Option<std::fs::File>, // temporary file that contains the keymap
     #[cfg(feature = "wayland")]
+    keymap_mappings: Option<Vec<(usize, Keysym)>>, // sorted mappings in the file
+    #[cfg(feature = "wayland")]
     modifiers: ModifierBitflag, // state of the modifiers
     #[cfg(feature = "x11rb")]
     last_keys: Vec<Keycode>, // last pressed keycodes
@@ -47,9 +49,6 @@ impl<
             + TryInto<usize>
             + std::convert::TryFrom<usize>,
     > KeyMap<Keycode>
-where
-    <Keycode as TryInto<usize>>::Error: std::fmt::Debug,
-    <Keycode as TryFrom<usize>>::Error: std::fmt::Debug,
 {
     /// Create a new `KeyMap`
     pub fn new(
@@ -59,14 +58,19 @@ where
         keysyms_per_keycode: u8,
         keysyms: Vec<u32>,
     ) -> Self {
-        let capacity: usize = keycode_max.try_into().unwrap() - keycode_min.try_into().unwrap();
-        let capacity = capacity + 1;
+        // The capacity is only a hint, so invalid bounds don't need to be an error
+        let capacity = match (keycode_max.try_into(), keycode_min.try_into()) {
+            (Ok(max), Ok(min)) => usize::saturating_sub(max, min).saturating_add(1),
+            _ => 0,
+        };
         let keymap = HashMap::with_capacity(capacity);
         let held_keycodes = vec![];
         let needs_regeneration = true;
         #[cfg(feature = "wayland")]
         let file = None;
         #[cfg(feature = "wayland")]
+        let keymap_mappings = None;
+        #[cfg(feature = "wayland")]
         let modifiers = 0;
         #[cfg(feature = "x11rb")]
         let last_keys = vec![];
@@ -88,6 +92,8 @@ where
             #[cfg(feature = "wayland")]
             file,
             #[cfg(feature = "wayland")]
+            keymap_mappings,
+            #[cfg(feature = "wayland")]
             modifiers,
             #[cfg(feature = "x11rb")]
             last_keys,
@@ -100,18 +106,18 @@ where
         }
     }
 
-    fn keysym_to_keycode(&self, keysym: Keysym) -> Option<Keycode> {
-        let keycode_min: usize = self.keycode_min.try_into().unwrap();
-        let keycode_max: usize = self.keycode_max.try_into().unwrap();
+    /// Search the first `levels` columns of the keymap for the keysym
+    fn keysym_to_keycode(&self, keysym: Keysym, levels: u8) -> Option<Keycode> {
+        let keycode_min: usize = self.keycode_min.try_into().ok()?;
+        let keycode_max: usize = self.keycode_max.try_into().ok()?;
+        let min_keycode = KeyCode::from(u32::try_from(keycode_min).ok()?);
 
-        // TODO: Change this range to 0..self.keysyms_per_keycode once we find out how
-        // to detect the level and switch it
-        for j in 0..1 {
+        for j in 0..levels {
             for i in keycode_min..=keycode_max {
-                let i: u32 = i.try_into().unwrap();
-                let min_keycode: u32 = keycode_min.try_into().unwrap();
-                let keycode = KeyCode::from(i);
-                let min_keycode = KeyCode::from(min_keycode);
+                let Ok(raw_keycode) = u32::try_from(i) else {
+                    break;
+                };
+                let keycode = KeyCode::from(raw_keycode);
                 if let Some(ks) = xkeysym::keysym(
                     keycode,
                     j,
@@ -120,8 +126,7 @@ where
                     &self.keysyms,
                 ) {
                     if ks == keysym {
-                        let i: usize = i.try_into().unwrap();
-                        let i: Keycode = i.try_into().unwrap();
+                        let i: Keycode = i.try_into().ok()?;
                         trace!("found keysym in row {i}, col {j}");
                         return Some(i);
                     }
@@ -136,7 +141,9 @@ where
     pub fn key_to_keycode<C: Bind<Keycode>>(&mut self, c: &C, key: Key) -> InputResult<Keycode> {
         let sym = Keysym::from(key);
 
-        if let Some(keycode) = self.keysym_to_keycode(sym) {
+        // TODO: Search all self.keysyms_per_keycode levels once we find out how
+        // to detect the level and switch it
+        if let Some(keycode) = self.keysym_to_keycode(sym, 1) {
             return Ok(keycode);
         }
 
@@ -158,6 +165,20 @@ where
         Ok(keycode)
     }
 
+    /// Get the keycode of the key that produces the keysym on any level of the
+    /// layout without mapping it
+    #[cfg(feature = "x11rb")]
+    pub fn layout_keycode(&mut self, key: Key) -> InputResult<Keycode> {
+        let sym = Keysym::from(key);
+        let Some(keycode) = self.keysym_to_keycode(sym, self.keysyms_per_keycode) else {
+            return Err(InputError::Mapping(format!(
+                "no key of the active layout produces {sym:?}"
+            )));
+        };
+        self.update_delays(keycode);
+        Ok(keycode)
+    }
+
     /// Get the pending delay
     #[cfg(feature = "x11rb")]
     pub fn pending_delays(&self) -> u32 {
@@ -283,21 +304,43 @@ where
             return Ok(None);
         }
 
+        // Sort the mappings so the same mappings always result in the same keymap
+        let mut mappings: Vec<(usize, Keysym)> = self
+            .additionally_mapped
+            .iter()
+            .map(|(&keysym, &keycode)| {
+                let keycode = keycode.try_into().map_err(|_| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("the keycode {keycode} is out of range"),
+                    )
+                })?;
+                Ok((keycode, keysym))
+            })
+            .collect::<Result<_, std::io::Error>>()?;
+        mappings.sort_unstable_by_key(|&(keycode, keysym)| (keycode, keysym.raw()));
+
+        // Skip writing the keymap if it is the same as the one the compositor
+        // already has (e.g. a key was mapped and unmapped again)
+        if self.keymap_mappings.as_ref() == Some(&mappings) {
+            debug!("the regenerated keymap is identical to the current one");
+            self.needs_regeneration = false;
+            return Ok(None);
+        }
+
         // Create a file to store the layout
-        if self.file.is_none() {
+        let keymap_file = if let Some(file) = self.file.take() {
+            file
+        } else {
             let mut temp_file = tempfile::tempfile()?;
             temp_file.write_all(KEYMAP_BEGINNING)?;
-            self.file = Some(temp_file);
-        }
-
-        let keymap_file = self
-            .file
-            .as_mut()
-            .expect("There was no file to write to. This should not be possible!");
+            temp_file
+        };
+        let keymap_file = self.file.insert(keymap_file);
         // Move the virtual cursor of the file to the end of the part of the keymap that
         // is always the same so we only overwrite the parts that can change.
         keymap_file.seek(SeekFrom::Start(KEYMAP_BEGINNING.len() as u64))?;
-        for (&keysym, &keycode) in &self.additionally_mapped {
+        for &(keycode, keysym) in &mappings {
             write!(
                 keymap_file,
                 "
@@ -312,6 +355,7 @@ where
         let keymap_len = keymap_file.stream_position()?;
         keymap_file.set_len(keymap_len)?;
         self.needs_regeneration = false;
+        self.keymap_mappings = Some(mappings);
         match keymap_len.try_into() {
             Ok(v) => {
                 debug!("regenerated the keymap");
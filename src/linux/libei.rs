@@ -1,18 +1,31 @@
-use ashpd::desktop::remote_desktop::RemoteDesktop;
+use ashpd::desktop::{remote_desktop::RemoteDesktop, Session};
 use log::{debug, error, trace, warn};
 use reis::{
     ei::{self, Connection},
     handshake::HandshakeResp,
     PendingRequestResult,
 };
-use std::{collections::HashMap, os::unix::net::UnixStream, time::Instant};
+use std::{
+    collections::HashMap,
+    os::unix::{io::AsRawFd, net::UnixStream},
+    time::{Duration, Instant},
+};
 use xkbcommon::xkb;
 
+use super::xkb_keymap::Keymap;
 use crate::{
-    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse, NewConError,
+    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse,
+    NewConError, ScrollUnit,
 };
 pub type Keycode = u32;
 
+/// Time to wait for the replies of the EIS implementation when setting up the
+/// connection
+const SETUP_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Time to wait for the EIS implementation to acknowledge the requests
+const SYNC_TIMEOUT: Duration = Duration::from_secs(1);
+
 static INTERFACES: once_cell::sync::Lazy<HashMap<&'static str, u32>> =
     once_cell::sync::Lazy::new(|| {
         let mut m = HashMap::new();
@@ -66,6 +79,12 @@ impl DeviceData {
     fn interface<T: reis::Interface>(&self) -> Option<T> {
         self.interfaces.get(T::NAME)?.clone().downcast()
     }
+
+    /// Returns true if no device was selected or the device has the selected
+    /// name
+    fn is_selected(&self, device_name: Option<&str>) -> bool {
+        device_name.is_none_or(|name| self.name.as_deref() == Some(name))
+    }
 }
 
 /// The main struct for handling the event emitting
@@ -78,13 +97,25 @@ pub struct Con {
     // TODO: Release device when dropped, so compositor knows it wont be used anymore
     devices: HashMap<ei::Device, DeviceData>,
     keyboards: HashMap<ei::Keyboard, xkb::Keymap>,
+    /// Name of the seat to bind to. Binds to all seats if it is `None`
+    seat_name: Option<String>,
+    /// Name of the device to use. Uses the first suitable device if it is
+    /// `None`
+    device_name: Option<String>,
+    /// Index of the effective layout group of the keyboards
+    group: u32,
+    /// Locked modifiers of the keyboards
+    locked: u32,
+    /// Incremented every time the keymap or the layout group changes
+    layout_generation: u64,
     /// `None` if there was no disconnect
     disconnect: Option<(ei::connection::DisconnectReason, String)>,
     sequence: u32,
     last_serial: u32,
     context: ei::Context,
     connection: Connection,
-    time_created: Instant,
+    /// Time of the frames that was set with `Enigo::set_event_time`
+    event_time: Option<Duration>,
 }
 
 // This is safe, we have a unique pointer.
@@ -128,26 +159,60 @@ impl Con {
         }
     }
 
-    #[allow(clippy::unnecessary_wraps)]
+    /// Connect to the EIS implementation of a remote desktop session that was
+    /// already started
+    pub async fn connect_to_session(
+        remote_desktop: &RemoteDesktop<'_>,
+        session: &Session<'_, RemoteDesktop<'_>>,
+    ) -> Result<ei::Context, NewConError> {
+        trace!("connect_to_session");
+        let fd = remote_desktop.connect_to_eis(session).await.map_err(|e| {
+            error!("{e}");
+            NewConError::EstablishCon("unable to connect to the EIS of the portal session")
+        })?;
+        let stream = UnixStream::from(fd);
+        stream.set_nonblocking(true).map_err(|_| {
+            NewConError::EstablishCon("unable to make the libei socket non-blocking")
+        })?;
+        ei::Context::new(stream)
+            .map_err(|_| NewConError::EstablishCon("unable to create the libei context"))
+    }
+
     /// Create a new Enigo instance
-    pub fn new() -> Result<Self, NewConError> {
+    ///
+    /// If `seat_name` or `device_name` are provided, only the seat and devices
+    /// with these names are used to simulate input
+    pub fn new(seat_name: Option<&str>, device_name: Option<&str>) -> Result<Self, NewConError> {
         debug!("using libei");
 
+        // Initialize a Tokio runtime
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|_| NewConError::EstablishCon("failed to create tokio runtime"))?;
+
+        // Block on an async function within this runtime
+        let context = runtime.block_on(async { Self::open_connection().await });
+
+        Self::with_context(context, seat_name, device_name)
+    }
+
+    /// Create a new Enigo instance that uses an already opened libei context
+    ///
+    /// If `seat_name` or `device_name` are provided, only the seat and devices
+    /// with these names are used to simulate input
+    pub fn with_context(
+        context: ei::Context,
+        seat_name: Option<&str>,
+        device_name: Option<&str>,
+    ) -> Result<Self, NewConError> {
         let libei_name = "enigo";
 
         let seats = HashMap::new();
         let devices = HashMap::new();
         let keyboards = HashMap::new();
+        let group = 0;
+        let layout_generation = 0;
         let disconnect = None;
         let sequence = 0;
-        let time_created = Instant::now();
-
-        // Initialize a Tokio runtime
-        let runtime = tokio::runtime::Runtime::new()
-            .map_err(|_| NewConError::EstablishCon("failed to create tokio runtime"))?;
-
-        // Block on an async function within this runtime
-        let context = runtime.block_on(async { Self::open_connection().await });
 
         let HandshakeResp {
             connection,
@@ -171,15 +236,20 @@ impl Con {
             seats,
             devices,
             keyboards,
+            seat_name: seat_name.map(str::to_string),
+            device_name: device_name.map(str::to_string),
+            group,
+            locked: 0,
+            layout_generation,
             disconnect,
             sequence,
             last_serial: serial.wrapping_add(1),
             context,
             connection,
-            time_created,
+            event_time: None,
         };
 
-        con.update(libei_name)
+        con.update(libei_name, SETUP_TIMEOUT)
             .map_err(|_| NewConError::EstablishCon("unable to update the libei connection"))?;
 
         for (device, device_data) in con.devices.iter_mut().filter(|(_, ref device_data)| {
@@ -194,16 +264,49 @@ impl Con {
             device_data.state = DeviceState::Emulating;
         }
 
-        con.update(libei_name)
+        con.update(libei_name, SETUP_TIMEOUT)
             .map_err(|_| NewConError::EstablishCon("unable to update the libei connection"))?;
 
         Ok(con)
     }
 
-    #[allow(clippy::too_many_lines)]
-    fn update(&mut self, libei_name: &str) -> InputResult<()> {
-        let mut had_pending_events = true;
+    /// Wait until the EIS implementation sent new data or the timeout elapsed.
+    /// Returns true if there is data to read
+    fn wait_for_events(&self, timeout: Duration) -> InputResult<bool> {
+        let mut fds = [libc::pollfd {
+            fd: self.context.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        }];
+        let timeout = timeout.as_millis().try_into().unwrap_or(libc::c_int::MAX);
+        // SAFETY: fds is a valid array of one pollfd that outlives the call and
+        // the file descriptor of the context stays open while self is borrowed
+        match unsafe { libc::poll(fds.as_mut_ptr(), 1, timeout) } {
+            -1 => {
+                let err = std::io::Error::last_os_error();
+                if err.kind() == std::io::ErrorKind::Interrupted {
+                    return Ok(true);
+                }
+                error!("polling the libei socket failed: {err}");
+                Err(InputError::Simulate("Failed to poll the libei socket"))
+            }
+            0 => Ok(false),
+            _ => Ok(true),
+        }
+    }
 
+    /// Handle all events of the EIS implementation. After the requests were
+    /// flushed and acknowledged, it waits up to `timeout` for new events and
+    /// returns once no more events arrived in that time
+    #[allow(clippy::too_many_lines)]
+    fn update(&mut self, libei_name: &str, timeout: Duration) -> InputResult<()> {
+        // The EIS implementation only answers the sync request after it
+        // processed all requests that were sent before it. Once the callback is
+        // done, the input was received and the process can e.g. exit without
+        // losing it
+        let sync = self.connection.sync(1);
+        let mut synced = false;
+        let start = Instant::now();
         loop {
             debug!("update");
             if self.context.read().is_err() {
@@ -212,7 +315,6 @@ impl Con {
             }
 
             while let Some(result) = self.context.pending_event() {
-                had_pending_events = true;
                 trace!("found pending_event");
 
                 let request = match result {
@@ -306,6 +408,12 @@ impl Con {
                                 data.capabilities.insert(interface, mask);
                             }
                             ei::seat::Event::Done => {
+                                if let Some(seat_name) = &self.seat_name {
+                                    if data.name.as_ref() != Some(seat_name) {
+                                        debug!("not binding to the seat {:?}", data.name);
+                                        continue;
+                                    }
+                                }
                                 let mut bitmask = 0;
                                 if let Some(bits) = data.capabilities.get("ei_button") {
                                     bitmask |= bits;
@@ -396,6 +504,12 @@ impl Con {
                             }
                         }
                     }
+                    ei::Event::Callback(callback, ei::callback::Event::Done { .. }) => {
+                        if callback == sync {
+                            trace!("the requests were acknowledged");
+                            synced = true;
+                        }
+                    }
                     ei::Event::Keyboard(keyboard, request) => {
                         trace!("keyboard event");
                         match request {
@@ -408,24 +522,33 @@ impl Con {
                                 size,
                                 keymap,
                             } => {
+                                // The keymap comes from the EIS implementation. A
+                                // malformed one must not take down the
+                                // application, so the keyboard is ignored instead
                                 if keymap_type != ei::keyboard::KeymapType::Xkb {
                                     error!("The keymap is of the wrong type");
+                                    continue;
                                 }
                                 let context = xkb::Context::new(0);
-                                self.keyboards.insert(
-                                    keyboard,
-                                    unsafe {
-                                        xkb::Keymap::new_from_fd(
-                                            &context,
-                                            keymap,
-                                            size as _,
-                                            xkb::KEYMAP_FORMAT_TEXT_V1,
-                                            0,
-                                        )
+                                let compiled = unsafe {
+                                    xkb::Keymap::new_from_fd(
+                                        &context,
+                                        keymap,
+                                        size as _,
+                                        xkb::KEYMAP_FORMAT_TEXT_V1,
+                                        0,
+                                    )
+                                };
+                                match compiled {
+                                    Ok(Some(compiled)) => {
+                                        self.keyboards.insert(keyboard, compiled);
+                                        debug!("received a new keymap");
+                                        self.layout_generation =
+                                            self.layout_generation.wrapping_add(1);
                                     }
-                                    .unwrap()
-                                    .unwrap(),
-                                );
+                                    Ok(None) => error!("unable to compile the keymap"),
+                                    Err(e) => error!("unable to read the keymap: {e}"),
+                                }
                             }
                             ei::keyboard::Event::Modifiers {
                                 serial,
@@ -433,12 +556,20 @@ impl Con {
                                 locked,
                                 latched,
                                 group,
-                            } => { // TODO: Handle updated modifiers
-                                 // Notification that the EIS
-                                 // implementation has changed modifier states
-                                 // on this device. Future ei_keyboard.key
-                                 // requests must take the new modifier state
-                                 // into account.
+                            } => {
+                                // TODO: Handle updated modifiers
+                                // Notification that the EIS
+                                // implementation has changed modifier states
+                                // on this device. Future ei_keyboard.key
+                                // requests must take the new modifier state
+                                // into account.
+                                trace!("modifiers event");
+                                self.locked = locked;
+                                if self.group != group {
+                                    debug!("the effective layout group is now {group}");
+                                    self.group = group;
+                                    self.layout_generation = self.layout_generation.wrapping_add(1);
+                                }
                             }
                             _ => {}
                         }
@@ -457,20 +588,127 @@ impl Con {
                 error!("flush fail");
             }
 
-            // This is needed so anything is typed
-            std::thread::sleep(std::time::Duration::from_millis(10));
             trace!("update flush");
-            trace!("update done");
 
-            // We can stop looking for updates if there was no event to be handled
-            // previously
-            if !had_pending_events {
+            // We can stop looking for updates once the requests were acknowledged
+            // and there was no new event before the timeout
+            let wait = if synced {
+                timeout
+            } else {
+                SYNC_TIMEOUT.saturating_sub(start.elapsed())
+            };
+            if !self.wait_for_events(wait)? {
+                if !synced {
+                    warn!("the EIS implementation did not acknowledge the requests in time");
+                }
+                trace!("update done");
                 break;
             }
-            had_pending_events = false;
         }
         Ok(())
     }
+
+    /// Set the time of the frames in microseconds of `CLOCK_MONOTONIC`
+    pub fn set_event_time(&mut self, time: Option<Duration>) {
+        self.event_time = time;
+    }
+
+    /// Returns the names of all seats the EIS implementation offered
+    pub fn seats(&self) -> Vec<String> {
+        self.seats
+            .values()
+            .filter_map(|seat_data| seat_data.name.clone())
+            .collect()
+    }
+
+    /// Returns the names of all devices that were added to the bound seats
+    pub fn devices(&self) -> Vec<String> {
+        self.devices
+            .values()
+            .filter_map(|device_data| device_data.name.clone())
+            .collect()
+    }
+
+    /// Returns the names of the layout groups of the keymap of the keyboard
+    pub fn layout_groups(&self) -> InputResult<Vec<String>> {
+        let Some(keymap) = self.keyboards.values().next() else {
+            return Err(InputError::Simulate("no keyboard with a keymap available"));
+        };
+        Ok((0..keymap.num_layouts())
+            .map(|idx| keymap.layout_get_name(idx).to_string())
+            .collect())
+    }
+
+    /// Returns the keymap of the keyboard
+    pub fn keymap(&self) -> InputResult<Keymap> {
+        self.keyboards
+            .values()
+            .next()
+            .map(|keymap| Keymap::from(keymap.clone()))
+            .ok_or(InputError::Simulate("no keyboard with a keymap available"))
+    }
+
+    /// Returns the index of the currently effective layout group
+    #[allow(clippy::unnecessary_wraps)]
+    pub fn layout_group(&self) -> InputResult<u32> {
+        Ok(self.group)
+    }
+
+    /// Returns a counter that is incremented every time the EIS implementation
+    /// sent a new keymap or changed the layout group
+    pub fn layout_generation(&mut self) -> InputResult<u64> {
+        self.update("enigo", Duration::ZERO).map_err(|_| {
+            InputError::Simulate("unable to update the libei connection to get the layout")
+        })?;
+        Ok(self.layout_generation)
+    }
+
+    /// Returns true if the modifier of `Key::CapsLock` or `Key::Numlock` is
+    /// locked
+    pub fn lock_state(&mut self, key: Key) -> InputResult<bool> {
+        self.update("enigo", Duration::ZERO).map_err(|_| {
+            InputError::Simulate("unable to update the libei connection to get the modifiers")
+        })?;
+        let name = match key {
+            Key::CapsLock => xkb::MOD_NAME_CAPS,
+            Key::Numlock => xkb::MOD_NAME_NUM,
+            Key::ScrollLock => {
+                return Err(InputError::Simulate(
+                    "the state of Scroll Lock is not available with libei",
+                ))
+            }
+            _ => return Err(InputError::InvalidInput("the key is not a lock key")),
+        };
+        let Some(keymap) = self.keyboards.values().next() else {
+            return Err(InputError::Simulate("no keyboard with a keymap available"));
+        };
+        let idx = keymap.mod_get_index(name);
+        if idx == xkb::MOD_INVALID {
+            return Err(InputError::Simulate(
+                "the keymap has no modifier for the key",
+            ));
+        }
+        Ok(self.locked & (1 << idx) != 0)
+    }
+
+    /// The locked modifiers are owned by the EIS implementation, so the lock
+    /// key is clicked if the state differs
+    pub fn set_lock_state(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        if self.lock_state(key)? != locked {
+            self.key(key, Direction::Click)?;
+        }
+        Ok(())
+    }
+
+    /// The layout group is owned by the EIS implementation. There is no way to
+    /// change it with libei
+    #[allow(clippy::unused_self)]
+    pub fn set_layout_group(&mut self, group: u32) -> InputResult<()> {
+        warn!("unable to switch to the layout group {group}");
+        Err(InputError::Simulate(
+            "switching the layout group is not possible with libei",
+        ))
+    }
 }
 
 impl Keyboard for Con {
@@ -481,13 +719,13 @@ impl Keyboard for Con {
     }
 
     fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
-        if let Some((device, device_data)) = self
-            .devices
-            .iter_mut()
-            .find(|(_, ref device_data)| device_data.interface::<ei::Keyboard>().is_some())
-        {
-            if let Some((keyboard, keymap)) = self.keyboards.iter().next() {
-                let keycode = key_to_keycode(keymap, key)?;
+        if let Some((device, device_data)) = self.devices.iter_mut().find(|(_, device_data)| {
+            device_data.interface::<ei::Keyboard>().is_some()
+                && device_data.is_selected(self.device_name.as_deref())
+        }) {
+            let keyboard = device_data.interface::<ei::Keyboard>().unwrap();
+            if let Some(keymap) = self.keyboards.get(&keyboard) {
+                let keycode = key_to_keycode(keymap, self.group, key)?;
 
                 if direction == Direction::Press || direction == Direction::Click {
                     keyboard.key(keycode - 8, ei::keyboard::KeyState::Press);
@@ -496,11 +734,11 @@ impl Keyboard for Con {
                     keyboard.key(keycode - 8, ei::keyboard::KeyState::Released);
                 }
 
-                let elapsed = self.time_created.elapsed().as_secs(); // Is seconds fine?
+                let elapsed = frame_time(self.event_time);
 
                 device.frame(self.sequence, elapsed);
                 self.sequence = self.sequence.wrapping_add(1);
-                self.update("enigo").map_err(|_| {
+                self.update("enigo", Duration::ZERO).map_err(|_| {
                     InputError::Simulate("unable to update the libei connection to scroll")
                 })?;
             }
@@ -508,14 +746,54 @@ impl Keyboard for Con {
         Ok(())
     }
 
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        if let Some((device, device_data)) = self.devices.iter_mut().find(|(_, device_data)| {
+            device_data.interface::<ei::Keyboard>().is_some()
+                && device_data.is_selected(self.device_name.as_deref())
+        }) {
+            let keyboard = device_data.interface::<ei::Keyboard>().unwrap();
+            if let Some(keymap) = self.keyboards.get(&keyboard) {
+                // Look up all keycodes first, so nothing is pressed if one of the keys can't
+                // be entered
+                let keycodes = keys
+                    .iter()
+                    .map(|&key| key_to_keycode(keymap, self.group, key))
+                    .collect::<InputResult<Vec<_>>>()?;
+
+                // All presses are sent in one frame and all releases in reverse order in the
+                // next one
+                if direction != Direction::Release {
+                    for &keycode in &keycodes {
+                        keyboard.key(keycode - 8, ei::keyboard::KeyState::Press);
+                    }
+                    let elapsed = frame_time(self.event_time);
+                    device.frame(self.sequence, elapsed);
+                    self.sequence = self.sequence.wrapping_add(1);
+                }
+                if direction != Direction::Press {
+                    for &keycode in keycodes.iter().rev() {
+                        keyboard.key(keycode - 8, ei::keyboard::KeyState::Released);
+                    }
+                    let elapsed = frame_time(self.event_time);
+                    device.frame(self.sequence, elapsed);
+                    self.sequence = self.sequence.wrapping_add(1);
+                }
+
+                self.update("enigo", Duration::ZERO).map_err(|_| {
+                    InputError::Simulate("unable to update the libei connection to enter keys")
+                })?;
+            }
+        }
+        Ok(())
+    }
+
     fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
         let keycode = keycode as u32;
 
-        if let Some((device, device_data)) = self
-            .devices
-            .iter_mut()
-            .find(|(_, ref device_data)| device_data.interface::<ei::Keyboard>().is_some())
-        {
+        if let Some((device, device_data)) = self.devices.iter_mut().find(|(_, device_data)| {
+            device_data.interface::<ei::Keyboard>().is_some()
+                && device_data.is_selected(self.device_name.as_deref())
+        }) {
             let keyboard = device_data.interface::<ei::Keyboard>().unwrap();
 
             if direction == Direction::Press || direction == Direction::Click {
@@ -525,11 +803,11 @@ impl Keyboard for Con {
                 keyboard.key(keycode - 8, ei::keyboard::KeyState::Released);
             }
 
-            let elapsed = self.time_created.elapsed().as_secs(); // Is seconds fine?
+            let elapsed = frame_time(self.event_time);
 
             device.frame(self.sequence, elapsed);
             self.sequence = self.sequence.wrapping_add(1);
-            self.update("enigo").map_err(|_| {
+            self.update("enigo", Duration::ZERO).map_err(|_| {
                 InputError::Simulate("unable to update the libei connection to scroll")
             })?;
         }
@@ -539,11 +817,10 @@ impl Keyboard for Con {
 
 impl Mouse for Con {
     fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
-        if let Some((device, device_data)) = self
-            .devices
-            .iter_mut()
-            .find(|(_, ref device_data)| device_data.interface::<ei::Button>().is_some())
-        {
+        if let Some((device, device_data)) = self.devices.iter_mut().find(|(_, device_data)| {
+            device_data.interface::<ei::Button>().is_some()
+                && device_data.is_selected(self.device_name.as_deref())
+        }) {
             // Do nothing if one of the mouse scroll buttons was released
             // Releasing one of the scroll mouse buttons has no effect
             if direction == Direction::Release {
@@ -560,17 +837,13 @@ impl Mouse for Con {
                 }
             };
 
-            let button = match button {
-                // Taken from /linux/input-event-codes.h
-                Button::Left => 0x110,
-                Button::Right => 0x111,
-                Button::Back => 0x116,
-                Button::Forward => 0x115,
-                Button::Middle => 0x112,
-                Button::ScrollDown => return self.scroll(1, Axis::Vertical),
-                Button::ScrollUp => return self.scroll(-1, Axis::Vertical),
-                Button::ScrollRight => return self.scroll(1, Axis::Horizontal),
-                Button::ScrollLeft => return self.scroll(-1, Axis::Horizontal),
+            let Some(button) = button_code(button) else {
+                return match button {
+                    Button::ScrollDown => self.scroll(1, Axis::Vertical),
+                    Button::ScrollUp => self.scroll(-1, Axis::Vertical),
+                    Button::ScrollRight => self.scroll(1, Axis::Horizontal),
+                    _ => self.scroll(-1, Axis::Horizontal),
+                };
             };
 
             let vp = device_data.interface::<ei::Button>().unwrap();
@@ -578,8 +851,8 @@ impl Mouse for Con {
             if direction == Direction::Press || direction == Direction::Click {
                 trace!("vp.button({button}, ei::button::ButtonState::Pressed)");
                 vp.button(button, ei::button::ButtonState::Press);
-                // self.update("enigo");
-                let elapsed = self.time_created.elapsed().as_secs(); // Is seconds fine?
+                // self.update("enigo", Duration::ZERO);
+                let elapsed = frame_time(self.event_time);
                 device.frame(self.sequence, elapsed);
                 self.sequence = self.sequence.wrapping_add(1);
             }
@@ -587,12 +860,53 @@ impl Mouse for Con {
             if direction == Direction::Release || direction == Direction::Click {
                 trace!("vp.button({button}, ei::button::ButtonState::Released)");
                 vp.button(button, ei::button::ButtonState::Released);
-                // self.update("enigo");
-                let elapsed = self.time_created.elapsed().as_secs(); // Is seconds fine?
+                // self.update("enigo", Duration::ZERO);
+                let elapsed = frame_time(self.event_time);
                 device.frame(self.sequence, elapsed);
                 self.sequence = self.sequence.wrapping_add(1);
             }
-            self.update("enigo").map_err(|_| {
+            self.update("enigo", Duration::ZERO).map_err(|_| {
+                InputError::Simulate("unable to update the libei connection to simulate a button")
+            })?;
+        }
+        Ok(())
+    }
+
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        // The scroll buttons cannot be part of the frame
+        let mut chord = vec![];
+        for &(button, direction) in buttons {
+            match button_code(button) {
+                Some(code) => chord.push((code, direction)),
+                None => self.button(button, direction)?,
+            }
+        }
+
+        if let Some((device, device_data)) = self.devices.iter().find(|(_, device_data)| {
+            device_data.interface::<ei::Button>().is_some()
+                && device_data.is_selected(self.device_name.as_deref())
+        }) {
+            let vp = device_data.interface::<ei::Button>().unwrap();
+            // All presses are sent in one frame and all releases in the next one
+            for (state, skipped) in [
+                (ei::button::ButtonState::Press, Direction::Release),
+                (ei::button::ButtonState::Released, Direction::Press),
+            ] {
+                let mut any = false;
+                for &(code, direction) in &chord {
+                    if direction != skipped {
+                        trace!("vp.button({code}, {state:?})");
+                        vp.button(code, state);
+                        any = true;
+                    }
+                }
+                if any {
+                    let elapsed = frame_time(self.event_time);
+                    device.frame(self.sequence, elapsed);
+                    self.sequence = self.sequence.wrapping_add(1);
+                }
+            }
+            self.update("enigo", Duration::ZERO).map_err(|_| {
                 InputError::Simulate("unable to update the libei connection to simulate a button")
             })?;
         }
@@ -600,25 +914,34 @@ impl Mouse for Con {
     }
 
     fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
-        #[allow(clippy::cast_precision_loss)]
+        self.move_mouse_f(f64::from(x), f64::from(y), coordinate)
+    }
+
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        if !x.is_finite() || !y.is_finite() {
+            return Err(InputError::InvalidInput(
+                "the coordinates have to be finite",
+            ));
+        }
+        // libei uses single precision
+        #[allow(clippy::cast_possible_truncation)]
         let (x, y) = (x as f32, y as f32);
         match coordinate {
             Coordinate::Rel => {
                 trace!("vp.motion_relative({x}, {y})");
-                if let Some((device, device_data)) = self
-                    .devices
-                    .iter()
-                    .find(|(_, device_data)| device_data.interface::<ei::Pointer>().is_some())
-                {
+                if let Some((device, device_data)) = self.devices.iter().find(|(_, device_data)| {
+                    device_data.interface::<ei::Pointer>().is_some()
+                        && device_data.is_selected(self.device_name.as_deref())
+                }) {
                     let vp = device_data.interface::<ei::Pointer>().unwrap();
                     vp.motion_relative(x, y);
 
-                    let elapsed = self.time_created.elapsed().as_secs(); // Is seconds fine?
+                    let elapsed = frame_time(self.event_time);
 
                     device.frame(self.sequence, elapsed);
                     self.sequence = self.sequence.wrapping_add(1);
 
-                    self.update("enigo").map_err(|_| {
+                    self.update("enigo", Duration::ZERO).map_err(|_| {
                         InputError::Simulate(
                             "unable to update the libei connection to move the mouse",
                         )
@@ -635,16 +958,17 @@ impl Mouse for Con {
                 trace!("vp.motion_absolute({x}, {y}, u32::MAX, u32::MAX)");
                 if let Some((device, device_data)) = self.devices.iter().find(|(_, device_data)| {
                     device_data.interface::<ei::PointerAbsolute>().is_some()
+                        && device_data.is_selected(self.device_name.as_deref())
                 }) {
                     let vp = device_data.interface::<ei::PointerAbsolute>().unwrap();
                     vp.motion_absolute(x, y);
 
-                    let elapsed = self.time_created.elapsed().as_secs(); // Is seconds fine?
+                    let elapsed = frame_time(self.event_time);
 
                     device.frame(self.sequence, elapsed);
                     self.sequence = self.sequence.wrapping_add(1);
 
-                    self.update("enigo").map_err(|_| {
+                    self.update("enigo", Duration::ZERO).map_err(|_| {
                         InputError::Simulate(
                             "unable to update the libei connection to move the mouse",
                         )
@@ -662,11 +986,10 @@ impl Mouse for Con {
     fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
         #[allow(clippy::cast_precision_loss)]
         let length = length as f32;
-        if let Some((device, device_data)) = self
-            .devices
-            .iter()
-            .find(|(_, device_data)| device_data.interface::<ei::Scroll>().is_some())
-        {
+        if let Some((device, device_data)) = self.devices.iter().find(|(_, device_data)| {
+            device_data.interface::<ei::Scroll>().is_some()
+                && device_data.is_selected(self.device_name.as_deref())
+        }) {
             let (x, y) = match axis {
                 Axis::Horizontal => (length, 0.0),
                 Axis::Vertical => (0.0, length),
@@ -675,11 +998,45 @@ impl Mouse for Con {
             let vp = device_data.interface::<ei::Scroll>().unwrap();
             vp.scroll(x, y);
 
-            let elapsed = self.time_created.elapsed().as_secs(); // Is seconds fine?
+            let elapsed = frame_time(self.event_time);
 
             device.frame(self.sequence, elapsed);
             self.sequence = self.sequence.wrapping_add(1);
-            self.update("enigo").map_err(|_| {
+            self.update("enigo", Duration::ZERO).map_err(|_| {
+                InputError::Simulate("unable to update the libei connection to scroll")
+            })?;
+            return Ok(());
+        }
+        Err(InputError::Simulate(
+            "None of the devices implements the Scroll interface so there is no way to scroll",
+        ))
+    }
+
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        if let Some((device, device_data)) = self.devices.iter().find(|(_, device_data)| {
+            device_data.interface::<ei::Scroll>().is_some()
+                && device_data.is_selected(self.device_name.as_deref())
+        }) {
+            let vp = device_data.interface::<ei::Scroll>().unwrap();
+            match unit {
+                ScrollUnit::Line => {
+                    // One click of a mouse wheel is 120
+                    trace!("vp.scroll_discrete({}, {})", dx * 120, dy * 120);
+                    vp.scroll_discrete(dx * 120, dy * 120);
+                }
+                ScrollUnit::Pixel => {
+                    #[allow(clippy::cast_precision_loss)]
+                    let (x, y) = (dx as f32, dy as f32);
+                    trace!("vp.scroll({x}, {y})");
+                    vp.scroll(x, y);
+                }
+            }
+
+            let elapsed = frame_time(self.event_time);
+
+            device.frame(self.sequence, elapsed);
+            self.sequence = self.sequence.wrapping_add(1);
+            self.update("enigo", Duration::ZERO).map_err(|_| {
                 InputError::Simulate("unable to update the libei connection to scroll")
             })?;
             return Ok(());
@@ -720,14 +1077,27 @@ impl Drop for Con {
     }
 }
 
-fn key_to_keycode(keymap: &xkb::Keymap, key: Key) -> InputResult<Keycode> {
+/// Returns the evdev code of the button or `None` for the scroll buttons
+fn button_code(button: Button) -> Option<u32> {
+    match button {
+        // Taken from /linux/input-event-codes.h
+        Button::Left => Some(0x110),
+        Button::Right => Some(0x111),
+        Button::Back => Some(0x116),
+        Button::Forward => Some(0x115),
+        Button::Middle => Some(0x112),
+        Button::ScrollDown | Button::ScrollUp | Button::ScrollRight | Button::ScrollLeft => None,
+    }
+}
+
+fn key_to_keycode(keymap: &xkb::Keymap, layout: u32, key: Key) -> InputResult<Keycode> {
     let all_keycodes = keymap.min_keycode().raw()..keymap.max_keycode().raw();
 
     let keysym = xkb::Keysym::from(key);
     let mut keycode = None;
     'outer: for i in all_keycodes.clone() {
         for j in 0..=1 {
-            let syms = keymap.key_get_syms_by_level(xkb::Keycode::new(i), 0, j);
+            let syms = keymap.key_get_syms_by_level(xkb::Keycode::new(i), layout, j);
             if syms.contains(&keysym) {
                 keycode = Some(i);
                 break 'outer;
@@ -737,3 +1107,13 @@ fn key_to_keycode(keymap: &xkb::Keymap, key: Key) -> InputResult<Keycode> {
     // Panics if the keysym was not mapped
     keycode.ok_or(crate::InputError::InvalidInput("Key is not mapped"))
 }
+
+/// Time of the next frame in microseconds of `CLOCK_MONOTONIC`. It is the time
+/// that was set with `Enigo::set_event_time` or the current time
+fn frame_time(event_time: Option<Duration>) -> u64 {
+    event_time
+        .or_else(super::system::monotonic_now)
+        .map_or(0, |time| {
+            u64::try_from(time.as_micros()).unwrap_or(u64::MAX)
+        })
+}
@@ -1,8 +1,23 @@
+use std::{
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
 use log::{debug, error, trace, warn};
 
 use crate::{
-    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse,
-    NewConError, Settings,
+    diagnostics::{self, DiagnosticsReport},
+    metrics::MetricsHook,
+    origin_to_top_left, origin_to_top_left_f, round_coordinates,
+    settings::{warn_unchangeable, SettingsError},
+    text_with_policy,
+    throttle::Throttle,
+    AbsoluteMode, Axis, Button, Capability, Coordinate, Direction, InputError, InputResult, Key,
+    Keyboard, LayoutInfo, Mouse, NewConError, PointerMode, Rect, ScrollUnit, Settings, Subpixel,
 };
 
 // If none of these features is enabled, there is no way to simulate input
@@ -34,9 +49,72 @@ use constants::{KEYMAP_BEGINNING, KEYMAP_END};
 #[cfg(any(feature = "wayland", feature = "x11rb"))]
 mod keymap;
 
+pub mod xkb_keymap;
+
+#[cfg(any(feature = "a11y", feature = "libei", feature = "wayland"))]
+mod dbus;
+
+pub(crate) mod health;
+
+pub(crate) mod keep_awake;
+
+pub(crate) mod system;
+
+#[cfg(feature = "a11y")]
+pub(crate) mod a11y;
+
+/// Repeat rate (keys per second) that is used if the compositor did not report
+/// one
+const DEFAULT_REPEAT_RATE: u32 = 25;
+/// Repeat delay that is used if the compositor did not report one
+const DEFAULT_REPEAT_DELAY: Duration = Duration::from_millis(600);
+
+/// Establish a connection with the function. If there is a timeout, the
+/// function is run on a separate thread and the connection is given up if it
+/// did not finish in time. The thread keeps running until the function returns
+/// and drops the connection
+fn connect_with_timeout<T: Send + 'static>(
+    timeout: Option<Duration>,
+    f: impl FnOnce() -> Result<T, NewConError> + Send + 'static,
+) -> Result<T, NewConError> {
+    let Some(timeout) = timeout else {
+        return f();
+    };
+    let (tx, rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("enigo-connect".to_string())
+        .spawn(move || {
+            // The receiver is gone if the timeout elapsed
+            let _ = tx.send(f());
+        })
+        .map_err(|_| NewConError::EstablishCon("unable to spawn a thread to connect"))?;
+    match rx.recv_timeout(timeout) {
+        Ok(con) => con,
+        Err(RecvTimeoutError::Timeout) => {
+            warn!("the connection was not established within {timeout:?}");
+            Err(NewConError::Timeout)
+        }
+        Err(RecvTimeoutError::Disconnected) => Err(NewConError::EstablishCon(
+            "the thread establishing the connection panicked",
+        )),
+    }
+}
+
 pub struct Enigo {
     held: (Vec<Key>, Vec<u16>), // Currently held keys and held keycodes
     release_keys_when_dropped: bool,
+    double_click_interval: Option<Duration>,
+    absolute_mode: AbsoluteMode,
+    confinement: Option<Rect>, // The simulated movements are clamped to the rectangle
+    event_time: Option<Duration>, // Time of the simulated events instead of the current time
+    pointer_mode: PointerMode,
+    subpixel: Subpixel, // Fractions of the relative movements that were not simulated yet
+    repeating: Option<Vec<(u16, Instant)>>, // Held keycodes and when they need to be repeated next
+    metrics_hook: Option<Arc<dyn MetricsHook>>,
+    throttle: Option<Throttle>,
+    pub(crate) focus_guard: bool,
+    settings: Settings, // Redacted copy of the settings for the diagnostics
+    connected: bool,    // False if `Settings::lazy_connect` deferred establishing the connections
     #[cfg(feature = "wayland")]
     wayland: Option<wayland::Con>,
     #[cfg(any(feature = "x11rb", feature = "xdo"))]
@@ -49,35 +127,193 @@ impl Enigo {
     /// Create a new Enigo struct to establish the connection to simulate input
     /// with the specified settings
     ///
+    /// If `Settings::lazy_connect` is enabled, no connection is established
+    /// yet. Have a look at `Enigo::connect`
+    ///
     /// # Errors
     /// Have a look at the documentation of `NewConError` to see under which
     /// conditions an error will be returned.
     pub fn new(settings: &Settings) -> Result<Self, NewConError> {
+        let Settings {
+            release_keys_when_dropped,
+            double_click_interval,
+            linux_emulate_key_repeat,
+            lazy_connect,
+            ..
+        } = settings;
+
+        let mut enigo = Self {
+            held: (Vec::new(), Vec::new()),
+            release_keys_when_dropped: *release_keys_when_dropped,
+            double_click_interval: *double_click_interval,
+            absolute_mode: settings.absolute_mode,
+            confinement: None,
+            event_time: None,
+            pointer_mode: PointerMode::Normal,
+            subpixel: Subpixel::default(),
+            repeating: linux_emulate_key_repeat.then(Vec::new),
+            metrics_hook: None,
+            throttle: Throttle::new(settings),
+            focus_guard: settings.focus_guard,
+            connected: false,
+            settings: settings.clone(),
+            #[cfg(feature = "wayland")]
+            wayland: None,
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            x11: None,
+            #[cfg(feature = "libei")]
+            libei: None,
+        };
+        if *lazy_connect {
+            debug!("the connections are established on first use");
+        } else {
+            enigo.connect()?;
+        }
+        Ok(enigo)
+    }
+
+    /// Create a new Enigo struct that simulates input via the EIS
+    /// implementation of an already started `ashpd` remote desktop session
+    ///
+    /// This allows applications that already asked the user for permission
+    /// (e.g. to capture the screen) to reuse that session instead of showing a
+    /// second permission dialog. The devices that were selected for the session
+    /// must include the keyboard and the pointer. Only the libei connection is
+    /// established and `Settings::lazy_connect` is ignored
+    ///
+    /// # Errors
+    /// Have a look at the documentation of `NewConError` to see under which
+    /// conditions an error will be returned.
+    #[cfg(feature = "libei")]
+    pub async fn from_portal_session(
+        settings: &Settings,
+        remote_desktop: &ashpd::desktop::remote_desktop::RemoteDesktop<'_>,
+        session: &ashpd::desktop::Session<'_, ashpd::desktop::remote_desktop::RemoteDesktop<'_>>,
+    ) -> Result<Self, NewConError> {
+        debug!("\x1b[93mfrom_portal_session()\x1b[0m");
+        let context = libei::Con::connect_to_session(remote_desktop, session).await?;
+        Self::from_ei_context(settings, context)
+    }
+
+    /// Create a new Enigo struct that simulates input via an already
+    /// established libei sender context
+    ///
+    /// This allows compositor plugins and applications that embed enigo to
+    /// share their connection. The handshake is done by enigo, so the context
+    /// must not have been used yet. Only the libei connection is established
+    /// and `Settings::lazy_connect` is ignored
+    ///
+    /// # Errors
+    /// Have a look at the documentation of `NewConError` to see under which
+    /// conditions an error will be returned.
+    #[cfg(feature = "libei")]
+    pub fn from_ei_context(
+        settings: &Settings,
+        context: reis::ei::Context,
+    ) -> Result<Self, NewConError> {
+        debug!("\x1b[93mfrom_ei_context()\x1b[0m");
+        let mut enigo = Self::unconnected(settings)?;
+        let con = libei::Con::with_context(
+            context,
+            settings.libei_seat.as_deref(),
+            settings.libei_device.as_deref(),
+        )?;
+        debug!("libei connection established");
+        enigo.libei = Some(con);
+        enigo.connected = true;
+        enigo.set_event_time(enigo.event_time);
+        Ok(enigo)
+    }
+
+    /// Create a new Enigo struct that simulates input via an already
+    /// established wayland connection
+    ///
+    /// This allows compositor plugins and applications that embed enigo to
+    /// share their connection. Enigo uses its own event queue on it. Only the
+    /// wayland connection is established and `Settings::lazy_connect` is
+    /// ignored
+    ///
+    /// # Errors
+    /// Have a look at the documentation of `NewConError` to see under which
+    /// conditions an error will be returned.
+    #[cfg(feature = "wayland")]
+    pub fn from_wayland_connection(
+        settings: &Settings,
+        connection: &wayland_client::Connection,
+    ) -> Result<Self, NewConError> {
+        debug!("\x1b[93mfrom_wayland_connection()\x1b[0m");
+        let mut enigo = Self::unconnected(settings)?;
+        let con = wayland::Con::with_connection(connection)?;
+        debug!("wayland connection established");
+        enigo.wayland = Some(con);
+        enigo.connected = true;
+        enigo.set_event_time(enigo.event_time);
+        Ok(enigo)
+    }
+
+    /// Create a new Enigo struct without establishing any connection, no
+    /// matter what `Settings::lazy_connect` is set to
+    #[cfg(any(feature = "libei", feature = "wayland"))]
+    fn unconnected(settings: &Settings) -> Result<Self, NewConError> {
+        let mut enigo = Self::new(&Settings {
+            lazy_connect: true,
+            ..settings.clone()
+        })?;
+        enigo.settings.lazy_connect = settings.lazy_connect;
+        Ok(enigo)
+    }
+
+    /// Establish the connections to simulate input if that did not happen yet
+    ///
+    /// This is only needed if `Settings::lazy_connect` is enabled. Otherwise
+    /// `Enigo::new` already established them. The functions that simulate
+    /// input call it automatically, but the functions that only take `&self`
+    /// (e.g. `Mouse::location`) return an error until the connections are
+    /// established. If it fails, the next call tries again
+    ///
+    /// # Errors
+    /// Have a look at the documentation of `NewConError` to see under which
+    /// conditions an error will be returned.
+    #[allow(clippy::too_many_lines)]
+    pub fn connect(&mut self) -> Result<(), NewConError> {
+        if self.connected {
+            return Ok(());
+        }
+        let settings = &self.settings;
+        debug!("\x1b[93mconnect()\x1b[0m");
         let mut connection_established = false;
+        let mut timed_out = false;
         #[allow(unused_variables)]
         let Settings {
             linux_delay,
             x11_display,
             wayland_display,
-            release_keys_when_dropped,
+            libei_seat,
+            libei_device,
+            unicode_key_mode,
+            connection_timeout,
             ..
         } = settings;
-
-        let held = (Vec::new(), Vec::new());
         #[cfg(feature = "wayland")]
-        let wayland = match wayland::Con::new(wayland_display.as_deref()) {
-            Ok(con) => {
-                connection_established = true;
-                debug!("wayland connection established");
-                Some(con)
-            }
-            Err(e) => {
-                warn!("{e}");
-                None
+        let wayland = {
+            let wayland_display = wayland_display.clone();
+            match connect_with_timeout(*connection_timeout, move || {
+                wayland::Con::new(wayland_display.as_deref())
+            }) {
+                Ok(con) => {
+                    connection_established = true;
+                    debug!("wayland connection established");
+                    Some(con)
+                }
+                Err(e) => {
+                    timed_out |= e == NewConError::Timeout;
+                    warn!("{e}");
+                    None
+                }
             }
         };
         #[cfg(any(feature = "x11rb", feature = "xdo"))]
-        match x11_display {
+        match x11_display.as_ref() {
             Some(name) => {
                 debug!(
                     "\x1b[93mtrying to establish a x11 connection to: {}\x1b[0m",
@@ -89,129 +325,185 @@ impl Enigo {
             }
         }
         #[cfg(any(feature = "x11rb", feature = "xdo"))]
-        let x11 = match x11::Con::new(x11_display.as_deref(), *linux_delay) {
-            Ok(con) => {
-                connection_established = true;
-                debug!("x11 connection established");
-                Some(con)
-            }
-            Err(e) => {
-                warn!("failed to establish x11 connection: {e}");
-                None
+        let x11 = {
+            let (x11_display, linux_delay, unicode_key_mode) =
+                (x11_display.clone(), *linux_delay, *unicode_key_mode);
+            match connect_with_timeout(*connection_timeout, move || {
+                x11::Con::new(x11_display.as_deref(), linux_delay, unicode_key_mode)
+            }) {
+                Ok(con) => {
+                    connection_established = true;
+                    debug!("x11 connection established");
+                    Some(con)
+                }
+                Err(e) => {
+                    timed_out |= e == NewConError::Timeout;
+                    warn!("failed to establish x11 connection: {e}");
+                    None
+                }
             }
         };
         #[cfg(feature = "libei")]
-        let libei = match libei::Con::new() {
-            Ok(con) => {
-                connection_established = true;
-                debug!("libei connection established");
-                Some(con)
-            }
-            Err(e) => {
-                warn!("failed to establish libei connection: {e}");
-                None
+        let libei = {
+            let (libei_seat, libei_device) = (libei_seat.clone(), libei_device.clone());
+            match connect_with_timeout(*connection_timeout, move || {
+                libei::Con::new(libei_seat.as_deref(), libei_device.as_deref())
+            }) {
+                Ok(con) => {
+                    connection_established = true;
+                    debug!("libei connection established");
+                    Some(con)
+                }
+                Err(e) => {
+                    timed_out |= e == NewConError::Timeout;
+                    warn!("failed to establish libei connection: {e}");
+                    None
+                }
             }
         };
+        if !connection_established && timed_out {
+            error!("no connection was established in time");
+            return Err(NewConError::Timeout);
+        }
         if !connection_established {
             error!("no successful connection");
             return Err(NewConError::EstablishCon("no successful connection"));
         }
 
-        Ok(Self {
-            held,
-            release_keys_when_dropped: *release_keys_when_dropped,
-            #[cfg(feature = "wayland")]
-            wayland,
-            #[cfg(any(feature = "x11rb", feature = "xdo"))]
-            x11,
-            #[cfg(feature = "libei")]
-            libei,
-        })
-    }
-
-    /// Get the delay per keypress
-    #[must_use]
-    pub fn delay(&self) -> u32 {
-        // On Wayland there is no delay
-
+        #[cfg(feature = "wayland")]
+        {
+            self.wayland = wayland;
+        }
         #[cfg(any(feature = "x11rb", feature = "xdo"))]
-        if let Some(con) = self.x11.as_ref() {
-            return con.delay();
+        {
+            self.x11 = x11;
         }
-        0
+        #[cfg(feature = "libei")]
+        {
+            self.libei = libei;
+        }
+        self.connected = true;
+        self.set_event_time(self.event_time);
+        Ok(())
     }
 
-    /// Set the delay per keypress
-    #[allow(unused_variables)]
-    pub fn set_delay(&mut self, delay: u32) {
-        // On Wayland there is no delay
-
-        #[cfg(any(feature = "x11rb", feature = "xdo"))]
-        if let Some(con) = self.x11.as_mut() {
-            con.set_delay(delay);
+    /// Clamp the destination of a movement to the rectangle the cursor is
+    /// confined to
+    fn confined(&self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<(i32, i32)> {
+        let Some(rect) = self.confinement else {
+            return Ok((x, y));
+        };
+        match coordinate {
+            Coordinate::Abs => Ok(rect.clamp(x, y)),
+            Coordinate::Rel => {
+                let (current_x, current_y) = self.cursor_location()?;
+                let (target_x, target_y) =
+                    rect.clamp(current_x.saturating_add(x), current_y.saturating_add(y));
+                Ok((target_x - current_x, target_y - current_y))
+            }
         }
     }
 
-    /// Returns a list of all currently pressed keys
-    pub fn held(&mut self) -> (Vec<Key>, Vec<u16>) {
-        self.held.clone()
+    /// Error if no connection simulated the input. It names the missing
+    /// capability if the Wayland compositor lacks it
+    #[cfg_attr(not(feature = "wayland"), allow(clippy::unused_self, unused_variables))]
+    fn no_protocol(&self, capability: Capability) -> InputError {
+        #[cfg(feature = "wayland")]
+        if self
+            .wayland
+            .as_ref()
+            .is_some_and(|con| !con.supports(capability))
+        {
+            return InputError::NotSupported {
+                capability,
+                backend: "wayland",
+            };
+        }
+        InputError::Simulate("No protocol to enter the result")
     }
-}
 
-impl Mouse for Enigo {
-    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mbutton(button: {button:?}, direction: {direction:?})\x1b[0m");
-        let mut success = false;
+    /// Location of the cursor with the top left origin
+    fn cursor_location(&self) -> InputResult<(i32, i32)> {
         #[cfg(feature = "libei")]
-        if let Some(con) = self.libei.as_mut() {
-            trace!("try sending button event via libei");
-            con.button(button, direction)?;
-            debug!("sent button event via libei");
-            success = true;
+        if let Some(con) = self.libei.as_ref() {
+            trace!("try getting the mouse location via libei");
+            return con.location();
         }
         #[cfg(feature = "wayland")]
-        if let Some(con) = self.wayland.as_mut() {
-            trace!("try sending button event via wayland");
-            con.button(button, direction)?;
-            debug!("sent button event via wayland");
-            success = true;
+        if let Some(con) = self.wayland.as_ref() {
+            trace!("try getting the mouse location via wayland");
+            return con.location();
         }
         #[cfg(any(feature = "x11rb", feature = "xdo"))]
-        if let Some(con) = self.x11.as_mut() {
-            trace!("try sending button event via x11");
-            con.button(button, direction)?;
-            debug!("sent button event via x11");
-            success = true;
-        }
-        if success {
-            debug!("sent button event");
-            Ok(())
-        } else {
-            Err(InputError::Simulate("No protocol to enter the result"))
+        if let Some(con) = self.x11.as_ref() {
+            trace!("try getting the mouse location via x11");
+            return con.location();
         }
+        Err(InputError::Simulate(
+            "No protocol could read the location of the mouse",
+        ))
     }
 
-    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
-        debug!("\x1b[93mmove_mouse(x: {x:?}, y: {y:?}, coordinate:{coordinate:?})\x1b[0m");
+    /// Move the cursor without acquiring the throttle. The libei and wayland
+    /// connections move by fractions of a pixel and X11 by whole pixels
+    fn move_cursor(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        round_coordinates(x, y)?;
+        if coordinate == Coordinate::Abs && self.pointer_mode == PointerMode::RelativeOnly {
+            return Err(InputError::InvalidInput(
+                "absolute movements are refused in PointerMode::RelativeOnly",
+            ));
+        }
+        self.connect_lazily()?;
+        let (x, y) = match coordinate {
+            Coordinate::Abs => origin_to_top_left_f(self, self.settings.origin, x, y)?,
+            Coordinate::Rel => (x, y),
+        };
+        let rounded = round_coordinates(x, y)?;
+        // X11 and the confinement only work with whole pixels
+        let whole = match coordinate {
+            Coordinate::Abs => rounded,
+            Coordinate::Rel => self.subpixel.take(x, y),
+        };
+        let whole = self.confined(whole.0, whole.1, coordinate)?;
+        #[cfg(any(feature = "libei", feature = "wayland"))]
+        let (x, y) = if self.confinement.is_some() {
+            (f64::from(whole.0), f64::from(whole.1))
+        } else {
+            (x, y)
+        };
+        if coordinate == Coordinate::Abs && self.absolute_mode == AbsoluteMode::Warp {
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try warping the mouse via x11");
+                return con.warp_mouse(whole.0, whole.1);
+            }
+            return Err(InputError::Simulate(
+                "warping the cursor is only supported on X11",
+            ));
+        }
         let mut success = false;
         #[cfg(feature = "libei")]
         if let Some(con) = self.libei.as_mut() {
             trace!("try moving the mouse via libei");
-            con.move_mouse(x, y, coordinate)?;
+            con.move_mouse_f(x, y, coordinate)?;
             debug!("moved the mouse via libei");
             success = true;
         }
         #[cfg(feature = "wayland")]
-        if let Some(con) = self.wayland.as_mut() {
+        if let Some(con) = self
+            .wayland
+            .as_mut()
+            .filter(|con| con.supports(Capability::Pointer))
+        {
             trace!("try moving the mouse via wayland");
-            con.move_mouse(x, y, coordinate)?;
+            con.move_mouse_f(x, y, coordinate)?;
             debug!("moved the mouse via wayland");
             success = true;
         }
         #[cfg(any(feature = "x11rb", feature = "xdo"))]
         if let Some(con) = self.x11.as_mut() {
             trace!("try moving the mouse via x11");
-            con.move_mouse(x, y, coordinate)?;
+            con.move_mouse(whole.0, whole.1, coordinate)?;
             debug!("moved the mouse via x11");
             success = true;
         }
@@ -219,186 +511,1125 @@ impl Mouse for Enigo {
             debug!("moved the mouse");
             Ok(())
         } else {
-            Err(InputError::Simulate("No protocol to enter the result"))
+            Err(self.no_protocol(Capability::Pointer))
         }
     }
 
-    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
-        debug!("\x1b[93mscroll(length: {length:?}, axis: {axis:?})\x1b[0m");
-        let mut success = false;
+    /// Establish the connections if `Settings::lazy_connect` is enabled and
+    /// it did not happen yet
+    fn connect_lazily(&mut self) -> InputResult<()> {
+        self.connect().map_err(InputError::Connect)
+    }
+
+    /// Apply the settings without establishing the connections again
+    ///
+    /// The options `linux_delay`, `release_keys_when_dropped`,
+    /// `double_click_interval`, `absolute_mode`, `origin`,
+    /// `linux_emulate_key_repeat`, `max_events_per_second`,
+    /// `rate_limit_behavior` and `focus_guard` are applied. The others are
+    /// used to establish the connections, so they are kept and a warning is
+    /// logged if they differ. If the connections were not established yet
+    /// because of `Settings::lazy_connect`, all options are applied
+    ///
+    /// # Errors
+    /// Returns an error and changes nothing if the settings are invalid. Have
+    /// a look at `Settings::validate`
+    pub fn update_settings(&mut self, settings: &Settings) -> Result<(), SettingsError> {
+        debug!("\x1b[93mupdate_settings(settings: {settings:?})\x1b[0m");
+        settings.validate()?;
+        let old = std::mem::replace(&mut self.settings, settings.clone());
+        if self.connected {
+            warn_unchangeable(&[
+                ("x11_display", old.x11_display != settings.x11_display),
+                (
+                    "wayland_display",
+                    old.wayland_display != settings.wayland_display,
+                ),
+                ("libei_seat", old.libei_seat != settings.libei_seat),
+                ("libei_device", old.libei_device != settings.libei_device),
+                (
+                    "unicode_key_mode",
+                    old.unicode_key_mode != settings.unicode_key_mode,
+                ),
+                (
+                    "connection_timeout",
+                    old.connection_timeout != settings.connection_timeout,
+                ),
+                ("lazy_connect", old.lazy_connect != settings.lazy_connect),
+            ]);
+            self.settings = Settings {
+                x11_display: old.x11_display,
+                wayland_display: old.wayland_display,
+                libei_seat: old.libei_seat,
+                libei_device: old.libei_device,
+                unicode_key_mode: old.unicode_key_mode,
+                connection_timeout: old.connection_timeout,
+                lazy_connect: old.lazy_connect,
+                ..settings.clone()
+            };
+        }
+
+        self.set_delay(settings.linux_delay);
+        self.release_keys_when_dropped = settings.release_keys_when_dropped;
+        self.double_click_interval = settings.double_click_interval;
+        self.absolute_mode = settings.absolute_mode;
+        if settings.linux_emulate_key_repeat != self.repeating.is_some() {
+            self.repeating = settings.linux_emulate_key_repeat.then(Vec::new);
+        }
+        // Keep the state of the rate limit if it did not change
+        if old.max_events_per_second != settings.max_events_per_second
+            || old.rate_limit_behavior != settings.rate_limit_behavior
+        {
+            self.throttle = Throttle::new(settings);
+        }
+        self.focus_guard = settings.focus_guard;
+        Ok(())
+    }
+
+    /// Get the delay per keypress
+    #[must_use]
+    pub fn delay(&self) -> u32 {
+        // On Wayland there is no delay
+
+        #[cfg(any(feature = "x11rb", feature = "xdo"))]
+        if let Some(con) = self.x11.as_ref() {
+            return con.delay();
+        }
+        0
+    }
+
+    /// Set the delay per keypress
+    #[allow(unused_variables)]
+    pub fn set_delay(&mut self, delay: u32) {
+        // On Wayland there is no delay
+
+        #[cfg(any(feature = "x11rb", feature = "xdo"))]
+        if let Some(con) = self.x11.as_mut() {
+            con.set_delay(delay);
+        }
+    }
+
+    /// Returns the maximum interval between two clicks so they count as a
+    /// double click. There is no system wide setting on Linux, so 400 ms (the
+    /// default of GTK) is returned unless it was overridden
+    #[must_use]
+    pub fn double_click_interval(&self) -> Duration {
+        self.double_click_interval
+            .unwrap_or(Duration::from_millis(400))
+    }
+
+    /// Override the maximum interval between two clicks so they count as a
+    /// double click. If it is `None`, the default of 400 ms is used
+    pub fn set_double_click_interval(&mut self, interval: Option<Duration>) {
+        self.double_click_interval = interval;
+    }
+
+    /// Set the time of the events that are simulated from now on (e.g. to
+    /// replay recorded input with its original timing). With libei it is the
+    /// time of the frames in `CLOCK_MONOTONIC` and with Wayland the time of the
+    /// events in milliseconds. X11 (`XTest`) always simulates the input at the
+    /// current time, so it is ignored there. Pass `None` to use the current
+    /// time
+    pub fn set_event_time(&mut self, time: Option<Duration>) {
+        debug!("\x1b[93mset_event_time(time: {time:?})\x1b[0m");
+        self.event_time = time;
         #[cfg(feature = "libei")]
         if let Some(con) = self.libei.as_mut() {
-            trace!("try scrolling via libei");
-            con.scroll(length, axis)?;
-            debug!("scrolled via libei");
-            success = true;
+            con.set_event_time(time);
         }
         #[cfg(feature = "wayland")]
         if let Some(con) = self.wayland.as_mut() {
-            trace!("try scrolling via wayland");
-            con.scroll(length, axis)?;
-            debug!("scrolled via wayland");
-            success = true;
+            con.set_event_time(time);
+        }
+    }
+
+    /// Set which mouse movements are simulated. Have a look at the
+    /// documentation of `PointerMode`
+    pub fn set_pointer_mode(&mut self, mode: PointerMode) {
+        debug!("\x1b[93mset_pointer_mode(mode: {mode:?})\x1b[0m");
+        self.pointer_mode = mode;
+    }
+
+    /// Returns which mouse movements are simulated
+    #[must_use]
+    pub fn pointer_mode(&self) -> PointerMode {
+        self.pointer_mode
+    }
+
+    /// Set the hook that gets notified about each call of a function of the
+    /// `Keyboard` and `Mouse` traits. Have a look at the `metrics` module.
+    /// Pass `None` to remove it
+    pub fn set_metrics_hook(&mut self, hook: Option<Arc<dyn MetricsHook>>) {
+        self.metrics_hook = hook;
+    }
+
+    /// Returns the names of the connections that are used to simulate input
+    fn backend(&self) -> String {
+        let mut backends = vec![];
+        #[cfg(feature = "libei")]
+        if self.libei.is_some() {
+            backends.push("libei");
+        }
+        #[cfg(feature = "wayland")]
+        if self.wayland.is_some() {
+            backends.push("wayland");
         }
         #[cfg(any(feature = "x11rb", feature = "xdo"))]
-        if let Some(con) = self.x11.as_mut() {
-            trace!("try scrolling via x11");
-            con.scroll(length, axis)?;
-            debug!("scrolled via x11");
-            success = true;
+        if self.x11.is_some() {
+            backends.push("x11");
         }
-        if success {
-            debug!("scrolled");
-            Ok(())
-        } else {
-            Err(InputError::Simulate("No protocol to enter the result"))
+        backends.join(",")
+    }
+
+    /// Returns a list of all currently pressed keys
+    pub fn held(&mut self) -> (Vec<Key>, Vec<u16>) {
+        self.held.clone()
+    }
+
+    /// Returns the names of the seats that are offered by the libei
+    /// connection. Use one of them for `Settings::libei_seat`
+    #[cfg(feature = "libei")]
+    #[must_use]
+    pub fn libei_seats(&self) -> Vec<String> {
+        self.libei
+            .as_ref()
+            .map(libei::Con::seats)
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of the devices of the libei connection. Use one of
+    /// them for `Settings::libei_device`
+    #[cfg(feature = "libei")]
+    #[must_use]
+    pub fn libei_devices(&self) -> Vec<String> {
+        self.libei
+            .as_ref()
+            .map(libei::Con::devices)
+            .unwrap_or_default()
+    }
+
+    /// Returns the name, the position and the size of all monitors of the X11
+    /// screen
+    ///
+    /// # Errors
+    /// Returns an error if there is no X11 connection or the monitors could
+    /// not be queried
+    #[cfg(feature = "x11rb")]
+    pub fn x11_monitors(&self) -> InputResult<Vec<x11::Monitor>> {
+        debug!("\x1b[93mx11_monitors()\x1b[0m");
+        match self.x11.as_ref() {
+            Some(con) => con.monitors(),
+            None => Err(InputError::Simulate("No X11 connection")),
         }
     }
 
-    fn main_display(&self) -> InputResult<(i32, i32)> {
-        debug!("\x1b[93mmain_display()\x1b[0m");
+    /// Target the monitor with the index `monitor` of the list returned by
+    /// `x11_monitors`. Absolute mouse movements are then relative to the top
+    /// left corner of that monitor and `main_display` returns its size. Use
+    /// `None` to target the whole screen again
+    ///
+    /// # Errors
+    /// Returns an error if there is no X11 connection or no monitor with that
+    /// index
+    #[cfg(feature = "x11rb")]
+    pub fn set_x11_monitor(&mut self, monitor: Option<usize>) -> InputResult<()> {
+        debug!("\x1b[93mset_x11_monitor(monitor: {monitor:?})\x1b[0m");
+        match self.x11.as_mut() {
+            Some(con) => con.set_monitor(monitor),
+            None => Err(InputError::Simulate("No X11 connection")),
+        }
+    }
+
+    /// Returns the id of the active window
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections is able to get the active
+    /// window. Only the `x11rb` and `xdo` features support it
+    pub fn active_window(&self) -> InputResult<u64> {
+        debug!("\x1b[93mactive_window()\x1b[0m");
+        #[cfg(any(feature = "x11rb", feature = "xdo"))]
+        if let Some(con) = self.x11.as_ref() {
+            trace!("try getting the active window via x11");
+            return con.active_window();
+        }
+        Err(InputError::Simulate(
+            "No protocol supports getting the active window",
+        ))
+    }
+
+    /// Returns the names of the layouts (xkb groups) of the active keymap. The
+    /// index of a name is the index of the group
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections supports layout groups.
+    /// This is the case for the `wayland` and `xdo` features
+    pub fn layout_groups(&self) -> InputResult<Vec<String>> {
+        debug!("\x1b[93mlayout_groups()\x1b[0m");
         #[cfg(feature = "libei")]
         if let Some(con) = self.libei.as_ref() {
-            trace!("try getting the dimensions of the display via libei");
-            return con.main_display();
+            trace!("try getting the layout groups via libei");
+            return con.layout_groups();
         }
-        #[cfg(feature = "wayland")]
-        if let Some(con) = self.wayland.as_ref() {
-            trace!("try getting the dimensions of the display via wayland");
-            return con.main_display();
-        }
-        #[cfg(any(feature = "x11rb", feature = "xdo"))]
+        #[cfg(feature = "x11rb")]
         if let Some(con) = self.x11.as_ref() {
-            trace!("try getting the dimensions of the display via x11");
-            return con.main_display();
+            trace!("try getting the layout groups via x11");
+            return con.layout_groups();
         }
-        Err(InputError::Simulate("No protocol to enter the result"))
+        Err(InputError::Simulate("No protocol supports layout groups"))
     }
 
-    fn location(&self) -> InputResult<(i32, i32)> {
-        debug!("\x1b[93mlocation()\x1b[0m");
+    /// Returns the keymap that is currently used to interpret the simulated
+    /// keycodes. Use it to translate between keycodes, keysyms and characters
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections is able to provide the
+    /// keymap. Currently only the `libei` feature supports it
+    pub fn keymap(&self) -> InputResult<xkb_keymap::Keymap> {
+        debug!("\x1b[93mkeymap()\x1b[0m");
         #[cfg(feature = "libei")]
         if let Some(con) = self.libei.as_ref() {
-            trace!("try getting the mouse location via libei");
-            return con.location();
+            trace!("try getting the keymap via libei");
+            return con.keymap();
         }
-        #[cfg(feature = "wayland")]
-        if let Some(con) = self.wayland.as_ref() {
-            trace!("try getting the mouse location via wayland");
-            return con.location();
+        Err(InputError::Simulate(
+            "No protocol is able to provide the keymap",
+        ))
+    }
+
+    /// Returns the index of the currently active layout group
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections supports layout groups.
+    /// This is the case for the `wayland` and `xdo` features
+    pub fn layout_group(&self) -> InputResult<u32> {
+        debug!("\x1b[93mlayout_group()\x1b[0m");
+        #[cfg(feature = "libei")]
+        if let Some(con) = self.libei.as_ref() {
+            trace!("try getting the layout group via libei");
+            return con.layout_group();
         }
-        #[cfg(any(feature = "x11rb", feature = "xdo"))]
+        #[cfg(feature = "x11rb")]
         if let Some(con) = self.x11.as_ref() {
-            trace!("try getting the mouse location via x11");
-            return con.location();
+            trace!("try getting the layout group via x11");
+            return con.layout_group();
         }
-        Err(InputError::Simulate("No protocol to enter the result"))
+        Err(InputError::Simulate("No protocol supports layout groups"))
     }
-}
 
-impl Keyboard for Enigo {
-    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
-        debug!("\x1b[93mfast_text(text: {text})\x1b[0m");
+    /// Returns the name of the currently active layout (e.g. "English (US)")
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections supports layout groups.
+    /// This is the case for the `wayland` and `xdo` features
+    pub fn current_layout_name(&self) -> InputResult<String> {
+        debug!("\x1b[93mcurrent_layout_name()\x1b[0m");
+        let group = self.layout_group()?;
+        self.layout_groups()?
+            .into_iter()
+            .nth(group as usize)
+            .ok_or(InputError::Simulate("the active layout group has no name"))
+    }
+
+    /// Returns information about the currently active layout
+    ///
+    /// The name is the same as the one of `current_layout_name`. With the
+    /// `x11rb` feature, the language is the short xkb layout name (e.g. "de")
+    /// and the variant is the xkb variant (e.g. "nodeadkeys"). They are not
+    /// available with the other connections
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections supports layout groups.
+    /// This is the case for the `wayland` and `xdo` features
+    pub fn keyboard_layout(&self) -> InputResult<LayoutInfo> {
+        debug!("\x1b[93mkeyboard_layout()\x1b[0m");
+        let name = self.current_layout_name()?;
+        #[allow(unused_mut)]
+        let mut info = LayoutInfo {
+            name,
+            language: None,
+            variant: None,
+        };
+        #[cfg(feature = "x11rb")]
+        if let Some(con) = self.x11.as_ref() {
+            trace!("try getting the layout rules names via x11");
+            let group = self.layout_group()? as usize;
+            match con.layout_rules_names() {
+                Ok((layouts, variants)) => {
+                    let non_empty = |name: Option<String>| name.filter(|n| !n.is_empty());
+                    info.language = non_empty(layouts.into_iter().nth(group));
+                    info.variant = non_empty(variants.into_iter().nth(group));
+                }
+                Err(e) => warn!("unable to get the xkb layout and variant: {e}"),
+            }
+        }
+        Ok(info)
+    }
 
+    /// Collect information about the environment to attach it to bug reports
+    ///
+    /// The backends are the connections that were established. Have a look at
+    /// [`DiagnosticsReport`] for the other information
+    #[must_use]
+    pub fn diagnostics(&self) -> DiagnosticsReport {
+        debug!("\x1b[93mdiagnostics()\x1b[0m");
+        #[allow(unused_mut)]
+        let mut backends = Vec::new();
         #[cfg(feature = "libei")]
-        if let Some(con) = self.libei.as_mut() {
-            trace!("try entering text fast via libei");
-            con.text(text)?;
+        if self.libei.is_some() {
+            backends.push("libei".to_string());
         }
         #[cfg(feature = "wayland")]
-        if let Some(con) = self.wayland.as_mut() {
-            trace!("try entering text fast via wayland");
-            con.text(text)?;
+        if self.wayland.is_some() {
+            backends.push("wayland".to_string());
         }
         #[cfg(any(feature = "x11rb", feature = "xdo"))]
+        if self.x11.is_some() {
+            let name = if cfg!(feature = "x11rb") {
+                "x11rb"
+            } else {
+                "xdo"
+            };
+            backends.push(name.to_string());
+        }
+        diagnostics::report(
+            backends,
+            None,
+            self.keyboard_layout().ok().map(|layout| layout.name),
+            self.main_display().ok(),
+            &diagnostics::redact(&self.settings),
+        )
+    }
+
+    /// Returns a counter that is incremented whenever the keymap or the active
+    /// layout changed. Poll it and compare it to the previous value to know
+    /// when mapping tables that depend on the layout have to be translated
+    /// again
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections supports layout groups.
+    /// This is the case for the `wayland` and `xdo` features
+    pub fn layout_generation(&mut self) -> InputResult<u64> {
+        debug!("\x1b[93mlayout_generation()\x1b[0m");
+        self.connect_lazily()?;
+        #[cfg(feature = "libei")]
+        if let Some(con) = self.libei.as_mut() {
+            trace!("try getting the layout generation via libei");
+            return con.layout_generation();
+        }
+        #[cfg(feature = "x11rb")]
         if let Some(con) = self.x11.as_mut() {
-            trace!("try entering text fast via x11");
-            con.text(text)?;
+            trace!("try getting the layout generation via x11");
+            return con.layout_generation();
         }
-        debug!("entered the text fast");
-        Ok(Some(()))
+        Err(InputError::Simulate("No protocol supports layout groups"))
     }
 
-    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mkey(key: {key:?}, direction: {direction:?})\x1b[0m");
-        // Nothing to do
-        if key == Key::Unicode('\0') {
-            debug!("entering the null byte is a noop");
-            return Ok(());
+    /// Switch to the layout group with the index `group`. All following input
+    /// is interpreted with this layout
+    ///
+    /// # Errors
+    /// Returns an error if none of the connections is able to switch the
+    /// layout group. Only the `x11rb` feature supports it
+    #[allow(unused_variables)]
+    pub fn set_layout_group(&mut self, group: u32) -> InputResult<()> {
+        debug!("\x1b[93mset_layout_group(group: {group:?})\x1b[0m");
+        self.connect_lazily()?;
+        #[cfg(feature = "libei")]
+        if let Some(con) = self.libei.as_mut() {
+            trace!("try setting the layout group via libei");
+            return con.set_layout_group(group);
+        }
+        #[cfg(feature = "x11rb")]
+        if let Some(con) = self.x11.as_mut() {
+            trace!("try setting the layout group via x11");
+            return con.set_layout_group(group);
         }
+        Err(InputError::Simulate("No protocol supports layout groups"))
+    }
 
+    /// Temporarily switch to the layout group with the index `group` and run
+    /// `f`. Afterwards the previously active group is restored, even if `f`
+    /// returned an error
+    ///
+    /// ```no_run
+    /// use enigo::{Enigo, Keyboard, Settings};
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// // Enter text with the second layout (e.g. "ru" of a "us,ru" keymap)
+    /// enigo.with_layout_group(1, |enigo| enigo.text("Привет")).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the layout group could not be switched or restored
+    /// or the error of `f`
+    pub fn with_layout_group<T>(
+        &mut self,
+        group: u32,
+        f: impl FnOnce(&mut Self) -> InputResult<T>,
+    ) -> InputResult<T> {
+        let previous = self.layout_group()?;
+        self.set_layout_group(group)?;
+        let res = f(self);
+        self.set_layout_group(previous)?;
+        res
+    }
+
+    /// Returns true if the lock key (`Key::CapsLock`, `Key::Numlock` or
+    /// `Key::ScrollLock`) is active. On X11 the state of its XKB indicator is
+    /// returned. On Wayland it is the state of the locked modifier of the
+    /// virtual keyboard and with libei the one the EIS implementation reported
+    ///
+    /// # Errors
+    /// Returns an error if the key is not a lock key or none of the
+    /// connections can get its state. The state of Scroll Lock is only
+    /// available on X11. The `xdo` feature does not support it at all
+    pub fn lock_state(&mut self, key: Key) -> InputResult<bool> {
+        debug!("\x1b[93mlock_state(key: {key:?})\x1b[0m");
+        self.connect_lazily()?;
         #[cfg(feature = "libei")]
         if let Some(con) = self.libei.as_mut() {
-            trace!("try entering the key via libei");
-            con.key(key, direction)?;
-            debug!("entered the key via libei");
+            trace!("try getting the lock state via libei");
+            return con.lock_state(key);
+        }
+        #[cfg(feature = "wayland")]
+        if let Some(con) = self.wayland.as_ref() {
+            trace!("try getting the lock state via wayland");
+            return con.lock_state(key);
+        }
+        #[cfg(feature = "x11rb")]
+        if let Some(con) = self.x11.as_ref() {
+            trace!("try getting the lock state via x11");
+            return con.lock_state(key);
         }
+        Err(InputError::Simulate("No protocol supports lock states"))
+    }
 
+    /// Activate or deactivate the lock key (`Key::CapsLock`, `Key::Numlock`
+    /// or `Key::ScrollLock`). Use it to normalize the lock state before
+    /// entering keys. Nothing happens if it already has the state
+    ///
+    /// ```no_run
+    /// use enigo::{Direction::Click, Enigo, Key, Keyboard, Settings};
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// enigo.set_lock_state(Key::CapsLock, false).unwrap();
+    /// enigo.key(Key::Unicode('a'), Click).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the key is not a lock key or none of the
+    /// connections can set its state. Have a look at `lock_state`
+    pub fn set_lock_state(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        debug!("\x1b[93mset_lock_state(key: {key:?}, locked: {locked})\x1b[0m");
+        self.connect_lazily()?;
+        #[cfg(feature = "libei")]
+        if let Some(con) = self.libei.as_mut() {
+            trace!("try setting the lock state via libei");
+            return con.set_lock_state(key, locked);
+        }
         #[cfg(feature = "wayland")]
         if let Some(con) = self.wayland.as_mut() {
-            trace!("try entering the key via wayland");
-            con.key(key, direction)?;
-            debug!("entered the key via wayland");
+            trace!("try setting the lock state via wayland");
+            return con.set_lock_state(key, locked);
         }
-        #[cfg(any(feature = "x11rb", feature = "xdo"))]
+        #[cfg(feature = "x11rb")]
         if let Some(con) = self.x11.as_mut() {
-            trace!("try entering the key via x11");
-            con.key(key, direction)?;
-            debug!("entered the key via x11");
+            trace!("try setting the lock state via x11");
+            return con.set_lock_state(key, locked);
+        }
+        Err(InputError::Simulate("No protocol supports lock states"))
+    }
+
+    /// Returns the repeat rate (keys per second) and delay that are used to
+    /// emulate the auto-repeat of held keycodes
+    #[cfg_attr(not(feature = "wayland"), allow(clippy::unused_self))]
+    fn repeat_info(&mut self) -> (u32, Duration) {
+        #[cfg(feature = "wayland")]
+        if let Some(repeat_info) = self.wayland.as_mut().and_then(wayland::Con::repeat_info) {
+            return repeat_info;
+        }
+        (DEFAULT_REPEAT_RATE, DEFAULT_REPEAT_DELAY)
+    }
+
+    /// Emulate the auto-repeat of the keycodes that are held with
+    /// `Keyboard::raw` if `Settings::linux_emulate_key_repeat` is enabled.
+    /// Wayland and libei leave repeating keys to the clients, but some of them
+    /// misbehave if they never receive repeated key presses. Call this
+    /// regularly (e.g. every 10 ms) while keys are held. It sends another press
+    /// event via Wayland and libei for each keycode that was held longer than
+    /// the repeat delay and was not repeated within the interval of the repeat
+    /// rate. Returns the number of repeated keycodes
+    ///
+    /// # Errors
+    /// Returns an error if a press event could not be sent
+    pub fn repeat_held_keys(&mut self) -> InputResult<usize> {
+        let Some(repeating) = self.repeating.as_ref() else {
+            return Ok(0);
+        };
+        let now = Instant::now();
+        let due: Vec<u16> = repeating
+            .iter()
+            .filter(|&&(_, next)| next <= now)
+            .map(|&(keycode, _)| keycode)
+            .collect();
+        if due.is_empty() {
+            return Ok(0);
+        }
+        let (rate, _) = self.repeat_info();
+        if rate == 0 {
+            return Ok(0);
         }
+        let interval = Duration::from_secs(1) / rate;
 
-        match direction {
-            Direction::Press => {
-                debug!("added the key {key:?} to the held keys");
-                self.held.0.push(key);
+        for &keycode in &due {
+            trace!("repeating the keycode {keycode}");
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                con.raw(keycode, Direction::Press)?;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self.wayland.as_mut() {
+                con.raw(keycode, Direction::Press)?;
             }
-            Direction::Release => {
-                debug!("removed the key {key:?} from the held keys");
-                self.held.0.retain(|&k| k != key);
+        }
+        if let Some(repeating) = self.repeating.as_mut() {
+            for (keycode, next) in repeating.iter_mut() {
+                if due.contains(keycode) {
+                    *next = now + interval;
+                }
             }
-            Direction::Click => (),
         }
+        Ok(due.len())
+    }
+}
 
-        debug!("entered the key");
-        Ok(())
+impl Mouse for Enigo {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        throttled!("button", self, [button, direction], || {
+            self.connect_lazily()?;
+            crate::cleanup::button(button, direction);
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try sending button event via libei");
+                con.button(button, direction)?;
+                debug!("sent button event via libei");
+                success = true;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Pointer))
+            {
+                trace!("try sending button event via wayland");
+                con.button(button, direction)?;
+                debug!("sent button event via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try sending button event via x11");
+                con.button(button, direction)?;
+                debug!("sent button event via x11");
+                success = true;
+            }
+            if success {
+                debug!("sent button event");
+                Ok(())
+            } else {
+                Err(self.no_protocol(Capability::Pointer))
+            }
+        })
+    }
+
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        throttled!("buttons", self, [buttons], || {
+            self.connect_lazily()?;
+            for &(button, direction) in buttons {
+                crate::cleanup::button(button, direction);
+            }
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try sending button events via libei");
+                con.buttons(buttons)?;
+                debug!("sent button events via libei");
+                success = true;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Pointer))
+            {
+                trace!("try sending button events via wayland");
+                con.buttons(buttons)?;
+                debug!("sent button events via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try sending button events via x11");
+                con.buttons(buttons)?;
+                debug!("sent button events via x11");
+                success = true;
+            }
+            if success {
+                debug!("sent button events");
+                Ok(())
+            } else {
+                Err(self.no_protocol(Capability::Pointer))
+            }
+        })
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        throttled!("move_mouse", self, [x, y, coordinate], || {
+            self.move_cursor(f64::from(x), f64::from(y), coordinate)
+        })
+    }
+
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        throttled!("move_mouse_f", self, [x, y, coordinate], || {
+            self.move_cursor(x, y, coordinate)
+        })
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        throttled!("scroll", self, [length, axis], || {
+            self.connect_lazily()?;
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try scrolling via libei");
+                con.scroll(length, axis)?;
+                debug!("scrolled via libei");
+                success = true;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Pointer))
+            {
+                trace!("try scrolling via wayland");
+                con.scroll(length, axis)?;
+                debug!("scrolled via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try scrolling via x11");
+                con.scroll(length, axis)?;
+                debug!("scrolled via x11");
+                success = true;
+            }
+            if success {
+                debug!("scrolled");
+                Ok(())
+            } else {
+                Err(self.no_protocol(Capability::Pointer))
+            }
+        })
+    }
+
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        throttled!("scroll_2d", self, [dx, dy, unit], || {
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try scrolling on both axes via libei");
+                con.scroll_2d(dx, dy, unit)?;
+                debug!("scrolled on both axes via libei");
+                success = true;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Pointer))
+            {
+                trace!("try scrolling on both axes via wayland");
+                con.scroll_2d(dx, dy, unit)?;
+                debug!("scrolled on both axes via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try scrolling on both axes via x11");
+                con.scroll_2d(dx, dy, unit)?;
+                debug!("scrolled on both axes via x11");
+                success = true;
+            }
+            if success {
+                debug!("scrolled on both axes");
+                Ok(())
+            } else {
+                Err(self.no_protocol(Capability::Pointer))
+            }
+        })
+    }
+
+    fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+        traced!("scroll_by_pixels_approx", self, [target_px, axis], || {
+            self.connect_lazily()?;
+            // X11 can only scroll by notches. All connections scroll by notches in that
+            // case, because otherwise the others would scroll before X11 fails
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if self.x11.is_some() {
+                debug!("scrolling by notches because x11 can't scroll by pixels");
+                return self.scroll(
+                    crate::pixels_to_notches(
+                        target_px,
+                        crate::DEFAULT_WHEEL_SCROLL_LINES * crate::LINE_HEIGHT,
+                    )?,
+                    axis,
+                );
+            }
+            let (dx, dy) = match axis {
+                Axis::Horizontal => (target_px, 0),
+                Axis::Vertical => (0, target_px),
+            };
+            self.scroll_2d(dx, dy, ScrollUnit::Pixel)
+        })
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        traced!("main_display", self, [], || {
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_ref() {
+                trace!("try getting the dimensions of the display via libei");
+                return con.main_display();
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self.wayland.as_ref() {
+                trace!("try getting the dimensions of the display via wayland");
+                return con.main_display();
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_ref() {
+                trace!("try getting the dimensions of the display via x11");
+                return con.main_display();
+            }
+            Err(InputError::Simulate("No protocol to enter the result"))
+        })
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        traced!("location", self, [], || {
+            let (x, y) = self.cursor_location()?;
+            origin_to_top_left(self, self.settings.origin, x, y)
+        })
+    }
+
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        traced!("show_cursor", self, [visible], || {
+            self.connect_lazily()?;
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try showing or hiding the cursor via x11");
+                return con.show_cursor(visible);
+            }
+            Err(InputError::Simulate(
+                "showing and hiding the cursor is only supported on X11",
+            ))
+        })
+    }
+
+    fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+        traced!("confine_to", self, [rect], || {
+            rect.check_not_empty()?;
+            self.confinement = Some(rect);
+            Ok(())
+        })
+    }
+
+    fn release_confinement(&mut self) -> InputResult<()> {
+        traced!("release_confinement", self, [], || {
+            self.confinement = None;
+            Ok(())
+        })
+    }
+}
+
+impl Keyboard for Enigo {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        throttled!("fast_text", self, [text], || {
+            self.connect_lazily()?;
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try entering text fast via libei");
+                text_with_policy(con, text, self.settings.unmappable_char_policy)?;
+                success = true;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Keyboard))
+            {
+                trace!("try entering text fast via wayland");
+                text_with_policy(con, text, self.settings.unmappable_char_policy)?;
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try entering text fast via x11");
+                text_with_policy(con, text, self.settings.unmappable_char_policy)?;
+                success = true;
+            }
+            if !success {
+                return Err(self.no_protocol(Capability::Keyboard));
+            }
+            debug!("entered the text fast");
+            Ok(Some(()))
+        })
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        throttled!("key", self, [key, direction], || {
+            self.connect_lazily()?;
+            // Nothing to do
+            if key == Key::Unicode('\0') {
+                debug!("entering the null byte is a noop");
+                return Ok(());
+            }
+            let mut success = false;
+
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try entering the key via libei");
+                con.key(key, direction)?;
+                debug!("entered the key via libei");
+                success = true;
+            }
+
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Keyboard))
+            {
+                trace!("try entering the key via wayland");
+                con.key(key, direction)?;
+                debug!("entered the key via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try entering the key via x11");
+                con.key(key, direction)?;
+                debug!("entered the key via x11");
+                success = true;
+            }
+
+            if !success {
+                return Err(self.no_protocol(Capability::Keyboard));
+            }
+
+            match direction {
+                Direction::Press => {
+                    debug!("added the key {key:?} to the held keys");
+                    crate::cleanup::key(key, direction);
+                    self.held.0.push(key);
+                }
+                Direction::Release => {
+                    debug!("removed the key {key:?} from the held keys");
+                    crate::cleanup::key(key, direction);
+                    self.held.0.retain(|&k| k != key);
+                }
+                Direction::Click => (),
+            }
+
+            debug!("entered the key");
+            Ok(())
+        })
+    }
+
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        throttled!("key_sequence", self, [keys, direction], || {
+            self.connect_lazily()?;
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try entering the key sequence via libei");
+                con.key_sequence(keys, direction)?;
+                debug!("entered the key sequence via libei");
+                success = true;
+            }
+
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Keyboard))
+            {
+                trace!("try entering the key sequence via wayland");
+                con.key_sequence(keys, direction)?;
+                debug!("entered the key sequence via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try entering the key sequence via x11");
+                con.key_sequence(keys, direction)?;
+                debug!("entered the key sequence via x11");
+                success = true;
+            }
+
+            if !success {
+                return Err(self.no_protocol(Capability::Keyboard));
+            }
+
+            for &key in keys {
+                match direction {
+                    Direction::Press => {
+                        debug!("added the key {key:?} to the held keys");
+                        crate::cleanup::key(key, direction);
+                        self.held.0.push(key);
+                    }
+                    Direction::Release => {
+                        debug!("removed the key {key:?} from the held keys");
+                        crate::cleanup::key(key, direction);
+                        self.held.0.retain(|&k| k != key);
+                    }
+                    Direction::Click => (),
+                }
+            }
+
+            debug!("entered the key sequence");
+            Ok(())
+        })
     }
 
     fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mraw(keycode: {keycode:?}, direction: {direction:?})\x1b[0m");
+        throttled!("raw", self, [keycode, direction], || {
+            self.connect_lazily()?;
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try entering the keycode via libei");
+                con.raw(keycode, direction)?;
+                debug!("entered the keycode via libei");
+                success = true;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self
+                .wayland
+                .as_mut()
+                .filter(|con| con.supports(Capability::Keyboard))
+            {
+                trace!("try entering the keycode via wayland");
+                con.raw(keycode, direction)?;
+                debug!("entered the keycode via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try entering the keycode via x11");
+                con.raw(keycode, direction)?;
+                debug!("entered the keycode via x11");
+                success = true;
+            }
 
-        #[cfg(feature = "libei")]
-        if let Some(con) = self.libei.as_mut() {
-            trace!("try entering the keycode via libei");
-            con.raw(keycode, direction)?;
-            debug!("entered the keycode via libei");
-        }
-        #[cfg(feature = "wayland")]
-        if let Some(con) = self.wayland.as_mut() {
-            trace!("try entering the keycode via wayland");
-            con.raw(keycode, direction)?;
-            debug!("entered the keycode via wayland");
-        }
-        #[cfg(any(feature = "x11rb", feature = "xdo"))]
-        if let Some(con) = self.x11.as_mut() {
-            trace!("try entering the keycode via x11");
-            con.raw(keycode, direction)?;
-            debug!("entered the keycode via x11");
-        }
+            if !success {
+                return Err(self.no_protocol(Capability::Keyboard));
+            }
 
-        match direction {
-            Direction::Press => {
-                debug!("added the keycode {keycode:?} to the held keys");
-                self.held.1.push(keycode);
+            match direction {
+                Direction::Press => {
+                    debug!("added the keycode {keycode:?} to the held keys");
+                    crate::cleanup::raw(keycode, direction);
+                    self.held.1.push(keycode);
+                    if self.repeating.is_some() {
+                        let (_, delay) = self.repeat_info();
+                        if let Some(repeating) = self.repeating.as_mut() {
+                            repeating.retain(|&(k, _)| k != keycode);
+                            repeating.push((keycode, Instant::now() + delay));
+                        }
+                    }
+                }
+                Direction::Release => {
+                    debug!("removed the keycode {keycode:?} from the held keys");
+                    crate::cleanup::raw(keycode, direction);
+                    self.held.1.retain(|&k| k != keycode);
+                    if let Some(repeating) = self.repeating.as_mut() {
+                        repeating.retain(|&(k, _)| k != keycode);
+                    }
+                }
+                Direction::Click => (),
             }
-            Direction::Release => {
-                debug!("removed the keycode {keycode:?} from the held keys");
-                self.held.1.retain(|&k| k != keycode);
+
+            debug!("entered the keycode");
+            Ok(())
+        })
+    }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        throttled!("latch_modifier", self, [key], || {
+            self.connect_lazily()?;
+            #[allow(unused_mut)]
+            let mut success = false;
+            // The modifiers are owned by the EIS implementation, so they cannot be
+            // latched with libei
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self.wayland.as_mut() {
+                trace!("try latching the modifier via wayland");
+                con.latch_modifier(key)?;
+                debug!("latched the modifier via wayland");
+                success = true;
             }
-            Direction::Click => (),
-        }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try latching the modifier via x11");
+                con.latch_modifier(key)?;
+                debug!("latched the modifier via x11");
+                success = true;
+            }
+            if success {
+                Ok(())
+            } else {
+                Err(InputError::Simulate(
+                    "No protocol supports latching modifiers",
+                ))
+            }
+        })
+    }
 
-        debug!("entered the keycode");
-        Ok(())
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        throttled!("lock_modifier", self, [key, locked], || {
+            self.connect_lazily()?;
+            #[allow(unused_mut)]
+            let mut success = false;
+            #[cfg(feature = "libei")]
+            if let Some(con) = self.libei.as_mut() {
+                trace!("try locking the modifier via libei");
+                con.lock_modifier(key, locked)?;
+                debug!("locked the modifier via libei");
+                success = true;
+            }
+            #[cfg(feature = "wayland")]
+            if let Some(con) = self.wayland.as_mut() {
+                trace!("try locking the modifier via wayland");
+                con.lock_modifier(key, locked)?;
+                debug!("locked the modifier via wayland");
+                success = true;
+            }
+            #[cfg(any(feature = "x11rb", feature = "xdo"))]
+            if let Some(con) = self.x11.as_mut() {
+                trace!("try locking the modifier via x11");
+                con.lock_modifier(key, locked)?;
+                debug!("locked the modifier via x11");
+                success = true;
+            }
+            if success {
+                Ok(())
+            } else {
+                Err(InputError::Simulate(
+                    "No protocol supports locking modifiers",
+                ))
+            }
+        })
     }
 }
 
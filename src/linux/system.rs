@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use crate::{Capability, InputError, InputResult};
+
+/// Error for the settings that Linux does not have
+fn not_supported(capability: Capability) -> InputError {
+    InputError::NotSupported {
+        capability,
+        backend: "linux",
+    }
+}
+
+pub(crate) fn mouse_speed() -> InputResult<f64> {
+    Err(not_supported(Capability::MouseSpeed))
+}
+
+pub(crate) fn mouse_acceleration_enabled() -> InputResult<bool> {
+    Err(not_supported(Capability::MouseAcceleration))
+}
+
+pub(crate) fn double_click_interval() -> InputResult<Duration> {
+    Err(not_supported(Capability::DoubleClickInterval))
+}
+
+pub(crate) fn screen_dpi() -> InputResult<f64> {
+    Err(not_supported(Capability::ScreenDpi))
+}
+
+pub(crate) fn idle_time() -> InputResult<Duration> {
+    #[cfg(feature = "x11rb")]
+    if let Some(idle) = x11_idle_time() {
+        return Ok(idle);
+    }
+    super::health::idle_time().ok_or(InputError::Simulate("unable to get the idle time"))
+}
+
+/// Ask the `MIT-SCREEN-SAVER` extension for the time since the last input.
+/// It is skipped on Wayland, because Xwayland only knows about the input to
+/// X11 clients
+#[cfg(feature = "x11rb")]
+fn x11_idle_time() -> Option<Duration> {
+    use std::env;
+
+    use x11rb::{connection::Connection as _, protocol::screensaver::ConnectionExt as _};
+
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        return None;
+    }
+    env::var_os("DISPLAY")?;
+    let (connection, screen) = x11rb::connect(None).ok()?;
+    let root = connection.setup().roots.get(screen)?.root;
+    let info = connection.screensaver_query_info(root).ok()?.reply().ok()?;
+    Some(Duration::from_millis(info.ms_since_user_input.into()))
+}
+
+/// Current time of `CLOCK_MONOTONIC`, which logind uses for its timestamps
+#[cfg(any(feature = "a11y", feature = "libei", feature = "wayland"))]
+pub(crate) fn monotonic_now() -> Option<Duration> {
+    let mut now = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &raw mut now) } != 0 {
+        return None;
+    }
+    Some(Duration::new(
+        u64::try_from(now.tv_sec).ok()?,
+        u32::try_from(now.tv_nsec).ok()?,
+    ))
+}
@@ -4,12 +4,12 @@ use std::env;
 use std::os::unix::io::AsFd;
 use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use log::{debug, error, trace, warn};
 use wayland_client::{
-    protocol::{wl_pointer, wl_registry, wl_seat},
-    Connection, Dispatch, EventQueue, QueueHandle,
+    protocol::{wl_keyboard, wl_pointer, wl_registry, wl_seat},
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum,
 };
 use wayland_protocols_misc::{
     zwp_input_method_v2::client::{zwp_input_method_manager_v2, zwp_input_method_v2},
@@ -22,12 +22,15 @@ use wayland_protocols_wlr::virtual_pointer::v1::client::{
 
 use super::keymap::{Bind, KeyMap};
 use crate::{
-    keycodes::Modifier, keycodes::ModifierBitflag, Axis, Button, Coordinate, Direction, InputError,
-    InputResult, Key, Keyboard, Mouse, NewConError,
+    keycodes::Modifier, keycodes::ModifierBitflag, round_coordinates, Axis, Button, Capability,
+    Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse, NewConError, ScrollUnit,
 };
 
 pub type Keycode = u32;
 
+/// Minimum time between two checks of the state of the session
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 pub struct Con {
     keymap: KeyMap<Keycode>,
     event_queue: EventQueue<WaylandState>,
@@ -36,6 +39,11 @@ pub struct Con {
     input_method: Option<(zwp_input_method_v2::ZwpInputMethodV2, u32)>,
     virtual_pointer: Option<zwlr_virtual_pointer_v1::ZwlrVirtualPointerV1>,
     base_time: std::time::Instant,
+    event_time: Option<Duration>, // Time of the events that was set with `Enigo::set_event_time`
+    last_session_check: Option<Instant>,
+    session_locked: bool,
+    latched_modifiers: ModifierBitflag,
+    locked_modifiers: ModifierBitflag,
 }
 
 impl Con {
@@ -74,6 +82,12 @@ impl Con {
             }
         };
 
+        Self::with_connection(&connection)
+    }
+
+    /// Create a new Enigo instance that uses an already established wayland
+    /// connection
+    pub fn with_connection(connection: &Connection) -> Result<Self, NewConError> {
         // Check to see if there was an error trying to connect
         if let Some(e) = connection.protocol_error() {
             error!(
@@ -120,11 +134,17 @@ impl Con {
             input_method,
             virtual_pointer,
             base_time,
+            event_time: None,
+            last_session_check: None,
+            session_locked: false,
+            latched_modifiers: 0,
+            locked_modifiers: 0,
         };
 
         connection.init_protocols()?;
 
-        if connection.apply_keymap().is_err() {
+        // Without a virtual keyboard there is no keymap to apply
+        if connection.virtual_keyboard.is_some() && connection.apply_keymap().is_err() {
             return Err(NewConError::EstablishCon("unable to apply the keymap"));
         };
         Ok(connection)
@@ -180,11 +200,74 @@ impl Con {
                 "no protocol available to simulate input",
             ));
         }
+        for capability in [Capability::Keyboard, Capability::Pointer] {
+            if !self.supports(capability) {
+                warn!(
+                    "the compositor lacks the {capability}, so wayland can't simulate that input"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the compositor offers the protocol for the capability
+    pub fn supports(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::Keyboard => self.virtual_keyboard.is_some(),
+            Capability::Pointer => self.virtual_pointer.is_some(),
+            Capability::MouseSpeed
+            | Capability::MouseAcceleration
+            | Capability::DoubleClickInterval
+            | Capability::ScreenDpi
+            | Capability::IdleTime
+            | Capability::KeepAwake
+            | Capability::Accessibility => false,
+        }
+    }
+
+    /// Returns an `InputError::NotSupported` if the compositor does not offer
+    /// the protocol for the capability
+    fn check_supported(&self, capability: Capability) -> InputResult<()> {
+        if self.supports(capability) {
+            Ok(())
+        } else {
+            Err(InputError::NotSupported {
+                capability,
+                backend: "wayland",
+            })
+        }
+    }
+
+    /// Returns an error if the session is locked or inactive. The compositor
+    /// silently drops the input in that case. The state is asked from logind
+    /// at most once per `SESSION_CHECK_INTERVAL`
+    fn check_session(&mut self) -> InputResult<()> {
+        if self
+            .last_session_check
+            .is_none_or(|last| last.elapsed() >= SESSION_CHECK_INTERVAL)
+        {
+            self.session_locked =
+                super::health::session_state().is_some_and(|state| state.locked || !state.active);
+            self.last_session_check = Some(Instant::now());
+        }
+        if self.session_locked {
+            warn!("the session is locked or inactive");
+            return Err(InputError::SessionLocked);
+        }
         Ok(())
     }
 
-    /// Get the duration since the Keymap was created
+    /// Set the time of the events in milliseconds. The timestamps of Wayland
+    /// wrap around after 49.7 days
+    pub fn set_event_time(&mut self, time: Option<Duration>) {
+        self.event_time = time;
+    }
+
+    /// Get the time that was set or the duration since the Keymap was created
     fn get_time(&self) -> u32 {
+        if let Some(time) = self.event_time {
+            return u32::try_from(time.as_millis() & u128::from(u32::MAX)).unwrap_or(u32::MAX);
+        }
         let duration = self.base_time.elapsed();
         let time = duration.as_millis();
         time.try_into().unwrap_or(u32::MAX)
@@ -218,7 +301,10 @@ impl Con {
             }
             return Ok(());
         }
-        Err(InputError::Simulate("no way to enter key"))
+        Err(InputError::NotSupported {
+            capability: Capability::Keyboard,
+            backend: "wayland",
+        })
     }
 
     /// Sends a modifier event with the updated bitflag of the modifiers to the
@@ -226,15 +312,45 @@ impl Con {
     fn send_modifier_event(&mut self, modifiers: ModifierBitflag) -> InputResult<()> {
         if let Some(vk) = &self.virtual_keyboard {
             is_alive(vk)?;
-            trace!("vk.modifiers({modifiers}, 0, 0, 0)");
-            vk.modifiers(modifiers, 0, 0, 0);
+            let (latched, locked) = (self.latched_modifiers, self.locked_modifiers);
+            trace!("vk.modifiers({modifiers}, {latched}, {locked}, 0)");
+            vk.modifiers(modifiers, latched, locked, 0);
             // TODO: Change to flush()
             if self.event_queue.roundtrip(&mut self.state).is_err() {
                 return Err(InputError::Simulate("The roundtrip on Wayland failed"));
             }
             return Ok(());
         }
-        Err(InputError::Simulate("no way to enter modifier"))
+        Err(InputError::NotSupported {
+            capability: Capability::Keyboard,
+            backend: "wayland",
+        })
+    }
+
+    /// Returns true if the modifier of `Key::CapsLock` or `Key::Numlock` was
+    /// locked with the virtual keyboard
+    pub fn lock_state(&self, key: Key) -> InputResult<bool> {
+        let modifier = lock_key_modifier(key)?;
+        Ok(self.locked_modifiers & modifier.bitflag() != 0)
+    }
+
+    /// Locks or unlocks the modifier of `Key::CapsLock` or `Key::Numlock` of
+    /// the virtual keyboard
+    pub fn set_lock_state(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        self.check_session()?;
+        let modifier = lock_key_modifier(key)?;
+        self.set_locked_modifier(modifier, locked)
+    }
+
+    /// Locks or unlocks the modifier of the virtual keyboard
+    fn set_locked_modifier(&mut self, modifier: Modifier, locked: bool) -> InputResult<()> {
+        if locked {
+            self.locked_modifiers |= modifier.bitflag();
+        } else {
+            self.locked_modifiers &= !modifier.bitflag();
+        }
+        let modifiers = self.keymap.enter_modifier(0, Direction::Click);
+        self.send_modifier_event(modifiers)
     }
 
     /// Apply the current keymap
@@ -251,11 +367,14 @@ impl Con {
                 ));
             };
             // Only send an updated keymap if we had to regenerate it
-            // There should always be a file at this point so unwrapping is fine
-            // here
             if let Some(keymap_size) = keymap_res {
+                let Some(file) = self.keymap.file.as_ref() else {
+                    return Err(InputError::Mapping(
+                        "the regenerated keymap has no file".to_string(),
+                    ));
+                };
                 trace!("update wayland keymap");
-                vk.keymap(1, self.keymap.file.as_ref().unwrap().as_fd(), keymap_size);
+                vk.keymap(1, file.as_fd(), keymap_size);
                 // TODO: Change to flush()
                 if self.event_queue.roundtrip(&mut self.state).is_err() {
                     return Err(InputError::Simulate("The roundtrip on Wayland failed"));
@@ -263,7 +382,10 @@ impl Con {
             }
             return Ok(());
         }
-        Err(InputError::Simulate("no way to apply keymap"))
+        Err(InputError::NotSupported {
+            capability: Capability::Keyboard,
+            backend: "wayland",
+        })
     }
 
     fn raw(&mut self, keycode: Keycode, direction: Direction) -> InputResult<()> {
@@ -273,10 +395,36 @@ impl Con {
         // Let the keymap know that the key was held/no longer held
         // This is important to avoid unmapping held keys
         self.keymap.key(keycode, direction);
+        // Latched modifiers only apply to the next key
+        if direction != Direction::Release && self.latched_modifiers != 0 {
+            self.latched_modifiers = 0;
+            let modifiers = self.keymap.enter_modifier(0, Direction::Click);
+            self.send_modifier_event(modifiers)?;
+        }
 
         Ok(())
     }
 
+    /// Returns the repeat rate (keys per second) and delay the compositor
+    /// reported for the keyboard of the seat. A rate of 0 disables repeating.
+    /// The keyboard is only bound when this is called for the first time,
+    /// because nothing else needs it
+    pub fn repeat_info(&mut self) -> Option<(u32, Duration)> {
+        if self.state.keyboard.is_none() && self.state.seat_has_keyboard {
+            if let Some(seat) = &self.state.seat {
+                let qh = self.event_queue.handle();
+                self.state.keyboard = Some(seat.get_keyboard(&qh, ()));
+                // The repeat info is sent after the keyboard was created
+                let _ = self.event_queue.roundtrip(&mut self.state);
+            }
+        }
+        let (rate, delay) = self.state.repeat_info?;
+        Some((
+            rate.try_into().unwrap_or_default(),
+            Duration::from_millis(delay.try_into().unwrap_or_default()),
+        ))
+    }
+
     /// Flush the Wayland queue
     fn flush(&self) -> InputResult<()> {
         match self.event_queue.flush() {
@@ -309,6 +457,11 @@ impl Drop for Con {
         if let Some(vp) = &self.virtual_pointer {
             vp.destroy();
         }
+        if let Some(keyboard) = &self.state.keyboard {
+            if keyboard.version() >= 3 {
+                keyboard.release();
+            }
+        }
         if self.flush().is_err() {
             error!("could not flush wayland queue");
         }
@@ -325,6 +478,10 @@ struct WaylandState {
     pointer_manager: Option<zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1>,
     kde_input: Option<org_kde_kwin_fake_input::OrgKdeKwinFakeInput>,
     seat: Option<wl_seat::WlSeat>,
+    seat_has_keyboard: bool,
+    keyboard: Option<wl_keyboard::WlKeyboard>,
+    // Rate in characters per second and delay in ms
+    repeat_info: Option<(i32, i32)>,
     /*  output: Option<wl_output::WlOutput>,
     width: i32,
     height: i32,*/
@@ -338,6 +495,9 @@ impl WaylandState {
             pointer_manager: None,
             kde_input: None,
             seat: None,
+            seat_has_keyboard: false,
+            keyboard: None,
+            repeat_info: None,
             /*  output: None,
             width: 0,
             height: 0,*/
@@ -359,12 +519,13 @@ impl Dispatch<wl_registry::WlRegistry, ()> for WaylandState {
         if let wl_registry::Event::Global {
             name,
             interface,
-            version: _,
+            version,
         } = event
         {
             match &interface[..] {
                 "wl_seat" => {
-                    let seat = registry.bind::<wl_seat::WlSeat, _, _>(name, 1, qh, ());
+                    // Version 4 is needed to get the repeat info of the keyboard
+                    let seat = registry.bind::<wl_seat::WlSeat, _, _>(name, version.min(4), qh, ());
                     state.seat = Some(seat);
                 }
                 /*"wl_output" => {
@@ -487,14 +648,41 @@ impl Dispatch<org_kde_kwin_fake_input::OrgKdeKwinFakeInput, ()> for WaylandState
 
 impl Dispatch<wl_seat::WlSeat, ()> for WaylandState {
     fn event(
-        _state: &mut Self,
+        state: &mut Self,
         _seat: &wl_seat::WlSeat,
         event: wl_seat::Event,
         (): &(),
         _: &Connection,
         _qh: &QueueHandle<Self>,
     ) {
-        warn!("Got a seat event {:?}", event);
+        match event {
+            wl_seat::Event::Capabilities {
+                capabilities: WEnum::Value(capabilities),
+            } => {
+                state.seat_has_keyboard = capabilities.contains(wl_seat::Capability::Keyboard);
+            }
+            event => trace!("Got a seat event {event:?}"),
+        }
+    }
+}
+
+impl Dispatch<wl_keyboard::WlKeyboard, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _keyboard: &wl_keyboard::WlKeyboard,
+        event: wl_keyboard::Event,
+        (): &(),
+        _: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_keyboard::Event::RepeatInfo { rate, delay } => {
+                debug!("the repeat rate is {rate}/s with a delay of {delay} ms");
+                state.repeat_info = Some((rate, delay));
+            }
+            // The keymap fd is closed when the event is dropped
+            event => trace!("Got a keyboard event {event:?}"),
+        }
     }
 }
 
@@ -576,6 +764,7 @@ impl Drop for WaylandState {
 
 impl Keyboard for Con {
     fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        self.check_session()?;
         if let Some((im, serial)) = self.input_method.as_mut() {
             is_alive(im)?;
             trace!("fast text input with imput_method protocol");
@@ -592,6 +781,7 @@ impl Keyboard for Con {
     }
 
     fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        self.check_session()?;
         // Send the events to the compositor
         if let Ok(modifier) = Modifier::try_from(key) {
             trace!("it is a modifier: {modifier:?}");
@@ -616,11 +806,30 @@ impl Keyboard for Con {
     }
 
     fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        self.check_session()?;
         self.raw(keycode as u32, direction)
     }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        self.check_session()?;
+        let modifier = Modifier::try_from(key)
+            .map_err(|_| InputError::InvalidInput("the key is not a modifier"))?;
+        self.latched_modifiers |= modifier.bitflag();
+        let modifiers = self.keymap.enter_modifier(0, Direction::Click);
+        self.send_modifier_event(modifiers)
+    }
+
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        self.check_session()?;
+        let modifier = Modifier::try_from(key)
+            .map_err(|_| InputError::InvalidInput("the key is not a modifier"))?;
+        self.set_locked_modifier(modifier, locked)
+    }
 }
 impl Mouse for Con {
     fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        self.check_session()?;
+        self.check_supported(Capability::Pointer)?;
         if let Some(vp) = &self.virtual_pointer {
             // Do nothing if one of the mouse scroll buttons was released
             // Releasing one of the scroll mouse buttons has no effect
@@ -673,14 +882,27 @@ impl Mouse for Con {
     }
 
     fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        self.move_mouse_f(f64::from(x), f64::from(y), coordinate)
+    }
+
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        self.check_session()?;
+        self.check_supported(Capability::Pointer)?;
         if let Some(vp) = &self.virtual_pointer {
             let time = self.get_time();
             match coordinate {
                 Coordinate::Rel => {
+                    if !x.is_finite() || !y.is_finite() {
+                        return Err(InputError::InvalidInput(
+                            "the coordinates have to be finite",
+                        ));
+                    }
                     trace!("vp.motion({time}, {x}, {y})");
-                    vp.motion(time, x as f64, y as f64);
+                    vp.motion(time, x, y);
                 }
                 Coordinate::Abs => {
+                    // The absolute motion only takes whole pixels
+                    let (x, y) = round_coordinates(x, y)?;
                     let Ok(x) = x.try_into() else {
                         return Err(InputError::InvalidInput(
                             "the absolute coordinates cannot be negative",
@@ -711,6 +933,8 @@ impl Mouse for Con {
     }
 
     fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        self.check_session()?;
+        self.check_supported(Capability::Pointer)?;
         if let Some(vp) = &self.virtual_pointer {
             // TODO: Check what the value of length should be
             // TODO: Check if it would be better to use .axis_discrete here
@@ -730,6 +954,42 @@ impl Mouse for Con {
         }
     }
 
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        self.check_session()?;
+        self.check_supported(Capability::Pointer)?;
+        if let Some(vp) = &self.virtual_pointer {
+            let time = self.get_time();
+            for (axis, length) in [
+                (wl_pointer::Axis::HorizontalScroll, dx),
+                (wl_pointer::Axis::VerticalScroll, dy),
+            ] {
+                if length == 0 {
+                    continue;
+                }
+                match unit {
+                    ScrollUnit::Line => {
+                        // libinput reports 15 for each click of a mouse wheel
+                        trace!(
+                            "vp.axis_discrete(time, {axis:?}, {}, {length})",
+                            length * 15
+                        );
+                        vp.axis_discrete(time, axis, (length * 15).into(), length);
+                    }
+                    ScrollUnit::Pixel => {
+                        trace!("vp.axis(time, {axis:?}, {length})");
+                        vp.axis(time, axis, length.into());
+                    }
+                }
+            }
+            // Both axes are part of the same frame
+            vp.frame();
+        }
+        match self.event_queue.roundtrip(&mut self.state) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(InputError::Simulate("The roundtrip on Wayland failed")),
+        }
+    }
+
     fn main_display(&self) -> InputResult<(i32, i32)> {
         // TODO Implement this
         error!("You tried to get the dimensions of the main display. I don't know how this is possible under Wayland. Let me know if there is a new protocol");
@@ -743,6 +1003,18 @@ impl Mouse for Con {
     }
 }
 
+/// Returns the modifier of the lock key. Scroll Lock has no modifier
+fn lock_key_modifier(key: Key) -> InputResult<Modifier> {
+    match key {
+        Key::CapsLock | Key::Numlock => Modifier::try_from(key)
+            .map_err(|_| InputError::InvalidInput("the lock key has no modifier")),
+        Key::ScrollLock => Err(InputError::Simulate(
+            "Scroll Lock has no modifier that could be locked on Wayland",
+        )),
+        _ => Err(InputError::InvalidInput("the key is not a lock key")),
+    }
+}
+
 fn is_alive<P: wayland_client::Proxy>(proxy: &P) -> InputResult<()> {
     if proxy.is_alive() {
         Ok(())
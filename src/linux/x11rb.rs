@@ -5,9 +5,14 @@ use log::{debug, error, trace, warn};
 use x11rb::{
     connection::Connection,
     protocol::{
-        randr::ConnectionExt as _,
+        randr::{ConnectionExt as _, MonitorInfo},
+        xfixes::ConnectionExt as _,
         xinput::DeviceUse,
-        xproto::{ConnectionExt as _, GetKeyboardMappingReply, GetModifierMappingReply, Screen},
+        xkb::{self, ConnectionExt as _},
+        xproto::{
+            Atom, AtomEnum, ConnectionExt as _, GetKeyboardMappingReply, GetModifierMappingReply,
+            ModMask, Screen,
+        },
         xtest::ConnectionExt as _,
     },
     rust_connection::{ConnectError, ConnectionError, DefaultStream, ReplyError, RustConnection},
@@ -17,19 +22,28 @@ use x11rb::{
 use super::keymap::{Bind, KeyMap, Keysym};
 use crate::{
     keycodes::Modifier, Axis, Button, Coordinate, Direction, InputError, InputResult, Key,
-    Keyboard, Mouse, NewConError,
+    Keyboard, Mouse, NewConError, UnicodeKeyMode,
 };
 
 type CompositorConnection = RustConnection<DefaultStream>;
 
 pub type Keycode = u8;
 
+/// Name, position and size of a monitor
+pub type Monitor = (String, (i32, i32), (i32, i32));
+
 pub struct Con {
     connection: CompositorConnection,
     screen: Screen,
     keymap: KeyMap<Keycode>,
     modifiers: Vec<Keycode>,
+    xkb: bool,  // Is the XKB extension available
     delay: u32, // milliseconds
+    unicode_key_mode: UnicodeKeyMode,
+    // Last observed layout group and names of the groups
+    last_layout: Option<(u32, Vec<String>)>,
+    layout_generation: u64,
+    monitor: Option<(i16, i16, u16, u16)>, // x, y, width, height of the selected monitor
 }
 
 impl From<ConnectionError> for NewConError {
@@ -60,10 +74,15 @@ impl Con {
     ///   properly enter all chars
     /// * `dpy_name` - If no `dpy_name` is provided, the value from $DISPLAY is
     ///   used
+    /// * `unicode_key_mode` - How a `Key::Unicode` is simulated
     ///
     /// # Errors
     /// TODO
-    pub fn new(dpy_name: Option<&str>, delay: u32) -> Result<Con, NewConError> {
+    pub fn new(
+        dpy_name: Option<&str>,
+        delay: u32,
+        unicode_key_mode: UnicodeKeyMode,
+    ) -> Result<Con, NewConError> {
         debug!("using x11rb");
         let (connection, screen_idx) = x11rb::connect(dpy_name.as_deref())?;
         let setup = connection.setup();
@@ -89,12 +108,28 @@ impl Con {
         // Get the keycodes of the modifiers
         let modifiers = Self::find_modifier_keycodes(&connection)?;
 
+        // The XKB extension is needed to query and switch the layout groups. The
+        // request fails if the X server does not have the extension at all
+        let xkb = connection
+            .xkb_use_extension(1, 0)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|reply| reply.supported);
+        if !xkb {
+            warn!("the XKB extension is not supported by the X server");
+        }
+
         Ok(Con {
             connection,
             screen,
             keymap,
             modifiers,
+            xkb,
             delay,
+            unicode_key_mode,
+            last_layout: None,
+            layout_generation: 0,
+            monitor: None,
         })
     }
 
@@ -109,6 +144,31 @@ impl Con {
         self.delay = delay;
     }
 
+    /// Warp the pointer to the absolute location without simulating a motion
+    /// event
+    pub fn warp_mouse(&mut self, x: i32, y: i32) -> InputResult<()> {
+        let (x, y) = match self.monitor {
+            Some((mon_x, mon_y, _, _)) => (x + i32::from(mon_x), y + i32::from(mon_y)),
+            None => (x, y),
+        };
+        let (Ok(x), Ok(y)) = (i16::try_from(x), i16::try_from(y)) else {
+            return Err(InputError::InvalidInput(
+                "the coordinates cannot be negative and must fit in i16",
+            ));
+        };
+        debug!("warp_pointer with x {x}, y {y}");
+        self.connection
+            .warp_pointer(x11rb::NONE, self.screen.root, 0, 0, 0, 0, x, y)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when warping the pointer with x11rb")
+            })?;
+        self.connection.sync().map_err(|e| {
+            error!("{e}");
+            InputError::Simulate("error when syncing with X server using x11rb")
+        })
+    }
+
     /// Find keycodes that have not yet been mapped any keysyms
     fn get_keyboard_mapping(
         connection: &CompositorConnection,
@@ -185,6 +245,435 @@ impl Con {
         Ok(modifier_keycodes)
     }
 
+    /// Returns the names of the layout groups of the core keyboard
+    pub fn layout_groups(&self) -> InputResult<Vec<String>> {
+        if !self.xkb {
+            return Err(InputError::Simulate("the XKB extension is not available"));
+        }
+        let groups = self
+            .connection
+            .xkb_get_names(xkb::ID::USE_CORE_KBD.into(), xkb::NameDetail::GROUP_NAMES)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting xkb_get_names with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of xkb_get_names with x11rb: {e:?}")
+            })?
+            .value_list
+            .groups
+            .unwrap_or_default();
+
+        groups
+            .into_iter()
+            .map(|atom| self.atom_name(atom))
+            .collect()
+    }
+
+    /// Returns the xkb layouts and variants of all groups (e.g. `["us", "de"]`
+    /// and `["", "nodeadkeys"]`). They are read from the `_XKB_RULES_NAMES`
+    /// property of the root window that is set by `setxkbmap`
+    pub fn layout_rules_names(&self) -> InputResult<(Vec<String>, Vec<String>)> {
+        let atom = self
+            .connection
+            .intern_atom(true, b"_XKB_RULES_NAMES")
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting intern_atom with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of intern_atom with x11rb: {e:?}")
+            })?
+            .atom;
+        if atom == x11rb::NONE {
+            return Err(InputError::Simulate(
+                "the _XKB_RULES_NAMES property is not set",
+            ));
+        }
+        let value = self
+            .connection
+            .get_property(false, self.screen.root, atom, AtomEnum::STRING, 0, 1024)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting get_property with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of get_property with x11rb: {e:?}")
+            })?
+            .value;
+
+        // The property contains the null terminated rules, model, layout, variant
+        // and options
+        let names: Vec<_> = value
+            .split(|&b| b == 0)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+        let split = |idx: usize| -> Vec<String> {
+            names
+                .get(idx)
+                .map(|list| list.split(',').map(str::to_string).collect())
+                .unwrap_or_default()
+        };
+        Ok((split(2), split(3)))
+    }
+
+    /// Returns the id of the active window. It is read from the
+    /// `_NET_ACTIVE_WINDOW` property of the root window that is set by the
+    /// window manager
+    pub fn active_window(&self) -> InputResult<u64> {
+        let atom = self
+            .connection
+            .intern_atom(true, b"_NET_ACTIVE_WINDOW")
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting intern_atom with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of intern_atom with x11rb: {e:?}")
+            })?
+            .atom;
+        if atom == x11rb::NONE {
+            return Err(InputError::Simulate(
+                "the window manager does not support _NET_ACTIVE_WINDOW",
+            ));
+        }
+        let reply = self
+            .connection
+            .get_property(false, self.screen.root, atom, AtomEnum::WINDOW, 0, 1)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting get_property with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of get_property with x11rb: {e:?}")
+            })?;
+        reply
+            .value32()
+            .and_then(|mut windows| windows.next())
+            .filter(|&window| window != x11rb::NONE)
+            .map(u64::from)
+            .ok_or(InputError::Simulate("there is no active window"))
+    }
+
+    /// Returns the name of the atom
+    fn atom_name(&self, atom: Atom) -> InputResult<String> {
+        let name = self
+            .connection
+            .get_atom_name(atom)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting get_atom_name with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of get_atom_name with x11rb: {e:?}")
+            })?
+            .name;
+        Ok(String::from_utf8_lossy(&name).into_owned())
+    }
+
+    /// Returns the active `RandR` monitors of the screen
+    fn randr_monitors(&self) -> InputResult<Vec<MonitorInfo>> {
+        Ok(self
+            .connection
+            .randr_get_monitors(self.screen.root, true)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting randr_get_monitors with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of randr_get_monitors with x11rb: {e:?}")
+            })?
+            .monitors)
+    }
+
+    /// Returns the name, the position and the size of all active monitors of
+    /// the screen
+    pub fn monitors(&self) -> InputResult<Vec<Monitor>> {
+        self.randr_monitors()?
+            .into_iter()
+            .map(|m| {
+                Ok((
+                    self.atom_name(m.name)?,
+                    (m.x.into(), m.y.into()),
+                    (m.width.into(), m.height.into()),
+                ))
+            })
+            .collect()
+    }
+
+    /// Select the monitor with the index `monitor` of the list returned by
+    /// `monitors`. Absolute coordinates are then relative to the top left
+    /// corner of the monitor and `main_display` returns its size. With `None`,
+    /// the whole screen is used again
+    pub fn set_monitor(&mut self, monitor: Option<usize>) -> InputResult<()> {
+        let Some(monitor) = monitor else {
+            self.monitor = None;
+            return Ok(());
+        };
+        let Some(m) = self.randr_monitors()?.into_iter().nth(monitor) else {
+            return Err(InputError::InvalidInput(
+                "there is no monitor with that index",
+            ));
+        };
+        debug!(
+            "selected monitor {monitor} at ({}, {}) with the size {}x{}",
+            m.x, m.y, m.width, m.height
+        );
+        self.monitor = Some((m.x, m.y, m.width, m.height));
+        Ok(())
+    }
+
+    /// Returns the index of the currently effective layout group of the core
+    /// keyboard
+    pub fn layout_group(&self) -> InputResult<u32> {
+        if !self.xkb {
+            return Err(InputError::Simulate("the XKB extension is not available"));
+        }
+        let state = self
+            .connection
+            .xkb_get_state(xkb::ID::USE_CORE_KBD.into())
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting xkb_get_state with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of xkb_get_state with x11rb: {e:?}")
+            })?;
+        Ok(u8::from(state.group).into())
+    }
+
+    /// Locks the layout group of the core keyboard to the group with the index
+    /// `group`
+    pub fn set_layout_group(&mut self, group: u32) -> InputResult<()> {
+        if !self.xkb {
+            return Err(InputError::Simulate("the XKB extension is not available"));
+        }
+        // XKB supports at most four groups
+        let Ok(group @ 0..=3) = u8::try_from(group) else {
+            return Err(InputError::InvalidInput(
+                "the layout group has to be in the range 0..=3 on X11",
+            ));
+        };
+        debug!("xkb_latch_lock_state with group {group}");
+        self.connection
+            .xkb_latch_lock_state(
+                xkb::ID::USE_CORE_KBD.into(),
+                ModMask::default(),
+                ModMask::default(),
+                true,
+                xkb::Group::from(group),
+                ModMask::default(),
+                false,
+                0,
+            )
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when using xkb_latch_lock_state with x11rb: {e:?}")
+            })?;
+        self.connection.sync().map_err(|e| {
+            error!("{e}");
+            InputError::Simulate(
+                "error when syncing with X server using x11rb after the layout group was changed: {e:?}",
+            )
+        })
+    }
+
+    /// Returns true if the indicator of the lock key (`Key::CapsLock`,
+    /// `Key::Numlock` or `Key::ScrollLock`) is on
+    pub fn lock_state(&self, key: Key) -> InputResult<bool> {
+        if !self.xkb {
+            return Err(InputError::Simulate("the XKB extension is not available"));
+        }
+        let name: &[u8] = match key {
+            Key::CapsLock => b"Caps Lock",
+            Key::Numlock => b"Num Lock",
+            Key::ScrollLock => b"Scroll Lock",
+            _ => return Err(InputError::InvalidInput("the key is not a lock key")),
+        };
+        let indicator = self
+            .connection
+            .intern_atom(true, name)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting intern_atom with x11rb: {e:?}")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error with the reply of intern_atom with x11rb: {e:?}")
+            })?
+            .atom;
+        let reply = self
+            .connection
+            .xkb_get_named_indicator(
+                xkb::ID::USE_CORE_KBD.into(),
+                xkb::LedClass::DFLT_XI_CLASS,
+                xkb::ID::DFLT_XI_ID,
+                indicator,
+            )
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate(
+                    "error when requesting xkb_get_named_indicator with x11rb: {e:?}",
+                )
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate(
+                    "error with the reply of xkb_get_named_indicator with x11rb: {e:?}",
+                )
+            })?;
+        if !reply.found {
+            return Err(InputError::Simulate(
+                "the keymap has no indicator for the key",
+            ));
+        }
+        Ok(reply.on)
+    }
+
+    /// Locks or unlocks the modifier of `Key::CapsLock` or `Key::Numlock`.
+    /// Scroll Lock has no modifier, so the key is clicked if the state of its
+    /// indicator differs
+    pub fn set_lock_state(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        match key {
+            Key::CapsLock | Key::Numlock => self.lock_modifier(key, locked),
+            Key::ScrollLock => {
+                if self.lock_state(key)? != locked {
+                    self.key(key, Direction::Click)?;
+                }
+                Ok(())
+            }
+            _ => Err(InputError::InvalidInput("the key is not a lock key")),
+        }
+    }
+
+    /// Returns the real modifier `Key::Numlock` is bound to in the modifier
+    /// mapping. It is usually `Mod2`, but that is not guaranteed
+    fn numlock_modifier(&self) -> InputResult<Modifier> {
+        const MODIFIERS: [Modifier; 8] = [
+            Modifier::Shift,
+            Modifier::Lock,
+            Modifier::Control,
+            Modifier::Mod1,
+            Modifier::Mod2,
+            Modifier::Mod3,
+            Modifier::Mod4,
+            Modifier::Mod5,
+        ];
+        let map_err = |e: ReplyError| {
+            error!("{e}");
+            InputError::Simulate("unable to get the mapping of the modifiers")
+        };
+        let setup = self.connection.setup();
+        let (keysyms_per_keycode, keysyms) =
+            Self::get_keyboard_mapping(&self.connection, setup.min_keycode, setup.max_keycode)
+                .map_err(map_err)?;
+        let numlock_keycodes: Vec<Keycode> = keysyms
+            .chunks(keysyms_per_keycode.max(1).into())
+            .zip(setup.min_keycode..=setup.max_keycode)
+            .filter(|(syms, _)| syms.contains(&Keysym::Num_Lock.raw()))
+            .map(|(_, keycode)| keycode)
+            .collect();
+
+        let modifier_reply = self
+            .connection
+            .get_modifier_mapping()
+            .map_err(|e| map_err(e.into()))?
+            .reply()
+            .map_err(map_err)?;
+        let keycodes_per_modifier = usize::from(modifier_reply.keycodes_per_modifier()).max(1);
+        modifier_reply
+            .keycodes
+            .chunks(keycodes_per_modifier)
+            .zip(MODIFIERS)
+            .find(|(keycodes, _)| keycodes.iter().any(|k| numlock_keycodes.contains(k)))
+            .map(|(_, modifier)| modifier)
+            .inspect(|modifier| debug!("Num Lock is bound to {modifier:?}"))
+            .ok_or(InputError::Simulate("Num Lock is not bound to a modifier"))
+    }
+
+    /// Returns the modifier of the key. The modifier of `Key::Numlock` is
+    /// looked up in the modifier mapping
+    fn modifier(&self, key: Key) -> InputResult<Modifier> {
+        if key == Key::Numlock {
+            return self.numlock_modifier();
+        }
+        Modifier::try_from(key).map_err(|_| InputError::InvalidInput("the key is not a modifier"))
+    }
+
+    /// Latches or locks the modifiers of the core keyboard
+    fn latch_lock_modifier(
+        &self,
+        modifier: Modifier,
+        latch: bool,
+        locked: bool,
+    ) -> InputResult<()> {
+        if !self.xkb {
+            return Err(InputError::Simulate("the XKB extension is not available"));
+        }
+        let mask = ModMask::from(u16::try_from(modifier.bitflag()).unwrap_or_default());
+        let (affect_locks, locks, affect_latches) = if latch {
+            (ModMask::default(), ModMask::default(), mask)
+        } else if locked {
+            (mask, mask, ModMask::default())
+        } else {
+            (mask, ModMask::default(), ModMask::default())
+        };
+        debug!("xkb_latch_lock_state with the modifier {modifier:?} (latch: {latch}, locked: {locked})");
+        self.connection
+            .xkb_latch_lock_state(
+                xkb::ID::USE_CORE_KBD.into(),
+                affect_locks,
+                locks,
+                false,
+                xkb::Group::M1,
+                affect_latches,
+                false,
+                0,
+            )
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when using xkb_latch_lock_state with x11rb: {e:?}")
+            })?;
+        self.connection.sync().map_err(|e| {
+            error!("{e}");
+            InputError::Simulate(
+                "error when syncing with X server using x11rb after the modifiers were changed: {e:?}",
+            )
+        })
+    }
+
+    /// Returns a counter that is incremented every time the active layout group
+    /// or the names of the groups changed since the last call
+    pub fn layout_generation(&mut self) -> InputResult<u64> {
+        let layout = (self.layout_group()?, self.layout_groups()?);
+        if self.last_layout.as_ref() != Some(&layout) {
+            if self.last_layout.is_some() {
+                debug!("the layout changed to {layout:?}");
+                self.layout_generation = self.layout_generation.wrapping_add(1);
+            }
+            self.last_layout = Some(layout);
+        }
+        Ok(self.layout_generation)
+    }
+
     // Get the device id of the first device that is found which has the same usage
     // as the input parameter
     fn device_id(&self, usage: DeviceUse) -> InputResult<u8> {
@@ -257,6 +746,12 @@ impl Keyboard for Con {
                 debug!("it is a modifier: {modifier:?}");
                 self.modifiers[modifier.no()].into()
             }
+            Err(_)
+                if matches!(key, Key::Unicode(_))
+                    && self.unicode_key_mode == UnicodeKeyMode::Layout =>
+            {
+                self.keymap.layout_keycode(key)?.into()
+            }
             // All regular keys might have to get mapped
             _ => self.keymap.key_to_keycode(&self.connection, key)?.into(),
         };
@@ -332,6 +827,16 @@ impl Keyboard for Con {
 
         Ok(())
     }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        let modifier = self.modifier(key)?;
+        self.latch_lock_modifier(modifier, true, false)
+    }
+
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        let modifier = self.modifier(key)?;
+        self.latch_lock_modifier(modifier, false, locked)
+    }
 }
 
 impl Mouse for Con {
@@ -410,7 +915,15 @@ impl Mouse for Con {
             Coordinate::Abs => 0,
         };
         let time = x11rb::CURRENT_TIME;
-        let root = x11rb::NONE; //  the root window of the screen the pointer is currently on
+        let (root, x, y) = match (coordinate, self.monitor) {
+            // Move relative to the selected monitor on the screen of the connection
+            (Coordinate::Abs, Some((mon_x, mon_y, _, _))) => {
+                (self.screen.root, x + i32::from(mon_x), y + i32::from(mon_y))
+            }
+            (Coordinate::Abs, None) => (self.screen.root, x, y),
+            // The root window of the screen the pointer is currently on
+            (Coordinate::Rel, _) => (x11rb::NONE, x, y),
+        };
 
         let Ok(root_x) = x.try_into() else {
             return Err(InputError::InvalidInput(
@@ -464,25 +977,18 @@ impl Mouse for Con {
     }
 
     fn main_display(&self) -> InputResult<(i32, i32)> {
-        let main_display = self
-            .connection
-            .randr_get_screen_resources(self.screen.root)
-            .map_err(|e| {
-                error!("{e}");
-                InputError::Simulate(
-                    "error when requesting randr_get_screen_resources with x11rb: {e:?}",
-                )
-            })?
-            .reply()
-            .map_err(|e| {
-                error!("{e}");
-                InputError::Simulate(
-                    "error with the reply of randr_get_screen_resources with x11rb: {e:?}",
-                )
-            })?
-            .modes[0];
-
-        Ok((main_display.width as i32, main_display.height as i32))
+        if let Some((_, _, width, height)) = self.monitor {
+            return Ok((width.into(), height.into()));
+        }
+        // Use the primary monitor or the whole screen if there is none
+        let monitors = self.randr_monitors()?;
+        if let Some(primary) = monitors.iter().find(|m| m.primary) {
+            return Ok((primary.width.into(), primary.height.into()));
+        }
+        Ok((
+            self.screen.width_in_pixels.into(),
+            self.screen.height_in_pixels.into(),
+        ))
     }
 
     fn location(&self) -> InputResult<(i32, i32)> {
@@ -500,4 +1006,34 @@ impl Mouse for Con {
             })?;
         Ok((reply.root_x as i32, reply.root_y as i32))
     }
+
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        // The version has to be negotiated before the extension can be used
+        self.connection
+            .xfixes_query_version(4, 0)
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("error when requesting the XFixes version with x11rb")
+            })?
+            .reply()
+            .map_err(|e| {
+                error!("{e}");
+                InputError::Simulate("the X server does not support XFixes 4")
+            })?;
+        let res = if visible {
+            debug!("show the cursor with xfixes");
+            self.connection.xfixes_show_cursor(self.screen.root)
+        } else {
+            debug!("hide the cursor with xfixes");
+            self.connection.xfixes_hide_cursor(self.screen.root)
+        };
+        res.map_err(|e| {
+            error!("{e}");
+            InputError::Simulate("error when showing or hiding the cursor with x11rb")
+        })?;
+        self.connection.sync().map_err(|e| {
+            error!("{e}");
+            InputError::Simulate("error when syncing with X server using x11rb")
+        })
+    }
 }
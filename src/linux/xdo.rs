@@ -5,10 +5,11 @@ use std::{
 
 use libc::useconds_t;
 
-use log::debug;
+use log::{debug, warn};
 
 use crate::{
-    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse, NewConError,
+    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse,
+    NewConError, UnicodeKeyMode,
 };
 use xkeysym::Keysym;
 
@@ -61,6 +62,8 @@ extern "C" {
         screen: c_int,
     ) -> c_int;
 
+    fn xdo_get_active_window(xdo: Xdo, window: *mut Window) -> c_int;
+
     fn xdo_get_mouse_location2(
         xdo: Xdo,
         x: *mut c_int,
@@ -97,8 +100,15 @@ impl Con {
     /// Create a new Enigo instance
     /// If no `dyp_name` is provided, the $DISPLAY environment variable is read
     /// and used instead
-    pub fn new(dyp_name: Option<&str>, delay: u32) -> Result<Self, NewConError> {
+    pub fn new(
+        dyp_name: Option<&str>,
+        delay: u32,
+        unicode_key_mode: UnicodeKeyMode,
+    ) -> Result<Self, NewConError> {
         debug!("using xdo");
+        if unicode_key_mode == UnicodeKeyMode::Layout {
+            warn!("xdo does not support UnicodeKeyMode::Layout. The characters are always entered regardless of the layout");
+        }
         let xdo = match dyp_name {
             Some(name) => {
                 let Ok(string) = CString::new(name.as_bytes()) else {
@@ -132,6 +142,26 @@ impl Con {
     pub fn set_delay(&mut self, delay: u32) {
         self.delay = delay * 1000;
     }
+
+    /// Warping the pointer is not supported by libxdo
+    #[allow(clippy::unused_self)]
+    pub fn warp_mouse(&mut self, _x: i32, _y: i32) -> InputResult<()> {
+        Err(InputError::Simulate(
+            "warping the cursor is not supported with the xdo feature",
+        ))
+    }
+
+    /// Returns the id of the active window
+    #[allow(clippy::useless_conversion)] // Window is not a u64 on all platforms
+    pub fn active_window(&self) -> InputResult<u64> {
+        let mut window = CURRENT_WINDOW;
+        debug!("xdo_get_active_window");
+        let res = unsafe { xdo_get_active_window(self.xdo, &raw mut window) };
+        if res != XDO_SUCCESS || window == CURRENT_WINDOW {
+            return Err(InputError::Simulate("unable to get the active window"));
+        }
+        Ok(window.into())
+    }
 }
 
 impl Drop for Con {
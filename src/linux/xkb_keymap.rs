@@ -0,0 +1,328 @@
+//! Utilities to translate between keysyms, keycodes and characters with an xkb
+//! keymap
+//!
+//! This is useful if you need to know which keycode produces which symbol with
+//! the current layout, for example when relaying physical key presses in a
+//! remote desktop server or when announcing them in a screen reader.
+//!
+//! [`TestKeyboard::from_keymap`](crate::test_keyboard::TestKeyboard::from_keymap)
+//! uses a keymap to predict the text that simulated input produces without
+//! simulating anything. This allows testing macros in unit tests.
+
+use xkbcommon::xkb;
+pub use xkeysym::Keysym;
+
+use crate::{Direction, InputError, InputResult, Key};
+
+/// An xkb keymap that can be used to look up keysyms and keycodes
+///
+/// All keycodes are xkb keycodes (the evdev keycode + 8)
+#[derive(Clone)]
+pub struct Keymap {
+    keymap: xkb::Keymap,
+}
+
+impl std::fmt::Debug for Keymap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Keymap")
+            .field("min_keycode", &self.keymap.min_keycode().raw())
+            .field("max_keycode", &self.keymap.max_keycode().raw())
+            .field("layouts", &self.layout_names())
+            .finish()
+    }
+}
+
+impl From<xkb::Keymap> for Keymap {
+    fn from(keymap: xkb::Keymap) -> Self {
+        Self { keymap }
+    }
+}
+
+impl Keymap {
+    /// Compile a keymap from its textual representation (`xkb_keymap { ...
+    /// };`)
+    ///
+    /// # Errors
+    /// Returns an error if the keymap contains a NUL byte before its end or
+    /// could not be compiled
+    pub fn from_string(keymap: &str) -> InputResult<Self> {
+        // Keymaps that are shared via a file descriptor are often terminated by
+        // a NUL byte. Any other NUL byte would make xkbcommon panic
+        let keymap = keymap.trim_end_matches('\0');
+        if keymap.contains('\0') {
+            return Err(InputError::InvalidInput(
+                "the keymap must not contain a NUL byte",
+            ));
+        }
+        let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+        xkb::Keymap::new_from_string(
+            &context,
+            keymap.to_string(),
+            xkb::KEYMAP_FORMAT_TEXT_V1,
+            xkb::KEYMAP_COMPILE_NO_FLAGS,
+        )
+        .map(Self::from)
+        .ok_or(InputError::InvalidInput("unable to compile the keymap"))
+    }
+
+    /// Compile a keymap from RMLVO names (e.g. `layout` "us,ru"). Empty
+    /// strings use the system defaults
+    ///
+    /// # Errors
+    /// Returns an error if the keymap could not be compiled
+    pub fn from_names(
+        rules: &str,
+        model: &str,
+        layout: &str,
+        variant: &str,
+        options: Option<String>,
+    ) -> InputResult<Self> {
+        // xkbcommon panics if a name contains a NUL byte
+        if [
+            rules,
+            model,
+            layout,
+            variant,
+            options.as_deref().unwrap_or_default(),
+        ]
+        .iter()
+        .any(|name| name.contains('\0'))
+        {
+            return Err(InputError::InvalidInput(
+                "the names must not contain a NUL byte",
+            ));
+        }
+        let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+        xkb::Keymap::new_from_names(
+            &context,
+            rules,
+            model,
+            layout,
+            variant,
+            options,
+            xkb::KEYMAP_COMPILE_NO_FLAGS,
+        )
+        .map(Self::from)
+        .ok_or(InputError::InvalidInput("unable to compile the keymap"))
+    }
+
+    /// Returns the names of the layouts (groups) of the keymap
+    #[must_use]
+    pub fn layout_names(&self) -> Vec<String> {
+        (0..self.keymap.num_layouts())
+            .map(|idx| self.keymap.layout_get_name(idx).to_string())
+            .collect()
+    }
+
+    /// Returns the first keysym that is produced by the keycode in the given
+    /// layout and shift level
+    #[must_use]
+    pub fn keysym(&self, keycode: u32, layout: u32, level: u32) -> Option<Keysym> {
+        self.keymap
+            .key_get_syms_by_level(xkb::Keycode::new(keycode), layout, level)
+            .first()
+            .copied()
+    }
+
+    /// Returns the keycode and shift level that produce the keysym in the given
+    /// layout
+    #[must_use]
+    pub fn keycode_for_keysym(&self, keysym: Keysym, layout: u32) -> Option<(u32, u32)> {
+        let min = self.keymap.min_keycode().raw();
+        let max = self.keymap.max_keycode().raw();
+        for keycode in min..=max {
+            let kc = xkb::Keycode::new(keycode);
+            for level in 0..self.keymap.num_levels_for_key(kc, layout) {
+                if self
+                    .keymap
+                    .key_get_syms_by_level(kc, layout, level)
+                    .contains(&keysym)
+                {
+                    return Some((keycode, level));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns the keycode and shift level that produce the character in the
+    /// given layout
+    #[must_use]
+    pub fn keycode_for_char(&self, c: char, layout: u32) -> Option<(u32, u32)> {
+        self.keycode_for_keysym(Keysym::from(Key::Unicode(c)), layout)
+    }
+
+    /// Returns all keycodes of the keymap that do not produce any keysym in any
+    /// layout. They can be used to map additional keysyms
+    #[must_use]
+    pub fn unused_keycodes(&self) -> Vec<u32> {
+        let min = self.keymap.min_keycode().raw();
+        let max = self.keymap.max_keycode().raw();
+        (min..=max)
+            .filter(|&keycode| {
+                let kc = xkb::Keycode::new(keycode);
+                (0..self.keymap.num_layouts_for_key(kc)).all(|layout| {
+                    (0..self.keymap.num_levels_for_key(kc, layout)).all(|level| {
+                        self.keymap
+                            .key_get_syms_by_level(kc, layout, level)
+                            .is_empty()
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+/// Predicts the text that input produces with an xkb keymap. It is used by
+/// [`TestKeyboard`](crate::test_keyboard::TestKeyboard) on Linux
+pub(crate) struct Predictor {
+    keymap: Keymap,
+    state: xkb::State,
+    compose: Option<xkb::compose::State>,
+}
+
+impl Predictor {
+    /// Create a predictor for the keymap that loads the compose table for dead
+    /// keys for the given locale. Dead keys do not produce any text if there
+    /// is no compose table for the locale
+    pub(crate) fn new(keymap: Keymap, locale: &str) -> Self {
+        let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+        let compose = xkb::compose::Table::new_from_locale(
+            &context,
+            locale.as_ref(),
+            xkb::compose::COMPILE_NO_FLAGS,
+        )
+        .ok()
+        .map(|table| xkb::compose::State::new(&table, xkb::compose::STATE_NO_FLAGS));
+        let state = xkb::State::new(&keymap.keymap);
+        Self {
+            keymap,
+            state,
+            compose,
+        }
+    }
+
+    pub(crate) fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub(crate) fn has_compose_table(&self) -> bool {
+        self.compose.is_some()
+    }
+
+    /// Returns the index of the active layout
+    pub(crate) fn active_layout(&self) -> u32 {
+        self.state.serialize_layout(xkb::STATE_LAYOUT_EFFECTIVE)
+    }
+
+    /// Feed the keysym to the compose state and return the produced text.
+    /// `text` is the text the key produces without composing
+    fn produce(&mut self, keysym: Keysym, text: String) -> String {
+        let Some(compose) = self.compose.as_mut() else {
+            return text;
+        };
+        if compose.feed(keysym) == xkb::compose::FeedResult::Ignored {
+            return text;
+        }
+        match compose.status() {
+            xkb::compose::Status::Composed => {
+                let composed = compose.utf8().unwrap_or_default();
+                compose.reset();
+                composed
+            }
+            xkb::compose::Status::Nothing => text,
+            xkb::compose::Status::Composing | xkb::compose::Status::Cancelled => String::new(),
+        }
+    }
+
+    /// Returns the text the keycode produces
+    pub(crate) fn keycode(&mut self, keycode: u32, direction: Direction) -> String {
+        let kc = xkb::Keycode::new(keycode);
+        let mut text = String::new();
+        if direction == Direction::Press || direction == Direction::Click {
+            let keysym = self.state.key_get_one_sym(kc);
+            let utf8 = self.state.key_get_utf8(kc);
+            text = self.produce(keysym, utf8);
+            self.state.update_key(kc, xkb::KeyDirection::Down);
+        }
+        if direction == Direction::Release || direction == Direction::Click {
+            self.state.update_key(kc, xkb::KeyDirection::Up);
+        }
+        text
+    }
+
+    /// Returns the text the key produces
+    pub(crate) fn key(&mut self, key: Key, direction: Direction) -> InputResult<String> {
+        let keysym = Keysym::from(key);
+        if keysym == Keysym::NoSymbol {
+            return Err(InputError::Mapping(format!("{key:?}")));
+        }
+        // Like enigo, only keys that produce the keysym without a modifier
+        // are pressed
+        match self.keymap.keycode_for_keysym(keysym, self.active_layout()) {
+            Some((keycode, 0)) => Ok(self.keycode(keycode, direction)),
+            _ => {
+                if direction == Direction::Press || direction == Direction::Click {
+                    Ok(self.produce(keysym, xkb::keysym_to_utf8(keysym)))
+                } else {
+                    Ok(String::new())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Keymap;
+    use crate::{
+        test_keyboard::TestKeyboard,
+        Direction::{Click, Press, Release},
+        Key, Keyboard,
+    };
+
+    #[test]
+    fn keycode_for_char_us_ru() {
+        let keymap = Keymap::from_names("", "", "us,ru", "", None).unwrap();
+        assert_eq!(keymap.layout_names().len(), 2);
+        // The key "A" is KEY_A (30) + 8
+        assert_eq!(keymap.keycode_for_char('a', 0), Some((38, 0)));
+        assert_eq!(keymap.keycode_for_char('A', 0), Some((38, 1)));
+        assert_eq!(keymap.keycode_for_char('ф', 1), Some((38, 0)));
+        assert_eq!(keymap.keycode_for_char('ф', 0), None);
+        assert_eq!(keymap.keysym(38, 1, 0), Some(super::Keysym::Cyrillic_ef));
+    }
+
+    #[test]
+    fn test_keyboard_modifiers_and_layouts() {
+        let keymap = Keymap::from_names("", "", "us,ru", "", None).unwrap();
+        let mut keyboard = TestKeyboard::from_keymap_with_compose_locale(keymap, "C");
+        keyboard.key(Key::Shift, Press).unwrap();
+        keyboard.key(Key::Unicode('h'), Click).unwrap();
+        keyboard.key(Key::Shift, Release).unwrap();
+        keyboard.key(Key::Unicode('i'), Click).unwrap();
+        keyboard.key(Key::Unicode('x'), Click).unwrap();
+        keyboard.key(Key::Backspace, Click).unwrap();
+        // The key is not on the active layout, so a keycode gets mapped to it
+        keyboard.key(Key::Unicode('ф'), Click).unwrap();
+        keyboard.text("!").unwrap();
+        // Raw keycodes are interpreted with the active layout
+        keyboard.raw(38, Click).unwrap();
+        assert_eq!(keyboard.take_output(), "Hiф!a");
+        assert_eq!(keyboard.output(), "");
+    }
+
+    #[test]
+    fn nul_bytes_are_rejected() {
+        let keymap = Keymap::from_names("", "", "us", "", None).unwrap();
+        let mut text = keymap
+            .keymap
+            .get_as_string(super::xkb::KEYMAP_FORMAT_TEXT_V1);
+        // Keymaps shared via a file descriptor are terminated by a NUL byte
+        text.push('\0');
+        assert!(Keymap::from_string(&text).is_ok());
+        text.insert(0, '\0');
+        assert!(Keymap::from_string(&text).is_err());
+        assert!(Keymap::from_names("", "", "u\0s", "", None).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+use core_foundation::{
+    base::{CFType, CFTypeRef, TCFType},
+    string::{CFString, CFStringRef},
+};
+
+use crate::{a11y::FocusedElement, InputError, InputResult};
+
+/// Roles of elements that accept text input
+const EDITABLE_ROLES: [&str; 3] = ["AXTextField", "AXTextArea", "AXComboBox"];
+
+type AXUIElementRef = CFTypeRef;
+type AXError = i32;
+
+const K_AX_ERROR_SUCCESS: AXError = 0;
+
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXUIElementCreateSystemWide() -> AXUIElementRef;
+    fn AXUIElementCopyAttributeValue(
+        element: AXUIElementRef,
+        attribute: CFStringRef,
+        value: *mut CFTypeRef,
+    ) -> AXError;
+}
+
+/// Returns the value of the attribute of the element
+fn attribute(element: &CFType, name: &str) -> Option<CFType> {
+    let name = CFString::new(name);
+    let mut value: CFTypeRef = std::ptr::null();
+    let res = unsafe {
+        AXUIElementCopyAttributeValue(
+            element.as_CFTypeRef(),
+            name.as_concrete_TypeRef(),
+            &raw mut value,
+        )
+    };
+    if res != K_AX_ERROR_SUCCESS || value.is_null() {
+        return None;
+    }
+    Some(unsafe { CFType::wrap_under_create_rule(value) })
+}
+
+/// Returns the value of the attribute of the element if it is a string
+fn string_attribute(element: &CFType, name: &str) -> Option<String> {
+    attribute(element, name)?
+        .downcast::<CFString>()
+        .map(|value| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+pub(crate) fn focused_element() -> InputResult<FocusedElement> {
+    let system_wide = unsafe { AXUIElementCreateSystemWide() };
+    if system_wide.is_null() {
+        return Err(InputError::Simulate(
+            "unable to create the system wide accessibility element",
+        ));
+    }
+    let system_wide = unsafe { CFType::wrap_under_create_rule(system_wide) };
+    let element = attribute(&system_wide, "AXFocusedUIElement")
+        .ok_or(InputError::Simulate("no element has the focus"))?;
+    let role = string_attribute(&element, "AXRole")
+        .ok_or(InputError::Simulate("the focused element has no role"))?;
+    let name = string_attribute(&element, "AXTitle")
+        .or_else(|| string_attribute(&element, "AXDescription"));
+    Ok(FocusedElement {
+        editable: EDITABLE_ROLES.contains(&role.as_str()),
+        role,
+        name,
+    })
+}
@@ -0,0 +1,48 @@
+use core_foundation::{
+    base::{CFType, TCFType},
+    boolean::CFBoolean,
+    dictionary::{CFDictionary, CFDictionaryRef},
+    string::CFString,
+};
+use log::debug;
+
+use super::macos_impl::has_permission;
+use crate::health::HealthIssue;
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGSessionCopyCurrentDictionary() -> CFDictionaryRef;
+}
+
+/// Returns the boolean value of the key in the dictionary of the session
+fn session_flag(session: &CFDictionary<CFString, CFType>, key: &'static str) -> Option<bool> {
+    let key = CFString::from_static_string(key);
+    session
+        .find(&key)
+        .and_then(|value| value.downcast::<CFBoolean>())
+        .map(bool::from)
+}
+
+pub(crate) fn issues() -> Vec<HealthIssue> {
+    let mut issues = Vec::new();
+    // There is no session of the window server if the process was started
+    // e.g. from a SSH session
+    let session = unsafe { CGSessionCopyCurrentDictionary() };
+    if session.is_null() {
+        debug!("there is no window server session");
+        issues.push(HealthIssue::NoDisplay);
+    } else {
+        let session: CFDictionary<CFString, CFType> =
+            unsafe { CFDictionary::wrap_under_create_rule(session) };
+        if session_flag(&session, "CGSSessionScreenIsLocked") == Some(true) {
+            issues.push(HealthIssue::SessionLocked);
+        }
+        if session_flag(&session, "kCGSSessionOnConsoleKey") == Some(false) {
+            issues.push(HealthIssue::SessionInactive);
+        }
+    }
+    if !has_permission(false) {
+        issues.push(HealthIssue::PermissionMissing);
+    }
+    issues
+}
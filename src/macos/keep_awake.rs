@@ -0,0 +1,60 @@
+use core_foundation::{
+    base::TCFType,
+    string::{CFString, CFStringRef},
+};
+use log::{debug, error};
+
+use crate::{InputError, InputResult};
+
+/// Prevents the display from sleeping, which also keeps the system awake and
+/// prevents the screen saver
+const ASSERTION_TYPE: &str = "PreventUserIdleDisplaySleep";
+#[allow(non_upper_case_globals)]
+const kIOPMAssertionLevelOn: u32 = 255;
+#[allow(non_upper_case_globals)]
+const kIOReturnSuccess: i32 = 0;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOPMAssertionCreateWithName(
+        assertion_type: CFStringRef,
+        level: u32,
+        name: CFStringRef,
+        id: *mut u32,
+    ) -> i32;
+    fn IOPMAssertionRelease(id: u32) -> i32;
+}
+
+#[derive(Debug)]
+pub(crate) struct Inhibitor {
+    id: u32,
+}
+
+impl Inhibitor {
+    pub(crate) fn new(reason: &str) -> InputResult<Self> {
+        let assertion_type = CFString::new(ASSERTION_TYPE);
+        let name = CFString::new(reason);
+        let mut id = 0;
+        let res = unsafe {
+            IOPMAssertionCreateWithName(
+                assertion_type.as_concrete_TypeRef(),
+                kIOPMAssertionLevelOn,
+                name.as_concrete_TypeRef(),
+                &raw mut id,
+            )
+        };
+        if res != kIOReturnSuccess {
+            return Err(InputError::Simulate("unable to create the power assertion"));
+        }
+        debug!("created the power assertion {id}");
+        Ok(Self { id })
+    }
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        if unsafe { IOPMAssertionRelease(self.id) } != kIOReturnSuccess {
+            error!("unable to release the power assertion {}", self.id);
+        }
+    }
+}
@@ -1,12 +1,13 @@
 use std::os::raw::c_void;
 use std::{
-    thread,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
 use core_foundation::{
-    array::CFIndex,
-    base::{OSStatus, TCFType, UInt16, UInt32, UInt8},
+    array::{CFArray, CFArrayRef, CFIndex},
+    base::{CFRelease, CFType, OSStatus, TCFType, UInt16, UInt32, UInt8},
+    boolean::CFBoolean,
     data::{CFDataGetBytePtr, CFDataRef},
     dictionary::{CFDictionary, CFDictionaryRef},
     string::{CFString, CFStringRef, UniChar},
@@ -19,17 +20,55 @@ use core_graphics::{
     },
     event_source::{CGEventSource, CGEventSourceStateID},
 };
-use foreign_types_shared::ForeignTypeRef as _;
+use foreign_types_shared::{ForeignType as _, ForeignTypeRef as _};
 use log::{debug, error, info};
 use objc2::msg_send;
-use objc2_app_kit::{NSEvent, NSEventModifierFlags, NSEventType};
-use objc2_foundation::NSPoint;
+use objc2_app_kit::{NSEvent, NSEventModifierFlags, NSEventType, NSWorkspace};
+use objc2_foundation::{NSPoint, NSString, NSUserDefaults};
 
 use crate::{
-    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse,
-    NewConError, Settings,
+    clock::{Clock, SystemClock},
+    diagnostics::{self, DiagnosticsReport},
+    metrics::MetricsHook,
+    origin_to_top_left, origin_to_top_left_f, round_coordinates,
+    settings::{warn_unchangeable, SettingsError},
+    throttle::Throttle,
+    AbsoluteMode, Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard,
+    LayoutInfo, Mouse, NewConError, PointerMode, Rect, ScrollUnit, Settings, Subpixel, TapLocation,
+    UnicodeKeyMode,
 };
 
+/// Bounds (x, y, width, height) of the display in the global coordinate space
+#[allow(clippy::cast_possible_truncation)]
+fn display_bounds(id: u32) -> (i32, i32, i32, i32) {
+    let bounds = CGDisplay::new(id).bounds();
+    (
+        bounds.origin.x as i32,
+        bounds.origin.y as i32,
+        bounds.size.width as i32,
+        bounds.size.height as i32,
+    )
+}
+
+/// Move the point to the closest point on any of the active displays
+fn clamp_to_displays((x, y): (i32, i32)) -> InputResult<(i32, i32)> {
+    Enigo::displays()?
+        .into_iter()
+        .map(|id| {
+            let (left, top, width, height) = display_bounds(id);
+            (
+                x.clamp(left, left + (width - 1).max(0)),
+                y.clamp(top, top + (height - 1).max(0)),
+            )
+        })
+        .min_by_key(|&(cx, cy)| {
+            u64::from(cx.abs_diff(x))
+                .pow(2)
+                .saturating_add(u64::from(cy.abs_diff(y)).pow(2))
+        })
+        .ok_or(InputError::Simulate("there are no active displays"))
+}
+
 #[repr(C)]
 struct __TISInputSource;
 type TISInputSourceRef = *const __TISInputSource;
@@ -43,9 +82,27 @@ extern "C" {
     fn TISCopyCurrentKeyboardInputSource() -> TISInputSourceRef;
     fn TISCopyCurrentKeyboardLayoutInputSource() -> TISInputSourceRef;
     fn TISCopyCurrentASCIICapableKeyboardLayoutInputSource() -> TISInputSourceRef;
+    #[allow(non_snake_case)]
+    fn TISCreateInputSourceList(
+        properties: CFDictionaryRef,
+        includeAllInstalled: bool,
+    ) -> CFArrayRef;
+    fn TISSelectInputSource(inputSource: TISInputSourceRef) -> OSStatus;
 
     #[allow(non_upper_case_globals)]
     static kTISPropertyUnicodeKeyLayoutData: CFStringRef;
+    #[allow(non_upper_case_globals)]
+    static kTISPropertyLocalizedName: CFStringRef;
+    #[allow(non_upper_case_globals)]
+    static kTISPropertyInputSourceID: CFStringRef;
+    #[allow(non_upper_case_globals)]
+    static kTISPropertyInputSourceLanguages: CFStringRef;
+    #[allow(non_upper_case_globals)]
+    static kTISPropertyInputSourceCategory: CFStringRef;
+    #[allow(non_upper_case_globals)]
+    static kTISPropertyInputSourceIsSelectCapable: CFStringRef;
+    #[allow(non_upper_case_globals)]
+    static kTISCategoryKeyboardInputSource: CFStringRef;
 
     #[allow(non_snake_case)]
     fn TISGetInputSourceProperty(
@@ -70,17 +127,43 @@ extern "C" {
     fn LMGetKbdType() -> UInt8;
 }
 
+/// Convert the location to the one of core graphics
+fn tap_location(location: TapLocation) -> CGEventTapLocation {
+    match location {
+        TapLocation::Hid => CGEventTapLocation::HID,
+        TapLocation::Session => CGEventTapLocation::Session,
+        TapLocation::AnnotatedSession => CGEventTapLocation::AnnotatedSession,
+    }
+}
+
 /// The main struct for handling the event emitting
+#[allow(clippy::struct_excessive_bools)]
 pub struct Enigo {
     event_source: CGEventSource,
     display: CGDisplay,
     held: (Vec<Key>, Vec<CGKeyCode>), // Currently held keys
+    latched: Vec<Key>,                // Modifiers that are pressed with the next key
     event_source_user_data: i64,
+    tap_location: CGEventTapLocation,
     release_keys_when_dropped: bool,
+    unicode_key_mode: UnicodeKeyMode,
+    absolute_mode: AbsoluteMode,
     event_flags: CGEventFlags,
-    double_click_delay: Duration,
-    // Instant when the last event was sent and the duration that needs to be waited for after that
-    // instant to make sure all events were handled by the OS
+    double_click_interval: Option<Duration>,
+    respect_button_swap: bool,
+    metrics_hook: Option<Arc<dyn MetricsHook>>,
+    throttle: Option<Throttle>,
+    pub(crate) focus_guard: bool,
+    settings: Settings, // Settings the struct was created with or updated to
+    cursor_hidden: bool,
+    cursor_disassociated: bool,
+    confinement: Option<Rect>, // The simulated movements are clamped to the rectangle
+    event_time: Option<Duration>, // Timestamp of the posted events instead of the time they are posted
+    pointer_mode: PointerMode,
+    subpixel: Subpixel, // Fractions of the relative movements that were not posted yet
+    clock: Arc<dyn Clock>,
+    // Instant when the last event was sent and the maximum duration that is waited for after that
+    // instant for the OS to handle all events
     last_event: (Instant, Duration),
     // TODO: Use mem::variant_count::<Button>() here instead of 9 once it is stabilized
     last_mouse_click: [(i64, Instant); 9], /* For each of the nine Button variants, we
@@ -92,179 +175,263 @@ pub struct Enigo {
                                             * not yet been released */
 }
 
+// SAFETY: The `CGEventSource` is the only field that is not `Send` (`CGDisplay`
+// is a plain display id and the trait objects are `Send + Sync`). It is a Core
+// Foundation object without thread affinity (unlike `AppKit` objects it does
+// not have to be used on the main thread) and `CFRetain`/`CFRelease` are
+// thread-safe. The source is created in `Enigo::new` and never handed out. The
+// only other references are the retains of the `CGEvent`s that are created,
+// posted and dropped within a single method call. Moving the struct to another
+// thread therefore can't lead to concurrent use of the source. `Sync` is not
+// implemented, so it is never accessed from two threads at the same time
+unsafe impl Send for Enigo {}
+
 impl Mouse for Enigo {
     // Sends a button event to the X11 server via `XTest` extension
     fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mbutton(button: {button:?}, direction: {direction:?})\x1b[0m");
-        let (current_x, current_y) = self.location()?;
-
-        if direction == Direction::Click || direction == Direction::Press {
-            let click_count = self.nth_button_press(button, Direction::Press);
-            let (button, event_type, button_number) = match button {
-                Button::Left => (CGMouseButton::Left, CGEventType::LeftMouseDown, None),
-                Button::Middle => (CGMouseButton::Center, CGEventType::OtherMouseDown, Some(2)),
-                Button::Right => (CGMouseButton::Right, CGEventType::RightMouseDown, None),
-                Button::Back => (CGMouseButton::Center, CGEventType::OtherMouseDown, Some(3)),
-                Button::Forward => (CGMouseButton::Center, CGEventType::OtherMouseDown, Some(4)),
-                Button::ScrollUp => return self.scroll(-1, Axis::Vertical),
-                Button::ScrollDown => return self.scroll(1, Axis::Vertical),
-                Button::ScrollLeft => return self.scroll(-1, Axis::Horizontal),
-                Button::ScrollRight => return self.scroll(1, Axis::Horizontal),
+        throttled!("button", self, [button, direction], || {
+            crate::cleanup::button(button, direction);
+            let (current_x, current_y) = self.cursor_location()?;
+            // macOS does not apply the swap of the buttons to the simulated events,
+            // so they need to be swapped to press the physical button
+            let button = match button {
+                Button::Left if !self.respect_button_swap && Self::buttons_swapped() => {
+                    Button::Right
+                }
+                Button::Right if !self.respect_button_swap && Self::buttons_swapped() => {
+                    Button::Left
+                }
+                button => button,
             };
-            let dest = CGPoint::new(current_x as f64, current_y as f64);
 
-            let Ok(event) =
-                CGEvent::new_mouse_event(self.event_source.clone(), event_type, dest, button)
-            else {
-                return Err(InputError::Simulate(
-                    "failed creating event to enter mouse button",
-                ));
+            if direction == Direction::Click || direction == Direction::Press {
+                let click_count = self.nth_button_press(button, Direction::Press);
+                let (button, event_type, button_number) = match button {
+                    Button::Left => (CGMouseButton::Left, CGEventType::LeftMouseDown, None),
+                    Button::Middle => (CGMouseButton::Center, CGEventType::OtherMouseDown, Some(2)),
+                    Button::Right => (CGMouseButton::Right, CGEventType::RightMouseDown, None),
+                    Button::Back => (CGMouseButton::Center, CGEventType::OtherMouseDown, Some(3)),
+                    Button::Forward => {
+                        (CGMouseButton::Center, CGEventType::OtherMouseDown, Some(4))
+                    }
+                    Button::ScrollUp => return self.scroll(-1, Axis::Vertical),
+                    Button::ScrollDown => return self.scroll(1, Axis::Vertical),
+                    Button::ScrollLeft => return self.scroll(-1, Axis::Horizontal),
+                    Button::ScrollRight => return self.scroll(1, Axis::Horizontal),
+                };
+                let dest = CGPoint::new(current_x as f64, current_y as f64);
+
+                let Ok(event) =
+                    CGEvent::new_mouse_event(self.event_source.clone(), event_type, dest, button)
+                else {
+                    return Err(InputError::Simulate(
+                        "failed creating event to enter mouse button",
+                    ));
+                };
+
+                if let Some(button_number) = button_number {
+                    event.set_integer_value_field(
+                        EventField::MOUSE_EVENT_BUTTON_NUMBER,
+                        button_number,
+                    );
+                }
+                event.set_integer_value_field(EventField::MOUSE_EVENT_CLICK_STATE, click_count);
+                event.set_integer_value_field(
+                    EventField::EVENT_SOURCE_USER_DATA,
+                    self.event_source_user_data,
+                );
+                event.set_flags(self.event_flags);
+                self.post(&event);
+                self.update_wait_time();
+            }
+            if direction == Direction::Click || direction == Direction::Release {
+                let click_count = self.nth_button_press(button, Direction::Release);
+                let (button, event_type, button_number) = match button {
+                    Button::Left => (CGMouseButton::Left, CGEventType::LeftMouseUp, None),
+                    Button::Middle => (CGMouseButton::Center, CGEventType::OtherMouseUp, Some(2)),
+                    Button::Right => (CGMouseButton::Right, CGEventType::RightMouseUp, None),
+                    Button::Back => (CGMouseButton::Center, CGEventType::OtherMouseUp, Some(3)),
+                    Button::Forward => (CGMouseButton::Center, CGEventType::OtherMouseUp, Some(4)),
+                    Button::ScrollUp
+                    | Button::ScrollDown
+                    | Button::ScrollLeft
+                    | Button::ScrollRight => {
+                        info!("On macOS the mouse_up function has no effect when called with one of the Scroll buttons");
+                        return Ok(());
+                    }
+                };
+                let dest = CGPoint::new(current_x as f64, current_y as f64);
+                let Ok(event) =
+                    CGEvent::new_mouse_event(self.event_source.clone(), event_type, dest, button)
+                else {
+                    return Err(InputError::Simulate(
+                        "failed creating event to enter mouse button",
+                    ));
+                };
+
+                if let Some(button_number) = button_number {
+                    event.set_integer_value_field(
+                        EventField::MOUSE_EVENT_BUTTON_NUMBER,
+                        button_number,
+                    );
+                }
+                event.set_integer_value_field(EventField::MOUSE_EVENT_CLICK_STATE, click_count);
+                event.set_integer_value_field(
+                    EventField::EVENT_SOURCE_USER_DATA,
+                    self.event_source_user_data,
+                );
+                event.set_flags(self.event_flags);
+                self.post(&event);
+                self.update_wait_time();
+            }
+            Ok(())
+        })
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        throttled!("move_mouse", self, [x, y, coordinate], || {
+            let (x, y) = match coordinate {
+                Coordinate::Abs => origin_to_top_left(self, self.settings.origin, x, y)?,
+                Coordinate::Rel => (x, y),
             };
+            self.move_cursor(x, y, (0.0, 0.0), coordinate)
+        })
+    }
 
-            if let Some(button_number) = button_number {
-                event.set_integer_value_field(EventField::MOUSE_EVENT_BUTTON_NUMBER, button_number);
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        throttled!("move_mouse_f", self, [x, y, coordinate], || {
+            let (x, y) = match coordinate {
+                Coordinate::Abs => origin_to_top_left_f(self, self.settings.origin, x, y)?,
+                Coordinate::Rel => (x, y),
+            };
+            let rounded = round_coordinates(x, y)?;
+            match coordinate {
+                Coordinate::Abs => {
+                    let fraction = (x - f64::from(rounded.0), y - f64::from(rounded.1));
+                    self.move_cursor(rounded.0, rounded.1, fraction, Coordinate::Abs)
+                }
+                Coordinate::Rel => {
+                    let (dx, dy) = self.subpixel.take(x, y);
+                    if dx == 0 && dy == 0 {
+                        return Ok(());
+                    }
+                    self.move_cursor(dx, dy, (0.0, 0.0), Coordinate::Rel)
+                }
             }
-            event.set_integer_value_field(EventField::MOUSE_EVENT_CLICK_STATE, click_count);
+        })
+    }
+
+    // Sends a scroll event to the X11 server via `XTest` extension
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        throttled!("scroll", self, [length, axis], || {
+            let (ax, len_x, len_y) = match axis {
+                Axis::Horizontal => (2, 0, -length),
+                Axis::Vertical => (1, -length, 0),
+            };
+
+            let Ok(event) = CGEvent::new_scroll_event(
+                self.event_source.clone(),
+                ScrollEventUnit::LINE,
+                ax,
+                len_x,
+                len_y,
+                0,
+            ) else {
+                return Err(InputError::Simulate("failed creating event to scroll"));
+            };
+
             event.set_integer_value_field(
                 EventField::EVENT_SOURCE_USER_DATA,
                 self.event_source_user_data,
             );
             event.set_flags(self.event_flags);
-            event.post(CGEventTapLocation::HID);
+            self.post(&event);
             self.update_wait_time();
-        }
-        if direction == Direction::Click || direction == Direction::Release {
-            let click_count = self.nth_button_press(button, Direction::Release);
-            let (button, event_type, button_number) = match button {
-                Button::Left => (CGMouseButton::Left, CGEventType::LeftMouseUp, None),
-                Button::Middle => (CGMouseButton::Center, CGEventType::OtherMouseUp, Some(2)),
-                Button::Right => (CGMouseButton::Right, CGEventType::RightMouseUp, None),
-                Button::Back => (CGMouseButton::Center, CGEventType::OtherMouseUp, Some(3)),
-                Button::Forward => (CGMouseButton::Center, CGEventType::OtherMouseUp, Some(4)),
-                Button::ScrollUp
-                | Button::ScrollDown
-                | Button::ScrollLeft
-                | Button::ScrollRight => {
-                    info!("On macOS the mouse_up function has no effect when called with one of the Scroll buttons");
-                    return Ok(());
-                }
+            Ok(())
+        })
+    }
+
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        throttled!("scroll_2d", self, [dx, dy, unit], || {
+            let unit = match unit {
+                ScrollUnit::Line => ScrollEventUnit::LINE,
+                ScrollUnit::Pixel => ScrollEventUnit::PIXEL,
             };
-            let dest = CGPoint::new(current_x as f64, current_y as f64);
+
+            // The first wheel is the vertical and the second one the horizontal axis
             let Ok(event) =
-                CGEvent::new_mouse_event(self.event_source.clone(), event_type, dest, button)
+                CGEvent::new_scroll_event(self.event_source.clone(), unit, 2, -dy, -dx, 0)
             else {
-                return Err(InputError::Simulate(
-                    "failed creating event to enter mouse button",
-                ));
+                return Err(InputError::Simulate("failed creating event to scroll"));
             };
 
-            if let Some(button_number) = button_number {
-                event.set_integer_value_field(EventField::MOUSE_EVENT_BUTTON_NUMBER, button_number);
-            }
-            event.set_integer_value_field(EventField::MOUSE_EVENT_CLICK_STATE, click_count);
             event.set_integer_value_field(
                 EventField::EVENT_SOURCE_USER_DATA,
                 self.event_source_user_data,
             );
             event.set_flags(self.event_flags);
-            event.post(CGEventTapLocation::HID);
+            self.post(&event);
             self.update_wait_time();
-        }
-        Ok(())
+            Ok(())
+        })
     }
 
-    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
-        debug!("\x1b[93mmove_mouse(x: {x:?}, y: {y:?}, coordinate:{coordinate:?})\x1b[0m");
-        let pressed = unsafe { NSEvent::pressedMouseButtons() };
-        let (current_x, current_y) = self.location()?;
-
-        let (absolute, relative) = match coordinate {
-            // TODO: Check the bounds
-            Coordinate::Abs => ((x, y), (current_x - x, current_y - y)),
-            Coordinate::Rel => ((current_x + x, current_y + y), (x, y)),
-        };
-
-        let (event_type, button) = if pressed & 1 > 0 {
-            (CGEventType::LeftMouseDragged, CGMouseButton::Left)
-        } else if pressed & 2 > 0 {
-            (CGEventType::RightMouseDragged, CGMouseButton::Right)
-        } else {
-            (CGEventType::MouseMoved, CGMouseButton::Left) // The mouse button
-                                                           // here is ignored so
-                                                           // it can be anything
-        };
-
-        let dest = CGPoint::new(absolute.0 as f64, absolute.1 as f64);
-        let Ok(event) =
-            CGEvent::new_mouse_event(self.event_source.clone(), event_type, dest, button)
-        else {
-            return Err(InputError::Simulate(
-                "failed creating event to move the mouse",
-            ));
-        };
-
-        // Add information by how much the mouse was moved
-        event.set_integer_value_field(
-            core_graphics::event::EventField::MOUSE_EVENT_DELTA_X,
-            relative.0.into(),
-        );
-        event.set_integer_value_field(
-            core_graphics::event::EventField::MOUSE_EVENT_DELTA_Y,
-            relative.1.into(),
-        );
-
-        event.set_integer_value_field(
-            EventField::EVENT_SOURCE_USER_DATA,
-            self.event_source_user_data,
-        );
-        event.set_flags(self.event_flags);
-        event.post(CGEventTapLocation::HID);
-        self.update_wait_time();
-        Ok(())
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        traced!("main_display", self, [], || {
+            Ok((
+                self.display.pixels_wide() as i32,
+                self.display.pixels_high() as i32,
+            ))
+        })
     }
 
-    // Sends a scroll event to the X11 server via `XTest` extension
-    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
-        debug!("\x1b[93mscroll(length: {length:?}, axis: {axis:?})\x1b[0m");
-        let (ax, len_x, len_y) = match axis {
-            Axis::Horizontal => (2, 0, -length),
-            Axis::Vertical => (1, -length, 0),
-        };
-
-        let Ok(event) = CGEvent::new_scroll_event(
-            self.event_source.clone(),
-            ScrollEventUnit::LINE,
-            ax,
-            len_x,
-            len_y,
-            0,
-        ) else {
-            return Err(InputError::Simulate("failed creating event to scroll"));
-        };
+    fn location(&self) -> InputResult<(i32, i32)> {
+        traced!("location", self, [], || {
+            let (x, y) = self.cursor_location()?;
+            origin_to_top_left(self, self.settings.origin, x, y)
+        })
+    }
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        traced!("show_cursor", self, [visible], || {
+            let res = if visible {
+                self.display.show_cursor()
+            } else {
+                self.display.hide_cursor()
+            };
+            res.map_err(|_| InputError::Simulate("unable to change the visibility of the cursor"))?;
+            self.cursor_hidden = !visible;
+            Ok(())
+        })
+    }
 
-        event.set_integer_value_field(
-            EventField::EVENT_SOURCE_USER_DATA,
-            self.event_source_user_data,
-        );
-        event.set_flags(self.event_flags);
-        event.post(CGEventTapLocation::HID);
-        self.update_wait_time();
-        Ok(())
+    fn associate_cursor(&mut self, associated: bool) -> InputResult<()> {
+        traced!("associate_cursor", self, [associated], || {
+            self.cursor_disassociated = !associated;
+            self.update_association()
+        })
     }
 
-    fn main_display(&self) -> InputResult<(i32, i32)> {
-        debug!("\x1b[93mmain_display()\x1b[0m");
-        Ok((
-            self.display.pixels_wide() as i32,
-            self.display.pixels_high() as i32,
-        ))
+    fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+        traced!("confine_to", self, [rect], || {
+            rect.check_not_empty()?;
+            self.confinement = Some(rect);
+            self.update_association()?;
+            // Move the cursor into the rectangle like ClipCursor on Windows does
+            let (x, y) = self.cursor_location()?;
+            if rect.contains(x, y) {
+                return Ok(());
+            }
+            let (x, y) = rect.clamp(x, y);
+            CGDisplay::warp_mouse_cursor_position(CGPoint::new(f64::from(x), f64::from(y)))
+                .map_err(|_| InputError::Simulate("unable to move the cursor into the rectangle"))
+        })
     }
 
-    fn location(&self) -> InputResult<(i32, i32)> {
-        debug!("\x1b[93mlocation()\x1b[0m");
-        let pt = unsafe { NSEvent::mouseLocation() };
-        let (x, y_inv) = (pt.x as i32, pt.y as i32);
-        Ok((x, self.display.pixels_high() as i32 - y_inv))
+    fn release_confinement(&mut self) -> InputResult<()> {
+        traced!("release_confinement", self, [], || {
+            self.confinement = None;
+            self.update_association()
+        })
     }
 }
 
@@ -290,215 +457,258 @@ impl Keyboard for Enigo {
             })
         }
 
-        debug!("\x1b[93mfast_text(text: {text})\x1b[0m");
-        // WORKAROUND: This is a fix for issue https://github.com/enigo-rs/enigo/issues/68
-        // The CGEventKeyboardSetUnicodeString function (used inside of
-        // event.set_string(chunk)) truncates strings down to 20 characters
-        for mut chunk in chunks(text, 20) {
-            let Ok(event) = CGEvent::new_keyboard_event(self.event_source.clone(), 0, true) else {
-                return Err(InputError::Simulate(
-                    "failed creating event to enter the text",
-                ));
-            };
-            // WORKAROUND: This is a fix for issue https://github.com/enigo-rs/enigo/issues/260
-            // This is needed to get rid of all leading line feed, tab and carriage return
-            // characters. event.set_string(chunk)) silently fails if the chunk
-            // starts with a newline character
-            loop {
-                if chunk.starts_with('\t') {
-                    self.key(Key::Tab, Direction::Click)?;
-                    chunk = &chunk[1..];
-                    continue;
-                }
-                if chunk.starts_with('\r') {
-                    self.fast_text("\u{200B}\r")?;
-                    chunk = &chunk[1..];
-                    continue;
-                }
-                if chunk.starts_with('\n') {
-                    self.fast_text("\u{200B}\n")?;
-                    chunk = &chunk[1..];
-                    continue;
+        throttled!("fast_text", self, [text], || {
+            // WORKAROUND: This is a fix for issue https://github.com/enigo-rs/enigo/issues/68
+            // The CGEventKeyboardSetUnicodeString function (used inside of
+            // event.set_string(chunk)) truncates strings down to 20 characters
+            for mut chunk in chunks(text, 20) {
+                let Ok(event) = CGEvent::new_keyboard_event(self.event_source.clone(), 0, true)
+                else {
+                    return Err(InputError::Simulate(
+                        "failed creating event to enter the text",
+                    ));
+                };
+                // WORKAROUND: This is a fix for issue https://github.com/enigo-rs/enigo/issues/260
+                // This is needed to get rid of all leading line feed, tab and carriage return
+                // characters. event.set_string(chunk)) silently fails if the chunk
+                // starts with a newline character
+                loop {
+                    if chunk.starts_with('\t') {
+                        self.key(Key::Tab, Direction::Click)?;
+                        chunk = &chunk[1..];
+                        continue;
+                    }
+                    if chunk.starts_with('\r') {
+                        self.fast_text("\u{200B}\r")?;
+                        chunk = &chunk[1..];
+                        continue;
+                    }
+                    if chunk.starts_with('\n') {
+                        self.fast_text("\u{200B}\n")?;
+                        chunk = &chunk[1..];
+                        continue;
+                    }
+                    break;
                 }
-                break;
-            }
 
-            event.set_string(chunk);
-            event.set_integer_value_field(
-                EventField::EVENT_SOURCE_USER_DATA,
-                self.event_source_user_data,
-            );
-            // We want to ignore all modifiers when entering text
-            event.set_flags(CGEventFlags::CGEventFlagNull);
-            event.post(CGEventTapLocation::HID);
-            self.update_wait_time();
-        }
-        Ok(Some(()))
+                event.set_string(chunk);
+                event.set_integer_value_field(
+                    EventField::EVENT_SOURCE_USER_DATA,
+                    self.event_source_user_data,
+                );
+                // We want to ignore all modifiers when entering text
+                event.set_flags(CGEventFlags::CGEventFlagNull);
+                self.post(&event);
+                self.update_wait_time();
+            }
+            Ok(Some(()))
+        })
     }
 
     #[allow(clippy::too_many_lines)]
     fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mkey(key: {key:?}, direction: {direction:?})\x1b[0m");
-        // Nothing to do
-        if key == Key::Unicode('\0') {
-            return Ok(());
-        }
-        match key {
-            Key::VolumeUp => {
-                debug!("special case for handling the VolumeUp key");
-                self.special_keys(0, direction)?;
-            }
-            Key::VolumeDown => {
-                debug!("special case for handling the VolumeDown key");
-                self.special_keys(1, direction)?;
-            }
-            Key::BrightnessUp => {
-                debug!("special case for handling the BrightnessUp key");
-                self.special_keys(2, direction)?;
-            }
-            Key::BrightnessDown => {
-                debug!("special case for handling the BrightnessDown key");
-                self.special_keys(3, direction)?;
+        // Press the latched modifiers around the next key that is not a modifier
+        if !self.latched.is_empty() && direction != Direction::Release && !key.is_modifier() {
+            let latched = std::mem::take(&mut self.latched);
+            for &modifier in &latched {
+                self.key(modifier, Direction::Press)?;
             }
-            Key::Power => {
-                debug!("special case for handling the Power key");
-                self.special_keys(6, direction)?;
+            let res = self.key(key, direction);
+            for &modifier in latched.iter().rev() {
+                self.key(modifier, Direction::Release)?;
             }
-            Key::VolumeMute => {
-                debug!("special case for handling the VolumeMute key");
-                self.special_keys(7, direction)?;
+            return res;
+        }
+        throttled!("key", self, [key, direction], || {
+            // Nothing to do
+            if key == Key::Unicode('\0') {
+                return Ok(());
             }
+            match key {
+                Key::VolumeUp => {
+                    debug!("special case for handling the VolumeUp key");
+                    self.special_keys(0, direction)?;
+                }
+                Key::VolumeDown => {
+                    debug!("special case for handling the VolumeDown key");
+                    self.special_keys(1, direction)?;
+                }
+                Key::BrightnessUp => {
+                    debug!("special case for handling the BrightnessUp key");
+                    self.special_keys(2, direction)?;
+                }
+                Key::BrightnessDown => {
+                    debug!("special case for handling the BrightnessDown key");
+                    self.special_keys(3, direction)?;
+                }
+                Key::Power => {
+                    debug!("special case for handling the Power key");
+                    self.special_keys(6, direction)?;
+                }
+                Key::VolumeMute => {
+                    debug!("special case for handling the VolumeMute key");
+                    self.special_keys(7, direction)?;
+                }
 
-            Key::ContrastUp => {
-                debug!("special case for handling the ContrastUp key");
-                self.special_keys(11, direction)?;
-            }
-            Key::ContrastDown => {
-                debug!("special case for handling the ContrastDown key");
-                self.special_keys(12, direction)?;
-            }
-            Key::LaunchPanel => {
-                debug!("special case for handling the LaunchPanel key");
-                self.special_keys(13, direction)?;
-            }
-            Key::Eject => {
-                debug!("special case for handling the Eject key");
-                self.special_keys(14, direction)?;
-            }
-            Key::VidMirror => {
-                debug!("special case for handling the VidMirror key");
-                self.special_keys(15, direction)?;
-            }
-            Key::MediaPlayPause => {
-                debug!("special case for handling the MediaPlayPause key");
-                self.special_keys(16, direction)?;
-            }
-            Key::MediaNextTrack => {
-                debug!("special case for handling the MediaNextTrack key");
-                self.special_keys(17, direction)?;
-            }
-            Key::MediaPrevTrack => {
-                debug!("special case for handling the MediaPrevTrack key");
-                self.special_keys(18, direction)?;
-            }
-            Key::MediaFast => {
-                debug!("special case for handling the MediaFast key");
-                self.special_keys(19, direction)?;
-            }
-            Key::MediaRewind => {
-                debug!("special case for handling the MediaRewind key");
-                self.special_keys(20, direction)?;
-            }
-            Key::IlluminationUp => {
-                debug!("special case for handling the IlluminationUp key");
-                self.special_keys(21, direction)?;
-            }
-            Key::IlluminationDown => {
-                debug!("special case for handling the IlluminationDown key");
-                self.special_keys(22, direction)?;
-            }
-            Key::IlluminationToggle => {
-                debug!("special case for handling the IlluminationToggle key");
-                self.special_keys(23, direction)?;
-            }
-            _ => {
-                let Ok(keycode) = CGKeyCode::try_from(key) else {
-                    return Err(InputError::InvalidInput(
-                        "virtual keycodes on macOS have to fit into u16",
-                    ));
-                };
-                self.raw(keycode, direction)?;
+                Key::ContrastUp => {
+                    debug!("special case for handling the ContrastUp key");
+                    self.special_keys(11, direction)?;
+                }
+                Key::ContrastDown => {
+                    debug!("special case for handling the ContrastDown key");
+                    self.special_keys(12, direction)?;
+                }
+                Key::LaunchPanel => {
+                    debug!("special case for handling the LaunchPanel key");
+                    self.special_keys(13, direction)?;
+                }
+                Key::Eject => {
+                    debug!("special case for handling the Eject key");
+                    self.special_keys(14, direction)?;
+                }
+                Key::VidMirror => {
+                    debug!("special case for handling the VidMirror key");
+                    self.special_keys(15, direction)?;
+                }
+                Key::MediaPlayPause => {
+                    debug!("special case for handling the MediaPlayPause key");
+                    self.special_keys(16, direction)?;
+                }
+                Key::MediaNextTrack => {
+                    debug!("special case for handling the MediaNextTrack key");
+                    self.special_keys(17, direction)?;
+                }
+                Key::MediaPrevTrack => {
+                    debug!("special case for handling the MediaPrevTrack key");
+                    self.special_keys(18, direction)?;
+                }
+                Key::MediaFast => {
+                    debug!("special case for handling the MediaFast key");
+                    self.special_keys(19, direction)?;
+                }
+                Key::MediaRewind => {
+                    debug!("special case for handling the MediaRewind key");
+                    self.special_keys(20, direction)?;
+                }
+                Key::IlluminationUp => {
+                    debug!("special case for handling the IlluminationUp key");
+                    self.special_keys(21, direction)?;
+                }
+                Key::IlluminationDown => {
+                    debug!("special case for handling the IlluminationDown key");
+                    self.special_keys(22, direction)?;
+                }
+                Key::IlluminationToggle => {
+                    debug!("special case for handling the IlluminationToggle key");
+                    self.special_keys(23, direction)?;
+                }
+                _ => match (CGKeyCode::try_from(key), key) {
+                    (Ok(keycode), _) => self.raw(keycode, direction)?,
+                    (Err(()), Key::Unicode(c)) => {
+                        if self.unicode_key_mode == UnicodeKeyMode::Layout {
+                            return Err(InputError::Mapping(format!(
+                                "no key of the active layout produces {c:?}"
+                            )));
+                        }
+                        debug!("no key produces {c:?}. Entering it as text");
+                        if direction != Direction::Release {
+                            self.fast_text(&c.to_string())?;
+                        }
+                    }
+                    (Err(()), _) => {
+                        return Err(InputError::InvalidInput(
+                            "virtual keycodes on macOS have to fit into u16",
+                        ));
+                    }
+                },
             }
-        }
 
-        // TODO: The list of keys will contain the key and also the associated keycode.
-        // They are a duplicate
-        match direction {
-            Direction::Press => {
-                debug!("added the key {key:?} to the held keys");
-                self.held.0.push(key);
-            }
-            Direction::Release => {
-                debug!("removed the key {key:?} from the held keys");
-                self.held.0.retain(|&k| k != key);
+            // TODO: The list of keys will contain the key and also the associated keycode.
+            // They are a duplicate
+            match direction {
+                Direction::Press => {
+                    debug!("added the key {key:?} to the held keys");
+                    crate::cleanup::key(key, direction);
+                    self.held.0.push(key);
+                }
+                Direction::Release => {
+                    debug!("removed the key {key:?} from the held keys");
+                    crate::cleanup::key(key, direction);
+                    self.held.0.retain(|&k| k != key);
+                }
+                Direction::Click => (),
             }
-            Direction::Click => (),
-        }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mraw(keycode: {keycode:?}, direction: {direction:?})\x1b[0m");
-
-        if direction == Direction::Click || direction == Direction::Press {
-            let Ok(event) = CGEvent::new_keyboard_event(self.event_source.clone(), keycode, true)
-            else {
-                return Err(InputError::Simulate(
-                    "failed creating event to press the key",
-                ));
-            };
-
-            event.set_integer_value_field(
-                EventField::EVENT_SOURCE_USER_DATA,
-                self.event_source_user_data,
-            );
-            self.add_event_flag(keycode, Direction::Press);
-            event.set_flags(self.event_flags);
-            event.post(CGEventTapLocation::HID);
-            self.update_wait_time();
-        }
+        throttled!("raw", self, [keycode, direction], || {
+            if direction == Direction::Click || direction == Direction::Press {
+                let Ok(event) =
+                    CGEvent::new_keyboard_event(self.event_source.clone(), keycode, true)
+                else {
+                    return Err(InputError::Simulate(
+                        "failed creating event to press the key",
+                    ));
+                };
 
-        if direction == Direction::Click || direction == Direction::Release {
-            let Ok(event) = CGEvent::new_keyboard_event(self.event_source.clone(), keycode, false)
-            else {
-                return Err(InputError::Simulate(
-                    "failed creating event to release the key",
-                ));
-            };
+                event.set_integer_value_field(
+                    EventField::EVENT_SOURCE_USER_DATA,
+                    self.event_source_user_data,
+                );
+                self.add_event_flag(keycode, Direction::Press);
+                event.set_flags(self.event_flags);
+                self.post(&event);
+                self.update_wait_time();
+            }
 
-            event.set_integer_value_field(
-                EventField::EVENT_SOURCE_USER_DATA,
-                self.event_source_user_data,
-            );
-            self.add_event_flag(keycode, Direction::Release);
-            event.set_flags(self.event_flags);
-            event.post(CGEventTapLocation::HID);
-            self.update_wait_time();
-        }
+            if direction == Direction::Click || direction == Direction::Release {
+                let Ok(event) =
+                    CGEvent::new_keyboard_event(self.event_source.clone(), keycode, false)
+                else {
+                    return Err(InputError::Simulate(
+                        "failed creating event to release the key",
+                    ));
+                };
 
-        match direction {
-            Direction::Press => {
-                debug!("added the keycode {keycode:?} to the held keys");
-                self.held.1.push(keycode);
+                event.set_integer_value_field(
+                    EventField::EVENT_SOURCE_USER_DATA,
+                    self.event_source_user_data,
+                );
+                self.add_event_flag(keycode, Direction::Release);
+                event.set_flags(self.event_flags);
+                self.post(&event);
+                self.update_wait_time();
             }
-            Direction::Release => {
-                debug!("removed the keycode {keycode:?} from the held keys");
-                self.held.1.retain(|&k| k != keycode);
+
+            match direction {
+                Direction::Press => {
+                    debug!("added the keycode {keycode:?} to the held keys");
+                    crate::cleanup::raw(keycode, direction);
+                    self.held.1.push(keycode);
+                }
+                Direction::Release => {
+                    debug!("removed the keycode {keycode:?} from the held keys");
+                    crate::cleanup::raw(keycode, direction);
+                    self.held.1.retain(|&k| k != keycode);
+                }
+                Direction::Click => (),
             }
-            Direction::Click => (),
-        }
 
+            Ok(())
+        })
+    }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        debug!("\x1b[93mlatch_modifier(key: {key:?})\x1b[0m");
+        if !key.is_modifier() {
+            return Err(InputError::InvalidInput("the key is not a modifier"));
+        }
+        if !self.latched.contains(&key) {
+            self.latched.push(key);
+        }
         Ok(())
     }
 }
@@ -516,6 +726,10 @@ impl Enigo {
             event_source_user_data,
             open_prompt_to_get_permissions,
             independent_of_keyboard_state,
+            macos_tap_location,
+            unicode_key_mode,
+            double_click_interval,
+            respect_button_swap,
             ..
         } = settings;
 
@@ -531,11 +745,6 @@ impl Enigo {
         event_flags.set(CGEventFlags::from_bits_retain(0x2000_0000), true); // I don't know if this is needed or what this flag does. Correct events have it
                                                                             // set so we also do it (until we know it is wrong)
 
-        let double_click_delay = Duration::from_secs(1);
-        let double_click_delay_setting = unsafe { NSEvent::doubleClickInterval() };
-        // Returns the double click interval (https://developer.apple.com/documentation/appkit/nsevent/1528384-doubleclickinterval). This is a TimeInterval which is a f64 of the number of seconds
-        let double_click_delay = double_click_delay.mul_f64(double_click_delay_setting);
-
         let event_source_state = if *independent_of_keyboard_state {
             CGEventSourceStateID::Private
         } else {
@@ -547,20 +756,75 @@ impl Enigo {
 
         debug!("\x1b[93mconnection established on macOS\x1b[0m");
 
+        let tap_location = tap_location(*macos_tap_location);
+
         let last_event = (Instant::now(), Duration::from_secs(0));
         Ok(Enigo {
             event_source,
             display: CGDisplay::main(),
             held,
+            latched: Vec::new(),
             release_keys_when_dropped: *release_keys_when_dropped,
+            unicode_key_mode: *unicode_key_mode,
+            absolute_mode: settings.absolute_mode,
             event_flags,
-            double_click_delay,
+            double_click_interval: *double_click_interval,
+            respect_button_swap: *respect_button_swap,
+            metrics_hook: None,
+            throttle: Throttle::new(settings),
+            focus_guard: settings.focus_guard,
+            settings: settings.clone(),
+            cursor_hidden: false,
+            cursor_disassociated: false,
+            confinement: None,
+            event_time: None,
+            pointer_mode: PointerMode::Normal,
+            subpixel: Subpixel::default(),
+            clock: Arc::new(SystemClock),
             last_event,
             last_mouse_click: [(0, Instant::now()); 9],
             event_source_user_data: event_source_user_data.unwrap_or(crate::EVENT_MARKER as i64),
+            tap_location,
         })
     }
 
+    /// Apply the settings without creating a new Enigo struct
+    ///
+    /// All options except `independent_of_keyboard_state` are applied. It is
+    /// used to create the event source, so a warning is logged if it differs
+    ///
+    /// # Errors
+    /// Returns an error and changes nothing if the settings are invalid. Have
+    /// a look at `Settings::validate`
+    pub fn update_settings(&mut self, settings: &Settings) -> Result<(), SettingsError> {
+        debug!("\x1b[93mupdate_settings(settings: {settings:?})\x1b[0m");
+        settings.validate()?;
+        let old = std::mem::replace(&mut self.settings, settings.clone());
+        warn_unchangeable(&[(
+            "independent_of_keyboard_state",
+            old.independent_of_keyboard_state != settings.independent_of_keyboard_state,
+        )]);
+        self.settings.independent_of_keyboard_state = old.independent_of_keyboard_state;
+
+        self.release_keys_when_dropped = settings.release_keys_when_dropped;
+        self.event_source_user_data = settings
+            .event_source_user_data
+            .unwrap_or(crate::EVENT_MARKER as i64);
+        self.tap_location = tap_location(settings.macos_tap_location);
+        self.unicode_key_mode = settings.unicode_key_mode;
+        self.absolute_mode = settings.absolute_mode;
+        self.double_click_interval = settings.double_click_interval;
+        self.respect_button_swap = settings.respect_button_swap;
+        // Keep the state of the rate limit if it did not change
+        if old.max_events_per_second != settings.max_events_per_second
+            || old.rate_limit_behavior != settings.rate_limit_behavior
+        {
+            self.throttle = Throttle::new(settings);
+        }
+        self.focus_guard = settings.focus_guard;
+        Ok(())
+    }
+
     /// Returns a list of all currently pressed keys
     pub fn held(&mut self) -> (Vec<Key>, Vec<CGKeyCode>) {
         self.held.clone()
@@ -572,6 +836,349 @@ impl Enigo {
         self.event_source_user_data
     }
 
+    /// Mark the events that are simulated through the returned guard with the
+    /// tag instead of the value of `Settings::event_source_user_data`. The previous
+    /// value is restored when the guard is dropped. This allows e.g. a
+    /// remote-control server with several clients to attribute the injected
+    /// events to a client in its own hooks, where the tag is the `kCGEventSourceUserData` field of
+    /// the event
+    ///
+    /// ```no_run
+    /// use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+    ///
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// let client_id = 42;
+    /// enigo.tagged(client_id).key(Key::Unicode('a'), Direction::Click).unwrap();
+    /// assert_ne!(enigo.get_marker_value(), client_id);
+    /// ```
+    #[must_use]
+    pub fn tagged(&mut self, tag: i64) -> Tagged<'_> {
+        debug!("\x1b[93mtagged(tag: {tag:?})\x1b[0m");
+        let previous = std::mem::replace(&mut self.event_source_user_data, tag);
+        Tagged {
+            enigo: self,
+            previous,
+        }
+    }
+
+    /// Returns information about the current keyboard input source
+    ///
+    /// The language is the first language of the input source (e.g. "de") and
+    /// the variant is the ID of the input source (e.g.
+    /// "com.apple.keylayout.German")
+    ///
+    /// # Errors
+    /// Returns an error if there is no current keyboard input source or it has
+    /// no name
+    pub fn keyboard_layout(&self) -> InputResult<LayoutInfo> {
+        debug!("\x1b[93mkeyboard_layout()\x1b[0m");
+        let input_source = unsafe { TISCopyCurrentKeyboardInputSource() };
+        if input_source.is_null() {
+            return Err(InputError::Simulate(
+                "there is no current keyboard input source",
+            ));
+        }
+        let layout = input_source_info(input_source);
+        unsafe { CFRelease(input_source.cast()) };
+        layout
+    }
+
+    /// Collect information about the environment to attach it to bug reports
+    ///
+    /// Have a look at [`DiagnosticsReport`] for the information it contains
+    #[must_use]
+    pub fn diagnostics(&self) -> DiagnosticsReport {
+        debug!("\x1b[93mdiagnostics()\x1b[0m");
+        diagnostics::report(
+            vec![self.backend().to_string()],
+            Some(has_permission(false)),
+            self.keyboard_layout().ok().map(|layout| layout.name),
+            self.main_display().ok(),
+            &diagnostics::redact(&self.settings),
+        )
+    }
+
+    /// Returns the keyboard input sources that can be selected with
+    /// `select_input_source`. The variant of each of them is the ID of the
+    /// input source
+    ///
+    /// # Errors
+    /// Returns an error if the input sources could not be listed
+    pub fn input_sources(&self) -> InputResult<Vec<LayoutInfo>> {
+        debug!("\x1b[93minput_sources()\x1b[0m");
+        Ok(keyboard_input_sources(None)?
+            .iter()
+            .filter_map(|input_source| input_source_info(input_source.as_CFTypeRef().cast()).ok())
+            .collect())
+    }
+
+    /// Select the keyboard input source with the ID (e.g.
+    /// "com.apple.keylayout.ABC"). It is selected for the whole session like
+    /// selecting it in the menu bar. Following `Key::Unicode` keys are looked
+    /// up in the newly selected input source
+    ///
+    /// # Errors
+    /// Returns an error if there is no input source with the ID that can be
+    /// selected or selecting it failed
+    pub fn select_input_source(&mut self, id: &str) -> InputResult<()> {
+        debug!("\x1b[93mselect_input_source(id: {id:?})\x1b[0m");
+        let input_sources = keyboard_input_sources(Some(id))?;
+        let Some(input_source) = input_sources.get(0) else {
+            return Err(InputError::InvalidInput(
+                "there is no keyboard input source with the ID",
+            ));
+        };
+        let status = unsafe { TISSelectInputSource(input_source.as_CFTypeRef().cast()) };
+        if status != 0 {
+            error!("TISSelectInputSource failed with status: {status}");
+            return Err(InputError::Simulate(
+                "unable to select the keyboard input source",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Select the keyboard input source with the ID while `f` runs and select
+    /// the previous one afterwards. This is useful to simulate shortcuts with
+    /// a specific layout (e.g. "com.apple.keylayout.ABC")
+    ///
+    /// ```no_run
+    /// use enigo::{Direction::Click, Enigo, Key, Keyboard, Settings};
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// enigo
+    ///     .with_input_source("com.apple.keylayout.ABC", |enigo| {
+    ///         enigo.key(Key::Unicode('z'), Click)
+    ///     })
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the input source could not be selected or restored
+    /// or the error of `f`
+    pub fn with_input_source<T>(
+        &mut self,
+        id: &str,
+        f: impl FnOnce(&mut Self) -> InputResult<T>,
+    ) -> InputResult<T> {
+        let previous = self.keyboard_layout()?.variant.ok_or(InputError::Simulate(
+            "the current keyboard input source has no ID",
+        ))?;
+        self.select_input_source(id)?;
+        let res = f(self);
+        self.select_input_source(&previous)?;
+        res
+    }
+
+    /// Location of the cursor with the top left origin
+    fn cursor_location(&self) -> InputResult<(i32, i32)> {
+        // The location of an empty event is the location of the mouse in the global
+        // coordinate space. Its origin is the top left corner of the main display
+        let Ok(event) = CGEvent::new(self.event_source.clone()) else {
+            return Err(InputError::Simulate(
+                "failed creating event to get the location of the mouse",
+            ));
+        };
+        let pt = event.location();
+        Ok((pt.x as i32, pt.y as i32))
+    }
+
+    /// Move the cursor without acquiring the throttle. The fraction of a pixel
+    /// is added to the absolute position the cursor is moved to
+    fn move_cursor(
+        &mut self,
+        x: i32,
+        y: i32,
+        fraction: (f64, f64),
+        coordinate: Coordinate,
+    ) -> InputResult<()> {
+        if coordinate == Coordinate::Abs && self.pointer_mode == PointerMode::RelativeOnly {
+            return Err(InputError::InvalidInput(
+                "absolute movements are refused in PointerMode::RelativeOnly",
+            ));
+        }
+        let pressed = unsafe { NSEvent::pressedMouseButtons() };
+        let (current_x, current_y) = self.cursor_location()?;
+
+        let target = match coordinate {
+            Coordinate::Abs => (x, y),
+            Coordinate::Rel => (current_x.saturating_add(x), current_y.saturating_add(y)),
+        };
+        // Displays left of or above the main display have negative coordinates. Points
+        // outside of all displays are moved to the closest display
+        let absolute = clamp_to_displays(target)?;
+        let absolute = self
+            .confinement
+            .map_or(absolute, |rect| rect.clamp(absolute.0, absolute.1));
+        // The fraction is dropped if the target had to be moved
+        let fraction = if absolute == target {
+            fraction
+        } else {
+            (0.0, 0.0)
+        };
+        if coordinate == Coordinate::Abs && self.absolute_mode == AbsoluteMode::Warp {
+            debug!("warping the cursor with CGWarpMouseCursorPosition");
+            let dest = CGPoint::new(
+                f64::from(absolute.0) + fraction.0,
+                f64::from(absolute.1) + fraction.1,
+            );
+            return CGDisplay::warp_mouse_cursor_position(dest)
+                .map_err(|_| InputError::Simulate("unable to warp the cursor"));
+        }
+        let relative = (absolute.0 - current_x, absolute.1 - current_y);
+
+        let (event_type, button) = if pressed & 1 > 0 {
+            (CGEventType::LeftMouseDragged, CGMouseButton::Left)
+        } else if pressed & 2 > 0 {
+            (CGEventType::RightMouseDragged, CGMouseButton::Right)
+        } else {
+            (CGEventType::MouseMoved, CGMouseButton::Left) // The mouse button
+                                                           // here is ignored so
+                                                           // it can be anything
+        };
+
+        let dest = CGPoint::new(
+            f64::from(absolute.0) + fraction.0,
+            f64::from(absolute.1) + fraction.1,
+        );
+        let Ok(event) =
+            CGEvent::new_mouse_event(self.event_source.clone(), event_type, dest, button)
+        else {
+            return Err(InputError::Simulate(
+                "failed creating event to move the mouse",
+            ));
+        };
+
+        // Add information by how much the mouse was moved
+        event.set_integer_value_field(
+            core_graphics::event::EventField::MOUSE_EVENT_DELTA_X,
+            relative.0.into(),
+        );
+        event.set_integer_value_field(
+            core_graphics::event::EventField::MOUSE_EVENT_DELTA_Y,
+            relative.1.into(),
+        );
+
+        event.set_integer_value_field(
+            EventField::EVENT_SOURCE_USER_DATA,
+            self.event_source_user_data,
+        );
+        event.set_flags(self.event_flags);
+        self.post(&event);
+        self.update_wait_time();
+        Ok(())
+    }
+
+    /// Set which mouse movements are simulated. Have a look at the
+    /// documentation of `PointerMode`
+    pub fn set_pointer_mode(&mut self, mode: PointerMode) {
+        debug!("\x1b[93mset_pointer_mode(mode: {mode:?})\x1b[0m");
+        self.pointer_mode = mode;
+    }
+
+    /// Returns which mouse movements are simulated
+    #[must_use]
+    pub fn pointer_mode(&self) -> PointerMode {
+        self.pointer_mode
+    }
+
+    /// Set the hook that gets notified about each call of a function of the
+    /// `Keyboard` and `Mouse` traits. Have a look at the `metrics` module.
+    /// Pass `None` to remove it
+    pub fn set_metrics_hook(&mut self, hook: Option<Arc<dyn MetricsHook>>) {
+        self.metrics_hook = hook;
+    }
+
+    /// Set the timestamp of the events that are posted from now on (e.g. to
+    /// replay recorded input with its original timing). The timestamp is the
+    /// time since the system started like `CGEventGetTimestamp` returns it.
+    /// Pass `None` to timestamp the events with the time they are posted
+    pub fn set_event_time(&mut self, time: Option<Duration>) {
+        debug!("\x1b[93mset_event_time(time: {time:?})\x1b[0m");
+        self.event_time = time;
+    }
+
+    /// Set the clock that is used to detect double clicks and to wait for the
+    /// OS to handle the posted events. Have a look at the `clock` module
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        let now = clock.now();
+        self.last_event = (now, Duration::from_secs(0));
+        for click in &mut self.last_mouse_click {
+            *click = (0, now);
+        }
+        self.clock = clock;
+    }
+
+    /// Returns the name of the API that is used to simulate input
+    #[allow(clippy::unused_self)]
+    fn backend(&self) -> &'static str {
+        "CGEvent"
+    }
+
+    /// Returns the process id of the frontmost application. Its windows
+    /// receive the simulated input
+    ///
+    /// # Errors
+    /// Returns an error if there is no frontmost application
+    #[allow(clippy::unused_self)]
+    pub fn active_window(&self) -> InputResult<u64> {
+        let application = unsafe { NSWorkspace::sharedWorkspace().frontmostApplication() }
+            .ok_or(InputError::Simulate("there is no frontmost application"))?;
+        let pid = unsafe { application.processIdentifier() };
+        u64::try_from(pid)
+            .map_err(|_| InputError::Simulate("the frontmost application has no process id"))
+    }
+
+    /// Returns the ids of all active displays. The first one is the main
+    /// display
+    ///
+    /// # Errors
+    /// Returns an error if the list of displays could not be retrieved
+    pub fn displays() -> InputResult<Vec<u32>> {
+        CGDisplay::active_displays()
+            .map_err(|_| InputError::Simulate("unable to get the active displays"))
+    }
+
+    /// Returns the bounds (x, y, width, height) of the display with the id in
+    /// the global coordinate space that is used by [`Mouse::move_mouse`] and
+    /// [`Mouse::location`]. The origin is the top left corner of the main
+    /// display, so displays left of or above it have negative coordinates
+    ///
+    /// # Errors
+    /// Returns an [`InputError::InvalidInput`] if there is no active display
+    /// with the id
+    pub fn display_bounds(id: u32) -> InputResult<(i32, i32, i32, i32)> {
+        if !Self::displays()?.contains(&id) {
+            return Err(InputError::InvalidInput("there is no display with the id"));
+        }
+        Ok(display_bounds(id))
+    }
+
+    /// Returns the maximum interval between two clicks so they count as a
+    /// double click. Unless it was overridden, the current setting of the OS
+    /// is returned
+    #[must_use]
+    pub fn double_click_interval(&self) -> Duration {
+        self.double_click_interval.unwrap_or_else(|| {
+            // Returns the double click interval (https://developer.apple.com/documentation/appkit/nsevent/1528384-doubleclickinterval). This is a TimeInterval which is a f64 of the number of seconds
+            let double_click_interval = unsafe { NSEvent::doubleClickInterval() };
+            Duration::from_secs(1).mul_f64(double_click_interval)
+        })
+    }
+
+    /// Override the maximum interval between two clicks so they count as a
+    /// double click. If it is `None`, the setting of the OS is used
+    pub fn set_double_click_interval(&mut self, interval: Option<Duration>) {
+        self.double_click_interval = interval;
+    }
+
+    /// Returns true if the user made the right mouse button the primary one
+    /// (left-handed use)
+    #[must_use]
+    pub fn buttons_swapped() -> bool {
+        let key = NSString::from_str("com.apple.mouse.swapLeftRightButton");
+        unsafe { NSUserDefaults::standardUserDefaults().boolForKey(&key) }
+    }
+
     // On macOS, we have to determine ourselves if it was a double click of a mouse
     // button. The Enigo struct stores the information needed to do so. This
     // function checks if the button was pressed down again fast enough to issue a
@@ -580,9 +1187,9 @@ impl Enigo {
     fn nth_button_press(&mut self, button: Button, direction: Direction) -> i64 {
         if direction == Direction::Press {
             let last_time = self.last_mouse_click[button as usize].1;
-            self.last_mouse_click[button as usize].1 = Instant::now();
+            self.last_mouse_click[button as usize].1 = self.clock.now();
 
-            if last_time.elapsed() < self.double_click_delay {
+            if self.clock.elapsed(last_time) < self.double_click_interval() {
                 self.last_mouse_click[button as usize].0 += 1;
             } else {
                 self.last_mouse_click[button as usize].0 = 1;
@@ -616,7 +1223,7 @@ impl Enigo {
                     self.event_source_user_data,
                 );
                 cg_event.set_flags(self.event_flags);
-                cg_event.post(CGEventTapLocation::HID);
+                self.post(&cg_event);
                 self.update_wait_time();
             } else {
                 return Err(InputError::Simulate(
@@ -647,7 +1254,7 @@ impl Enigo {
                     self.event_source_user_data,
                 );
                 cg_event.set_flags(self.event_flags);
-                cg_event.post(CGEventTapLocation::HID);
+                self.post(&cg_event);
                 self.update_wait_time();
             } else {
                 return Err(InputError::Simulate(
@@ -887,20 +1494,68 @@ impl Enigo {
         flag_fn(&mut self.event_flags, event_flag);
     }
 
-    /// Save the current Instant and calculate the remaining waiting time
-    /// We assume we need to wait for 20 ms for each event to make sure the OS
-    /// has time to handle it. Instead of simply adding 20 ms for each event, we
-    /// assume that the OS handled events between us sending events. That's why
-    /// we subtract the time we already waited between events.
+    /// Save the current Instant and calculate the maximum waiting time
+    /// We assume the OS needs at most 20 ms to handle each event. Instead of
+    /// simply adding 20 ms for each event, we assume that the OS handled events
+    /// between us sending events. That's why we subtract the time we already
+    /// waited between events.
     fn update_wait_time(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
         let wait_time = self
             .last_event
             .1
-            .saturating_sub(self.last_event.0.elapsed())
+            .saturating_sub(now.saturating_duration_since(self.last_event.0))
             + Duration::from_millis(20);
         self.last_event = (now, wait_time);
     }
+
+    /// Set the timestamp of the event if one was set with
+    /// `Enigo::set_event_time` and post it
+    fn post(&self, event: &CGEvent) {
+        if let Some(time) = self.event_time {
+            let timestamp = u64::try_from(time.as_nanos()).unwrap_or(u64::MAX);
+            unsafe { CGEventSetTimestamp(event.as_ptr().cast(), timestamp) };
+        }
+        event.post(self.tap_location);
+    }
+
+    /// Disassociate the cursor from the physical mouse if it is jailed or
+    /// confined to a rectangle. Otherwise associate it again
+    fn update_association(&self) -> InputResult<()> {
+        let associated = !self.cursor_disassociated && self.confinement.is_none();
+        CGDisplay::associate_mouse_and_mouse_cursor_position(associated)
+            .map_err(|_| InputError::Simulate("unable to (dis)associate the cursor and the mouse"))
+    }
+
+    /// Wait until the OS handled all posted events. The OS resets the time
+    /// since the last input event of the session when it handles an event, so
+    /// all of them were handled once that time is shorter than the time since
+    /// the last event was posted. The maximum waiting time is only reached if
+    /// the events are never observed (e.g. because they were posted at the
+    /// `TapLocation::AnnotatedSession`)
+    fn wait_for_posted_events(&self) {
+        let (posted, max_wait) = self.last_event;
+        loop {
+            let since_handled = unsafe {
+                CGEventSourceSecondsSinceLastEventType(
+                    CGEventSourceStateID::CombinedSessionState,
+                    kCGAnyInputEventType,
+                )
+            };
+            let since_handled = Duration::try_from_secs_f64(since_handled).unwrap_or(Duration::MAX);
+            let since_posted = self.clock.elapsed(posted);
+            if since_handled <= since_posted {
+                debug!("the OS handled all events");
+                return;
+            }
+            if since_posted >= max_wait {
+                debug!("timed out waiting for the OS to handle the events");
+                return;
+            }
+            self.clock
+                .sleep(EVENT_POLL_INTERVAL.min(max_wait.saturating_sub(since_posted)));
+        }
+    }
 }
 
 /// Converts a `Key` to a `CGKeyCode`
@@ -941,7 +1596,7 @@ impl TryFrom<Key> for core_graphics::event::CGKeyCode {
             Key::F18 => KeyCode::F18,
             Key::F19 => KeyCode::F19,
             Key::F20 => KeyCode::F20,
-            Key::Function => KeyCode::FUNCTION,
+            Key::Function | Key::Globe => KeyCode::FUNCTION,
             Key::Help => KeyCode::HELP,
             Key::Home => KeyCode::HOME,
             Key::Launchpad => 131,
@@ -962,7 +1617,7 @@ impl TryFrom<Key> for core_graphics::event::CGKeyCode {
             Key::VolumeDown => KeyCode::VOLUME_DOWN,
             Key::VolumeUp => KeyCode::VOLUME_UP,
             Key::VolumeMute => KeyCode::MUTE,
-            Key::Unicode(c) => get_layoutdependent_keycode(&c.to_string()),
+            Key::Unicode(c) => get_layoutdependent_keycode(&c.to_string()).ok_or(())?,
             Key::Other(v) => {
                 let Ok(v) = u16::try_from(v) else {
                     return Err(());
@@ -991,8 +1646,65 @@ impl TryFrom<Key> for core_graphics::event::CGKeyCode {
     }
 }
 
-fn get_layoutdependent_keycode(string: &str) -> CGKeyCode {
-    let mut pressed_keycode = 0;
+/// Returns the name, the first language and the ID of the input source
+fn input_source_info(input_source: TISInputSourceRef) -> InputResult<LayoutInfo> {
+    // The properties follow the "Get" rule, so they are not owned by us
+    let string_property = |key| {
+        let value = unsafe { TISGetInputSourceProperty(input_source, key) };
+        (!value.is_null())
+            .then(|| unsafe { CFString::wrap_under_get_rule(value.cast()) }.to_string())
+    };
+    let name = string_property(unsafe { kTISPropertyLocalizedName });
+    let variant = string_property(unsafe { kTISPropertyInputSourceID });
+    let languages =
+        unsafe { TISGetInputSourceProperty(input_source, kTISPropertyInputSourceLanguages) };
+    let language = (!languages.is_null()).then(|| {
+        let languages: CFArray<CFString> =
+            unsafe { CFArray::wrap_under_get_rule(languages as CFArrayRef) };
+        languages.get(0).map(|language| language.to_string())
+    });
+
+    Ok(LayoutInfo {
+        name: name.ok_or(InputError::Simulate(
+            "the keyboard input source has no name",
+        ))?,
+        language: language.flatten(),
+        variant,
+    })
+}
+
+/// Returns the enabled keyboard input sources that can be selected. If an ID
+/// is provided, only the input source with that ID is returned
+fn keyboard_input_sources(id: Option<&str>) -> InputResult<CFArray<CFType>> {
+    let mut properties = vec![
+        (
+            unsafe { CFString::wrap_under_get_rule(kTISPropertyInputSourceCategory) },
+            unsafe { CFString::wrap_under_get_rule(kTISCategoryKeyboardInputSource) }.as_CFType(),
+        ),
+        (
+            unsafe { CFString::wrap_under_get_rule(kTISPropertyInputSourceIsSelectCapable) },
+            CFBoolean::true_value().as_CFType(),
+        ),
+    ];
+    if let Some(id) = id {
+        properties.push((
+            unsafe { CFString::wrap_under_get_rule(kTISPropertyInputSourceID) },
+            CFString::new(id).as_CFType(),
+        ));
+    }
+    let properties = CFDictionary::from_CFType_pairs(&properties);
+    let input_sources =
+        unsafe { TISCreateInputSourceList(properties.as_concrete_TypeRef(), false) };
+    if input_sources.is_null() {
+        return Err(InputError::Simulate(
+            "unable to list the keyboard input sources",
+        ));
+    }
+    Ok(unsafe { CFArray::wrap_under_create_rule(input_sources) })
+}
+
+fn get_layoutdependent_keycode(string: &str) -> Option<CGKeyCode> {
+    let mut pressed_keycode = None;
 
     // loop through every keycode (0 - 127)
     for keycode in 0..128 {
@@ -1000,7 +1712,7 @@ fn get_layoutdependent_keycode(string: &str) -> CGKeyCode {
         if let Ok(key_string) = keycode_to_string(keycode, 0x100) {
             // debug!("{:?}", string);
             if string == key_string {
-                pressed_keycode = keycode;
+                pressed_keycode = Some(keycode);
             }
         }
 
@@ -1008,7 +1720,7 @@ fn get_layoutdependent_keycode(string: &str) -> CGKeyCode {
         if let Ok(key_string) = keycode_to_string(keycode, 0x20102) {
             // debug!("{:?}", string);
             if string == key_string {
-                pressed_keycode = keycode;
+                pressed_keycode = Some(keycode);
             }
         }
 
@@ -1081,6 +1793,23 @@ fn keycode_to_string(keycode: u16, modifier: u32) -> Result<String, String> {
     })
 }
 
+/// Matches any type of input event
+#[allow(non_upper_case_globals)]
+pub(super) const kCGAnyInputEventType: u32 = u32::MAX;
+
+/// Interval in which it is checked if the OS handled the posted events
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSetTimestamp(event: *mut c_void, timestamp: u64);
+
+    pub(super) fn CGEventSourceSecondsSinceLastEventType(
+        state_id: CGEventSourceStateID,
+        event_type: u32,
+    ) -> f64;
+}
+
 #[link(name = "ApplicationServices", kind = "framework")]
 extern "C" {
     pub fn AXIsProcessTrustedWithOptions(options: CFDictionaryRef) -> bool;
@@ -1092,6 +1821,7 @@ extern "C" {
 ///
 /// Returns true if the application has the permission and is allowed to
 /// simulate input
+#[must_use]
 pub fn has_permission(open_prompt_to_get_permissions: bool) -> bool {
     let key = unsafe { kAXTrustedCheckOptionPrompt };
     let key = unsafe { CFString::wrap_under_create_rule(key) };
@@ -1109,9 +1839,45 @@ pub fn has_permission(open_prompt_to_get_permissions: bool) -> bool {
     unsafe { AXIsProcessTrustedWithOptions(options) }
 }
 
+/// Guard that marks the simulated events with a tag until it is dropped. It
+/// dereferences to the [`Enigo`] struct. Have a look at [`Enigo::tagged`]
+#[derive(Debug)]
+pub struct Tagged<'a> {
+    enigo: &'a mut Enigo,
+    previous: i64,
+}
+
+impl std::ops::Deref for Tagged<'_> {
+    type Target = Enigo;
+
+    fn deref(&self) -> &Enigo {
+        self.enigo
+    }
+}
+
+impl std::ops::DerefMut for Tagged<'_> {
+    fn deref_mut(&mut self) -> &mut Enigo {
+        self.enigo
+    }
+}
+
+impl Drop for Tagged<'_> {
+    fn drop(&mut self) {
+        self.enigo.event_source_user_data = self.previous;
+    }
+}
+
 impl Drop for Enigo {
     // Release the held keys before the connection is dropped
     fn drop(&mut self) {
+        if self.cursor_hidden && self.display.show_cursor().is_err() {
+            error!("unable to show the cursor again");
+        }
+        if (self.cursor_disassociated || self.confinement.is_some())
+            && CGDisplay::associate_mouse_and_mouse_cursor_position(true).is_err()
+        {
+            error!("unable to associate the cursor and the mouse again");
+        }
         if self.release_keys_when_dropped {
             let (held_keys, held_keycodes) = self.held();
             for key in held_keys {
@@ -1128,11 +1894,10 @@ impl Drop for Enigo {
             debug!("released all held keys");
         }
 
-        // DO NOT REMOVE THE SLEEP
-        // This sleep is needed because all events that have not been
+        // DO NOT REMOVE THE WAIT
+        // This is needed because all events that have not been
         // processed until this point would just get ignored when the
         // struct is dropped
-        self.update_wait_time();
-        thread::sleep(self.last_event.1.saturating_sub(Duration::from_millis(20)));
+        self.wait_for_posted_events();
     }
 }
@@ -1,2 +1,7 @@
+#[cfg(feature = "a11y")]
+pub(crate) mod a11y;
+pub(crate) mod health;
+pub(crate) mod keep_awake;
 mod macos_impl;
-pub use macos_impl::Enigo;
+pub(crate) mod system;
+pub use macos_impl::{has_permission, Enigo, Tagged};
@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use core_graphics::{display::CGDisplay, event_source::CGEventSourceStateID};
+use objc2_app_kit::NSEvent;
+use objc2_foundation::{NSString, NSUserDefaults};
+
+use super::macos_impl::{kCGAnyInputEventType, CGEventSourceSecondsSinceLastEventType};
+use crate::{InputError, InputResult};
+
+/// Name of the default with the tracking speed of the mouse. A value of -1
+/// disables the acceleration
+const MOUSE_SCALING: &str = "com.apple.mouse.scaling";
+/// Number of millimeters per inch
+const MM_PER_INCH: f64 = 25.4;
+
+fn mouse_scaling() -> Option<f32> {
+    let key = NSString::from_str(MOUSE_SCALING);
+    let defaults = unsafe { NSUserDefaults::standardUserDefaults() };
+    unsafe { defaults.objectForKey(&key) }?;
+    Some(unsafe { defaults.floatForKey(&key) })
+}
+
+pub(crate) fn mouse_speed() -> InputResult<f64> {
+    mouse_scaling()
+        .map(f64::from)
+        .ok_or(InputError::Simulate("unable to get the mouse speed"))
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn mouse_acceleration_enabled() -> InputResult<bool> {
+    // The acceleration is enabled by default
+    Ok(mouse_scaling().is_none_or(|scaling| scaling >= 0.0))
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn double_click_interval() -> InputResult<Duration> {
+    // The interval is a TimeInterval which is a f64 of the number of seconds
+    let interval = unsafe { NSEvent::doubleClickInterval() };
+    Ok(Duration::from_secs(1).mul_f64(interval))
+}
+
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn screen_dpi() -> InputResult<f64> {
+    let display = CGDisplay::main();
+    let width_mm = display.screen_size().width;
+    if width_mm <= 0.0 {
+        return Err(InputError::Simulate(
+            "unable to get the physical size of the display",
+        ));
+    }
+    Ok(display.pixels_wide() as f64 * MM_PER_INCH / width_mm)
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn idle_time() -> InputResult<Duration> {
+    // The HID system state only contains the events of hardware devices
+    let idle = unsafe {
+        CGEventSourceSecondsSinceLastEventType(
+            CGEventSourceStateID::HIDSystemState,
+            kCGAnyInputEventType,
+        )
+    };
+    Ok(Duration::try_from_secs_f64(idle).unwrap_or(Duration::ZERO))
+}
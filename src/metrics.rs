@@ -0,0 +1,141 @@
+//! Hooks to monitor the simulated input
+//!
+//! Register a [`MetricsHook`] with `Enigo::set_metrics_hook` to get notified
+//! about each call of a function of the [`crate::Keyboard`] and
+//! [`crate::Mouse`] traits. The [`Recorder`] collects the number of events,
+//! the failures and the latency per backend. This is useful for long running
+//! applications (e.g. remote desktop servers) to detect when a connection
+//! degrades.
+//!
+//! ```no_run
+//! use enigo::{metrics::Recorder, Enigo, Keyboard, Settings};
+//! use std::sync::Arc;
+//!
+//! let recorder = Arc::new(Recorder::default());
+//! let mut enigo = Enigo::new(&Settings::default()).unwrap();
+//! enigo.set_metrics_hook(Some(recorder.clone()));
+//! enigo.text("Hello World").unwrap();
+//! for (backend, stats) in recorder.stats() {
+//!     println!(
+//!         "{backend}: {} events, {} failures, p99 {:?}",
+//!         stats.events,
+//!         stats.failures,
+//!         stats.latency_percentile(99.0)
+//!     );
+//! }
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::Duration,
+};
+
+use crate::InputError;
+
+/// Number of latencies per backend the [`Recorder`] keeps to calculate the
+/// percentiles
+pub const LATENCY_SAMPLES: usize = 1024;
+
+/// Information about one call of a function that simulates input
+#[derive(Debug, Clone, Copy)]
+pub struct InputMetric<'a> {
+    /// Name of the function (e.g. "key")
+    pub function: &'static str,
+    /// Name of the backend(s) that simulated the input (e.g. "libei" or
+    /// "`SendInput`"). On Linux, it contains all connections that were used
+    /// separated by a comma
+    pub backend: &'a str,
+    /// Time it took to simulate the input
+    pub latency: Duration,
+    /// The error if simulating the input failed
+    pub error: Option<&'a InputError>,
+}
+
+/// Gets notified about each call of a function that simulates input
+///
+/// It is implemented for closures, so you can also pass one to
+/// `Enigo::set_metrics_hook`. The hook is called on the thread that simulates
+/// the input, so it should return quickly.
+pub trait MetricsHook: Send + Sync {
+    /// Record the call of the function
+    fn record(&self, metric: &InputMetric<'_>);
+}
+
+impl<F> MetricsHook for F
+where
+    F: Fn(&InputMetric<'_>) + Send + Sync,
+{
+    fn record(&self, metric: &InputMetric<'_>) {
+        self(metric);
+    }
+}
+
+/// Statistics of a backend collected by the [`Recorder`]
+#[derive(Debug, Clone, Default)]
+pub struct BackendStats {
+    /// Number of calls
+    pub events: u64,
+    /// Number of calls that returned an error
+    pub failures: u64,
+    latencies: VecDeque<Duration>,
+}
+
+impl BackendStats {
+    /// Returns the latency below which the given percentage (0-100) of the
+    /// last [`LATENCY_SAMPLES`] calls was. Returns `None` if nothing was
+    /// recorded yet
+    #[must_use]
+    pub fn latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut latencies: Vec<_> = self.latencies.iter().copied().collect();
+        latencies.sort_unstable();
+        let max_idx = latencies.len().checked_sub(1)?;
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        #[allow(clippy::cast_precision_loss)]
+        let idx = ((percentile.clamp(0.0, 100.0) / 100.0) * max_idx as f64).round() as usize;
+        latencies.get(idx).copied()
+    }
+}
+
+/// Collects the number of events, the failures and the latency per backend
+#[derive(Debug, Default)]
+pub struct Recorder {
+    stats: Mutex<HashMap<String, BackendStats>>,
+}
+
+impl Recorder {
+    /// Returns the statistics of all backends that simulated input
+    ///
+    /// # Panics
+    /// Panics if a thread panicked while recording
+    #[must_use]
+    pub fn stats(&self) -> HashMap<String, BackendStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Removes all recorded statistics
+    ///
+    /// # Panics
+    /// Panics if a thread panicked while recording
+    pub fn reset(&self) {
+        self.stats.lock().unwrap().clear();
+    }
+}
+
+impl MetricsHook for Recorder {
+    fn record(&self, metric: &InputMetric<'_>) {
+        let Ok(mut all_stats) = self.stats.lock() else {
+            return;
+        };
+        let stats = all_stats.entry(metric.backend.to_string()).or_default();
+        stats.events += 1;
+        if metric.error.is_some() {
+            stats.failures += 1;
+        }
+        if stats.latencies.len() == LATENCY_SAMPLES {
+            stats.latencies.pop_front();
+        }
+        stats.latencies.push_back(metric.latency);
+    }
+}
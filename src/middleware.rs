@@ -0,0 +1,663 @@
+//! Wrappers that add behavior to anything implementing [`Keyboard`] and/or
+//! [`Mouse`]
+//!
+//! The wrappers implement the same traits as the wrapped type, so they can be
+//! nested and used everywhere an [`crate::Enigo`] can be used (including
+//! executing [`Token`]s with the [`crate::agent::Agent`] trait).
+//!
+//! ```no_run
+//! use enigo::{
+//!     middleware::{Logged, RateLimited},
+//!     Enigo, Keyboard, Settings,
+//! };
+//! use std::time::Duration;
+//!
+//! let enigo = Enigo::new(&Settings::default()).unwrap();
+//! let mut enigo = Logged::new(RateLimited::new(enigo, Duration::from_millis(50)));
+//! enigo.text("Hello World").unwrap();
+//! ```
+
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+
+use crate::{
+    agent::Token, Axis, Button, CancellationToken, Coordinate, Direction, InputResult, Key,
+    Keyboard, Mouse, PartialTextError, Rect, Scancode, ScrollUnit,
+};
+
+/// Forward all functions of the traits to `self.inner` and call `$before`
+/// before and `$after` after each function that simulates input
+macro_rules! forward {
+    ($wrapper:ident, $before:ident, $after:ident) => {
+        impl<E: Keyboard> Keyboard for $wrapper<E> {
+            fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+                self.$before();
+                let res = self.inner.fast_text(text);
+                self.$after();
+                res
+            }
+
+            fn text(&mut self, text: &str) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.text(text);
+                self.$after();
+                res
+            }
+
+            fn text_cancellable(
+                &mut self,
+                text: &str,
+                token: &CancellationToken,
+            ) -> Result<usize, PartialTextError> {
+                self.$before();
+                let res = self.inner.text_cancellable(text, token);
+                self.$after();
+                res
+            }
+
+            fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.key(key, direction);
+                self.$after();
+                res
+            }
+
+            fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.raw(keycode, direction);
+                self.$after();
+                res
+            }
+
+            fn physical(&mut self, scancode: Scancode, direction: Direction) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.physical(scancode, direction);
+                self.$after();
+                res
+            }
+
+            fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.key_sequence(keys, direction);
+                self.$after();
+                res
+            }
+
+            fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.latch_modifier(key);
+                self.$after();
+                res
+            }
+
+            fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.lock_modifier(key, locked);
+                self.$after();
+                res
+            }
+        }
+
+        impl<E: Mouse> Mouse for $wrapper<E> {
+            fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.button(button, direction);
+                self.$after();
+                res
+            }
+
+            fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.buttons(buttons);
+                self.$after();
+                res
+            }
+
+            fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.move_mouse(x, y, coordinate);
+                self.$after();
+                res
+            }
+
+            fn move_mouse_checked(
+                &mut self,
+                x: i32,
+                y: i32,
+                coordinate: Coordinate,
+            ) -> InputResult<(i32, i32)> {
+                self.$before();
+                let res = self.inner.move_mouse_checked(x, y, coordinate);
+                self.$after();
+                res
+            }
+
+            fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.move_mouse_f(x, y, coordinate);
+                self.$after();
+                res
+            }
+
+            fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.scroll(length, axis);
+                self.$after();
+                res
+            }
+
+            fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.scroll_2d(dx, dy, unit);
+                self.$after();
+                res
+            }
+
+            fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.scroll_by_pixels_approx(target_px, axis);
+                self.$after();
+                res
+            }
+
+            fn scroll_with_inertia(&mut self, velocity: f64, axis: Axis) -> InputResult<()> {
+                self.$before();
+                let res = self.inner.scroll_with_inertia(velocity, axis);
+                self.$after();
+                res
+            }
+
+            fn main_display(&self) -> InputResult<(i32, i32)> {
+                self.inner.main_display()
+            }
+
+            fn location(&self) -> InputResult<(i32, i32)> {
+                self.inner.location()
+            }
+
+            fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+                self.inner.show_cursor(visible)
+            }
+
+            fn associate_cursor(&mut self, associated: bool) -> InputResult<()> {
+                self.inner.associate_cursor(associated)
+            }
+
+            fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+                self.inner.confine_to(rect)
+            }
+
+            fn release_confinement(&mut self) -> InputResult<()> {
+                self.inner.release_confinement()
+            }
+        }
+    };
+}
+
+/// Add functions to access the wrapped value
+macro_rules! inner {
+    ($wrapper:ident) => {
+        impl<E> $wrapper<E> {
+            /// Returns a reference to the wrapped value
+            pub fn inner(&self) -> &E {
+                &self.inner
+            }
+
+            /// Returns a mutable reference to the wrapped value
+            pub fn inner_mut(&mut self) -> &mut E {
+                &mut self.inner
+            }
+
+            /// Returns the wrapped value
+            pub fn into_inner(self) -> E {
+                self.inner
+            }
+        }
+    };
+}
+
+/// Sleeps for a fixed duration after each simulated input
+#[derive(Debug)]
+pub struct Delayed<E> {
+    inner: E,
+    delay: Duration,
+}
+
+impl<E> Delayed<E> {
+    /// Wrap `inner` and sleep for `delay` after each simulated input
+    pub fn new(inner: E, delay: Duration) -> Self {
+        Self { inner, delay }
+    }
+
+    #[allow(clippy::unused_self)]
+    fn before(&mut self) {}
+
+    fn after(&mut self) {
+        thread::sleep(self.delay);
+    }
+}
+
+forward!(Delayed, before, after);
+inner!(Delayed);
+
+/// Logs each simulated input with the `info` level
+#[derive(Debug)]
+pub struct Logged<E> {
+    inner: E,
+}
+
+impl<E> Logged<E> {
+    /// Wrap `inner` and log each simulated input
+    pub fn new(inner: E) -> Self {
+        Self { inner }
+    }
+}
+
+inner!(Logged);
+
+impl<E: Keyboard> Keyboard for Logged<E> {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        info!("fast_text(text: {text})");
+        self.inner.fast_text(text)
+    }
+
+    fn text(&mut self, text: &str) -> InputResult<()> {
+        info!("text(text: {text})");
+        self.inner.text(text)
+    }
+
+    fn text_cancellable(
+        &mut self,
+        text: &str,
+        token: &CancellationToken,
+    ) -> Result<usize, PartialTextError> {
+        info!("text_cancellable(text: {text})");
+        self.inner.text_cancellable(text, token)
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        info!("key(key: {key:?}, direction: {direction:?})");
+        self.inner.key(key, direction)
+    }
+
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        info!("raw(keycode: {keycode:?}, direction: {direction:?})");
+        self.inner.raw(keycode, direction)
+    }
+
+    fn physical(&mut self, scancode: Scancode, direction: Direction) -> InputResult<()> {
+        info!("physical(scancode: {scancode:?}, direction: {direction:?})");
+        self.inner.physical(scancode, direction)
+    }
+
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        info!("key_sequence(keys: {keys:?}, direction: {direction:?})");
+        self.inner.key_sequence(keys, direction)
+    }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        info!("latch_modifier(key: {key:?})");
+        self.inner.latch_modifier(key)
+    }
+
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        info!("lock_modifier(key: {key:?}, locked: {locked})");
+        self.inner.lock_modifier(key, locked)
+    }
+}
+
+impl<E: Mouse> Mouse for Logged<E> {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        info!("button(button: {button:?}, direction: {direction:?})");
+        self.inner.button(button, direction)
+    }
+
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        info!("buttons(buttons: {buttons:?})");
+        self.inner.buttons(buttons)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        info!("move_mouse(x: {x:?}, y: {y:?}, coordinate:{coordinate:?})");
+        self.inner.move_mouse(x, y, coordinate)
+    }
+
+    fn move_mouse_checked(
+        &mut self,
+        x: i32,
+        y: i32,
+        coordinate: Coordinate,
+    ) -> InputResult<(i32, i32)> {
+        info!("move_mouse_checked(x: {x:?}, y: {y:?}, coordinate: {coordinate:?})");
+        self.inner.move_mouse_checked(x, y, coordinate)
+    }
+
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        info!("move_mouse_f(x: {x:?}, y: {y:?}, coordinate: {coordinate:?})");
+        self.inner.move_mouse_f(x, y, coordinate)
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        info!("scroll(length: {length:?}, axis: {axis:?})");
+        self.inner.scroll(length, axis)
+    }
+
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        info!("scroll_2d(dx: {dx:?}, dy: {dy:?}, unit: {unit:?})");
+        self.inner.scroll_2d(dx, dy, unit)
+    }
+
+    fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+        info!("scroll_by_pixels_approx(target_px: {target_px:?}, axis: {axis:?})");
+        self.inner.scroll_by_pixels_approx(target_px, axis)
+    }
+
+    fn scroll_with_inertia(&mut self, velocity: f64, axis: Axis) -> InputResult<()> {
+        info!("scroll_with_inertia(velocity: {velocity:?}, axis: {axis:?})");
+        self.inner.scroll_with_inertia(velocity, axis)
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        info!("main_display()");
+        self.inner.main_display()
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        info!("location()");
+        self.inner.location()
+    }
+
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        info!("show_cursor(visible: {visible:?})");
+        self.inner.show_cursor(visible)
+    }
+
+    fn associate_cursor(&mut self, associated: bool) -> InputResult<()> {
+        info!("associate_cursor(associated: {associated:?})");
+        self.inner.associate_cursor(associated)
+    }
+
+    fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+        info!("confine_to(rect: {rect:?})");
+        self.inner.confine_to(rect)
+    }
+
+    fn release_confinement(&mut self) -> InputResult<()> {
+        info!("release_confinement()");
+        self.inner.release_confinement()
+    }
+}
+
+/// Makes sure there is at least a minimum interval between two simulated
+/// inputs. Unlike [`Delayed`], there is no sleep if enough time passed
+/// since the last input
+#[derive(Debug)]
+pub struct RateLimited<E> {
+    inner: E,
+    min_interval: Duration,
+    last_input: Option<Instant>,
+}
+
+impl<E> RateLimited<E> {
+    /// Wrap `inner` and wait until at least `min_interval` passed since the
+    /// previous input before simulating the next one
+    pub fn new(inner: E, min_interval: Duration) -> Self {
+        Self {
+            inner,
+            min_interval,
+            last_input: None,
+        }
+    }
+
+    fn before(&mut self) {
+        if let Some(last_input) = self.last_input {
+            if let Some(remaining) = self.min_interval.checked_sub(last_input.elapsed()) {
+                thread::sleep(remaining);
+            }
+        }
+    }
+
+    fn after(&mut self) {
+        self.last_input = Some(Instant::now());
+    }
+}
+
+forward!(RateLimited, before, after);
+inner!(RateLimited);
+
+/// Records all simulated input as [`Token`]s. They can be replayed later with
+/// the [`crate::agent::Agent`] trait
+#[derive(Debug)]
+pub struct Recorded<E> {
+    inner: E,
+    tokens: Vec<Token>,
+    /// Size of the main display that starts each recording
+    main_display: Option<(i32, i32)>,
+}
+
+impl<E> Recorded<E> {
+    /// Wrap `inner` and record all simulated input
+    pub fn new(inner: E) -> Self {
+        Self {
+            inner,
+            tokens: vec![],
+            main_display: None,
+        }
+    }
+
+    /// Returns the recorded tokens
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Returns the recorded tokens and removes them from the recording
+    pub fn take_tokens(&mut self) -> Vec<Token> {
+        let tokens = std::mem::take(&mut self.tokens);
+        self.tokens
+            .extend(self.main_display.map(|(w, h)| Token::MainDisplay(w, h)));
+        tokens
+    }
+}
+
+impl<E: Mouse> Recorded<E> {
+    /// Same as [`Recorded::new`], but each recording starts with a
+    /// [`Token::MainDisplay`] with the size of the main display, so
+    /// [`crate::agent::Agent::replay`] can re-scale the coordinates if the
+    /// resolution changed in the meantime
+    pub fn with_main_display(inner: E) -> Self {
+        let mut recorded = Self::new(inner);
+        match recorded.inner.main_display() {
+            Ok(size) => recorded.main_display = Some(size),
+            Err(e) => warn!("unable to record the size of the main display: {e}"),
+        }
+        recorded
+            .tokens
+            .extend(recorded.main_display.map(|(w, h)| Token::MainDisplay(w, h)));
+        recorded
+    }
+}
+
+inner!(Recorded);
+
+impl<E: Keyboard> Keyboard for Recorded<E> {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        self.inner.fast_text(text)
+    }
+
+    fn text(&mut self, text: &str) -> InputResult<()> {
+        self.inner.text(text)?;
+        self.tokens.push(Token::Text(text.to_string()));
+        Ok(())
+    }
+
+    // Only the part of the text that was entered is recorded
+    fn text_cancellable(
+        &mut self,
+        text: &str,
+        token: &CancellationToken,
+    ) -> Result<usize, PartialTextError> {
+        let res = self.inner.text_cancellable(text, token);
+        let entered = match &res {
+            Ok(entered) => *entered,
+            Err(e) => e.entered,
+        };
+        if entered > 0 {
+            self.tokens
+                .push(Token::Text(text.chars().take(entered).collect()));
+        }
+        res
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        self.inner.key(key, direction)?;
+        self.tokens.push(Token::Key(key, direction));
+        Ok(())
+    }
+
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        self.inner.raw(keycode, direction)?;
+        self.tokens.push(Token::Raw(keycode, direction));
+        Ok(())
+    }
+
+    // There is no token for scancodes, so it is recorded as the keycode of the
+    // current platform
+    fn physical(&mut self, scancode: Scancode, direction: Direction) -> InputResult<()> {
+        let keycode = scancode.to_platform()?;
+        self.inner.physical(scancode, direction)?;
+        self.tokens.push(Token::Raw(keycode, direction));
+        Ok(())
+    }
+
+    // The sequence is recorded as the individual key events it consists of
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        self.inner.key_sequence(keys, direction)?;
+        if direction != Direction::Release {
+            self.tokens
+                .extend(keys.iter().map(|&key| Token::Key(key, Direction::Press)));
+        }
+        if direction != Direction::Press {
+            self.tokens.extend(
+                keys.iter()
+                    .rev()
+                    .map(|&key| Token::Key(key, Direction::Release)),
+            );
+        }
+        Ok(())
+    }
+
+    // There are no tokens for latched and locked modifiers, so they are not
+    // recorded
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        self.inner.latch_modifier(key)
+    }
+
+    fn lock_modifier(&mut self, key: Key, locked: bool) -> InputResult<()> {
+        self.inner.lock_modifier(key, locked)
+    }
+}
+
+impl<E: Mouse> Mouse for Recorded<E> {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        self.inner.button(button, direction)?;
+        self.tokens.push(Token::Button(button, direction));
+        Ok(())
+    }
+
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        self.inner.buttons(buttons)?;
+        self.tokens.extend(
+            buttons
+                .iter()
+                .map(|&(button, direction)| Token::Button(button, direction)),
+        );
+        Ok(())
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        self.inner.move_mouse(x, y, coordinate)?;
+        self.tokens.push(Token::MoveMouse(x, y, coordinate));
+        Ok(())
+    }
+
+    fn move_mouse_checked(
+        &mut self,
+        x: i32,
+        y: i32,
+        coordinate: Coordinate,
+    ) -> InputResult<(i32, i32)> {
+        let location = self.inner.move_mouse_checked(x, y, coordinate)?;
+        self.tokens.push(Token::MoveMouse(x, y, coordinate));
+        Ok(location)
+    }
+
+    /// Not recorded because there is no [`Token`] for movements by fractions
+    /// of a pixel
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        self.inner.move_mouse_f(x, y, coordinate)
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        self.inner.scroll(length, axis)?;
+        self.tokens.push(Token::Scroll(length, axis));
+        Ok(())
+    }
+
+    /// Scrolling by pixels is not recorded because there is no [`Token`] for
+    /// it
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        self.inner.scroll_2d(dx, dy, unit)?;
+        if unit == ScrollUnit::Line {
+            if dx != 0 {
+                self.tokens.push(Token::Scroll(dx, Axis::Horizontal));
+            }
+            if dy != 0 {
+                self.tokens.push(Token::Scroll(dy, Axis::Vertical));
+            }
+        }
+        Ok(())
+    }
+
+    /// Not recorded because the number of pixels or notches that get scrolled
+    /// depends on the platform
+    fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+        self.inner.scroll_by_pixels_approx(target_px, axis)
+    }
+
+    /// Not recorded because there is no [`Token`] for scrolling by pixels
+    fn scroll_with_inertia(&mut self, velocity: f64, axis: Axis) -> InputResult<()> {
+        self.inner.scroll_with_inertia(velocity, axis)
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        self.inner.main_display()
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        self.inner.location()
+    }
+
+    /// Not recorded because it does not simulate input
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        self.inner.show_cursor(visible)
+    }
+
+    /// Not recorded because it does not simulate input
+    fn associate_cursor(&mut self, associated: bool) -> InputResult<()> {
+        self.inner.associate_cursor(associated)
+    }
+
+    /// Not recorded because it does not simulate input
+    fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+        self.inner.confine_to(rect)
+    }
+
+    /// Not recorded because it does not simulate input
+    fn release_confinement(&mut self) -> InputResult<()> {
+        self.inner.release_confinement()
+    }
+}
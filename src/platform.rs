@@ -1,52 +1,73 @@
-use crate::{Keyboard, Mouse};
+//! Types and functions that only exist on one platform
+//!
+//! [`Enigo`](crate::Enigo) is the struct of the platform the crate is compiled
+//! for, so it has a few functions that are not available on the other
+//! platforms. Each of the modules [`windows`], [`macos`] and [`linux`] only
+//! exists on its platform and collects these extras, so the platform specific
+//! code of an application can be kept in one place:
+//!
+//! | Platform | Extras of `Enigo`                                                                                                                                                                                                                                                        |
+//! |----------|--------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------|
+//! | Windows  | `get_marker_value`, `tagged`, `with_keyboard_layout`, `relative_moves_transformed`, `mouse_acceleration`, `set_mouse_acceleration`, `with_mouse_acceleration_disabled`, `ime_active`, `when_foreground`, `set_event_time`, `tracked_position`, `reset_position_tracking` |
+//! | macOS    | `get_marker_value`, `tagged`, `input_sources`, `select_input_source`, `with_input_source`, `displays`, `display_bounds`, `set_clock`, `set_event_time`                                                                                                                   |
+//! | Linux    | `connect`, `delay`, `set_delay`, `libei_seats`, `libei_devices`, `keymap`, `layout_groups`, `set_layout_group`, `with_layout_group`, `lock_state`, `set_lock_state`, `repeat_held_keys`, `set_event_time`                                                                |
+//!
+//! Code that is generic over the type it simulates input with (e.g. because
+//! it is wrapped in [`middleware`](crate::middleware)) can reach the platform
+//! struct with [`Enigo::downcast_ref`](crate::Enigo::downcast_ref) and
+//! [`Enigo::downcast_mut`](crate::Enigo::downcast_mut).
+//!
+//! ```no_run
+//! use enigo::{Enigo, Settings};
+//!
+//! let enigo = Enigo::new(&Settings::default()).unwrap();
+//! #[cfg(target_os = "windows")]
+//! {
+//!     let windows: &enigo::platform::windows::Enigo = enigo.downcast_ref().unwrap();
+//!     println!("the events are marked with {}", windows.get_marker_value());
+//! }
+//! #[cfg(target_os = "macos")]
+//! {
+//!     let macos: &enigo::platform::macos::Enigo = enigo.downcast_ref().unwrap();
+//!     println!("the events are marked with {}", macos.get_marker_value());
+//! }
+//! ```
 
-// Enum without any variants
-// This can never get constructed
-// See https://github.com/enigo-rs/enigo/pull/269 for more details
-enum Never {}
+use std::any::Any;
 
-pub struct Enigo {
-    never: Never,
+/// Types and functions that only exist on Windows
+#[cfg(target_os = "windows")]
+pub mod windows {
+    pub use crate::os::{
+        set_dpi_awareness, Enigo, ForegroundTarget, MouseAcceleration, Tagged, EXT,
+    };
 }
 
-impl Mouse for Enigo {
-    fn button(&mut self, _: crate::Button, _: crate::Direction) -> crate::InputResult<()> {
-        match self.never {}
-    }
-
-    fn move_mouse(&mut self, _: i32, _: i32, _: crate::Coordinate) -> crate::InputResult<()> {
-        match self.never {}
-    }
-
-    fn scroll(&mut self, _: i32, _: crate::Axis) -> crate::InputResult<()> {
-        match self.never {}
-    }
-
-    fn main_display(&self) -> crate::InputResult<(i32, i32)> {
-        match self.never {}
-    }
-
-    fn location(&self) -> crate::InputResult<(i32, i32)> {
-        match self.never {}
-    }
+/// Types and functions that only exist on macOS
+#[cfg(target_os = "macos")]
+pub mod macos {
+    pub use crate::os::{has_permission, Enigo, Tagged};
 }
 
-impl Keyboard for Enigo {
-    fn fast_text(&mut self, _: &str) -> crate::InputResult<Option<()>> {
-        match self.never {}
-    }
-
-    fn key(&mut self, _: crate::Key, _: crate::Direction) -> crate::InputResult<()> {
-        match self.never {}
-    }
+/// Types and functions that only exist on Linux and the BSDs
+#[cfg(all(unix, not(target_os = "macos")))]
+pub mod linux {
+    pub use crate::os::{xkb_keymap as keymap, Enigo};
+}
 
-    fn raw(&mut self, _: u16, _: crate::Direction) -> crate::InputResult<()> {
-        match self.never {}
+impl crate::Enigo {
+    /// Returns a reference to `T` if it is the platform specific struct (e.g.
+    /// `platform::windows::Enigo` on Windows). Otherwise `None` is returned
+    #[must_use]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref()
     }
-}
 
-impl Drop for Enigo {
-    fn drop(&mut self) {
-        match self.never {}
+    /// Returns a mutable reference to `T` if it is the platform specific
+    /// struct (e.g. `platform::linux::Enigo` on Linux). Otherwise `None` is
+    /// returned
+    #[must_use]
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        (self as &mut dyn Any).downcast_mut()
     }
 }
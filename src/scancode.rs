@@ -0,0 +1,365 @@
+//! Conversion between the numbering schemes of the physical keys
+//!
+//! Every platform identifies the physical keys with its own codes: Linux uses
+//! the evdev keycodes, Windows the scan codes of set 1 and macOS its virtual
+//! keycodes. USB keyboards report the usages of the HID usage tables. The
+//! [`TABLE`] lists the codes of the keys of a standard 105 key keyboard
+//! (letters, digits, punctuation, function keys F1-F20, navigation, keypad,
+//! modifiers and volume keys) in all of the schemes, so e.g. remote desktop
+//! software can normalize the scan codes it receives before it feeds them to
+//! enigo on another OS. The name of each key is the value of the `code`
+//! property of the `KeyboardEvent` in browsers.
+//!
+//! ```
+//! use enigo::scancode::{convert, lookup, Scheme};
+//!
+//! // The right Control key on Windows is 0xE01D and 97 on Linux
+//! assert_eq!(convert(0xE01D, Scheme::WindowsSet1, Scheme::Evdev), Some(97));
+//! assert_eq!(lookup(97, Scheme::Evdev).unwrap().name, "ControlRight");
+//! ```
+//!
+//! Use [`Scancode`] with [`Keyboard::physical`](crate::Keyboard::physical) to
+//! press the key with the code of the current platform.
+
+use crate::{InputError, InputResult};
+
+/// Numbering scheme of the physical keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    /// Usage of the keyboard page (0x07) of the USB HID usage tables (e.g.
+    /// 0x04 for A)
+    HidUsage,
+    /// Linux evdev keycode (e.g. `KEY_A` = 30). It is the X11 keycode minus 8
+    Evdev,
+    /// Scan code of set 1 the way Windows reports it (e.g. in the `lParam` of
+    /// `WM_KEYDOWN` or by `MapVirtualKeyExW`). Extended keys have the prefix
+    /// 0xE0 (e.g. 0xE01D for the right Control key)
+    WindowsSet1,
+    /// Virtual keycode of macOS (e.g. `kVK_ANSI_A` = 0x00)
+    MacKeycode,
+}
+
+/// The codes of a key in all numbering schemes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// Name of the key. It is the `code` of the `KeyboardEvent` in browsers
+    /// (e.g. `KeyA` or `ControlRight`)
+    pub name: &'static str,
+    /// Usage of the keyboard page of the USB HID usage tables
+    pub hid_usage: u16,
+    /// Linux evdev keycode
+    pub evdev: u16,
+    /// Scan code of set 1 the way Windows uses it
+    pub windows_set1: u16,
+    /// Virtual keycode of macOS. Some keys don't exist on Mac keyboards
+    pub mac_keycode: Option<u16>,
+}
+
+impl Entry {
+    /// The code of the key in the scheme
+    #[must_use]
+    pub fn code(&self, scheme: Scheme) -> Option<u16> {
+        match scheme {
+            Scheme::HidUsage => Some(self.hid_usage),
+            Scheme::Evdev => Some(self.evdev),
+            Scheme::WindowsSet1 => Some(self.windows_set1),
+            Scheme::MacKeycode => self.mac_keycode,
+        }
+    }
+}
+
+const fn entry(
+    name: &'static str,
+    hid_usage: u16,
+    evdev: u16,
+    windows_set1: u16,
+    mac_keycode: Option<u16>,
+) -> Entry {
+    Entry {
+        name,
+        hid_usage,
+        evdev,
+        windows_set1,
+        mac_keycode,
+    }
+}
+
+/// Codes of the keys of a standard 105 key keyboard in all numbering schemes
+#[rustfmt::skip]
+pub const TABLE: &[Entry] = &[
+    entry("KeyA", 0x04, 30, 0x1E, Some(0x00)),
+    entry("KeyB", 0x05, 48, 0x30, Some(0x0B)),
+    entry("KeyC", 0x06, 46, 0x2E, Some(0x08)),
+    entry("KeyD", 0x07, 32, 0x20, Some(0x02)),
+    entry("KeyE", 0x08, 18, 0x12, Some(0x0E)),
+    entry("KeyF", 0x09, 33, 0x21, Some(0x03)),
+    entry("KeyG", 0x0A, 34, 0x22, Some(0x05)),
+    entry("KeyH", 0x0B, 35, 0x23, Some(0x04)),
+    entry("KeyI", 0x0C, 23, 0x17, Some(0x22)),
+    entry("KeyJ", 0x0D, 36, 0x24, Some(0x26)),
+    entry("KeyK", 0x0E, 37, 0x25, Some(0x28)),
+    entry("KeyL", 0x0F, 38, 0x26, Some(0x25)),
+    entry("KeyM", 0x10, 50, 0x32, Some(0x2E)),
+    entry("KeyN", 0x11, 49, 0x31, Some(0x2D)),
+    entry("KeyO", 0x12, 24, 0x18, Some(0x1F)),
+    entry("KeyP", 0x13, 25, 0x19, Some(0x23)),
+    entry("KeyQ", 0x14, 16, 0x10, Some(0x0C)),
+    entry("KeyR", 0x15, 19, 0x13, Some(0x0F)),
+    entry("KeyS", 0x16, 31, 0x1F, Some(0x01)),
+    entry("KeyT", 0x17, 20, 0x14, Some(0x11)),
+    entry("KeyU", 0x18, 22, 0x16, Some(0x20)),
+    entry("KeyV", 0x19, 47, 0x2F, Some(0x09)),
+    entry("KeyW", 0x1A, 17, 0x11, Some(0x0D)),
+    entry("KeyX", 0x1B, 45, 0x2D, Some(0x07)),
+    entry("KeyY", 0x1C, 21, 0x15, Some(0x10)),
+    entry("KeyZ", 0x1D, 44, 0x2C, Some(0x06)),
+    entry("Digit1", 0x1E, 2, 0x02, Some(0x12)),
+    entry("Digit2", 0x1F, 3, 0x03, Some(0x13)),
+    entry("Digit3", 0x20, 4, 0x04, Some(0x14)),
+    entry("Digit4", 0x21, 5, 0x05, Some(0x15)),
+    entry("Digit5", 0x22, 6, 0x06, Some(0x17)),
+    entry("Digit6", 0x23, 7, 0x07, Some(0x16)),
+    entry("Digit7", 0x24, 8, 0x08, Some(0x1A)),
+    entry("Digit8", 0x25, 9, 0x09, Some(0x1C)),
+    entry("Digit9", 0x26, 10, 0x0A, Some(0x19)),
+    entry("Digit0", 0x27, 11, 0x0B, Some(0x1D)),
+    entry("Enter", 0x28, 28, 0x1C, Some(0x24)),
+    entry("Escape", 0x29, 1, 0x01, Some(0x35)),
+    entry("Backspace", 0x2A, 14, 0x0E, Some(0x33)),
+    entry("Tab", 0x2B, 15, 0x0F, Some(0x30)),
+    entry("Space", 0x2C, 57, 0x39, Some(0x31)),
+    entry("Minus", 0x2D, 12, 0x0C, Some(0x1B)),
+    entry("Equal", 0x2E, 13, 0x0D, Some(0x18)),
+    entry("BracketLeft", 0x2F, 26, 0x1A, Some(0x21)),
+    entry("BracketRight", 0x30, 27, 0x1B, Some(0x1E)),
+    entry("Backslash", 0x31, 43, 0x2B, Some(0x2A)),
+    entry("Semicolon", 0x33, 39, 0x27, Some(0x29)),
+    entry("Quote", 0x34, 40, 0x28, Some(0x27)),
+    entry("Backquote", 0x35, 41, 0x29, Some(0x32)),
+    entry("Comma", 0x36, 51, 0x33, Some(0x2B)),
+    entry("Period", 0x37, 52, 0x34, Some(0x2F)),
+    entry("Slash", 0x38, 53, 0x35, Some(0x2C)),
+    entry("CapsLock", 0x39, 58, 0x3A, Some(0x39)),
+    entry("F1", 0x3A, 59, 0x3B, Some(0x7A)),
+    entry("F2", 0x3B, 60, 0x3C, Some(0x78)),
+    entry("F3", 0x3C, 61, 0x3D, Some(0x63)),
+    entry("F4", 0x3D, 62, 0x3E, Some(0x76)),
+    entry("F5", 0x3E, 63, 0x3F, Some(0x60)),
+    entry("F6", 0x3F, 64, 0x40, Some(0x61)),
+    entry("F7", 0x40, 65, 0x41, Some(0x62)),
+    entry("F8", 0x41, 66, 0x42, Some(0x64)),
+    entry("F9", 0x42, 67, 0x43, Some(0x65)),
+    entry("F10", 0x43, 68, 0x44, Some(0x6D)),
+    entry("F11", 0x44, 87, 0x57, Some(0x67)),
+    entry("F12", 0x45, 88, 0x58, Some(0x6F)),
+    entry("PrintScreen", 0x46, 99, 0xE037, None),
+    entry("ScrollLock", 0x47, 70, 0x46, None),
+    // The keyboard sends E1 1D 45 for Pause, Windows uses 0x45
+    entry("Pause", 0x48, 119, 0x45, None),
+    entry("Insert", 0x49, 110, 0xE052, Some(0x72)), // Help on macOS
+    entry("Home", 0x4A, 102, 0xE047, Some(0x73)),
+    entry("PageUp", 0x4B, 104, 0xE049, Some(0x74)),
+    entry("Delete", 0x4C, 111, 0xE053, Some(0x75)),
+    entry("End", 0x4D, 107, 0xE04F, Some(0x77)),
+    entry("PageDown", 0x4E, 109, 0xE051, Some(0x79)),
+    entry("ArrowRight", 0x4F, 106, 0xE04D, Some(0x7C)),
+    entry("ArrowLeft", 0x50, 105, 0xE04B, Some(0x7B)),
+    entry("ArrowDown", 0x51, 108, 0xE050, Some(0x7D)),
+    entry("ArrowUp", 0x52, 103, 0xE048, Some(0x7E)),
+    // Windows uses 0xE045 for Num Lock. It is Clear on macOS
+    entry("NumLock", 0x53, 69, 0xE045, Some(0x47)),
+    entry("NumpadDivide", 0x54, 98, 0xE035, Some(0x4B)),
+    entry("NumpadMultiply", 0x55, 55, 0x37, Some(0x43)),
+    entry("NumpadSubtract", 0x56, 74, 0x4A, Some(0x4E)),
+    entry("NumpadAdd", 0x57, 78, 0x4E, Some(0x45)),
+    entry("NumpadEnter", 0x58, 96, 0xE01C, Some(0x4C)),
+    entry("Numpad1", 0x59, 79, 0x4F, Some(0x53)),
+    entry("Numpad2", 0x5A, 80, 0x50, Some(0x54)),
+    entry("Numpad3", 0x5B, 81, 0x51, Some(0x55)),
+    entry("Numpad4", 0x5C, 75, 0x4B, Some(0x56)),
+    entry("Numpad5", 0x5D, 76, 0x4C, Some(0x57)),
+    entry("Numpad6", 0x5E, 77, 0x4D, Some(0x58)),
+    entry("Numpad7", 0x5F, 71, 0x47, Some(0x59)),
+    entry("Numpad8", 0x60, 72, 0x48, Some(0x5B)),
+    entry("Numpad9", 0x61, 73, 0x49, Some(0x5C)),
+    entry("Numpad0", 0x62, 82, 0x52, Some(0x52)),
+    entry("NumpadDecimal", 0x63, 83, 0x53, Some(0x41)),
+    entry("IntlBackslash", 0x64, 86, 0x56, Some(0x0A)), // Key between the left Shift and Z on ISO keyboards
+    entry("ContextMenu", 0x65, 127, 0xE05D, None),
+    entry("NumpadEqual", 0x67, 117, 0x59, Some(0x51)),
+    entry("F13", 0x68, 183, 0x64, Some(0x69)),
+    entry("F14", 0x69, 184, 0x65, Some(0x6B)),
+    entry("F15", 0x6A, 185, 0x66, Some(0x71)),
+    entry("F16", 0x6B, 186, 0x67, Some(0x6A)),
+    entry("F17", 0x6C, 187, 0x68, Some(0x40)),
+    entry("F18", 0x6D, 188, 0x69, Some(0x4F)),
+    entry("F19", 0x6E, 189, 0x6A, Some(0x50)),
+    entry("F20", 0x6F, 190, 0x6B, Some(0x5A)),
+    entry("AudioVolumeMute", 0x7F, 113, 0xE020, Some(0x4A)),
+    entry("AudioVolumeUp", 0x80, 115, 0xE030, Some(0x48)),
+    entry("AudioVolumeDown", 0x81, 114, 0xE02E, Some(0x49)),
+    entry("ControlLeft", 0xE0, 29, 0x1D, Some(0x3B)),
+    entry("ShiftLeft", 0xE1, 42, 0x2A, Some(0x38)),
+    entry("AltLeft", 0xE2, 56, 0x38, Some(0x3A)), // Option on macOS
+    entry("MetaLeft", 0xE3, 125, 0xE05B, Some(0x37)), // Windows key or Command
+    entry("ControlRight", 0xE4, 97, 0xE01D, Some(0x3E)),
+    entry("ShiftRight", 0xE5, 54, 0x36, Some(0x3C)),
+    entry("AltRight", 0xE6, 100, 0xE038, Some(0x3D)), // Option on macOS
+    entry("MetaRight", 0xE7, 126, 0xE05C, Some(0x36)), // Windows key or Command
+];
+
+/// The entry of the key with the code in the scheme
+#[must_use]
+pub fn lookup(code: u16, scheme: Scheme) -> Option<&'static Entry> {
+    TABLE.iter().find(|entry| entry.code(scheme) == Some(code))
+}
+
+/// The entry of the key with the name (e.g. `KeyA`)
+#[must_use]
+pub fn lookup_name(name: &str) -> Option<&'static Entry> {
+    TABLE.iter().find(|entry| entry.name == name)
+}
+
+/// Convert the code of a key from one scheme to another. Returns `None` if the
+/// key is not part of the [`TABLE`] or does not exist in the target scheme
+#[must_use]
+pub fn convert(code: u16, from: Scheme, to: Scheme) -> Option<u16> {
+    lookup(code, from)?.code(to)
+}
+
+/// Physical position of a key on the keyboard, independent of the layout
+///
+/// [`Keyboard::raw`](crate::Keyboard::raw) takes a bare `u16` whose meaning
+/// differs per platform (an X11 keycode on Linux, a scan code with extra bits
+/// for extended keys on Windows and a virtual keycode on macOS). A `Scancode`
+/// is created from one of the common numbering schemes and
+/// [`Keyboard::physical`](crate::Keyboard::physical) converts it to the value
+/// the current platform expects.
+///
+/// The conversion between the schemes is done with the [`TABLE`]. A code that
+/// is not part of the table can only be used on the platform of its scheme.
+///
+/// ```no_run
+/// use enigo::{Direction, Enigo, Keyboard, Scancode, Settings};
+///
+/// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+/// // The key in the position of W on a QWERTY layout (Z on AZERTY)
+/// let w = Scancode::from_hid_usage(0x1A);
+/// enigo.physical(w, Direction::Click).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Scancode(Repr);
+
+/// Codes that are part of the table are stored as the HID usage, so the same
+/// key is equal regardless of the scheme it was created from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Repr {
+    Hid(u16),
+    Evdev(u16),
+    WindowsSet1(u16),
+    Mac(u16),
+}
+
+impl Scancode {
+    /// Create a scancode from a Linux evdev keycode (e.g. `KEY_A` = 30). It is
+    /// the X11 keycode minus 8
+    #[must_use]
+    pub fn from_evdev(code: u16) -> Self {
+        Self::normalize(code, Scheme::Evdev, Repr::Evdev)
+    }
+
+    /// Create a scancode from a scan code of set 1 the way Windows reports it
+    /// (e.g. in the `lParam` of `WM_KEYDOWN` or by `MapVirtualKeyExW`).
+    /// Extended keys have the prefix 0xE0 (e.g. 0xE01D for the right Control
+    /// key)
+    #[must_use]
+    pub fn from_windows_set1(code: u16) -> Self {
+        Self::normalize(code, Scheme::WindowsSet1, Repr::WindowsSet1)
+    }
+
+    /// Create a scancode from a usage of the keyboard page (0x07) of the USB
+    /// HID usage tables (e.g. 0x04 for A)
+    #[must_use]
+    pub fn from_hid_usage(usage: u16) -> Self {
+        Self(Repr::Hid(usage))
+    }
+
+    /// Create a scancode from a virtual keycode of macOS (e.g. `kVK_ANSI_A` =
+    /// 0x00)
+    #[must_use]
+    pub fn from_mac_keycode(code: u16) -> Self {
+        Self::normalize(code, Scheme::MacKeycode, Repr::Mac)
+    }
+
+    /// Store the code as the HID usage if it is part of the table
+    fn normalize(code: u16, scheme: Scheme, repr: fn(u16) -> Repr) -> Self {
+        match lookup(code, scheme) {
+            Some(entry) => Self(Repr::Hid(entry.hid_usage)),
+            None => Self(repr(code)),
+        }
+    }
+
+    /// The entry of the table of the key
+    fn entry(self) -> Option<&'static Entry> {
+        match self.0 {
+            Repr::Hid(usage) => lookup(usage, Scheme::HidUsage),
+            _ => None,
+        }
+    }
+
+    /// The Linux evdev keycode of the key
+    #[must_use]
+    pub fn to_evdev(self) -> Option<u16> {
+        match self.0 {
+            Repr::Evdev(code) => Some(code),
+            _ => self.entry().map(|entry| entry.evdev),
+        }
+    }
+
+    /// The scan code of set 1 the way Windows uses it. Extended keys have the
+    /// prefix 0xE0
+    #[must_use]
+    pub fn to_windows_set1(self) -> Option<u16> {
+        match self.0 {
+            Repr::WindowsSet1(code) => Some(code),
+            _ => self.entry().map(|entry| entry.windows_set1),
+        }
+    }
+
+    /// The usage of the keyboard page of the USB HID usage tables
+    #[must_use]
+    pub fn to_hid_usage(self) -> Option<u16> {
+        match self.0 {
+            Repr::Hid(usage) => Some(usage),
+            _ => None,
+        }
+    }
+
+    /// The virtual keycode of macOS
+    #[must_use]
+    pub fn to_mac_keycode(self) -> Option<u16> {
+        match self.0 {
+            Repr::Mac(code) => Some(code),
+            _ => self.entry().and_then(|entry| entry.mac_keycode),
+        }
+    }
+
+    /// The value [`Keyboard::raw`](crate::Keyboard::raw) expects on the
+    /// current platform
+    ///
+    /// # Errors
+    /// Returns an error if the key can't be converted to the scheme of the
+    /// platform
+    pub(crate) fn to_platform(self) -> InputResult<u16> {
+        #[cfg(all(unix, not(target_os = "macos")))]
+        // The keycodes of X11 and XKB are offset by 8
+        let code = self.to_evdev().and_then(|code| code.checked_add(8));
+        #[cfg(target_os = "windows")]
+        let code = self.to_windows_set1();
+        #[cfg(target_os = "macos")]
+        let code = self.to_mac_keycode();
+        code.ok_or(InputError::InvalidInput(
+            "the scancode has no equivalent on this platform",
+        ))
+    }
+}
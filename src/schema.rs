@@ -0,0 +1,167 @@
+//! JSON Schema of the serialized [`Token`](crate::agent::Token)s
+//!
+//! Clients of a remote agent that are not written in Rust can use the schema
+//! to validate their payloads before they send them. The schema describes the
+//! JSON representation that `serde_json` produces and accepts. Only the pinned
+//! tags are listed, the aliases that are additionally accepted when
+//! deserializing are not part of the schema.
+//!
+//! The variants of [`Key`] depend on the platform, so the schema only contains
+//! the keys that are available on the platform it was generated on. Generate
+//! it on the platform the agent runs on.
+//!
+//! ```
+//! let schema = enigo::schema::token_schema();
+//! println!("{schema}");
+//! ```
+
+use std::fmt::Write;
+
+use crate::Key;
+
+/// The JSON Schema dialect the generated schema conforms to
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Serialized names of the variants of [`crate::Button`]
+pub const BUTTON_TAGS: &[&str] = &[
+    "Left",
+    "Middle",
+    "Right",
+    "Back",
+    "Forward",
+    "ScrollUp",
+    "ScrollDown",
+    "ScrollLeft",
+    "ScrollRight",
+];
+
+/// Serialized names of the variants of [`crate::Direction`]
+pub const DIRECTION_TAGS: &[&str] = &["Press", "Release", "Click"];
+
+/// Serialized names of the variants of [`crate::Axis`]
+pub const AXIS_TAGS: &[&str] = &["Horizontal", "Vertical"];
+
+/// Serialized names of the variants of [`crate::Coordinate`]
+pub const COORDINATE_TAGS: &[&str] = &["Abs", "Rel"];
+
+/// Serialized names of the variants of [`crate::agent::Token`]
+pub const TOKEN_TAGS: &[&str] = &[
+    "Text",
+    "Key",
+    "Raw",
+    "Button",
+    "MoveMouse",
+    "Scroll",
+    "Location",
+    "MainDisplay",
+    "Sleep",
+    "WithModifiers",
+];
+
+/// Serialized names of the unit variants of [`Key`] that are available on
+/// the current platform
+#[must_use]
+pub fn key_tags() -> Vec<String> {
+    // The tags are pinned to the names of the variants, so the Debug output
+    // of the unit variants is the serialized name
+    Key::ALL.iter().map(|key| format!("{key:?}")).collect()
+}
+
+/// JSON Schema of a [`crate::agent::Token`]
+///
+/// The definitions of [`Key`], [`crate::Button`], [`crate::Direction`],
+/// [`crate::Axis`] and [`crate::Coordinate`] are included in `$defs` and can be
+/// referenced with e.g. `#/$defs/Key`.
+#[must_use]
+pub fn token_schema() -> String {
+    let int = r#"{"type":"integer"}"#;
+    let tokens = [
+        variant("Text", r#"{"type":"string"}"#),
+        variant(
+            "Key",
+            &tuple(&[r##"{"$ref":"#/$defs/Key"}"##], &[DIRECTION]),
+        ),
+        variant(
+            "Raw",
+            &tuple(
+                &[r#"{"type":"integer","minimum":0,"maximum":65535}"#],
+                &[DIRECTION],
+            ),
+        ),
+        variant(
+            "Button",
+            &tuple(&[r##"{"$ref":"#/$defs/Button"}"##], &[DIRECTION]),
+        ),
+        variant(
+            "MoveMouse",
+            &tuple(&[int, int], &[r##"{"$ref":"#/$defs/Coordinate"}"##]),
+        ),
+        variant("Scroll", &tuple(&[int], &[r##"{"$ref":"#/$defs/Axis"}"##])),
+        variant("Location", &tuple(&[int, int], &[])),
+        variant("MainDisplay", &tuple(&[int, int], &[])),
+        variant("Sleep", r#"{"type":"integer","minimum":0}"#),
+        variant(
+            "WithModifiers",
+            r##"{"type":"object","properties":{"modifiers":{"type":"array","items":{"$ref":"#/$defs/Key"}},"body":{"type":"array","items":{"$ref":"#/$defs/Token"}}},"required":["modifiers","body"],"additionalProperties":false}"##,
+        ),
+    ];
+
+    let mut schema = String::new();
+    let _ = write!(
+        schema,
+        r##"{{"$schema":"{DIALECT}","title":"Token","$ref":"#/$defs/Token","$defs":{{"Token":{{"oneOf":[{}]}},"Key":{},"Button":{},"Direction":{},"Axis":{},"Coordinate":{}}}}}"##,
+        tokens.join(","),
+        key_schema(),
+        string_enum(BUTTON_TAGS),
+        string_enum(DIRECTION_TAGS),
+        string_enum(AXIS_TAGS),
+        string_enum(COORDINATE_TAGS),
+    );
+    schema
+}
+
+const DIRECTION: &str = r##"{"$ref":"#/$defs/Direction"}"##;
+
+/// Schema of a [`Key`]. The unit variants are strings, `Unicode` and `Other`
+/// are externally tagged objects
+fn key_schema() -> String {
+    let tags = key_tags();
+    let tags: Vec<&str> = tags.iter().map(String::as_str).collect();
+    format!(
+        "{{\"oneOf\":[{},{},{}]}}",
+        string_enum(&tags),
+        variant(
+            "Unicode",
+            r#"{"type":"string","minLength":1,"maxLength":1}"#
+        ),
+        variant(
+            "Other",
+            r#"{"type":"integer","minimum":0,"maximum":4294967295}"#
+        ),
+    )
+}
+
+/// Schema of a unit-only enum
+fn string_enum(tags: &[&str]) -> String {
+    let tags: Vec<String> = tags.iter().map(|tag| format!("\"{tag}\"")).collect();
+    format!("{{\"type\":\"string\",\"enum\":[{}]}}", tags.join(","))
+}
+
+/// Schema of an externally tagged variant with content
+fn variant(tag: &str, content: &str) -> String {
+    format!(
+        "{{\"type\":\"object\",\"properties\":{{\"{tag}\":{content}}},\"required\":[\"{tag}\"],\"additionalProperties\":false}}"
+    )
+}
+
+/// Schema of a tuple variant. The optional fields have a default value and can
+/// be omitted at the end of the array
+fn tuple(required: &[&str], optional: &[&str]) -> String {
+    let items: Vec<&str> = required.iter().chain(optional).copied().collect();
+    format!(
+        "{{\"type\":\"array\",\"prefixItems\":[{}],\"minItems\":{},\"maxItems\":{}}}",
+        items.join(","),
+        required.len(),
+        items.len()
+    )
+}
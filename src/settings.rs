@@ -0,0 +1,417 @@
+//! Builder for the [`Settings`] and their validation
+//!
+//! Most of the settings only have an effect on one platform. The
+//! [`SettingsBuilder`] groups them in the sub-builders
+//! [`SettingsBuilder::windows`], [`SettingsBuilder::macos`] and
+//! [`SettingsBuilder::linux`], so it is obvious where an option applies.
+//! [`SettingsBuilder::build`] checks that the options can be used together.
+//! The built [`Settings`] are the same struct as before, so they can still be
+//! (de)serialized with the `serde` feature.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use enigo::{Settings, TapLocation, TextEntryMethod};
+//!
+//! let settings = Settings::builder()
+//!     .release_keys_when_dropped(false)
+//!     .windows(|w| w.text_entry_method(TextEntryMethod::AltNumpad))
+//!     .macos(|m| m.tap_location(TapLocation::Session))
+//!     .linux(|l| l.delay(0).connection_timeout(Duration::from_secs(5)))
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(settings.linux_delay, 0);
+//! ```
+
+use std::{error::Error, fmt, time::Duration};
+
+use log::warn;
+
+use crate::{
+    AbsoluteMode, ImeStrategy, Origin, RateLimitBehavior, RelativeMode, Settings, TapLocation,
+    TextEntryMethod, UnicodeKeyMode, UnmappableCharPolicy,
+};
+
+/// Error when the settings are invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SettingsError {
+    /// The two settings can't be used together
+    Conflict(&'static str, &'static str),
+    /// The setting has an invalid value. The second value is the reason
+    Invalid(&'static str, &'static str),
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Conflict(first, second) => {
+                write!(
+                    f,
+                    "the settings {first} and {second} can't be used together"
+                )
+            }
+            SettingsError::Invalid(setting, reason) => {
+                write!(f, "the setting {setting} is invalid: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for SettingsError {}
+
+impl Settings {
+    /// Create a builder that starts with the default settings
+    #[must_use]
+    pub fn builder() -> SettingsBuilder {
+        SettingsBuilder::default()
+    }
+
+    /// Check that the options can be used together and have valid values.
+    /// This is useful for settings that were deserialized
+    ///
+    /// # Errors
+    /// Returns a `SettingsError::Conflict` if two options exclude each other
+    /// and a `SettingsError::Invalid` if an option has an invalid value
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.max_events_per_second == Some(0) {
+            return Err(SettingsError::Invalid(
+                "max_events_per_second",
+                "it must be greater than 0",
+            ));
+        }
+        if self.max_events_per_second.is_none()
+            && self.rate_limit_behavior != RateLimitBehavior::default()
+        {
+            return Err(SettingsError::Invalid(
+                "rate_limit_behavior",
+                "it has no effect without max_events_per_second",
+            ));
+        }
+        if self.double_click_interval == Some(Duration::ZERO) {
+            return Err(SettingsError::Invalid(
+                "double_click_interval",
+                "it must be greater than 0",
+            ));
+        }
+        if self.connection_timeout == Some(Duration::ZERO) {
+            return Err(SettingsError::Invalid(
+                "connection_timeout",
+                "it must be greater than 0",
+            ));
+        }
+        // Relative moves that are subject to the mouse speed are not moved by
+        // the exact number of pixels
+        if self.windows_subject_to_mouse_speed_and_acceleration_level
+            && self.relative_mode == RelativeMode::Precise
+        {
+            return Err(SettingsError::Conflict(
+                "windows_subject_to_mouse_speed_and_acceleration_level",
+                "relative_mode: RelativeMode::Precise",
+            ));
+        }
+        // Both decide how the characters are sent to the window
+        if self.windows_ime_strategy == ImeStrategy::ImeChar
+            && self.windows_text_entry_method == TextEntryMethod::AltNumpad
+        {
+            return Err(SettingsError::Conflict(
+                "windows_ime_strategy: ImeStrategy::ImeChar",
+                "windows_text_entry_method: TextEntryMethod::AltNumpad",
+            ));
+        }
+        if let Some(klid) = &self.windows_keyboard_layout {
+            if klid.len() != 8 || !klid.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(SettingsError::Invalid(
+                    "windows_keyboard_layout",
+                    "it must be a KLID of 8 hexadecimal digits (e.g. \"00000407\")",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builder for the [`Settings`]. The options that only apply to one platform
+/// are set with the sub-builders
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsBuilder {
+    settings: Settings,
+}
+
+impl From<Settings> for SettingsBuilder {
+    fn from(settings: Settings) -> Self {
+        Self { settings }
+    }
+}
+
+impl SettingsBuilder {
+    /// Set `Settings::release_keys_when_dropped`
+    #[must_use]
+    pub fn release_keys_when_dropped(mut self, release: bool) -> Self {
+        self.settings.release_keys_when_dropped = release;
+        self
+    }
+
+    /// Set `Settings::unicode_key_mode`
+    #[must_use]
+    pub fn unicode_key_mode(mut self, mode: UnicodeKeyMode) -> Self {
+        self.settings.unicode_key_mode = mode;
+        self
+    }
+
+    /// Set `Settings::unmappable_char_policy`
+    #[must_use]
+    pub fn unmappable_char_policy(mut self, policy: UnmappableCharPolicy) -> Self {
+        self.settings.unmappable_char_policy = policy;
+        self
+    }
+
+    /// Set `Settings::relative_mode`
+    #[must_use]
+    pub fn relative_mode(mut self, mode: RelativeMode) -> Self {
+        self.settings.relative_mode = mode;
+        self
+    }
+
+    /// Set `Settings::absolute_mode`
+    #[must_use]
+    pub fn absolute_mode(mut self, mode: AbsoluteMode) -> Self {
+        self.settings.absolute_mode = mode;
+        self
+    }
+
+    /// Set `Settings::origin`
+    #[must_use]
+    pub fn origin(mut self, origin: Origin) -> Self {
+        self.settings.origin = origin;
+        self
+    }
+
+    /// Set `Settings::double_click_interval`
+    #[must_use]
+    pub fn double_click_interval(mut self, interval: Duration) -> Self {
+        self.settings.double_click_interval = Some(interval);
+        self
+    }
+
+    /// Set `Settings::respect_button_swap`
+    #[must_use]
+    pub fn respect_button_swap(mut self, respect: bool) -> Self {
+        self.settings.respect_button_swap = respect;
+        self
+    }
+
+    /// Set `Settings::max_events_per_second` and
+    /// `Settings::rate_limit_behavior`
+    #[must_use]
+    pub fn rate_limit(mut self, max_events_per_second: u32, behavior: RateLimitBehavior) -> Self {
+        self.settings.max_events_per_second = Some(max_events_per_second);
+        self.settings.rate_limit_behavior = behavior;
+        self
+    }
+
+    /// Set `Settings::focus_guard`
+    #[must_use]
+    pub fn focus_guard(mut self, guard: bool) -> Self {
+        self.settings.focus_guard = guard;
+        self
+    }
+
+    /// Set the options that only apply to Windows
+    #[must_use]
+    pub fn windows(
+        mut self,
+        f: impl FnOnce(WindowsSettingsBuilder) -> WindowsSettingsBuilder,
+    ) -> Self {
+        self.settings = f(WindowsSettingsBuilder {
+            settings: self.settings,
+        })
+        .settings;
+        self
+    }
+
+    /// Set the options that only apply to macOS
+    #[must_use]
+    pub fn macos(mut self, f: impl FnOnce(MacosSettingsBuilder) -> MacosSettingsBuilder) -> Self {
+        self.settings = f(MacosSettingsBuilder {
+            settings: self.settings,
+        })
+        .settings;
+        self
+    }
+
+    /// Set the options that only apply to Linux
+    #[must_use]
+    pub fn linux(mut self, f: impl FnOnce(LinuxSettingsBuilder) -> LinuxSettingsBuilder) -> Self {
+        self.settings = f(LinuxSettingsBuilder {
+            settings: self.settings,
+        })
+        .settings;
+        self
+    }
+
+    /// Validate the options and return the settings
+    ///
+    /// # Errors
+    /// Have a look at `Settings::validate`
+    pub fn build(self) -> Result<Settings, SettingsError> {
+        self.settings.validate()?;
+        Ok(self.settings)
+    }
+}
+
+/// Sub-builder for the options that only apply to Windows. Have a look at
+/// `SettingsBuilder::windows`
+#[derive(Debug)]
+pub struct WindowsSettingsBuilder {
+    settings: Settings,
+}
+
+impl WindowsSettingsBuilder {
+    /// Set `Settings::windows_dw_extra_info`
+    #[must_use]
+    pub fn dw_extra_info(mut self, marker: usize) -> Self {
+        self.settings.windows_dw_extra_info = Some(marker);
+        self
+    }
+
+    /// Set `Settings::windows_subject_to_mouse_speed_and_acceleration_level`
+    #[must_use]
+    pub fn subject_to_mouse_speed_and_acceleration_level(mut self, subject: bool) -> Self {
+        self.settings
+            .windows_subject_to_mouse_speed_and_acceleration_level = subject;
+        self
+    }
+
+    /// Set `Settings::windows_text_entry_method`
+    #[must_use]
+    pub fn text_entry_method(mut self, method: TextEntryMethod) -> Self {
+        self.settings.windows_text_entry_method = method;
+        self
+    }
+
+    /// Set `Settings::windows_keyboard_layout`
+    #[must_use]
+    pub fn keyboard_layout(mut self, klid: impl Into<String>) -> Self {
+        self.settings.windows_keyboard_layout = Some(klid.into());
+        self
+    }
+
+    /// Set `Settings::windows_ime_strategy`
+    #[must_use]
+    pub fn ime_strategy(mut self, strategy: ImeStrategy) -> Self {
+        self.settings.windows_ime_strategy = strategy;
+        self
+    }
+}
+
+/// Sub-builder for the options that only apply to macOS. Have a look at
+/// `SettingsBuilder::macos`
+#[derive(Debug)]
+pub struct MacosSettingsBuilder {
+    settings: Settings,
+}
+
+impl MacosSettingsBuilder {
+    /// Set `Settings::event_source_user_data`
+    #[must_use]
+    pub fn event_source_user_data(mut self, marker: i64) -> Self {
+        self.settings.event_source_user_data = Some(marker);
+        self
+    }
+
+    /// Set `Settings::open_prompt_to_get_permissions`
+    #[must_use]
+    pub fn open_prompt_to_get_permissions(mut self, open: bool) -> Self {
+        self.settings.open_prompt_to_get_permissions = open;
+        self
+    }
+
+    /// Set `Settings::independent_of_keyboard_state`
+    #[must_use]
+    pub fn independent_of_keyboard_state(mut self, independent: bool) -> Self {
+        self.settings.independent_of_keyboard_state = independent;
+        self
+    }
+
+    /// Set `Settings::macos_tap_location`
+    #[must_use]
+    pub fn tap_location(mut self, location: TapLocation) -> Self {
+        self.settings.macos_tap_location = location;
+        self
+    }
+}
+
+/// Sub-builder for the options that only apply to Linux. Have a look at
+/// `SettingsBuilder::linux`
+#[derive(Debug)]
+pub struct LinuxSettingsBuilder {
+    settings: Settings,
+}
+
+impl LinuxSettingsBuilder {
+    /// Set `Settings::linux_delay`
+    #[must_use]
+    pub fn delay(mut self, delay: u32) -> Self {
+        self.settings.linux_delay = delay;
+        self
+    }
+
+    /// Set `Settings::x11_display`
+    #[must_use]
+    pub fn x11_display(mut self, name: impl Into<String>) -> Self {
+        self.settings.x11_display = Some(name.into());
+        self
+    }
+
+    /// Set `Settings::wayland_display`
+    #[must_use]
+    pub fn wayland_display(mut self, name: impl Into<String>) -> Self {
+        self.settings.wayland_display = Some(name.into());
+        self
+    }
+
+    /// Set `Settings::libei_seat`
+    #[must_use]
+    pub fn libei_seat(mut self, name: impl Into<String>) -> Self {
+        self.settings.libei_seat = Some(name.into());
+        self
+    }
+
+    /// Set `Settings::libei_device`
+    #[must_use]
+    pub fn libei_device(mut self, name: impl Into<String>) -> Self {
+        self.settings.libei_device = Some(name.into());
+        self
+    }
+
+    /// Set `Settings::linux_emulate_key_repeat`
+    #[must_use]
+    pub fn emulate_key_repeat(mut self, emulate: bool) -> Self {
+        self.settings.linux_emulate_key_repeat = emulate;
+        self
+    }
+
+    /// Set `Settings::connection_timeout`
+    #[must_use]
+    pub fn connection_timeout(mut self, timeout: Duration) -> Self {
+        self.settings.connection_timeout = Some(timeout);
+        self
+    }
+
+    /// Set `Settings::lazy_connect`
+    #[must_use]
+    pub fn lazy_connect(mut self, lazy: bool) -> Self {
+        self.settings.lazy_connect = lazy;
+        self
+    }
+}
+
+/// Log a warning for each of the options that differ, but can only be set
+/// when the Enigo struct is created. Used by `Enigo::update_settings`
+pub(crate) fn warn_unchangeable(options: &[(&str, bool)]) {
+    for (name, changed) in options {
+        if *changed {
+            warn!("Settings::{name} can only be changed by creating a new Enigo struct. The change is ignored");
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Stub for the platforms that are not supported. It lets the crate compile so
+//! that it can be a dependency of cross-platform applications, but the
+//! [`Enigo`] struct can never be constructed
+
+use crate::{Capability, InputError, InputResult, Keyboard, Mouse, NewConError, Settings};
+
+// Enum without any variants
+// This can never get constructed
+// See https://github.com/enigo-rs/enigo/pull/269 for more details
+enum Never {}
+
+pub struct Enigo {
+    never: Never,
+    pub(crate) focus_guard: bool,
+}
+
+impl Enigo {
+    /// Always returns an error, because the platform is not supported
+    ///
+    /// # Errors
+    /// Always returns a [`NewConError::EstablishCon`]
+    pub fn new(_: &Settings) -> Result<Self, NewConError> {
+        Err(NewConError::EstablishCon("the platform is not supported"))
+    }
+
+    pub(crate) fn active_window(&self) -> InputResult<u64> {
+        match self.never {}
+    }
+}
+
+impl Mouse for Enigo {
+    fn button(&mut self, _: crate::Button, _: crate::Direction) -> InputResult<()> {
+        match self.never {}
+    }
+
+    fn move_mouse(&mut self, _: i32, _: i32, _: crate::Coordinate) -> InputResult<()> {
+        match self.never {}
+    }
+
+    fn scroll(&mut self, _: i32, _: crate::Axis) -> InputResult<()> {
+        match self.never {}
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        match self.never {}
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        match self.never {}
+    }
+}
+
+impl Keyboard for Enigo {
+    fn fast_text(&mut self, _: &str) -> InputResult<Option<()>> {
+        match self.never {}
+    }
+
+    fn key(&mut self, _: crate::Key, _: crate::Direction) -> InputResult<()> {
+        match self.never {}
+    }
+
+    fn raw(&mut self, _: u16, _: crate::Direction) -> InputResult<()> {
+        match self.never {}
+    }
+}
+
+/// Error for everything the stub can't do
+fn not_supported(capability: Capability) -> InputError {
+    InputError::NotSupported {
+        capability,
+        backend: "stub",
+    }
+}
+
+impl Drop for Enigo {
+    fn drop(&mut self) {
+        match self.never {}
+    }
+}
+
+#[cfg(feature = "a11y")]
+pub(crate) mod a11y {
+    use crate::{a11y::FocusedElement, Capability, InputResult};
+
+    pub(crate) fn focused_element() -> InputResult<FocusedElement> {
+        Err(super::not_supported(Capability::Accessibility))
+    }
+}
+
+pub(crate) mod health {
+    use crate::health::HealthIssue;
+
+    pub(crate) fn issues() -> Vec<HealthIssue> {
+        vec![]
+    }
+}
+
+pub(crate) mod keep_awake {
+    use crate::{Capability, InputResult};
+
+    #[derive(Debug)]
+    pub(crate) struct Inhibitor;
+
+    impl Inhibitor {
+        pub(crate) fn new(_reason: &str) -> InputResult<Self> {
+            Err(super::not_supported(Capability::KeepAwake))
+        }
+    }
+}
+
+pub(crate) mod system {
+    use std::time::Duration;
+
+    use super::not_supported;
+    use crate::{Capability, InputResult};
+
+    pub(crate) fn mouse_speed() -> InputResult<f64> {
+        Err(not_supported(Capability::MouseSpeed))
+    }
+
+    pub(crate) fn mouse_acceleration_enabled() -> InputResult<bool> {
+        Err(not_supported(Capability::MouseAcceleration))
+    }
+
+    pub(crate) fn double_click_interval() -> InputResult<Duration> {
+        Err(not_supported(Capability::DoubleClickInterval))
+    }
+
+    pub(crate) fn screen_dpi() -> InputResult<f64> {
+        Err(not_supported(Capability::ScreenDpi))
+    }
+
+    pub(crate) fn idle_time() -> InputResult<Duration> {
+        Err(not_supported(Capability::IdleTime))
+    }
+}
@@ -0,0 +1,80 @@
+//! Settings of the system that influence how the simulated input is
+//! interpreted
+//!
+//! The functions are available on all platforms so cross-platform code does
+//! not need any `cfg` attributes. If a platform does not have a setting, an
+//! [`InputError::NotSupported`](crate::InputError::NotSupported) is returned.
+//!
+//! ```no_run
+//! use enigo::system;
+//!
+//! match system::double_click_interval() {
+//!     Ok(interval) => println!("double click within {interval:?}"),
+//!     Err(e) => println!("unknown double click interval: {e}"),
+//! }
+//! ```
+
+use std::time::Duration;
+
+use crate::{os, InputResult};
+
+/// Speed of the mouse pointer in the unit of the platform
+///
+/// - Windows: 1 (slowest) to 20 (fastest). At 10 the motion is not modified
+/// - macOS: The tracking speed from 0 (slowest) to 3 (fastest)
+/// - Linux: Not supported
+///
+/// # Errors
+/// Returns an error if the setting could not be read or the platform does not
+/// support it
+pub fn mouse_speed() -> InputResult<f64> {
+    os::system::mouse_speed()
+}
+
+/// Returns true if the system accelerates the mouse pointer, so the distance
+/// it moves depends on how fast the mouse is moved
+///
+/// # Errors
+/// Returns an error if the setting could not be read or the platform does not
+/// support it (Linux)
+pub fn mouse_acceleration_enabled() -> InputResult<bool> {
+    os::system::mouse_acceleration_enabled()
+}
+
+/// Maximum interval between two clicks so they count as a double click
+///
+/// # Errors
+/// Returns an error if the setting could not be read or the platform does not
+/// support it (Linux)
+pub fn double_click_interval() -> InputResult<Duration> {
+    os::system::double_click_interval()
+}
+
+/// Dots per inch of the main display. On Windows this is the DPI of the
+/// system (96 at a scale of 100 %) and on macOS it is calculated from the
+/// physical size of the display
+///
+/// # Errors
+/// Returns an error if the DPI could not be determined or the platform does
+/// not support it (Linux)
+pub fn screen_dpi() -> InputResult<f64> {
+    os::system::screen_dpi()
+}
+
+/// Time since the last input of the user. Automation can wait until it is
+/// long enough before it takes over the pointer
+///
+/// - Windows: Time since the last input of the session (`GetLastInputInfo`).
+///   Simulated input counts as input too
+/// - macOS: Time since the last event of a hardware device, so simulated
+///   input is ignored
+/// - Linux: On X11 the time since the last input according to the
+///   `MIT-SCREEN-SAVER` extension (requires the `x11rb` feature). Otherwise
+///   logind is asked for the time since the session became idle. It is zero
+///   until the desktop environment considers the session idle
+///
+/// # Errors
+/// Returns an error if the idle time could not be determined
+pub fn idle_time() -> InputResult<Duration> {
+    os::system::idle_time()
+}
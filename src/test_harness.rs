@@ -0,0 +1,355 @@
+//! End-to-end tests of simulated input with a browser
+//!
+//! The [`PAGE`] is opened in a browser. It connects to a WebSocket and reports
+//! every input event it receives as a [`BrowserEvent`]. The
+//! [`BrowserEventStream`] accepts the connection and offers typed assertions,
+//! so you can check that the input you simulated actually arrived in an
+//! application. Enigo uses it for its own integration tests, but it is useful
+//! for any crate that automates user interfaces.
+//!
+//! The harness needs a display and a browser, so it does not work in headless
+//! environments. The browser has to have the focus for the whole test.
+//!
+//! ```no_run
+//! use enigo::{
+//!     test_harness::{launch_firefox, BrowserEventStream, DEFAULT_ADDRESS},
+//!     Button, Coordinate, Direction, Enigo, Key, Keyboard, Mouse, Settings,
+//! };
+//!
+//! let _browser = launch_firefox().unwrap();
+//! let mut events = BrowserEventStream::listen(DEFAULT_ADDRESS).unwrap();
+//! let mut enigo = Enigo::new(&Settings::default()).unwrap();
+//!
+//! enigo.key(Key::Unicode('a'), Direction::Click).unwrap();
+//! events.expect_key_down("KeyA");
+//! events.expect_key_up("KeyA");
+//!
+//! enigo.move_mouse(200, 200, Coordinate::Abs).unwrap();
+//! events.expect_mouse_at(200, 200, 1);
+//!
+//! enigo.button(Button::Left, Direction::Click).unwrap();
+//! events.expect_mouse_down(Button::Left);
+//! events.expect_mouse_up(Button::Left);
+//! ```
+
+use std::{
+    fmt,
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    process::{Child, Command},
+    time::Duration,
+};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use tungstenite::{Message, Utf8Bytes, WebSocket};
+
+use crate::Button;
+
+/// Address the [`PAGE`] connects to
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:26541";
+
+/// Web page that reports the input events it receives to the
+/// [`BrowserEventStream`] listening on the [`DEFAULT_ADDRESS`]
+pub const PAGE: &str = include_str!("../tests/index.html");
+
+/// Input event that was received by the browser
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BrowserEvent {
+    /// The text field was cleared and has the focus
+    ReadyForText,
+    /// Content of the text field
+    Text(String),
+    /// A key was pressed. Contains the `key` and the `code` of the
+    /// `KeyboardEvent` (e.g. "a" and "`KeyA`")
+    KeyDown(String, String),
+    /// A key was released. Contains the `key` and the `code` of the
+    /// `KeyboardEvent` (e.g. "a" and "`KeyA`")
+    KeyUp(String, String),
+    /// A mouse button was pressed. Contains the `button` of the `MouseEvent`
+    MouseDown(u32),
+    /// A mouse button was released. Contains the `button` of the `MouseEvent`
+    MouseUp(u32),
+    /// The mouse was moved. Contains the relative movement and the absolute
+    /// position on the screen
+    MouseMove((i32, i32), (i32, i32)),
+    /// The mouse wheel was scrolled. Contains the horizontal and vertical
+    /// delta
+    MouseScroll(i32, i32),
+    /// The WebSocket was opened
+    Open,
+    /// The WebSocket was closed
+    Close,
+}
+
+/// Error when receiving a [`BrowserEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BrowserEventError {
+    /// The message was not a text or close message
+    UnknownMessageType,
+    /// The text of the message is not a [`BrowserEvent`]
+    ParseError,
+}
+
+impl fmt::Display for BrowserEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrowserEventError::UnknownMessageType => write!(f, "unknown type of message"),
+            BrowserEventError::ParseError => write!(f, "the message is not a browser event"),
+        }
+    }
+}
+
+impl std::error::Error for BrowserEventError {}
+
+impl TryFrom<Message> for BrowserEvent {
+    type Error = BrowserEventError;
+
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        match message {
+            Message::Close(_) => Ok(BrowserEvent::Close),
+            Message::Text(msg) => {
+                ron::from_str::<BrowserEvent>(&msg).map_err(|_| BrowserEventError::ParseError)
+            }
+            _ => Err(BrowserEventError::UnknownMessageType),
+        }
+    }
+}
+
+/// Error of the [`BrowserEventStream`]
+#[derive(Debug)]
+pub enum HarnessError {
+    /// The connection could not be established
+    Io(std::io::Error),
+    /// Error of the WebSocket (e.g. the timeout was reached)
+    WebSocket(Box<tungstenite::Error>),
+    /// The message could not be parsed
+    Event(BrowserEventError),
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HarnessError::Io(e) => write!(f, "unable to connect: {e}"),
+            HarnessError::WebSocket(e) => write!(f, "websocket error: {e}"),
+            HarnessError::Event(e) => write!(f, "invalid event: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for HarnessError {}
+
+impl From<tungstenite::Error> for HarnessError {
+    fn from(e: tungstenite::Error) -> Self {
+        HarnessError::WebSocket(Box::new(e))
+    }
+}
+
+/// Stream of the [`BrowserEvent`]s the [`PAGE`] sends
+pub struct BrowserEventStream {
+    websocket: WebSocket<TcpStream>,
+}
+
+impl fmt::Debug for BrowserEventStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BrowserEventStream").finish_non_exhaustive()
+    }
+}
+
+impl BrowserEventStream {
+    /// Wait for the page to connect. Reading an event times out after 5
+    /// seconds
+    ///
+    /// # Errors
+    /// Returns an error if nothing could listen on the address or the WebSocket
+    /// handshake failed
+    pub fn listen(address: impl ToSocketAddrs) -> Result<Self, HarnessError> {
+        let listener = TcpListener::bind(address).map_err(HarnessError::Io)?;
+        let (stream, addr) = listener.accept().map_err(HarnessError::Io)?;
+        debug!("new connection from {addr:?}");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .map_err(HarnessError::Io)?;
+        let websocket = tungstenite::accept(stream).map_err(|e| match e {
+            tungstenite::HandshakeError::Failure(e) => HarnessError::from(e),
+            tungstenite::HandshakeError::Interrupted(_) => {
+                HarnessError::Io(std::io::Error::from(std::io::ErrorKind::WouldBlock))
+            }
+        })?;
+        Ok(Self { websocket })
+    }
+
+    /// Change how long reading an event may block. `None` blocks forever
+    ///
+    /// # Errors
+    /// Returns an error if the timeout could not be set
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), HarnessError> {
+        self.websocket
+            .get_mut()
+            .set_read_timeout(timeout)
+            .map_err(HarnessError::Io)
+    }
+
+    /// Send a command to the page ("`ClearText`" or "`GetText`")
+    ///
+    /// # Errors
+    /// Returns an error if the message could not be sent
+    pub fn send(&mut self, command: &str) -> Result<(), HarnessError> {
+        debug!("sending {command}");
+        self.websocket
+            .send(Message::Text(Utf8Bytes::from(command)))
+            .map_err(HarnessError::from)
+    }
+
+    /// Wait for the next event
+    ///
+    /// # Errors
+    /// Returns an error if no event was received before the timeout, the
+    /// connection was lost or the message is not a [`BrowserEvent`]
+    pub fn next_event(&mut self) -> Result<BrowserEvent, HarnessError> {
+        let message = self.websocket.read().map_err(HarnessError::from)?;
+        let event = BrowserEvent::try_from(message).map_err(HarnessError::Event)?;
+        debug!("received {event:?}");
+        Ok(event)
+    }
+
+    /// Wait for the next event and panic if there is none
+    ///
+    /// # Panics
+    /// Panics if there was an error or the page closed the connection
+    pub fn expect_event(&mut self) -> BrowserEvent {
+        match self.next_event() {
+            Ok(BrowserEvent::Close) => panic!("the page closed the connection"),
+            Ok(event) => event,
+            Err(e) => panic!("no browser event received: {e}"),
+        }
+    }
+
+    /// Assert that the next event is the press of the key with the `code`
+    /// (e.g. "`KeyA`" or "`ShiftLeft`")
+    ///
+    /// # Panics
+    /// Panics if the next event is a different one
+    pub fn expect_key_down(&mut self, code: &str) {
+        match self.expect_event() {
+            BrowserEvent::KeyDown(_, received) if received == code => {}
+            event => panic!("expected KeyDown of {code}, received {event:?}"),
+        }
+    }
+
+    /// Assert that the next event is the release of the key with the `code`
+    /// (e.g. "`KeyA`" or "`ShiftLeft`")
+    ///
+    /// # Panics
+    /// Panics if the next event is a different one
+    pub fn expect_key_up(&mut self, code: &str) {
+        match self.expect_event() {
+            BrowserEvent::KeyUp(_, received) if received == code => {}
+            event => panic!("expected KeyUp of {code}, received {event:?}"),
+        }
+    }
+
+    /// Assert that the next event is the press of the mouse button
+    ///
+    /// # Panics
+    /// Panics if the next event is a different one
+    pub fn expect_mouse_down(&mut self, button: Button) {
+        match self.expect_event() {
+            BrowserEvent::MouseDown(received) if received == button as u32 => {}
+            event => panic!("expected MouseDown of {button:?}, received {event:?}"),
+        }
+    }
+
+    /// Assert that the next event is the release of the mouse button
+    ///
+    /// # Panics
+    /// Panics if the next event is a different one
+    pub fn expect_mouse_up(&mut self, button: Button) {
+        match self.expect_event() {
+            BrowserEvent::MouseUp(received) if received == button as u32 => {}
+            event => panic!("expected MouseUp of {button:?}, received {event:?}"),
+        }
+    }
+
+    /// Assert that the next event moved the mouse to the absolute position.
+    /// Each coordinate may be off by at most `tolerance` pixels
+    ///
+    /// # Panics
+    /// Panics if the next event is a different one or the position is too far
+    /// off
+    pub fn expect_mouse_at(&mut self, x: i32, y: i32, tolerance: i32) {
+        match self.expect_event() {
+            BrowserEvent::MouseMove(_, (rx, ry))
+                if (rx - x).abs() <= tolerance && (ry - y).abs() <= tolerance => {}
+            event => panic!("expected MouseMove to ({x}, {y}), received {event:?}"),
+        }
+    }
+
+    /// Assert that the next event moved the mouse relatively. Each coordinate
+    /// may be off by at most `tolerance` pixels
+    ///
+    /// # Panics
+    /// Panics if the next event is a different one or the movement is too far
+    /// off
+    pub fn expect_mouse_moved_by(&mut self, x: i32, y: i32, tolerance: i32) {
+        match self.expect_event() {
+            BrowserEvent::MouseMove((rx, ry), _)
+                if (rx - x).abs() <= tolerance && (ry - y).abs() <= tolerance => {}
+            event => panic!("expected MouseMove by ({x}, {y}), received {event:?}"),
+        }
+    }
+
+    /// Clear the text field of the page and focus it
+    ///
+    /// # Panics
+    /// Panics if the page did not confirm it
+    pub fn clear_text(&mut self) {
+        if let Err(e) = self.send("ClearText") {
+            panic!("unable to clear the text: {e}");
+        }
+        match self.expect_event() {
+            BrowserEvent::ReadyForText => {}
+            event => panic!("expected ReadyForText, received {event:?}"),
+        }
+    }
+
+    /// Assert that the text field of the page contains the text
+    ///
+    /// # Panics
+    /// Panics if the text is different
+    pub fn expect_text(&mut self, text: &str) {
+        if let Err(e) = self.send("GetText") {
+            panic!("unable to request the text: {e}");
+        }
+        match self.expect_event() {
+            BrowserEvent::Text(received) => assert_eq!(text, received),
+            event => panic!("expected Text, received {event:?}"),
+        }
+    }
+}
+
+/// Write the [`PAGE`] to the temporary directory and open it with Firefox in
+/// kiosk mode (full screen and can't be closed with F11)
+///
+/// # Errors
+/// Returns an error if the page could not be written or Firefox could not be
+/// started
+pub fn launch_firefox() -> std::io::Result<Child> {
+    let path: PathBuf = std::env::temp_dir().join("enigo_test_harness.html");
+    std::fs::write(&path, PAGE)?;
+    let url = format!("file://{}", path.display());
+
+    if cfg!(target_os = "windows") {
+        // On Windows, use cmd.exe to run the "start" command
+        Command::new("cmd")
+            .args(["/C", "start", "firefox", "--kiosk", &url])
+            .spawn()
+    } else if cfg!(target_os = "macos") {
+        // On macOS, use the "open" command to run Firefox
+        Command::new("open")
+            .args(["-a", "Firefox", "--args", "--kiosk", &url])
+            .spawn()
+    } else {
+        Command::new("firefox").args(["--kiosk", &url]).spawn()
+    }
+}
@@ -0,0 +1,304 @@
+//! Predict the text that simulated input produces without simulating anything
+//!
+//! [`TestKeyboard`] implements the [`Keyboard`] trait, so macros can be tested
+//! in unit tests on every platform. On Linux it can also predict the text with
+//! an xkb keymap.
+
+use crate::{Direction, InputError, InputResult, Key, Keyboard};
+
+/// Precomposed characters of the combining marks that act as dead keys. Each
+/// string contains pairs of a base character and its composition
+const COMPOSITIONS: [(char, &str); 8] = [
+    ('\u{300}', "aàeèiìoòuùAÀEÈIÌOÒUÙ"),
+    ('\u{301}', "aáeéiíoóuúyýcćnńsśzźAÁEÉIÍOÓUÚYÝCĆNŃSŚZŹ"),
+    ('\u{302}', "aâeêiîoôuûAÂEÊIÎOÔUÛ"),
+    ('\u{303}', "aãnñoõAÃNÑOÕ"),
+    ('\u{308}', "aäeëiïoöuüyÿAÄEËIÏOÖUÜYŸ"),
+    ('\u{30A}', "aåuůAÅUŮ"),
+    ('\u{30C}', "cčeěnňrřsšzžCČEĚNŇRŘSŠZŽ"),
+    ('\u{327}', "cçsşCÇSŞ"),
+];
+
+/// Returns the character the dead key produces together with the base
+/// character. Bases without a precomposed character are followed by the
+/// combining mark
+fn compose(mark: char, base: char) -> String {
+    let composed = COMPOSITIONS
+        .iter()
+        .find(|(m, _)| *m == mark)
+        .and_then(|(_, pairs)| {
+            let pairs: Vec<char> = pairs.chars().collect();
+            pairs
+                .chunks(2)
+                .find(|pair| pair[0] == base)
+                .map(|pair| pair[1])
+        });
+    match composed {
+        Some(c) => c.to_string(),
+        None => [base, mark].iter().collect(),
+    }
+}
+
+/// Returns true if the character is a combining diacritical mark
+fn is_dead(c: char) -> bool {
+    ('\u{300}'..='\u{36F}').contains(&c)
+}
+
+/// Model of a keyboard that does not depend on a layout
+#[derive(Debug, Default)]
+struct Generic {
+    /// Held Shift keys
+    shift: Vec<Key>,
+    /// Held Control, Alt and Meta keys
+    shortcut: Vec<Key>,
+    caps_lock: bool,
+    /// Combining mark of the dead key that waits for the next character
+    dead: Option<char>,
+}
+
+impl Generic {
+    /// Returns the text the character produces and combines it with a
+    /// pending dead key
+    fn character(&mut self, c: char) -> String {
+        if is_dead(c) {
+            // A second dead key releases the first one
+            return self.dead.replace(c).map(String::from).unwrap_or_default();
+        }
+        match self.dead.take() {
+            Some(mark) => compose(mark, c),
+            None => c.to_string(),
+        }
+    }
+
+    /// Returns the text a character of `Keyboard::text` produces. Combining
+    /// marks follow their base character in text, so they are not dead keys
+    fn text_character(&mut self, c: char) -> String {
+        if is_dead(c) {
+            let mut text: String = self.dead.take().map(String::from).unwrap_or_default();
+            text.push(c);
+            return text;
+        }
+        self.character(c)
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> String {
+        let press = direction == Direction::Press || direction == Direction::Click;
+        let held = match key {
+            Key::Shift | Key::LShift | Key::RShift => Some(&mut self.shift),
+            Key::Control | Key::LControl | Key::RControl | Key::Alt | Key::Meta => {
+                Some(&mut self.shortcut)
+            }
+            _ => None,
+        };
+        if let Some(held) = held {
+            if direction == Direction::Press && !held.contains(&key) {
+                held.push(key);
+            } else if direction == Direction::Release {
+                held.retain(|k| *k != key);
+            }
+            return String::new();
+        }
+        if key == Key::CapsLock && press {
+            self.caps_lock = !self.caps_lock;
+        }
+        // Applications interpret keys as shortcuts while these modifiers are
+        // held
+        if !press || !self.shortcut.is_empty() {
+            return String::new();
+        }
+        match key {
+            Key::Unicode(c) if c.is_lowercase() && self.shift.is_empty() == self.caps_lock => {
+                c.to_uppercase().map(|c| self.character(c)).collect()
+            }
+            Key::Unicode(c) => self.character(c),
+            Key::Return => self.character('\n'),
+            Key::Tab => self.character('\t'),
+            Key::Space => self.character(' '),
+            Key::Backspace => {
+                // Backspace cancels a pending dead key
+                if self.dead.take().is_some() {
+                    String::new()
+                } else {
+                    '\u{8}'.to_string()
+                }
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+enum Model {
+    Generic(Generic),
+    #[cfg(all(unix, not(target_os = "macos")))]
+    Xkb(crate::os::xkb_keymap::Predictor),
+}
+
+/// Predicts the text a sequence of simulated input produces without
+/// simulating anything
+///
+/// It implements the [`Keyboard`] trait, so it can be used in place of an
+/// `Enigo` struct (e.g. to execute [`crate::agent::Token`]s).
+///
+/// [`TestKeyboard::new`] works on all platforms and does not depend on a
+/// layout. Every `Key::Unicode` produces its character. Held Shift keys and
+/// Caps Lock change the case of letters and no text is produced while
+/// Control, Alt or Meta are held, because applications interpret the keys as
+/// shortcuts. Combining diacritical marks (e.g. `'\u{301}'`) that are
+/// simulated with [`Keyboard::key`] are dead keys that are combined with the
+/// next character. In text, they follow their base character. Raw keycodes
+/// depend on the layout, so they are not supported.
+///
+/// On Linux, [`TestKeyboard::from_keymap`] predicts the text with an xkb
+/// keymap instead. Raw keycodes, the active layout and the dead keys of the
+/// keymap are taken into account then.
+///
+/// ```
+/// use enigo::{
+///     test_keyboard::TestKeyboard,
+///     Direction::{Click, Press, Release},
+///     Key, Keyboard,
+/// };
+///
+/// let mut keyboard = TestKeyboard::new();
+/// keyboard.key(Key::Shift, Press).unwrap();
+/// keyboard.key(Key::Unicode('z'), Click).unwrap();
+/// keyboard.key(Key::Shift, Release).unwrap();
+/// keyboard.key(Key::Unicode('\u{308}'), Click).unwrap();
+/// keyboard.text("urich").unwrap();
+/// assert_eq!(keyboard.output(), "Zürich");
+/// ```
+pub struct TestKeyboard {
+    model: Model,
+    output: String,
+}
+
+impl std::fmt::Debug for TestKeyboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("TestKeyboard");
+        match &self.model {
+            Model::Generic(generic) => debug.field("model", generic),
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Model::Xkb(predictor) => debug
+                .field("keymap", predictor.keymap())
+                .field("compose", &predictor.has_compose_table()),
+        };
+        debug.field("output", &self.output).finish_non_exhaustive()
+    }
+}
+
+impl Default for TestKeyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestKeyboard {
+    /// Create a predictor that does not depend on a layout
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            model: Model::Generic(Generic::default()),
+            output: String::new(),
+        }
+    }
+
+    /// Create a predictor for the xkb keymap. The compose table for dead keys
+    /// is loaded for the locale of the environment (`LC_ALL`, `LC_CTYPE` or
+    /// `LANG`)
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[must_use]
+    pub fn from_keymap(keymap: crate::keymap::Keymap) -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .find_map(|var| std::env::var_os(var).filter(|v| !v.is_empty()))
+            .unwrap_or_else(|| "C".into());
+        Self::from_keymap_with_compose_locale(keymap, &locale.to_string_lossy())
+    }
+
+    /// Create a predictor for the xkb keymap that loads the compose table for
+    /// dead keys for the given locale (e.g. "`en_US.UTF-8`"). Dead keys do not
+    /// produce any text if there is no compose table for the locale
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[must_use]
+    pub fn from_keymap_with_compose_locale(keymap: crate::keymap::Keymap, locale: &str) -> Self {
+        Self {
+            model: Model::Xkb(crate::os::xkb_keymap::Predictor::new(keymap, locale)),
+            output: String::new(),
+        }
+    }
+
+    /// Returns the text that was produced so far
+    #[must_use]
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Returns the text that was produced so far and clears it. Held keys stay
+    /// held
+    pub fn take_output(&mut self) -> String {
+        std::mem::take(&mut self.output)
+    }
+
+    /// Returns the index of the active layout of the keymap or `None` if the
+    /// predictor does not use a keymap
+    #[must_use]
+    pub fn active_layout(&self) -> Option<u32> {
+        match &self.model {
+            Model::Generic(_) => None,
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Model::Xkb(predictor) => Some(predictor.active_layout()),
+        }
+    }
+
+    /// Add the text produced by a key press to the output. Backspace removes
+    /// the last character and other control characters are ignored
+    fn push(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\u{8}' => {
+                    self.output.pop();
+                }
+                '\r' | '\n' => self.output.push('\n'),
+                '\t' => self.output.push('\t'),
+                c if c.is_control() => {}
+                c => self.output.push(c),
+            }
+        }
+    }
+}
+
+impl Keyboard for TestKeyboard {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        let text: String = match &mut self.model {
+            Model::Generic(generic) => text.chars().map(|c| generic.text_character(c)).collect(),
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Model::Xkb(_) => text.to_string(),
+        };
+        self.push(&text);
+        Ok(Some(()))
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        let text: String = match &mut self.model {
+            Model::Generic(generic) => generic.key(key, direction),
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Model::Xkb(predictor) => predictor.key(key, direction)?,
+        };
+        self.push(&text);
+        Ok(())
+    }
+
+    // The keycode can only be predicted with a keymap
+    #[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(unused_variables))]
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        let text: String = match &mut self.model {
+            Model::Generic(_) => Err(InputError::InvalidInput(
+                "raw keycodes can only be predicted with a keymap",
+            )),
+            #[cfg(all(unix, not(target_os = "macos")))]
+            Model::Xkb(predictor) => Ok(predictor.keycode(keycode.into(), direction)),
+        }?;
+        self.push(&text);
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+//! Headless displays to run tests that simulate input in containers and CI
+//!
+//! A [`VirtualDisplay`] spawns a headless X server (`Xvfb` or `Xephyr`) or a
+//! headless Weston compositor, waits until it accepts connections and stops it
+//! again when it is dropped. [`VirtualDisplay::settings`] returns [`Settings`]
+//! that connect to it, so the tests never touch the display of the user and
+//! don't need `DISPLAY` or `WAYLAND_DISPLAY` to be set.
+//!
+//! The server has to be installed (e.g. the `xvfb` package on Debian). The
+//! headless Weston does not support the protocols to simulate input, so only
+//! the connection can be tested with it. Use an X server to test the simulated
+//! input.
+//!
+//! ```no_run
+//! use enigo::{
+//!     testing::{Server, VirtualDisplay},
+//!     Coordinate, Enigo, Mouse,
+//! };
+//!
+//! let display = VirtualDisplay::start(Server::Xvfb).unwrap();
+//! display.validate().unwrap();
+//!
+//! let mut enigo = Enigo::new(&display.settings()).unwrap();
+//! enigo.move_mouse(200, 200, Coordinate::Abs).unwrap();
+//! assert_eq!(enigo.location().unwrap(), (200, 200));
+//! ```
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::{debug, warn};
+
+use crate::{
+    Coordinate, Direction, Enigo, InputError, Key, Keyboard, Mouse, NewConError, Settings,
+};
+
+/// How long to wait for the server to accept connections
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The first display number that is tried for X servers. Lower numbers are
+/// usually used by the real displays
+const FIRST_X11_DISPLAY: u32 = 99;
+
+/// Display for the backend that is not used by the server. `/dev/null` is not
+/// a directory, so neither an X server nor a Wayland compositor can ever listen
+/// on this path
+pub(crate) const NO_DISPLAY: &str = "/dev/null/enigo-no-display";
+
+/// Headless display server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Server {
+    /// Virtual framebuffer X server
+    Xvfb,
+    /// Nested X server. It needs a running X server to open its window in
+    Xephyr,
+    /// Weston with the headless backend
+    Weston,
+}
+
+impl Server {
+    /// Name of the executable
+    #[must_use]
+    pub fn program(self) -> &'static str {
+        match self {
+            Server::Xvfb => "Xvfb",
+            Server::Xephyr => "Xephyr",
+            Server::Weston => "weston",
+        }
+    }
+
+    /// Returns true if the server is an X server
+    #[must_use]
+    pub fn is_x11(self) -> bool {
+        matches!(self, Server::Xvfb | Server::Xephyr)
+    }
+}
+
+/// Error when starting or validating a [`VirtualDisplay`]
+#[derive(Debug)]
+pub enum TestingError {
+    /// The server could not be spawned (e.g. it is not installed)
+    Spawn(std::io::Error),
+    /// The server exited before it accepted connections
+    Exited(std::process::ExitStatus),
+    /// The server did not accept connections in time
+    Timeout,
+    /// No free display number or socket name was found
+    NoFreeDisplay,
+    /// Enigo could not connect to the display
+    Connect(NewConError),
+    /// Simulating input on the display failed
+    Input(InputError),
+    /// The simulated input did not have the expected effect
+    Validation(String),
+}
+
+impl fmt::Display for TestingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestingError::Spawn(e) => write!(f, "unable to spawn the server: {e}"),
+            TestingError::Exited(status) => write!(f, "the server exited: {status}"),
+            TestingError::Timeout => write!(f, "the server did not start in time"),
+            TestingError::NoFreeDisplay => write!(f, "no free display was found"),
+            TestingError::Connect(e) => write!(f, "unable to connect to the display: {e}"),
+            TestingError::Input(e) => write!(f, "unable to simulate input: {e}"),
+            TestingError::Validation(e) => write!(f, "validation failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TestingError {}
+
+impl From<NewConError> for TestingError {
+    fn from(e: NewConError) -> Self {
+        TestingError::Connect(e)
+    }
+}
+
+impl From<InputError> for TestingError {
+    fn from(e: InputError) -> Self {
+        TestingError::Input(e)
+    }
+}
+
+/// A running headless display server. It is stopped when it is dropped
+#[derive(Debug)]
+pub struct VirtualDisplay {
+    server: Server,
+    name: String,
+    child: Child,
+}
+
+impl VirtualDisplay {
+    /// Spawn the server on a free display and wait until it accepts
+    /// connections
+    ///
+    /// X servers get the first free display number starting at `:99`. Weston
+    /// creates a socket with a unique name in `XDG_RUNTIME_DIR`.
+    ///
+    /// # Errors
+    /// Returns an error if the server could not be spawned, exited or did not
+    /// accept connections within 10 seconds
+    pub fn start(server: Server) -> Result<Self, TestingError> {
+        debug!("\x1b[93mstart(server: {server:?})\x1b[0m");
+        let (name, socket) = if server.is_x11() {
+            let number = free_x11_display().ok_or(TestingError::NoFreeDisplay)?;
+            (
+                format!(":{number}"),
+                PathBuf::from(format!("/tmp/.X11-unix/X{number}")),
+            )
+        } else {
+            let runtime_dir =
+                std::env::var_os("XDG_RUNTIME_DIR").map_or_else(std::env::temp_dir, PathBuf::from);
+            let name = (0..100)
+                .map(|i| format!("wayland-enigo-{}-{i}", std::process::id()))
+                .find(|name| !runtime_dir.join(name).exists())
+                .ok_or(TestingError::NoFreeDisplay)?;
+            let socket = runtime_dir.join(&name);
+            (name, socket)
+        };
+
+        let socket_arg = format!("--socket={name}");
+        let mut command = Command::new(server.program());
+        match server {
+            Server::Xvfb => command.args([
+                name.as_str(),
+                "-screen",
+                "0",
+                "1920x1080x24",
+                "-nolisten",
+                "tcp",
+            ]),
+            Server::Xephyr => {
+                command.args([name.as_str(), "-screen", "1920x1080", "-nolisten", "tcp"])
+            }
+            Server::Weston => command.args([
+                "--backend=headless",
+                "--width=1920",
+                "--height=1080",
+                &socket_arg,
+            ]),
+        };
+        let child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(TestingError::Spawn)?;
+
+        // Construct it now so the server is stopped if it fails to start
+        let mut display = Self {
+            server,
+            name,
+            child,
+        };
+        display.wait_for_socket(&socket)?;
+        debug!("{server:?} is listening on {}", display.name);
+        Ok(display)
+    }
+
+    /// The server that was spawned
+    #[must_use]
+    pub fn server(&self) -> Server {
+        self.server
+    }
+
+    /// Name of the display. It is the value for `DISPLAY` (e.g. `:99`) for X
+    /// servers and the value for `WAYLAND_DISPLAY` for Weston
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Default [`Settings`] that connect to this display
+    #[must_use]
+    pub fn settings(&self) -> Settings {
+        let mut settings = Settings::default();
+        self.apply(&mut settings);
+        settings
+    }
+
+    /// Change the settings so they connect to this display
+    ///
+    /// The backend for the other protocol is pointed at a display that does
+    /// not exist, so the input never reaches the session of the user. The
+    /// libei backend can't be pointed at a display and still connects to the
+    /// session, so don't enable the `libei` feature for these tests
+    pub fn apply(&self, settings: &mut Settings) {
+        if self.server.is_x11() {
+            settings.x11_display = Some(self.name.clone());
+            settings.wayland_display = Some(NO_DISPLAY.to_string());
+        } else {
+            settings.x11_display = Some(NO_DISPLAY.to_string());
+            settings.wayland_display = Some(self.name.clone());
+        }
+    }
+
+    /// Connect to the display and check that simulated input works
+    ///
+    /// On X servers the mouse is moved and its location is read back. Weston
+    /// does not report the location, so only the connection is checked there.
+    ///
+    /// # Errors
+    /// Returns an error if no connection could be established, simulating the
+    /// input failed or the mouse did not end up at the expected location
+    pub fn validate(&self) -> Result<(), TestingError> {
+        debug!("\x1b[93mvalidate()\x1b[0m");
+        let mut enigo = Enigo::new(&self.settings())?;
+        if !self.server.is_x11() {
+            return Ok(());
+        }
+
+        let target = (13, 17);
+        enigo.move_mouse(target.0, target.1, Coordinate::Abs)?;
+        let location = enigo.location()?;
+        if location != target {
+            return Err(TestingError::Validation(format!(
+                "the mouse was moved to {target:?} but is at {location:?}"
+            )));
+        }
+        enigo.key(Key::Shift, Direction::Click)?;
+        Ok(())
+    }
+
+    /// Wait until the socket of the server exists
+    fn wait_for_socket(&mut self, socket: &Path) -> Result<(), TestingError> {
+        let start = Instant::now();
+        loop {
+            if socket.exists() {
+                return Ok(());
+            }
+            if let Some(status) = self.child.try_wait().map_err(TestingError::Spawn)? {
+                return Err(TestingError::Exited(status));
+            }
+            if start.elapsed() > STARTUP_TIMEOUT {
+                return Err(TestingError::Timeout);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+impl Drop for VirtualDisplay {
+    fn drop(&mut self) {
+        debug!("stopping {:?} on {}", self.server, self.name);
+        if let Err(e) = self.child.kill() {
+            warn!("unable to stop the server: {e}");
+        }
+        let _ = self.child.wait();
+    }
+}
+
+/// Find the first display number that neither has a socket nor a lock file
+fn free_x11_display() -> Option<u32> {
+    (FIRST_X11_DISPLAY..FIRST_X11_DISPLAY + 100).find(|n| {
+        !Path::new(&format!("/tmp/.X11-unix/X{n}")).exists()
+            && !Path::new(&format!("/tmp/.X{n}-lock")).exists()
+    })
+}
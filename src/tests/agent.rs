@@ -0,0 +1,356 @@
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    agent::{optimize, rescale, Agent, Policy, RateLimit, Token},
+    clock::{Clock, MockClock},
+    middleware::Recorded,
+    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse,
+};
+
+/// Records the simulated keys and fails to simulate mouse buttons
+#[derive(Default)]
+struct FailingButtons {
+    keys: Vec<(Key, Direction)>,
+}
+
+impl Keyboard for FailingButtons {
+    fn fast_text(&mut self, _text: &str) -> InputResult<Option<()>> {
+        Ok(Some(()))
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        self.keys.push((key, direction));
+        Ok(())
+    }
+
+    fn raw(&mut self, _keycode: u16, _direction: Direction) -> InputResult<()> {
+        Ok(())
+    }
+}
+
+impl Mouse for FailingButtons {
+    fn button(&mut self, _button: Button, _direction: Direction) -> InputResult<()> {
+        Err(InputError::Simulate("no mouse"))
+    }
+
+    fn move_mouse(&mut self, _x: i32, _y: i32, _coordinate: Coordinate) -> InputResult<()> {
+        Ok(())
+    }
+
+    fn scroll(&mut self, _length: i32, _axis: Axis) -> InputResult<()> {
+        Ok(())
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        Ok((100, 100))
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        Ok((0, 0))
+    }
+}
+
+#[test]
+fn unit_policy() {
+    let mut policy = Policy {
+        allow_raw: false,
+        allowed_keys: Some(vec![Key::Return]),
+        allowed_buttons: Some(vec![Button::Left]),
+        bounds: Some((0, 0, 100, 100)),
+        max_sleep: Some(Duration::from_secs(1)),
+        ..Default::default()
+    };
+    let location = || Ok((90, 90));
+    let denied = |res| matches!(res, Err(InputError::PermissionDenied(_)));
+
+    assert!(policy
+        .check(&Token::Key(Key::Return, Direction::Click), location)
+        .is_ok());
+    assert!(denied(
+        policy.check(&Token::Key(Key::Meta, Direction::Click), location)
+    ));
+    assert!(denied(
+        policy.check(&Token::Raw(38, Direction::Click), location)
+    ));
+    assert!(denied(policy.check(
+        &Token::Button(Button::Right, Direction::Click),
+        location
+    )));
+    assert!(policy
+        .check(&Token::MoveMouse(99, 0, Coordinate::Abs), location)
+        .is_ok());
+    assert!(denied(
+        policy.check(&Token::MoveMouse(100, 0, Coordinate::Abs), location)
+    ));
+    assert!(policy
+        .check(&Token::MoveMouse(9, -90, Coordinate::Rel), location)
+        .is_ok());
+    assert!(denied(
+        policy.check(&Token::MoveMouse(10, 0, Coordinate::Rel), location)
+    ));
+    assert!(denied(policy.check(&Token::Sleep(1001), location)));
+    assert!(policy
+        .check(&Token::Text("hi".to_string()), location)
+        .is_ok());
+}
+
+#[test]
+fn unit_policy_rate() {
+    let mut policy = Policy {
+        rate_limit: Some(RateLimit::new(2)),
+        ..Default::default()
+    };
+    let token = Token::Scroll(1, crate::Axis::Vertical);
+    assert!(policy.check(&token, || Ok((0, 0))).is_ok());
+    assert!(policy.check(&token, || Ok((0, 0))).is_ok());
+    assert_eq!(
+        policy.check(&token, || Ok((0, 0))),
+        Err(InputError::PermissionDenied(
+            "the maximum rate of tokens was exceeded"
+        ))
+    );
+}
+
+#[test]
+// Check that the body of a token with modifiers can't escape the bounds in
+// small steps or exceed the rate limit at once
+fn unit_policy_with_modifiers() {
+    let mut policy = Policy {
+        bounds: Some((0, 0, 100, 100)),
+        rate_limit: Some(RateLimit::new(4)),
+        ..Default::default()
+    };
+    let location = || Ok((90, 90));
+    let steps = |n| Token::WithModifiers {
+        modifiers: vec![Key::Shift],
+        body: vec![Token::MoveMouse(5, 0, Coordinate::Rel); n],
+    };
+    assert!(policy.check(&steps(1), location).is_ok());
+    assert_eq!(
+        policy.check(&steps(2), location),
+        Err(InputError::PermissionDenied(
+            "the destination is out of bounds"
+        ))
+    );
+
+    // The first token counted twice, so there are two left
+    assert_eq!(
+        policy.check(
+            &Token::WithModifiers {
+                modifiers: vec![Key::Shift, Key::Control],
+                body: vec![Token::MoveMouse(1, 1, Coordinate::Abs)],
+            },
+            location
+        ),
+        Err(InputError::PermissionDenied(
+            "the maximum rate of tokens was exceeded"
+        ))
+    );
+    assert!(policy
+        .check(&Token::Key(Key::Return, Direction::Click), location)
+        .is_ok());
+    assert!(policy
+        .check(&Token::Key(Key::Return, Direction::Click), location)
+        .is_ok());
+}
+
+#[test]
+fn unit_with_modifiers_releases_after_error() {
+    let mut agent = FailingButtons::default();
+    let token = Token::WithModifiers {
+        modifiers: vec![Key::Control, Key::Shift],
+        body: vec![
+            Token::Key(Key::Unicode('a'), Direction::Click),
+            Token::Button(Button::Left, Direction::Click),
+            Token::Key(Key::Unicode('b'), Direction::Click),
+        ],
+    };
+    assert_eq!(agent.execute(&token), Err(InputError::Simulate("no mouse")));
+    assert_eq!(
+        agent.keys,
+        vec![
+            (Key::Control, Direction::Press),
+            (Key::Shift, Direction::Press),
+            (Key::Unicode('a'), Direction::Click),
+            (Key::Shift, Direction::Release),
+            (Key::Control, Direction::Release),
+        ]
+    );
+
+    let mut policy = Policy {
+        allowed_keys: Some(vec![Key::Control, Key::Unicode('a')]),
+        ..Default::default()
+    };
+    assert!(matches!(
+        policy.check(&token, || Ok((0, 0))),
+        Err(InputError::PermissionDenied(_))
+    ));
+}
+
+#[test]
+fn unit_mock_clock() {
+    let clock = MockClock::new();
+    let start = clock.now();
+
+    // Sleeping does not block but advances the clock
+    let mut agent = FailingButtons::default();
+    agent
+        .execute_with_clock(&Token::Sleep(60_000), &clock)
+        .unwrap();
+    assert_eq!(clock.elapsed(start), Duration::from_secs(60));
+
+    let mut policy = Policy {
+        rate_limit: Some(RateLimit::with_clock(1, Arc::new(clock.clone()))),
+        ..Default::default()
+    };
+    let token = Token::Scroll(1, Axis::Vertical);
+    assert!(policy.check(&token, || Ok((0, 0))).is_ok());
+    assert!(policy.check(&token, || Ok((0, 0))).is_err());
+    clock.advance(Duration::from_millis(999));
+    assert!(policy.check(&token, || Ok((0, 0))).is_err());
+    clock.advance(Duration::from_millis(1));
+    assert!(policy.check(&token, || Ok((0, 0))).is_ok());
+}
+
+#[test]
+fn unit_optimize() {
+    let tokens = vec![
+        Token::MoveMouse(3, 4, Coordinate::Rel),
+        Token::MoveMouse(-3, -4, Coordinate::Rel),
+        Token::MoveMouse(0, 0, Coordinate::Abs),
+        Token::MoveMouse(5, 0, Coordinate::Abs),
+        Token::MoveMouse(10, 1, Coordinate::Abs),
+        Token::MoveMouse(10, 1, Coordinate::Abs),
+        Token::MoveMouse(20, 20, Coordinate::Abs),
+        Token::Button(Button::Left, Direction::Press),
+        Token::Button(Button::Left, Direction::Release),
+        Token::Key(Key::Unicode('h'), Direction::Press),
+        Token::Key(Key::Unicode('h'), Direction::Release),
+        Token::Key(Key::Unicode('i'), Direction::Click),
+        Token::Text("!".to_string()),
+        Token::Key(Key::Control, Direction::Press),
+        Token::Key(Key::Unicode('c'), Direction::Click),
+        Token::Key(Key::Control, Direction::Release),
+        Token::MoveMouse(1, 2, Coordinate::Rel),
+        Token::MoveMouse(3, 4, Coordinate::Rel),
+        Token::WithModifiers {
+            modifiers: vec![Key::Shift],
+            body: vec![
+                Token::Key(Key::Unicode('a'), Direction::Press),
+                Token::Key(Key::Unicode('a'), Direction::Release),
+            ],
+        },
+    ];
+    assert_eq!(
+        optimize(&tokens),
+        vec![
+            Token::MoveMouse(0, 0, Coordinate::Abs),
+            Token::MoveMouse(10, 1, Coordinate::Abs),
+            Token::MoveMouse(20, 20, Coordinate::Abs),
+            Token::Button(Button::Left, Direction::Click),
+            Token::Text("hi!".to_string()),
+            Token::Key(Key::Control, Direction::Press),
+            Token::Key(Key::Unicode('c'), Direction::Click),
+            Token::Key(Key::Control, Direction::Release),
+            Token::MoveMouse(4, 6, Coordinate::Rel),
+            Token::WithModifiers {
+                modifiers: vec![Key::Shift],
+                body: vec![Token::Key(Key::Unicode('a'), Direction::Click)],
+            },
+        ]
+    );
+
+    // A drag keeps the press and release of the button
+    let drag = vec![
+        Token::Button(Button::Left, Direction::Press),
+        Token::MoveMouse(50, 50, Coordinate::Abs),
+        Token::Button(Button::Left, Direction::Release),
+    ];
+    assert_eq!(optimize(&drag), drag);
+}
+
+#[test]
+fn unit_rescale() {
+    let (from, to) = ((1920, 1080), (1280, 720));
+    assert_eq!(
+        rescale(&Token::MoveMouse(960, 540, Coordinate::Abs), from, to),
+        Ok(Token::MoveMouse(640, 360, Coordinate::Abs))
+    );
+    assert_eq!(
+        rescale(&Token::MoveMouse(-30, 15, Coordinate::Rel), from, to),
+        Ok(Token::MoveMouse(-20, 10, Coordinate::Rel))
+    );
+    assert_eq!(
+        rescale(&Token::MainDisplay(1920, 1080), from, to),
+        Ok(Token::MainDisplay(1280, 720))
+    );
+    assert_eq!(
+        rescale(
+            &Token::WithModifiers {
+                modifiers: vec![Key::Shift],
+                body: vec![
+                    Token::Location(300, 300),
+                    Token::Scroll(300, Axis::Vertical)
+                ],
+            },
+            from,
+            to
+        ),
+        Ok(Token::WithModifiers {
+            modifiers: vec![Key::Shift],
+            body: vec![
+                Token::Location(200, 200),
+                Token::Scroll(300, Axis::Vertical)
+            ],
+        })
+    );
+    assert!(matches!(
+        rescale(&Token::Location(0, 0), (0, 1080), to),
+        Err(InputError::InvalidInput(_))
+    ));
+
+    // FailingButtons has a display of 100x100
+    let mut agent = FailingButtons::default();
+    assert!(agent
+        .execute_rescaled(&Token::MainDisplay(400, 400), (400, 400))
+        .is_ok());
+}
+
+#[test]
+fn unit_replay() {
+    // FailingButtons has a display of 100x100
+    let mut recorded = Recorded::with_main_display(FailingButtons::default());
+    assert_eq!(recorded.tokens(), &[Token::MainDisplay(100, 100)]);
+
+    let recording = [
+        Token::MainDisplay(400, 200),
+        Token::MoveMouse(200, 100, Coordinate::Abs),
+        Token::Key(Key::Return, Direction::Click),
+    ];
+    assert!(recorded.replay(&recording).is_ok());
+    assert_eq!(
+        recorded.take_tokens(),
+        [
+            Token::MainDisplay(100, 100),
+            Token::MoveMouse(50, 50, Coordinate::Abs),
+            Token::Key(Key::Return, Direction::Click),
+        ]
+    );
+    // The next recording starts with the size of the display again
+    assert_eq!(recorded.tokens(), &[Token::MainDisplay(100, 100)]);
+
+    // Without the size of the display, the tokens are not re-scaled
+    assert!(recorded.replay(&recording[1..]).is_ok());
+    assert_eq!(
+        recorded.tokens()[1],
+        Token::MoveMouse(200, 100, Coordinate::Abs)
+    );
+
+    assert!(matches!(
+        recorded.replay(&[
+            Token::MainDisplay(0, 0),
+            Token::MoveMouse(1, 1, Coordinate::Abs)
+        ]),
+        Err(InputError::InvalidInput(_))
+    ));
+}
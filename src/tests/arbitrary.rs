@@ -0,0 +1,33 @@
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::agent::Token;
+
+// Deterministic pseudo random bytes so failures are reproducible
+fn bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.max(1);
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
+
+#[test]
+// Every token that can be constructed has to survive a round trip through the
+// serializer
+fn unit_arbitrary_token_ron_roundtrip() {
+    for seed in 0..500 {
+        let data = bytes(seed, 256);
+        let mut u = Unstructured::new(&data);
+        let Ok(tokens) = Vec::<Token>::arbitrary(&mut u) else {
+            continue;
+        };
+        let serialized = ron::to_string(&tokens).unwrap();
+        let deserialized: Vec<Token> = ron::from_str(&serialized)
+            .unwrap_or_else(|e| panic!("{serialized} could not be deserialized: {e}"));
+        assert_eq!(tokens, deserialized, "seed {seed}");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    CancellationToken, Direction, InputError, InputResult, Key, Keyboard, PartialTextError,
+    TEXT_CHUNK_LEN,
+};
+
+/// Records the chunks of text and cancels the token after the first one
+struct Chunks {
+    chunks: Vec<String>,
+    token: CancellationToken,
+}
+
+impl Keyboard for Chunks {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        self.chunks.push(text.to_string());
+        self.token.cancel();
+        Ok(Some(()))
+    }
+    fn key(&mut self, _: Key, _: Direction) -> InputResult<()> {
+        Ok(())
+    }
+    fn raw(&mut self, _: u16, _: Direction) -> InputResult<()> {
+        Ok(())
+    }
+}
+
+#[test]
+// Check that the clones of a token share its state
+fn unit_cancellation_token() {
+    let token = CancellationToken::new();
+    let clone = token.clone();
+    assert!(!token.is_cancelled());
+    clone.cancel();
+    assert!(token.is_cancelled());
+}
+
+#[test]
+// Check that the text is entered in chunks of characters and no chunk is
+// entered once the token was cancelled
+fn unit_text_cancellable() {
+    let token = CancellationToken::new();
+    let mut keyboard = Chunks {
+        chunks: vec![],
+        token: token.clone(),
+    };
+    let text = "ä".repeat(TEXT_CHUNK_LEN + 5);
+    let entered = keyboard.text_cancellable(&text, &token).unwrap();
+    assert_eq!(entered, TEXT_CHUNK_LEN);
+    assert_eq!(keyboard.chunks, vec!["ä".repeat(TEXT_CHUNK_LEN)]);
+
+    // Resume with the rest of the text
+    let token = CancellationToken::new();
+    let rest: String = text.chars().skip(entered).collect();
+    let entered = keyboard
+        .text_cancellable(&rest, &CancellationToken::new())
+        .unwrap();
+    assert_eq!(entered, 5);
+    assert_eq!(keyboard.chunks[1], "ä".repeat(5));
+    assert!(!token.is_cancelled());
+    assert_eq!(keyboard.text_cancellable("", &token).unwrap(), 0);
+}
+
+/// Enters the first chunk of text and fails for all others
+struct FailSecond {
+    chunks: usize,
+}
+
+impl Keyboard for FailSecond {
+    fn fast_text(&mut self, _: &str) -> InputResult<Option<()>> {
+        self.chunks += 1;
+        if self.chunks > 1 {
+            return Err(InputError::Simulate("second chunk"));
+        }
+        Ok(Some(()))
+    }
+    fn key(&mut self, _: Key, _: Direction) -> InputResult<()> {
+        Ok(())
+    }
+    fn raw(&mut self, _: u16, _: Direction) -> InputResult<()> {
+        Ok(())
+    }
+}
+
+#[test]
+// Check that the number of entered characters is returned with the error
+fn unit_text_cancellable_error() {
+    let mut keyboard = FailSecond { chunks: 0 };
+    let text = "a".repeat(TEXT_CHUNK_LEN * 2);
+    assert_eq!(
+        keyboard.text_cancellable(&text, &CancellationToken::new()),
+        Err(PartialTextError {
+            entered: TEXT_CHUNK_LEN,
+            error: InputError::Simulate("second chunk"),
+        })
+    );
+}
+
+#[test]
+// Check that long chunks of the stream are split and short ones are entered
+// as they are
+fn unit_text_stream() {
+    let mut keyboard = Chunks {
+        chunks: vec![],
+        token: CancellationToken::new(),
+    };
+    let long = "a".repeat(TEXT_CHUNK_LEN * 2 + 1);
+    keyboard
+        .text_stream(["Hello", "", long.as_str(), "❤️"])
+        .unwrap();
+    assert_eq!(
+        keyboard.chunks,
+        vec![
+            "Hello".to_string(),
+            "a".repeat(TEXT_CHUNK_LEN),
+            "a".repeat(TEXT_CHUNK_LEN),
+            "a".to_string(),
+            "❤️".to_string(),
+        ]
+    );
+}
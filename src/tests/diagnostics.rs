@@ -0,0 +1,46 @@
+use crate::{
+    diagnostics::{redact, report, REDACTED},
+    Settings,
+};
+
+#[test]
+fn unit_diagnostics_redact() {
+    let settings = Settings {
+        x11_display: Some(":1".to_string()),
+        libei_device: Some("/dev/input/event7".to_string()),
+        linux_delay: 3,
+        ..Default::default()
+    };
+    let redacted = redact(&settings);
+    assert_eq!(redacted.x11_display.as_deref(), Some(REDACTED));
+    assert_eq!(redacted.libei_device.as_deref(), Some(REDACTED));
+    // Values that were not set stay unset, so it is visible they were not used
+    assert_eq!(redacted.wayland_display, None);
+    assert_eq!(redacted.libei_seat, None);
+    assert_eq!(redacted.linux_delay, 3);
+}
+
+#[test]
+fn unit_diagnostics_report() {
+    let settings = redact(&Settings {
+        x11_display: Some(":1".to_string()),
+        ..Default::default()
+    });
+    let report = report(
+        vec!["x11rb".to_string()],
+        None,
+        Some("German".to_string()),
+        Some((1920, 1080)),
+        &settings,
+    );
+    assert_eq!(report.enigo_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(report.os, std::env::consts::OS);
+
+    let text = report.to_string();
+    assert!(text.contains("backends: x11rb"));
+    assert!(text.contains("keyboard layout: \"German\""));
+    assert!(text.contains("main display: (1920, 1080)"));
+    assert!(text.contains("permission: unknown"));
+    assert!(text.contains(REDACTED));
+    assert!(!text.contains("\":1\""));
+}
@@ -0,0 +1,60 @@
+use crate::{
+    agent::Token,
+    dsl::{parse, ParseError},
+    Axis, Button, Coordinate,
+    Direction::{Click, Press, Release},
+    Key,
+};
+
+#[test]
+fn unit_dsl_parse() {
+    let tokens = parse(
+        "{ctrl+a}Hi {{x}}{+shift}{return}{-Shift}{raw 38}{+button left}{move 1 2}{move_rel -3 4}{scroll 5}{hscroll -6}{sleep 7}{ctrl++}{-}",
+    )
+    .unwrap();
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Key(Key::Control, Press),
+            Token::Key(Key::Unicode('a'), Click),
+            Token::Key(Key::Control, Release),
+            Token::Text("Hi {x}".to_string()),
+            Token::Key(Key::Shift, Press),
+            Token::Key(Key::Return, Click),
+            Token::Key(Key::Shift, Release),
+            Token::Raw(38, Click),
+            Token::Button(Button::Left, Press),
+            Token::MoveMouse(1, 2, Coordinate::Abs),
+            Token::MoveMouse(-3, 4, Coordinate::Rel),
+            Token::Scroll(5, Axis::Vertical),
+            Token::Scroll(-6, Axis::Horizontal),
+            Token::Sleep(7),
+            Token::Key(Key::Control, Press),
+            Token::Key(Key::Unicode('+'), Click),
+            Token::Key(Key::Control, Release),
+            Token::Key(Key::Unicode('-'), Click),
+        ]
+    );
+}
+
+#[test]
+fn unit_dsl_errors() {
+    assert_eq!(parse("ab{ctrl"), Err(ParseError::UnclosedBrace(2)));
+    assert_eq!(parse("a}b"), Err(ParseError::UnexpectedBrace(1)));
+    assert_eq!(
+        parse("{nokey}"),
+        Err(ParseError::UnknownKey("nokey".to_string()))
+    );
+    assert_eq!(
+        parse("{button nobutton}"),
+        Err(ParseError::UnknownButton("nobutton".to_string()))
+    );
+    assert_eq!(
+        parse("{move 1}"),
+        Err(ParseError::UnknownCommand("move 1".to_string()))
+    );
+    assert_eq!(
+        parse("{sleep x}"),
+        Err(ParseError::InvalidNumber("x".to_string()))
+    );
+}
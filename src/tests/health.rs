@@ -0,0 +1,29 @@
+use crate::health::{health_check, HealthIssue, HealthReport};
+
+#[test]
+fn unit_health_report() {
+    let report = HealthReport::default();
+    assert!(report.is_healthy());
+    assert_eq!(report.to_string(), "no issues found");
+
+    let report = HealthReport {
+        issues: vec![HealthIssue::SessionLocked, HealthIssue::NoDisplay],
+    };
+    assert!(!report.is_healthy());
+    assert!(report.has(HealthIssue::NoDisplay));
+    assert!(!report.has(HealthIssue::SecureDesktop));
+    assert_eq!(
+        report.to_string(),
+        "the screen is locked, there is no display to send the input to"
+    );
+}
+
+#[test]
+fn unit_health_check() {
+    // The result depends on the environment, but it must not panic and every
+    // issue is reported only once
+    let report = health_check();
+    for issue in &report.issues {
+        assert_eq!(report.issues.iter().filter(|i| *i == issue).count(), 1);
+    }
+}
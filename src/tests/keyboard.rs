@@ -1,6 +1,7 @@
 use crate::{
+    text_with_policy,
     Direction::{Click, Press, Release},
-    Enigo, Key, Keyboard, Settings,
+    Enigo, InputError, InputResult, Key, Keyboard, Settings, UnmappableCharPolicy,
 };
 use std::thread;
 
@@ -181,3 +182,95 @@ fn unit_key_other_all_keycodes() {
         );
     }
 }
+
+#[test]
+// Check that Key::ALL lists every key except for the deprecated ones and that
+// all of them are available on the current platform
+fn unit_key_all() {
+    use strum::IntoEnumIterator;
+
+    let deprecated = [
+        Key::Command,
+        Key::Super,
+        Key::Windows,
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Print,
+        #[cfg(target_os = "windows")]
+        Key::Snapshot,
+    ];
+    for key in Key::iter() {
+        if matches!(key, Key::Unicode(_) | Key::Other(_)) || deprecated.contains(&key) {
+            assert!(!Key::ALL.contains(&key), "{key:?} must not be listed");
+        } else {
+            assert!(Key::ALL.contains(&key), "{key:?} is missing");
+        }
+    }
+    for key in Key::ALL {
+        let platforms = key.platforms();
+        #[cfg(target_os = "windows")]
+        assert!(platforms.windows, "{key:?}");
+        #[cfg(target_os = "macos")]
+        assert!(platforms.macos, "{key:?}");
+        #[cfg(all(unix, not(target_os = "macos")))]
+        assert!(platforms.linux, "{key:?}");
+    }
+    assert!(Key::Shift.is_modifier() && !Key::Shift.is_lock());
+    assert!(Key::CapsLock.is_lock() && !Key::CapsLock.is_modifier());
+    assert!(!Key::Unicode('a').is_modifier() && !Key::Unicode('a').is_lock());
+}
+
+/// Keyboard without fast text entry that can't map 'ß'
+#[derive(Default)]
+struct NoEszett(String);
+
+impl Keyboard for NoEszett {
+    fn fast_text(&mut self, _: &str) -> InputResult<Option<()>> {
+        Ok(None)
+    }
+    fn key(&mut self, key: Key, _: crate::Direction) -> InputResult<()> {
+        match key {
+            Key::Unicode('ß') => Err(InputError::Mapping("ß".to_string())),
+            Key::Unicode('\0') => Err(InputError::InvalidInput("null byte")),
+            Key::Unicode(c) => {
+                self.0.push(c);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+    fn raw(&mut self, _: u16, _: crate::Direction) -> InputResult<()> {
+        Ok(())
+    }
+}
+
+#[test]
+// Check that the characters that can't be mapped are handled according to
+// the policy and other errors are always returned
+fn unit_unmappable_char_policy() {
+    let mut keyboard = NoEszett::default();
+    let res = text_with_policy(&mut keyboard, "Maße", UnmappableCharPolicy::Abort);
+    assert_eq!(res, Err(InputError::Mapping("ß".to_string())));
+    assert_eq!(keyboard.0, "Ma");
+
+    let mut keyboard = NoEszett::default();
+    text_with_policy(&mut keyboard, "Maße", UnmappableCharPolicy::SkipUnmappable).unwrap();
+    assert_eq!(keyboard.0, "Mae");
+
+    let mut keyboard = NoEszett::default();
+    text_with_policy(
+        &mut keyboard,
+        "Maße",
+        UnmappableCharPolicy::ReplaceWith('?'),
+    )
+    .unwrap();
+    assert_eq!(keyboard.0, "Ma?e");
+
+    let mut keyboard = NoEszett::default();
+    let res = text_with_policy(&mut keyboard, "ßß", UnmappableCharPolicy::ReplaceWith('ß'));
+    assert!(res.is_err());
+
+    let mut keyboard = NoEszett::default();
+    let res = text_with_policy(&mut keyboard, "a\0b", UnmappableCharPolicy::SkipUnmappable);
+    assert_eq!(res, Err(InputError::InvalidInput("null byte")));
+    assert_eq!(keyboard.0, "a");
+}
@@ -0,0 +1,26 @@
+use crate::{Coordinate, Enigo, InputError, Mouse, NewConError, Settings};
+
+#[test]
+fn unit_lazy_connect() {
+    // Displays that don't exist, so connecting always fails
+    let settings = Settings {
+        lazy_connect: true,
+        x11_display: Some(":9999".to_string()),
+        wayland_display: Some("wayland-enigo-missing".to_string()),
+        ..Default::default()
+    };
+    assert!(Enigo::new(&Settings {
+        lazy_connect: false,
+        ..settings.clone()
+    })
+    .is_err());
+
+    // The error is only returned once the connection is used
+    let mut enigo = Enigo::new(&settings).unwrap();
+    assert!(matches!(
+        enigo.move_mouse(1, 1, Coordinate::Rel),
+        Err(InputError::Connect(NewConError::EstablishCon(_)))
+    ));
+    // It is tried again
+    assert!(enigo.connect().is_err());
+}
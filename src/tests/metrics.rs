@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use crate::{
+    metrics::{InputMetric, MetricsHook, Recorder},
+    InputError,
+};
+
+#[test]
+fn unit_metrics_recorder() {
+    let recorder = Recorder::default();
+    let error = InputError::Simulate("failed");
+    for ms in 1..=100 {
+        recorder.record(&InputMetric {
+            function: "key",
+            backend: "libei",
+            latency: Duration::from_millis(ms),
+            error: (ms % 10 == 0).then_some(&error),
+        });
+    }
+    let stats = recorder.stats();
+    let libei = &stats["libei"];
+    assert_eq!(libei.events, 100);
+    assert_eq!(libei.failures, 10);
+    assert_eq!(
+        libei.latency_percentile(0.0),
+        Some(Duration::from_millis(1))
+    );
+    assert_eq!(
+        libei.latency_percentile(50.0),
+        Some(Duration::from_millis(51))
+    );
+    assert_eq!(
+        libei.latency_percentile(100.0),
+        Some(Duration::from_millis(100))
+    );
+
+    recorder.reset();
+    assert!(recorder.stats().is_empty());
+}
@@ -0,0 +1,216 @@
+use crate::{
+    agent::Token,
+    middleware::{Delayed, RateLimited, Recorded},
+    Axis, Button, Coordinate,
+    Direction::{Click, Press, Release},
+    InputResult, Key, Keyboard, Mouse,
+};
+use std::time::{Duration, Instant};
+
+/// Does not simulate anything and counts the number of events
+#[derive(Default)]
+struct Noop(usize);
+
+impl Keyboard for Noop {
+    fn fast_text(&mut self, _: &str) -> InputResult<Option<()>> {
+        self.0 += 1;
+        Ok(Some(()))
+    }
+    fn key(&mut self, _: Key, _: crate::Direction) -> InputResult<()> {
+        self.0 += 1;
+        Ok(())
+    }
+    fn raw(&mut self, _: u16, _: crate::Direction) -> InputResult<()> {
+        self.0 += 1;
+        Ok(())
+    }
+}
+
+impl Mouse for Noop {
+    fn button(&mut self, _: Button, _: crate::Direction) -> InputResult<()> {
+        self.0 += 1;
+        Ok(())
+    }
+    fn move_mouse(&mut self, _: i32, _: i32, _: Coordinate) -> InputResult<()> {
+        self.0 += 1;
+        Ok(())
+    }
+    fn scroll(&mut self, _: i32, _: Axis) -> InputResult<()> {
+        self.0 += 1;
+        Ok(())
+    }
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        Ok((0, 0))
+    }
+    fn location(&self) -> InputResult<(i32, i32)> {
+        Ok((0, 0))
+    }
+}
+
+#[test]
+// Check that the recorded tokens match the simulated input and nested wrappers
+// forward everything
+fn unit_middleware_recorded() {
+    let mut recorded = Recorded::new(Delayed::new(Noop::default(), Duration::ZERO));
+    recorded.text("Hello").unwrap();
+    recorded.key(Key::Control, Press).unwrap();
+    recorded.raw(42, Release).unwrap();
+    recorded.button(Button::Left, Click).unwrap();
+    recorded.move_mouse(10, 20, Coordinate::Abs).unwrap();
+    recorded.scroll(-1, Axis::Vertical).unwrap();
+
+    assert_eq!(
+        recorded.tokens(),
+        &[
+            Token::Text("Hello".to_string()),
+            Token::Key(Key::Control, Press),
+            Token::Raw(42, Release),
+            Token::Button(Button::Left, Click),
+            Token::MoveMouse(10, 20, Coordinate::Abs),
+            Token::Scroll(-1, Axis::Vertical),
+        ]
+    );
+    assert_eq!(recorded.inner().inner().0, 6);
+}
+
+#[test]
+// Check that the rate limited wrapper waits between the events
+fn unit_middleware_rate_limited() {
+    let min_interval = Duration::from_millis(20);
+    let mut rate_limited = RateLimited::new(Noop::default(), min_interval);
+    let start = Instant::now();
+    for _ in 0..5 {
+        rate_limited.key(Key::Unicode('a'), Click).unwrap();
+    }
+    assert!(start.elapsed() >= min_interval * 4);
+    assert_eq!(rate_limited.into_inner().0, 5);
+}
+
+/// Stores the button events
+#[derive(Default)]
+struct ButtonLog(Vec<(Button, crate::Direction)>);
+
+impl Mouse for ButtonLog {
+    fn button(&mut self, button: Button, direction: crate::Direction) -> InputResult<()> {
+        self.0.push((button, direction));
+        Ok(())
+    }
+    fn move_mouse(&mut self, _: i32, _: i32, _: Coordinate) -> InputResult<()> {
+        Ok(())
+    }
+    fn scroll(&mut self, _: i32, _: Axis) -> InputResult<()> {
+        Ok(())
+    }
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        Ok((0, 0))
+    }
+    fn location(&self) -> InputResult<(i32, i32)> {
+        Ok((0, 0))
+    }
+}
+
+#[test]
+// Check that the default implementation of buttons sends all presses before
+// all releases
+fn unit_buttons_chord() {
+    let mut log = ButtonLog::default();
+    log.buttons(&[
+        (Button::Left, Click),
+        (Button::Right, Click),
+        (Button::Middle, Release),
+    ])
+    .unwrap();
+    assert_eq!(
+        log.0,
+        vec![
+            (Button::Left, Press),
+            (Button::Right, Press),
+            (Button::Left, Release),
+            (Button::Right, Release),
+            (Button::Middle, Release),
+        ]
+    );
+}
+
+#[test]
+// Check that a key sequence is recorded as the individual key events
+fn unit_middleware_key_sequence() {
+    let mut recorded = Recorded::new(Noop::default());
+    let keys = [Key::Control, Key::Shift, Key::Unicode('t')];
+    recorded.key_sequence(&keys, Click).unwrap();
+    recorded.key_sequence(&keys[..1], Release).unwrap();
+
+    assert_eq!(
+        recorded.tokens(),
+        &[
+            Token::Key(Key::Control, Press),
+            Token::Key(Key::Shift, Press),
+            Token::Key(Key::Unicode('t'), Press),
+            Token::Key(Key::Unicode('t'), Release),
+            Token::Key(Key::Shift, Release),
+            Token::Key(Key::Control, Release),
+            Token::Key(Key::Control, Release),
+        ]
+    );
+    assert_eq!(recorded.inner().0, 7);
+}
+
+/// Logs the key events and fails to press `Key::Unicode('x')`
+#[derive(Default)]
+struct Failing(Vec<(Key, crate::Direction)>);
+
+impl Keyboard for Failing {
+    fn fast_text(&mut self, _: &str) -> InputResult<Option<()>> {
+        Ok(None)
+    }
+    fn key(&mut self, key: Key, direction: crate::Direction) -> InputResult<()> {
+        if key == Key::Unicode('x') && direction == Press {
+            return Err(crate::InputError::Mapping("x".to_string()));
+        }
+        self.0.push((key, direction));
+        Ok(())
+    }
+    fn raw(&mut self, _: u16, _: crate::Direction) -> InputResult<()> {
+        Ok(())
+    }
+}
+
+#[test]
+// Check that the pressed keys are released in reverse order if a key of the
+// sequence fails
+fn unit_key_sequence_rollback() {
+    let mut keyboard = Failing::default();
+    let res = keyboard.key_sequence(
+        &[Key::Control, Key::Alt, Key::Unicode('x'), Key::Shift],
+        Click,
+    );
+    assert!(res.is_err());
+    assert_eq!(
+        keyboard.0,
+        [
+            (Key::Control, Press),
+            (Key::Alt, Press),
+            (Key::Alt, Release),
+            (Key::Control, Release),
+        ]
+    );
+}
+
+#[test]
+// Check that keyboards without a mouse can be recorded and failed input is
+// not recorded
+fn unit_middleware_recorded_failing() {
+    let mut recorded = Recorded::new(Failing::default());
+    recorded.key(Key::Unicode('a'), Click).unwrap();
+    assert!(recorded.key(Key::Unicode('x'), Press).is_err());
+    recorded.key(Key::Unicode('x'), Release).unwrap();
+
+    assert_eq!(
+        recorded.take_tokens(),
+        [
+            Token::Key(Key::Unicode('a'), Click),
+            Token::Key(Key::Unicode('x'), Release),
+        ]
+    );
+    assert!(recorded.tokens().is_empty());
+}
@@ -1,11 +1,57 @@
 use std::time::Duration;
 
+/// Module containing all the tests of the `Policy` of the `agent` module
+mod agent;
+/// Module containing the property tests of the types that implement
+/// `Arbitrary`
+#[cfg(all(feature = "arbitrary", feature = "serde"))]
+mod arbitrary;
+/// Module containing all the tests of `Keyboard::text_cancellable` and
+/// `Keyboard::text_stream`
+mod cancel;
+/// Module containing all the tests of the `diagnostics` module
+mod diagnostics;
+/// Module containing all the tests of the `dsl` module
+#[cfg(feature = "dsl")]
+mod dsl;
+/// Module containing all the tests of the `health` module
+mod health;
 /// Module containing all the tests related to the `Keyboard` trait
 /// that are platform independent
 mod keyboard;
+/// Module containing all the tests of `Settings::lazy_connect`
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "libei")))]
+mod lazy_connect;
+/// Module containing all the tests of the `metrics` module
+mod metrics;
+/// Module containing all the tests of the wrappers in the `middleware` module
+mod middleware;
 /// Module containing all the tests related to the `Mouse` trait
 /// that are platform independent
 mod mouse;
+/// Module containing all the tests of the `platform` module
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "libei")))]
+mod platform;
+/// Module containing all the tests of the `Scancode` struct
+mod scancode;
+/// Module containing all the tests of the `schema` module
+#[cfg(feature = "schema")]
+mod schema;
+/// Module containing all the tests of the `settings` module
+mod settings;
+/// Module containing all the tests of the `test_harness` module
+#[cfg(feature = "test-harness")]
+mod test_harness;
+/// Module containing all the tests of the `test_keyboard` module
+mod test_keyboard;
+/// Module containing all the tests of the `testing` module
+#[cfg(all(feature = "testing", unix, not(target_os = "macos")))]
+mod testing;
+/// Module containing all the tests of the rate limiting of the Enigo struct
+mod throttle;
+/// Module containing all the tests of the `webdriver` module
+#[cfg(feature = "webdriver")]
+mod webdriver;
 
 // Check if the code is running in the CI
 fn is_ci() -> bool {
@@ -1,7 +1,7 @@
 use crate::{
-    Button,
+    inertia_deltas, pixels_to_notches, round_coordinates, Button,
     Direction::{Click, Press, Release},
-    Enigo, Mouse, Settings,
+    Enigo, InputError, InputResult, Mouse, Origin, Rect, Settings, Subpixel,
     {Axis::Horizontal, Axis::Vertical},
     {Coordinate::Abs, Coordinate::Rel},
 };
@@ -271,3 +271,162 @@ fn unit_mouse_drag() {
     thread::sleep(delay);
     enigo.button(Button::Left, Release).unwrap();
 }
+
+#[test]
+// Check that the distance is rounded to the closest number of notches and
+// never rounded to zero
+fn unit_pixels_to_notches() {
+    assert_eq!(pixels_to_notches(0, 60), Ok(0));
+    assert_eq!(pixels_to_notches(10, 60), Ok(1));
+    assert_eq!(pixels_to_notches(-10, 60), Ok(-1));
+    assert_eq!(pixels_to_notches(89, 60), Ok(1));
+    assert_eq!(pixels_to_notches(90, 60), Ok(2));
+    assert_eq!(pixels_to_notches(-150, 60), Ok(-3));
+    assert_eq!(pixels_to_notches(i32::MIN, 1), Ok(-i32::MAX));
+    // A notch has to scroll some distance
+    assert!(matches!(
+        pixels_to_notches(100, 0),
+        Err(InputError::InvalidInput(_))
+    ));
+    assert!(pixels_to_notches(100, -60).is_err());
+}
+
+#[test]
+// Check that a fling decelerates, keeps its direction and comes to rest
+fn unit_inertia_deltas() {
+    let deltas = inertia_deltas(2000.0);
+    assert!(!deltas.is_empty());
+    assert!(deltas.iter().all(|&delta| delta > 0));
+    assert!(deltas.first() > deltas.last());
+    assert_eq!(deltas[0], 32);
+    // The distance of the whole fling is about v * frame / (1 - decay)
+    let distance: i32 = deltas.iter().sum();
+    assert!((600..=640).contains(&distance), "{distance}");
+
+    let negated: Vec<i32> = inertia_deltas(-2000.0).iter().map(|d| -d).collect();
+    assert_eq!(negated, deltas);
+
+    assert!(inertia_deltas(5.0).is_empty());
+    assert!(inertia_deltas(f64::NAN).is_empty());
+    assert!(inertia_deltas(f64::INFINITY).is_empty());
+}
+
+#[test]
+// Check that many small relative movements add up to the whole distance and
+// at most half a pixel is left over
+fn unit_subpixel() {
+    let mut subpixel = Subpixel::default();
+    let (mut x, mut y) = (0, 0);
+    for _ in 0..100 {
+        let (dx, dy) = subpixel.take(0.3, -0.7);
+        x += dx;
+        y += dy;
+    }
+    assert_eq!((x, y), (30, -70));
+    assert_eq!(subpixel.take(2.0, -3.0), (2, -3));
+    assert_eq!(Subpixel::default().take(0.4, 0.6), (0, 1));
+
+    assert_eq!(round_coordinates(1.4, -1.6), Ok((1, -2)));
+    assert!(round_coordinates(f64::NAN, 0.0).is_err());
+    assert!(round_coordinates(0.0, f64::INFINITY).is_err());
+    assert!(round_coordinates(1e10, 0.0).is_err());
+}
+
+/// Cursor on a display of 100x100 pixels that can hide its location
+struct Clamped {
+    position: (i32, i32),
+    hide_location: bool,
+}
+
+impl Mouse for Clamped {
+    fn button(&mut self, _: Button, _: crate::Direction) -> InputResult<()> {
+        Ok(())
+    }
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: crate::Coordinate) -> InputResult<()> {
+        let (x, y) = match coordinate {
+            Abs => (x, y),
+            Rel => (self.position.0 + x, self.position.1 + y),
+        };
+        self.position = (x.clamp(0, 99), y.clamp(0, 99));
+        Ok(())
+    }
+    fn scroll(&mut self, _: i32, _: crate::Axis) -> InputResult<()> {
+        Ok(())
+    }
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        Ok((100, 100))
+    }
+    fn location(&self) -> InputResult<(i32, i32)> {
+        if self.hide_location {
+            Err(InputError::Simulate("the location is hidden"))
+        } else {
+            Ok(self.position)
+        }
+    }
+}
+
+#[test]
+// Check that the reached position is returned and the requested one if the
+// location is unknown
+fn unit_move_mouse_checked() {
+    let mut mouse = Clamped {
+        position: (0, 0),
+        hide_location: false,
+    };
+    assert_eq!(mouse.move_mouse_checked(50, 150, Abs), Ok((50, 99)));
+    assert_eq!(mouse.move_mouse_checked(70, -10, Rel), Ok((99, 89)));
+
+    mouse.hide_location = true;
+    assert_eq!(mouse.move_mouse_checked(10, 20, Abs), Ok((10, 20)));
+    assert!(mouse.move_mouse_checked(10, 20, Rel).is_err());
+}
+
+#[test]
+// Check that the y coordinates are flipped at the height of the main display
+fn unit_origin() {
+    assert_eq!(Origin::TopLeft.to_top_left(10, 20, 1080), (10, 20));
+    assert_eq!(Origin::BottomLeft.to_top_left(10, 20, 1080), (10, 1060));
+    assert_eq!(Origin::BottomLeft.from_top_left(10, 1060, 1080), (10, 20));
+    // A window at the bottom of the screen
+    assert_eq!(
+        Origin::BottomLeft.rect_to_top_left(Rect::new(100, 0, 300, 200), 1080),
+        Rect::new(100, 880, 300, 200)
+    );
+    let rect = Rect::new(100, 50, 300, 200);
+    assert_eq!(Origin::TopLeft.rect_to_top_left(rect, 1080), rect);
+}
+
+#[test]
+// Check that points are clamped to the last pixel inside of the rectangle
+fn unit_rect() {
+    let rect = Rect::new(10, 20, 100, 50);
+    assert!(rect.contains(10, 20));
+    assert!(rect.contains(109, 69));
+    assert!(!rect.contains(110, 69));
+    assert!(!rect.contains(9, 20));
+    assert_eq!(rect.clamp(0, 0), (10, 20));
+    assert_eq!(rect.clamp(500, 500), (109, 69));
+    assert_eq!(rect.clamp(50, 30), (50, 30));
+    assert!(Rect::new(i32::MAX, i32::MAX, 10, 10).contains(i32::MAX, i32::MAX));
+    assert!(rect.check_not_empty().is_ok());
+    assert!(Rect::new(0, 0, 0, 10).check_not_empty().is_err());
+    assert!(Rect::new(0, 0, 10, -1).check_not_empty().is_err());
+}
+
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "libei")))] // Needs Settings::lazy_connect
+#[test]
+// Check that absolute movements are refused without connecting
+fn unit_pointer_mode_relative_only() {
+    let mut enigo = Enigo::new(&Settings {
+        lazy_connect: true,
+        x11_display: Some(":9999".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(enigo.pointer_mode(), crate::PointerMode::Normal);
+    enigo.set_pointer_mode(crate::PointerMode::RelativeOnly);
+    assert!(matches!(
+        enigo.move_mouse(10, 10, Abs),
+        Err(InputError::InvalidInput(_))
+    ));
+}
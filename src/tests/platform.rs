@@ -0,0 +1,17 @@
+use crate::{Enigo, Settings};
+
+#[test]
+fn unit_downcast() {
+    let mut enigo = Enigo::new(&Settings {
+        lazy_connect: true,
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(enigo
+        .downcast_ref::<crate::platform::linux::Enigo>()
+        .is_some());
+    assert!(enigo.downcast_ref::<Settings>().is_none());
+    assert!(enigo
+        .downcast_mut::<crate::platform::linux::Enigo>()
+        .is_some());
+}
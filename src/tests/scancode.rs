@@ -0,0 +1,84 @@
+use crate::Scancode;
+
+#[test]
+fn unit_scancode_conversion() {
+    // The key A in all schemes
+    let a = Scancode::from_hid_usage(0x04);
+    assert_eq!(Scancode::from_evdev(30), a);
+    assert_eq!(Scancode::from_windows_set1(0x1E), a);
+    assert_eq!(Scancode::from_mac_keycode(0x00), a);
+    assert_eq!(a.to_evdev(), Some(30));
+    assert_eq!(a.to_windows_set1(), Some(0x1E));
+    assert_eq!(a.to_mac_keycode(), Some(0x00));
+
+    // Extended keys keep the prefix
+    let right_control = Scancode::from_evdev(97);
+    assert_eq!(right_control.to_windows_set1(), Some(0xE01D));
+    assert_eq!(right_control.to_hid_usage(), Some(0xE4));
+
+    // Keys without an equivalent on macOS
+    assert_eq!(Scancode::from_hid_usage(0x46).to_mac_keycode(), None);
+}
+
+#[test]
+fn unit_scancode_unknown() {
+    // Codes that are not in the table can only be used in their own scheme
+    let unknown = Scancode::from_evdev(0x2FF);
+    assert_eq!(unknown.to_evdev(), Some(0x2FF));
+    assert_eq!(unknown.to_windows_set1(), None);
+    assert_eq!(unknown.to_hid_usage(), None);
+    assert_ne!(unknown, Scancode::from_windows_set1(0x2FF));
+}
+
+#[test]
+fn unit_scancode_round_trip() {
+    for usage in (0..=0xFF).filter(|usage| Scancode::from_hid_usage(*usage).to_evdev().is_some()) {
+        let scancode = Scancode::from_hid_usage(usage);
+        let evdev = scancode.to_evdev().unwrap();
+        let set1 = scancode.to_windows_set1().unwrap();
+        assert_eq!(Scancode::from_evdev(evdev), scancode, "evdev {evdev}");
+        assert_eq!(
+            Scancode::from_windows_set1(set1),
+            scancode,
+            "set1 {set1:#X}"
+        );
+        if let Some(mac) = scancode.to_mac_keycode() {
+            assert_eq!(Scancode::from_mac_keycode(mac), scancode, "mac {mac:#X}");
+        }
+    }
+}
+
+#[test]
+fn unit_scancode_table() {
+    use crate::scancode::{convert, lookup, lookup_name, Scheme, TABLE};
+
+    let schemes = [
+        Scheme::HidUsage,
+        Scheme::Evdev,
+        Scheme::WindowsSet1,
+        Scheme::MacKeycode,
+    ];
+    // Every code and name identifies exactly one key, so the lookups are
+    // bidirectional
+    for entry in TABLE {
+        assert_eq!(lookup_name(entry.name), Some(entry), "{}", entry.name);
+        for scheme in schemes {
+            if let Some(code) = entry.code(scheme) {
+                assert_eq!(
+                    lookup(code, scheme),
+                    Some(entry),
+                    "{} {scheme:?}",
+                    entry.name
+                );
+            }
+        }
+    }
+
+    assert_eq!(
+        convert(0xE01D, Scheme::WindowsSet1, Scheme::Evdev),
+        Some(97)
+    );
+    assert_eq!(convert(30, Scheme::Evdev, Scheme::MacKeycode), Some(0x00));
+    assert_eq!(convert(0x46, Scheme::HidUsage, Scheme::MacKeycode), None);
+    assert_eq!(convert(0x2FF, Scheme::Evdev, Scheme::HidUsage), None);
+}
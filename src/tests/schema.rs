@@ -0,0 +1,66 @@
+use strum::IntoEnumIterator;
+
+use crate::{
+    agent::Token,
+    schema::{
+        key_tags, token_schema, AXIS_TAGS, BUTTON_TAGS, COORDINATE_TAGS, DIRECTION_TAGS, TOKEN_TAGS,
+    },
+    Axis, Button, Coordinate, Direction, Key,
+};
+
+// The tags of the schema have to be the names serde uses
+fn tags<T: serde::Serialize>(values: &[T]) -> Vec<String> {
+    values
+        .iter()
+        .map(|value| {
+            let ron = ron::to_string(value).unwrap();
+            // Only keep the tag of variants with content
+            ron.split(['(', ' ']).next().unwrap().to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn unit_schema_tags() {
+    assert_eq!(tags(&Button::iter().collect::<Vec<_>>()), BUTTON_TAGS);
+    assert_eq!(
+        tags(&[Direction::Press, Direction::Release, Direction::Click]),
+        DIRECTION_TAGS
+    );
+    assert_eq!(tags(&[Axis::Horizontal, Axis::Vertical]), AXIS_TAGS);
+    assert_eq!(tags(&[Coordinate::Abs, Coordinate::Rel]), COORDINATE_TAGS);
+    assert_eq!(tags(Key::ALL), key_tags());
+
+    let tokens = [
+        Token::Text(String::new()),
+        Token::Key(Key::Unicode('a'), Direction::Click),
+        Token::Raw(0, Direction::Click),
+        Token::Button(Button::Left, Direction::Click),
+        Token::MoveMouse(0, 0, Coordinate::Abs),
+        Token::Scroll(0, Axis::Vertical),
+        Token::Location(0, 0),
+        Token::MainDisplay(0, 0),
+        Token::Sleep(0),
+        Token::WithModifiers {
+            modifiers: vec![],
+            body: vec![],
+        },
+    ];
+    assert_eq!(tags(&tokens), TOKEN_TAGS);
+}
+
+#[test]
+fn unit_schema_contains_tags() {
+    let schema = token_schema();
+    for tag in key_tags()
+        .iter()
+        .map(String::as_str)
+        .chain(BUTTON_TAGS.iter().copied())
+        .chain(TOKEN_TAGS.iter().copied())
+    {
+        assert!(schema.contains(&format!("\"{tag}\"")), "missing {tag}");
+    }
+    // Every opened bracket has to be closed
+    assert_eq!(schema.matches('{').count(), schema.matches('}').count());
+    assert_eq!(schema.matches('[').count(), schema.matches(']').count());
+}
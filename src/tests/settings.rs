@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use crate::{
+    ImeStrategy, RateLimitBehavior, RelativeMode, Settings, SettingsBuilder, SettingsError,
+    TapLocation, TextEntryMethod,
+};
+
+#[test]
+fn unit_settings_builder() {
+    assert_eq!(Settings::builder().build().unwrap(), Settings::default());
+
+    let settings = Settings::builder()
+        .release_keys_when_dropped(false)
+        .rate_limit(100, RateLimitBehavior::Error)
+        .windows(|w| {
+            w.dw_extra_info(42)
+                .text_entry_method(TextEntryMethod::AltNumpad)
+                .keyboard_layout("00000407")
+        })
+        .macos(|m| {
+            m.event_source_user_data(-1)
+                .tap_location(TapLocation::Session)
+        })
+        .linux(|l| {
+            l.delay(0)
+                .x11_display(":1")
+                .connection_timeout(Duration::from_secs(5))
+        })
+        .build()
+        .unwrap();
+    let expected = Settings {
+        release_keys_when_dropped: false,
+        max_events_per_second: Some(100),
+        rate_limit_behavior: RateLimitBehavior::Error,
+        windows_dw_extra_info: Some(42),
+        windows_text_entry_method: TextEntryMethod::AltNumpad,
+        windows_keyboard_layout: Some("00000407".to_string()),
+        event_source_user_data: Some(-1),
+        macos_tap_location: TapLocation::Session,
+        linux_delay: 0,
+        x11_display: Some(":1".to_string()),
+        connection_timeout: Some(Duration::from_secs(5)),
+        ..Default::default()
+    };
+    assert_eq!(settings, expected);
+
+    // Existing settings can be changed with the builder
+    let changed = SettingsBuilder::from(expected)
+        .linux(|l| l.delay(12))
+        .build()
+        .unwrap();
+    assert_eq!(changed.linux_delay, 12);
+    assert_eq!(changed.windows_dw_extra_info, Some(42));
+}
+
+#[test]
+fn unit_settings_validate() {
+    let conflict = Settings::builder()
+        .windows(|w| {
+            w.ime_strategy(ImeStrategy::ImeChar)
+                .text_entry_method(TextEntryMethod::AltNumpad)
+        })
+        .build();
+    assert!(matches!(conflict, Err(SettingsError::Conflict(_, _))));
+
+    let conflict = Settings::builder()
+        .windows(|w| w.subject_to_mouse_speed_and_acceleration_level(true))
+        .build();
+    assert!(matches!(conflict, Err(SettingsError::Conflict(_, _))));
+    assert!(Settings::builder()
+        .relative_mode(RelativeMode::Raw)
+        .windows(|w| w.subject_to_mouse_speed_and_acceleration_level(true))
+        .build()
+        .is_ok());
+
+    for invalid in [
+        Settings::builder().rate_limit(0, RateLimitBehavior::Block),
+        Settings::builder().double_click_interval(Duration::ZERO),
+        Settings::builder().linux(|l| l.connection_timeout(Duration::ZERO)),
+        Settings::builder().windows(|w| w.keyboard_layout("German")),
+    ] {
+        assert!(matches!(invalid.build(), Err(SettingsError::Invalid(_, _))));
+    }
+
+    let settings = Settings {
+        rate_limit_behavior: RateLimitBehavior::Error,
+        ..Default::default()
+    };
+    assert!(matches!(
+        settings.validate(),
+        Err(SettingsError::Invalid("rate_limit_behavior", _))
+    ));
+}
+
+#[cfg(all(unix, not(target_os = "macos"), not(feature = "libei")))] // Needs Settings::lazy_connect
+#[test]
+fn unit_update_settings() {
+    // Without a connection, the Enigo struct can be created on any machine
+    let settings = Settings {
+        lazy_connect: true,
+        x11_display: Some(":9999".to_string()),
+        ..Default::default()
+    };
+    let mut enigo = crate::Enigo::new(&settings).unwrap();
+
+    let invalid = Settings {
+        double_click_interval: Some(Duration::ZERO),
+        ..settings.clone()
+    };
+    assert!(enigo.update_settings(&invalid).is_err());
+    assert_eq!(enigo.double_click_interval(), Duration::from_millis(400));
+
+    let updated = Settings {
+        double_click_interval: Some(Duration::from_millis(250)),
+        ..settings
+    };
+    enigo.update_settings(&updated).unwrap();
+    assert_eq!(enigo.double_click_interval(), Duration::from_millis(250));
+}
@@ -0,0 +1,39 @@
+use tungstenite::{Message, Utf8Bytes};
+
+use crate::test_harness::BrowserEvent;
+
+#[test]
+fn unit_deserialize_browser_events() {
+    let messages = vec![
+        ("ReadyForText", BrowserEvent::ReadyForText),
+        (
+            "Text(\"Testing\")",
+            BrowserEvent::Text("Testing".to_string()),
+        ),
+        (
+            "Text(\"Hi how are you?❤️ äüß$3\")",
+            BrowserEvent::Text("Hi how are you?❤️ äüß$3".to_string()),
+        ),
+        (
+            "KeyDown(\"F11\",\"F11\")",
+            BrowserEvent::KeyDown("F11".to_string(), "F11".to_string()),
+        ),
+        (
+            "KeyUp(\"a\",\"KeyA\")",
+            BrowserEvent::KeyUp("a".to_string(), "KeyA".to_string()),
+        ),
+        ("MouseDown(0)", BrowserEvent::MouseDown(0)),
+        ("MouseUp(0)", BrowserEvent::MouseUp(0)),
+        (
+            "MouseMove((-1806,-487),(200,200))",
+            BrowserEvent::MouseMove((-1806, -487), (200, 200)),
+        ),
+        ("MouseScroll(3, -2)", BrowserEvent::MouseScroll(3, -2)),
+    ];
+
+    for (msg, event) in messages {
+        let msg = Message::Text(Utf8Bytes::from(msg));
+        assert_eq!(BrowserEvent::try_from(msg).unwrap(), event);
+    }
+    assert!(BrowserEvent::try_from(Message::Text(Utf8Bytes::from("Unknown"))).is_err());
+}
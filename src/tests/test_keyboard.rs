@@ -0,0 +1,89 @@
+use crate::{
+    test_keyboard::TestKeyboard,
+    Direction::{Click, Press, Release},
+    InputError, Key, Keyboard,
+};
+
+#[test]
+fn unit_test_keyboard_case() {
+    let mut keyboard = TestKeyboard::new();
+    keyboard.key(Key::Unicode('a'), Click).unwrap();
+    keyboard.key(Key::LShift, Press).unwrap();
+    keyboard.key(Key::Unicode('b'), Click).unwrap();
+    keyboard.key(Key::LShift, Release).unwrap();
+    keyboard.key(Key::CapsLock, Click).unwrap();
+    keyboard.key(Key::Unicode('c'), Click).unwrap();
+    keyboard.key(Key::Shift, Press).unwrap();
+    keyboard.key(Key::Unicode('d'), Click).unwrap();
+    keyboard.key(Key::Unicode('1'), Click).unwrap();
+    keyboard.key(Key::Shift, Release).unwrap();
+    keyboard.key(Key::CapsLock, Click).unwrap();
+    keyboard.key(Key::Unicode('e'), Click).unwrap();
+    assert_eq!(keyboard.output(), "aBCd1e");
+}
+
+#[test]
+fn unit_test_keyboard_shortcut() {
+    let mut keyboard = TestKeyboard::new();
+    keyboard.key(Key::Control, Press).unwrap();
+    keyboard.key(Key::Unicode('c'), Click).unwrap();
+    keyboard.key(Key::Control, Release).unwrap();
+    keyboard.key(Key::Alt, Press).unwrap();
+    keyboard.key(Key::Tab, Click).unwrap();
+    keyboard.key(Key::Alt, Release).unwrap();
+    keyboard.key(Key::Unicode('v'), Click).unwrap();
+    assert_eq!(keyboard.output(), "v");
+}
+
+#[test]
+fn unit_test_keyboard_dead_keys() {
+    let mut keyboard = TestKeyboard::new();
+    keyboard.key(Key::Unicode('\u{301}'), Click).unwrap();
+    keyboard.key(Key::Unicode('e'), Click).unwrap();
+    keyboard.key(Key::Unicode('\u{308}'), Click).unwrap();
+    keyboard.key(Key::Unicode('x'), Click).unwrap();
+    keyboard.key(Key::Unicode('\u{302}'), Click).unwrap();
+    keyboard.key(Key::Backspace, Click).unwrap();
+    keyboard.key(Key::Unicode('\u{30C}'), Click).unwrap();
+    keyboard.text("s").unwrap();
+    assert_eq!(keyboard.take_output(), "éx\u{308}š");
+    assert_eq!(keyboard.output(), "");
+}
+
+#[test]
+// Combining marks in text follow their base character instead of acting as
+// dead keys
+fn unit_test_keyboard_combining_text() {
+    let mut keyboard = TestKeyboard::new();
+    keyboard.text("e\u{301}a\u{308}\u{301}").unwrap();
+    assert_eq!(keyboard.take_output(), "e\u{301}a\u{308}\u{301}");
+
+    // A pending dead key is released before the combining mark
+    keyboard.key(Key::Unicode('\u{300}'), Click).unwrap();
+    keyboard.text("\u{301}o").unwrap();
+    assert_eq!(keyboard.output(), "\u{300}\u{301}o");
+}
+
+#[test]
+fn unit_test_keyboard_control_keys() {
+    let mut keyboard = TestKeyboard::new();
+    keyboard.text("ab").unwrap();
+    keyboard.key(Key::Backspace, Click).unwrap();
+    keyboard.key(Key::Return, Click).unwrap();
+    keyboard.key(Key::Tab, Click).unwrap();
+    keyboard.key(Key::Space, Click).unwrap();
+    keyboard.key(Key::Escape, Click).unwrap();
+    keyboard.text("c\r\u{7}").unwrap();
+    assert_eq!(keyboard.output(), "a\n\t c\n");
+}
+
+#[test]
+fn unit_test_keyboard_raw() {
+    let mut keyboard = TestKeyboard::new();
+    assert!(matches!(
+        keyboard.raw(38, Click),
+        Err(InputError::InvalidInput(_))
+    ));
+    assert_eq!(keyboard.active_layout(), None);
+    assert_eq!(keyboard.output(), "");
+}
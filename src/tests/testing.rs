@@ -0,0 +1,41 @@
+use crate::{
+    testing::{Server, VirtualDisplay, NO_DISPLAY},
+    Enigo, Settings,
+};
+
+#[test]
+fn unit_server_program() {
+    assert_eq!(Server::Xvfb.program(), "Xvfb");
+    assert_eq!(Server::Weston.program(), "weston");
+    assert!(Server::Xvfb.is_x11());
+    assert!(Server::Xephyr.is_x11());
+    assert!(!Server::Weston.is_x11());
+}
+
+#[test]
+// Check that the backend that is not used by the server can't connect to the
+// session of the user
+fn unit_no_display() {
+    let settings = Settings {
+        x11_display: Some(NO_DISPLAY.to_string()),
+        wayland_display: Some(NO_DISPLAY.to_string()),
+        ..Default::default()
+    };
+    assert!(Enigo::new(&settings).is_err());
+}
+
+#[ignore] // Needs Xvfb to be installed
+#[test]
+fn unit_virtual_display_xvfb() {
+    let display = VirtualDisplay::start(Server::Xvfb).unwrap();
+    assert!(display.name().starts_with(':'));
+    assert_eq!(
+        display.settings().x11_display.as_deref(),
+        Some(display.name())
+    );
+    assert_eq!(
+        display.settings().wayland_display.as_deref(),
+        Some(NO_DISPLAY)
+    );
+    display.validate().unwrap();
+}
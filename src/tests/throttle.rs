@@ -0,0 +1,32 @@
+use std::time::{Duration, Instant};
+
+use crate::{throttle::Throttle, InputError, RateLimitBehavior, Settings};
+
+#[test]
+fn unit_throttle_error() {
+    let settings = Settings {
+        max_events_per_second: Some(2),
+        rate_limit_behavior: RateLimitBehavior::Error,
+        ..Default::default()
+    };
+    let mut throttle = Throttle::new(&settings).unwrap();
+    assert_eq!(throttle.acquire(), Ok(()));
+    assert_eq!(throttle.acquire(), Ok(()));
+    assert_eq!(throttle.acquire(), Err(InputError::RateLimited));
+}
+
+#[test]
+fn unit_throttle_block() {
+    let settings = Settings {
+        max_events_per_second: Some(10),
+        ..Default::default()
+    };
+    let mut throttle = Throttle::new(&settings).unwrap();
+    let start = Instant::now();
+    for _ in 0..12 {
+        throttle.acquire().unwrap();
+    }
+    // The first 10 events are a burst, the other two have to wait 100 ms each
+    assert!(start.elapsed() >= Duration::from_millis(190));
+    assert!(Throttle::new(&Settings::default()).is_none());
+}
@@ -0,0 +1,198 @@
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use crate::{
+    webdriver::{
+        json_number_field, json_string, json_string_field, parse_response, Response, WebDriver,
+    },
+    Coordinate, Direction, Key, Keyboard, Mouse,
+};
+
+/// Starts a fake WebDriver server that answers every request with the body and
+/// sends the received requests to the channel. It also counts the connections
+/// that were opened
+fn fake_server(body: &'static str) -> (String, mpsc::Receiver<String>, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+            // Answer the requests until the client closes the connection
+            'requests: loop {
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                // Read until the whole body was received
+                loop {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break 'requests;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let len = head
+                            .lines()
+                            .find_map(|l| l.strip_prefix("Content-Length: "))
+                            .map_or(0, |l| l.parse().unwrap());
+                        if body.len() >= len {
+                            break;
+                        }
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+                if tx
+                    .send(String::from_utf8_lossy(&request).to_string())
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        }
+    });
+    (url, rx, connections)
+}
+
+#[test]
+fn unit_webdriver_actions() {
+    let (url, requests, connections) = fake_server(r#"{"value":{"width":1280,"height":720.0}}"#);
+    let mut driver = WebDriver::new(&url, "abc").unwrap();
+
+    driver.key(Key::Unicode('"'), Direction::Click).unwrap();
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("POST /session/abc/actions HTTP/1.1\r\n"));
+    assert!(request.contains(r#"{"type":"keyDown","value":"\""},{"type":"keyUp","value":"\""}"#));
+
+    driver.key(Key::Return, Direction::Press).unwrap();
+    assert!(requests
+        .recv()
+        .unwrap()
+        .contains("[{\"type\":\"keyDown\",\"value\":\"\u{E006}\"}]"));
+
+    driver.move_mouse(10, 20, Coordinate::Abs).unwrap();
+    assert!(requests
+        .recv()
+        .unwrap()
+        .contains(r#""origin":"viewport","x":10,"y":20"#));
+    driver.move_mouse(-5, 5, Coordinate::Rel).unwrap();
+    assert!(requests
+        .recv()
+        .unwrap()
+        .contains(r#""origin":"pointer","x":-5,"y":5"#));
+    assert_eq!(driver.location(), Ok((5, 25)));
+
+    assert_eq!(driver.main_display(), Ok((1280, 720)));
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("GET /session/abc/window/rect HTTP/1.1\r\n"));
+
+    assert!(driver.raw(1, Direction::Click).is_err());
+
+    // The held keys are released when it is dropped
+    drop(driver);
+    assert!(requests
+        .recv()
+        .unwrap()
+        .starts_with("DELETE /session/abc/actions HTTP/1.1\r\n"));
+    // All requests were sent over the same connection
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+// Check that the request is sent again if the server closed the connection
+// that was kept alive
+fn unit_webdriver_reconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        // Answer one request per connection and close it without telling the
+        // client
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut buf = [0; 4096];
+            let n = stream.read(&mut buf).unwrap();
+            let body = r#"{"value":{"width":800,"height":600}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            if tx
+                .send(String::from_utf8_lossy(&buf[..n]).to_string())
+                .is_err()
+            {
+                return;
+            }
+        }
+    });
+    let driver = WebDriver::new(&url, "abc").unwrap();
+    assert_eq!(driver.main_display(), Ok((800, 600)));
+    assert_eq!(driver.main_display(), Ok((800, 600)));
+    assert!(rx
+        .recv()
+        .unwrap()
+        .starts_with("GET /session/abc/window/rect"));
+    assert!(rx
+        .recv()
+        .unwrap()
+        .starts_with("GET /session/abc/window/rect"));
+}
+
+#[test]
+fn unit_webdriver_json() {
+    assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\u000a""#);
+    let json = r#"{"value": {"sessionId" : "1234-ab", "width":1280.4}}"#;
+    assert_eq!(
+        json_string_field(json, "sessionId").as_deref(),
+        Some("1234-ab")
+    );
+    assert_eq!(json_number_field(json, "width"), Some(1280));
+    assert_eq!(json_number_field(json, "height"), None);
+
+    let chunked = "HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n{\"a\"\r\n3\r\n:1}\r\n0\r\n\r\n";
+    assert_eq!(
+        parse_response(&mut chunked.as_bytes()),
+        Some(Response {
+            status: 404,
+            body: "{\"a\":1}".to_string(),
+            close: false,
+        })
+    );
+    assert_eq!(parse_response(&mut "garbage".as_bytes()), None);
+
+    // A character that is split between two chunks
+    let split =
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n1\r\n\xC3\r\n1\r\n\xA4\r\n0\r\n\r\n";
+    assert_eq!(
+        parse_response(&mut &split[..]).map(|r| r.body),
+        Some("ä".to_string())
+    );
+
+    // Only the first response is consumed, so the next one can be read
+    let mut two: &[u8] =
+        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}HTTP/1.0 200 OK\r\n\r\nrest";
+    assert_eq!(
+        parse_response(&mut two).map(|r| (r.body, r.close)),
+        Some(("{}".to_string(), false))
+    );
+    assert_eq!(
+        parse_response(&mut two).map(|r| (r.body, r.close)),
+        Some(("rest".to_string(), true))
+    );
+}
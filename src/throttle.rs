@@ -0,0 +1,56 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::debug;
+
+use crate::{InputError, InputResult, RateLimitBehavior, Settings};
+
+/// Token bucket that limits the number of simulated events per second. The
+/// bucket holds up to one second worth of events, so short bursts are possible
+#[derive(Debug)]
+pub(crate) struct Throttle {
+    max_per_second: f64,
+    available: f64,
+    last_refill: Instant,
+    behavior: RateLimitBehavior,
+}
+
+impl Throttle {
+    /// Returns `None` if the settings do not limit the rate
+    pub(crate) fn new(settings: &Settings) -> Option<Self> {
+        let max_per_second = f64::from(settings.max_events_per_second.filter(|&max| max > 0)?);
+        Some(Self {
+            max_per_second,
+            available: max_per_second,
+            last_refill: Instant::now(),
+            behavior: settings.rate_limit_behavior,
+        })
+    }
+
+    /// Take one event from the bucket. If it is empty, either wait until it
+    /// has one again or return an error depending on the behavior
+    pub(crate) fn acquire(&mut self) -> InputResult<()> {
+        let now = Instant::now();
+        let refill = now.duration_since(self.last_refill).as_secs_f64() * self.max_per_second;
+        self.available = (self.available + refill).min(self.max_per_second);
+        self.last_refill = now;
+
+        if self.available >= 1.0 {
+            self.available -= 1.0;
+            return Ok(());
+        }
+        match self.behavior {
+            RateLimitBehavior::Error => Err(InputError::RateLimited),
+            RateLimitBehavior::Block => {
+                let wait = Duration::from_secs_f64((1.0 - self.available) / self.max_per_second);
+                debug!("rate limit reached. Waiting for {wait:?}");
+                thread::sleep(wait);
+                self.available = 0.0;
+                self.last_refill = Instant::now();
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,609 @@
+//! Simulate input in a browser with the `WebDriver` Actions API
+//!
+//! The [`WebDriver`] struct implements the [`Keyboard`] and [`Mouse`] traits,
+//! but instead of injecting the input into the OS, it sends it to a `WebDriver`
+//! server (e.g. geckodriver or chromedriver). The input only reaches the
+//! browser that is controlled by the session. It works without a display and
+//! without any permissions, so test suites can use the same code to drive a
+//! headless browser in a container that they use with [`crate::Enigo`] on a
+//! desktop.
+//!
+//! Only plain HTTP is supported, which is what the drivers listen on locally.
+//! The connection to the server is kept alive and reused for all requests.
+//!
+//! ```no_run
+//! use enigo::{webdriver::WebDriver, Button, Coordinate, Direction, Keyboard, Mouse};
+//!
+//! // Start e.g. `geckodriver --port 4444` first
+//! let mut driver =
+//!     WebDriver::new_session("http://127.0.0.1:4444", r#"{"capabilities":{}}"#).unwrap();
+//! driver.move_mouse(100, 200, Coordinate::Abs).unwrap();
+//! driver.button(Button::Left, Direction::Click).unwrap();
+//! driver.text("Hello World").unwrap();
+//! ```
+
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::TcpStream,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use log::{debug, error};
+
+use crate::{
+    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse, LINE_HEIGHT,
+};
+
+/// How long a request to the `WebDriver` server may take
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Input sources of the actions. The server keeps the state of each source
+/// (e.g. the pressed keys) between requests
+const KEYBOARD_ID: &str = "enigo-keyboard";
+const MOUSE_ID: &str = "enigo-mouse";
+const WHEEL_ID: &str = "enigo-wheel";
+
+/// Simulates input in the browser of a `WebDriver` session
+#[derive(Debug)]
+pub struct WebDriver {
+    connection: Connection,
+    session_id: String,
+    owns_session: bool,
+    location: (i32, i32),
+}
+
+impl WebDriver {
+    /// Use an existing session of the `WebDriver` server at the url (e.g.
+    /// `http://127.0.0.1:4444`)
+    ///
+    /// # Errors
+    /// Returns an error if the url is not a plain HTTP url
+    pub fn new(url: &str, session_id: &str) -> InputResult<Self> {
+        Ok(Self {
+            connection: Connection::new(address(url)?),
+            session_id: session_id.to_string(),
+            owns_session: false,
+            location: (0, 0),
+        })
+    }
+
+    /// Create a new session with the capabilities (the JSON body of the "New
+    /// Session" command). The session is deleted when the struct is dropped
+    ///
+    /// # Errors
+    /// Returns an error if the url is not a plain HTTP url or the server did
+    /// not create a session
+    pub fn new_session(url: &str, capabilities: &str) -> InputResult<Self> {
+        let connection = Connection::new(address(url)?);
+        let response = connection.request("POST", "/session", capabilities)?;
+        let Some(session_id) = json_string_field(&response, "sessionId") else {
+            error!("the response did not contain a session id: {response}");
+            return Err(InputError::Simulate("unable to create a WebDriver session"));
+        };
+        debug!("created the WebDriver session {session_id}");
+        Ok(Self {
+            connection,
+            session_id,
+            owns_session: true,
+            location: (0, 0),
+        })
+    }
+
+    /// Returns the id of the session
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Release all keys and buttons that are still held
+    ///
+    /// # Errors
+    /// Returns an error if the request failed
+    pub fn release_all(&mut self) -> InputResult<()> {
+        let path = format!("/session/{}/actions", self.session_id);
+        self.connection.request("DELETE", &path, "").map(|_| ())
+    }
+
+    /// Perform the actions of one input source
+    fn perform(&self, source: &str, actions: &[String]) -> InputResult<()> {
+        let body = format!(
+            r#"{{"actions":[{{{source},"actions":[{}]}}]}}"#,
+            actions.join(",")
+        );
+        let path = format!("/session/{}/actions", self.session_id);
+        self.connection.request("POST", &path, &body).map(|_| ())
+    }
+}
+
+impl Drop for WebDriver {
+    fn drop(&mut self) {
+        if let Err(e) = self.release_all() {
+            error!("unable to release the held keys and buttons: {e}");
+        }
+        if self.owns_session {
+            let path = format!("/session/{}", self.session_id);
+            if let Err(e) = self.connection.request("DELETE", &path, "") {
+                error!("unable to delete the WebDriver session: {e}");
+            }
+        }
+    }
+}
+
+impl Keyboard for WebDriver {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        debug!("\x1b[93mfast_text(text: {text})\x1b[0m");
+        let mut actions = Vec::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            let value = json_string(&c.to_string());
+            actions.push(format!(r#"{{"type":"keyDown","value":{value}}}"#));
+            actions.push(format!(r#"{{"type":"keyUp","value":{value}}}"#));
+        }
+        self.perform(&key_source(), &actions)?;
+        Ok(Some(()))
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        debug!("\x1b[93mkey(key: {key:?}, direction: {direction:?})\x1b[0m");
+        let value = json_string(&key_value(key)?.to_string());
+        let mut actions = Vec::with_capacity(2);
+        if direction == Direction::Press || direction == Direction::Click {
+            actions.push(format!(r#"{{"type":"keyDown","value":{value}}}"#));
+        }
+        if direction == Direction::Release || direction == Direction::Click {
+            actions.push(format!(r#"{{"type":"keyUp","value":{value}}}"#));
+        }
+        self.perform(&key_source(), &actions)
+    }
+
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        debug!("\x1b[93mraw(keycode: {keycode}, direction: {direction:?})\x1b[0m");
+        Err(InputError::InvalidInput(
+            "WebDriver does not support raw keycodes",
+        ))
+    }
+}
+
+impl Mouse for WebDriver {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        debug!("\x1b[93mbutton(button: {button:?}, direction: {direction:?})\x1b[0m");
+        let (length, axis) = match button {
+            Button::ScrollUp => (-1, Axis::Vertical),
+            Button::ScrollDown => (1, Axis::Vertical),
+            Button::ScrollLeft => (-1, Axis::Horizontal),
+            Button::ScrollRight => (1, Axis::Horizontal),
+            // The numbers of the buttons in the DOM
+            Button::Left | Button::Middle | Button::Right | Button::Back | Button::Forward => {
+                let number = button as u32;
+                let mut actions = Vec::with_capacity(2);
+                if direction == Direction::Press || direction == Direction::Click {
+                    actions.push(format!(r#"{{"type":"pointerDown","button":{number}}}"#));
+                }
+                if direction == Direction::Release || direction == Direction::Click {
+                    actions.push(format!(r#"{{"type":"pointerUp","button":{number}}}"#));
+                }
+                return self.perform(&mouse_source(), &actions);
+            }
+        };
+        // Scroll buttons don't have a state, so they are only clicked
+        if direction == Direction::Release {
+            return Ok(());
+        }
+        self.scroll(length, axis)
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        debug!("\x1b[93mmove_mouse(x: {x:?}, y: {y:?}, coordinate:{coordinate:?})\x1b[0m");
+        let origin = match coordinate {
+            Coordinate::Abs => "viewport",
+            Coordinate::Rel => "pointer",
+        };
+        let action =
+            format!(r#"{{"type":"pointerMove","duration":0,"origin":"{origin}","x":{x},"y":{y}}}"#);
+        self.perform(&mouse_source(), &[action])?;
+        self.location = match coordinate {
+            Coordinate::Abs => (x, y),
+            Coordinate::Rel => (
+                self.location.0.saturating_add(x),
+                self.location.1.saturating_add(y),
+            ),
+        };
+        Ok(())
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        debug!("\x1b[93mscroll(length: {length:?}, axis: {axis:?})\x1b[0m");
+        let delta = length.saturating_mul(LINE_HEIGHT);
+        let (delta_x, delta_y) = match axis {
+            Axis::Horizontal => (delta, 0),
+            Axis::Vertical => (0, delta),
+        };
+        let (x, y) = self.location;
+        let action = format!(
+            r#"{{"type":"scroll","duration":0,"origin":"viewport","x":{x},"y":{y},"deltaX":{delta_x},"deltaY":{delta_y}}}"#
+        );
+        self.perform(&format!(r#""type":"wheel","id":"{WHEEL_ID}""#), &[action])
+    }
+
+    /// Returns the size of the window of the browser
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        debug!("\x1b[93mmain_display()\x1b[0m");
+        let path = format!("/session/{}/window/rect", self.session_id);
+        let response = self.connection.request("GET", &path, "")?;
+        match (
+            json_number_field(&response, "width"),
+            json_number_field(&response, "height"),
+        ) {
+            (Some(width), Some(height)) => Ok((width, height)),
+            _ => Err(InputError::Simulate(
+                "the WebDriver server did not return the size of the window",
+            )),
+        }
+    }
+
+    /// `WebDriver` can not query the location of the pointer, so the location
+    /// it was last moved to is returned
+    fn location(&self) -> InputResult<(i32, i32)> {
+        debug!("\x1b[93mlocation()\x1b[0m");
+        Ok(self.location)
+    }
+}
+
+fn key_source() -> String {
+    format!(r#""type":"key","id":"{KEYBOARD_ID}""#)
+}
+
+fn mouse_source() -> String {
+    format!(r#""type":"pointer","id":"{MOUSE_ID}","parameters":{{"pointerType":"mouse"}}"#)
+}
+
+/// Returns the value of the key in `WebDriver` (a normalized key value for
+/// special keys)
+#[allow(clippy::match_same_arms)]
+pub(crate) fn key_value(key: Key) -> InputResult<char> {
+    let value = match key {
+        Key::Unicode(c) => c,
+        Key::Help => '\u{E002}',
+        Key::Backspace => '\u{E003}',
+        Key::Tab => '\u{E004}',
+        Key::Return => '\u{E006}',
+        Key::Shift | Key::LShift => '\u{E008}',
+        Key::Control | Key::LControl => '\u{E009}',
+        Key::Alt | Key::Option => '\u{E00A}',
+        Key::Escape => '\u{E00C}',
+        Key::Space => '\u{E00D}',
+        Key::PageUp => '\u{E00E}',
+        Key::PageDown => '\u{E00F}',
+        Key::End => '\u{E010}',
+        Key::Home => '\u{E011}',
+        Key::LeftArrow => '\u{E012}',
+        Key::UpArrow => '\u{E013}',
+        Key::RightArrow => '\u{E014}',
+        Key::DownArrow => '\u{E015}',
+        Key::Delete => '\u{E017}',
+        Key::F1 => '\u{E031}',
+        Key::F2 => '\u{E032}',
+        Key::F3 => '\u{E033}',
+        Key::F4 => '\u{E034}',
+        Key::F5 => '\u{E035}',
+        Key::F6 => '\u{E036}',
+        Key::F7 => '\u{E037}',
+        Key::F8 => '\u{E038}',
+        Key::F9 => '\u{E039}',
+        Key::F10 => '\u{E03A}',
+        Key::F11 => '\u{E03B}',
+        Key::F12 => '\u{E03C}',
+        Key::Meta => '\u{E03D}',
+        Key::RShift => '\u{E050}',
+        Key::RControl => '\u{E051}',
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Cancel => '\u{E001}',
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Clear => '\u{E005}',
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Pause => '\u{E00B}',
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::Insert => '\u{E016}',
+        #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+        Key::LMenu => '\u{E00A}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad0 => '\u{E01A}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad1 => '\u{E01B}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad2 => '\u{E01C}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad3 => '\u{E01D}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad4 => '\u{E01E}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad5 => '\u{E01F}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad6 => '\u{E020}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad7 => '\u{E021}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad8 => '\u{E022}',
+        #[cfg(target_os = "windows")]
+        Key::Numpad9 => '\u{E023}',
+        #[cfg(target_os = "windows")]
+        Key::Multiply => '\u{E024}',
+        #[cfg(target_os = "windows")]
+        Key::Add => '\u{E025}',
+        #[cfg(target_os = "windows")]
+        Key::Separator => '\u{E026}',
+        #[cfg(target_os = "windows")]
+        Key::Subtract => '\u{E027}',
+        #[cfg(target_os = "windows")]
+        Key::Decimal => '\u{E028}',
+        #[cfg(target_os = "windows")]
+        Key::Divide => '\u{E029}',
+        #[cfg(target_os = "windows")]
+        Key::LWin => '\u{E03D}',
+        #[cfg(target_os = "windows")]
+        Key::RWin => '\u{E053}',
+        #[cfg(target_os = "windows")]
+        Key::RMenu => '\u{E052}',
+        #[cfg(target_os = "macos")]
+        Key::RCommand => '\u{E053}',
+        #[cfg(target_os = "macos")]
+        Key::ROption => '\u{E052}',
+        _ => {
+            return Err(InputError::InvalidInput(
+                "the key is not supported by WebDriver",
+            ))
+        }
+    };
+    Ok(value)
+}
+
+/// Returns "host:port" of a plain HTTP url
+fn address(url: &str) -> InputResult<String> {
+    let Some(rest) = url.strip_prefix("http://") else {
+        return Err(InputError::InvalidInput(
+            "the url of the WebDriver server has to start with http://",
+        ));
+    };
+    let host = rest.split('/').next().unwrap_or_default();
+    if host.is_empty() {
+        return Err(InputError::InvalidInput(
+            "the url of the WebDriver server has no host",
+        ));
+    }
+    if host.contains(':') {
+        Ok(host.to_string())
+    } else {
+        Ok(format!("{host}:80"))
+    }
+}
+
+/// Connection to a `WebDriver` server that is kept alive between requests
+#[derive(Debug)]
+struct Connection {
+    address: String,
+    stream: Mutex<Option<BufReader<TcpStream>>>,
+}
+
+impl Connection {
+    fn new(address: String) -> Self {
+        Self {
+            address,
+            stream: Mutex::new(None),
+        }
+    }
+
+    /// Send a request to the `WebDriver` server and return the body of the
+    /// response
+    fn request(&self, method: &str, path: &str, body: &str) -> InputResult<String> {
+        debug!("{method} {path} {body}");
+        let mut request = format!("{method} {path} HTTP/1.1\r\nHost: {}\r\n", self.address);
+        if !body.is_empty() || method == "POST" {
+            let _ = write!(
+                request,
+                "Content-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\n",
+                body.len()
+            );
+        }
+        let _ = write!(request, "\r\n{body}");
+
+        let mut stream = self.stream.lock().unwrap_or_else(PoisonError::into_inner);
+        // The server might have closed the connection since the last request.
+        // The request is only sent again if no part of a response was
+        // received, so it is never performed twice
+        let mut reused = None;
+        if let Some(mut reader) = stream.take() {
+            if let Some(response) = exchange(&mut reader, &request)? {
+                reused = Some((reader, response));
+            } else {
+                debug!("the WebDriver server closed the connection");
+            }
+        }
+        let (reader, response) = if let Some(reused) = reused {
+            reused
+        } else {
+            let mut reader = connect(&self.address)?;
+            let response = exchange(&mut reader, &request)?.ok_or(InputError::Simulate(
+                "no response from the WebDriver server",
+            ))?;
+            (reader, response)
+        };
+        if !response.close {
+            *stream = Some(reader);
+        }
+
+        if response.status != 200 {
+            error!(
+                "the WebDriver server returned {}: {}",
+                response.status, response.body
+            );
+            return Err(InputError::Simulate(
+                "the WebDriver server returned an error",
+            ));
+        }
+        Ok(response.body)
+    }
+}
+
+fn connect(address: &str) -> InputResult<BufReader<TcpStream>> {
+    let stream = TcpStream::connect(address)
+        .map_err(|_| InputError::Simulate("unable to connect to the WebDriver server"))?;
+    let _ = stream.set_read_timeout(Some(TIMEOUT));
+    let _ = stream.set_write_timeout(Some(TIMEOUT));
+    Ok(BufReader::new(stream))
+}
+
+/// Send the request and read the response. Returns `None` if the connection
+/// was closed before any part of the response was received
+fn exchange(reader: &mut BufReader<TcpStream>, request: &str) -> InputResult<Option<Response>> {
+    if reader.get_mut().write_all(request.as_bytes()).is_err() {
+        return Ok(None);
+    }
+    match reader.fill_buf() {
+        Ok([]) => return Ok(None),
+        Err(e)
+            if matches!(
+                e.kind(),
+                ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted
+            ) =>
+        {
+            return Ok(None);
+        }
+        Ok(_) | Err(_) => {}
+    }
+    parse_response(reader).map(Some).ok_or(InputError::Simulate(
+        "invalid response from the WebDriver server",
+    ))
+}
+
+/// HTTP response of the `WebDriver` server
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) body: String,
+    /// The server closes the connection after the response
+    pub(crate) close: bool,
+}
+
+/// Read one HTTP response. Only as many bytes as belong to the response are
+/// consumed, so the next response can be read from the same reader
+pub(crate) fn parse_response(reader: &mut impl BufRead) -> Option<Response> {
+    let mut status_line = Vec::new();
+    reader.read_until(b'\n', &mut status_line).ok()?;
+    let status_line = String::from_utf8_lossy(&status_line);
+    let mut parts = status_line.split(' ');
+    let version = parts.next()?;
+    let status = parts.next()?.trim().parse().ok()?;
+    let mut close = version == "HTTP/1.0";
+    let mut chunked = false;
+    let mut length = None;
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let line = String::from_utf8_lossy(&line);
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim().to_ascii_lowercase();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => length = Some(value.parse::<u64>().ok()?),
+            "transfer-encoding" => chunked = value.ends_with("chunked"),
+            "connection" => close = value == "close",
+            _ => {}
+        }
+    }
+
+    let mut body = Vec::new();
+    if matches!(status, 204 | 304) {
+        // These responses never have a body
+    } else if chunked {
+        loop {
+            let line = read_line(reader)?;
+            let size = String::from_utf8_lossy(&line);
+            let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+            if size == 0 {
+                // Skip the trailers
+                while !read_line(reader)?.is_empty() {}
+                break;
+            }
+            let start = body.len();
+            body.resize(start + size, 0);
+            reader.read_exact(&mut body[start..]).ok()?;
+            if !read_line(reader)?.is_empty() {
+                return None;
+            }
+        }
+    } else if let Some(length) = length {
+        reader.take(length).read_to_end(&mut body).ok()?;
+        if body.len() as u64 != length {
+            return None;
+        }
+    } else {
+        // The end of the body is the end of the connection
+        reader.read_to_end(&mut body).ok()?;
+        close = true;
+    }
+    Some(Response {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned(),
+        close,
+    })
+}
+
+/// Read a line that ends with CRLF and return it without the line ending.
+/// Returns `None` if the reader ended before the line did
+fn read_line(reader: &mut impl BufRead) -> Option<Vec<u8>> {
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line).ok()?;
+    let line = line.strip_suffix(b"\n")?;
+    Some(line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+}
+
+/// Returns the string as a JSON string literal
+pub(crate) fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", u32::from(c));
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Returns the value of the first field with the name if it is a string
+/// without escape sequences
+pub(crate) fn json_string_field(json: &str, name: &str) -> Option<String> {
+    let value = json_field(json, name)?.strip_prefix('"')?;
+    let end = value.find('"')?;
+    let value = &value[..end];
+    if value.contains('\\') {
+        return None;
+    }
+    Some(value.to_string())
+}
+
+/// Returns the value of the first field with the name if it is a number
+pub(crate) fn json_number_field(json: &str, name: &str) -> Option<i32> {
+    let value = json_field(json, name)?;
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+        .unwrap_or(value.len());
+    let number: f64 = value[..end].parse().ok()?;
+    // Truncating is fine for the size of a window
+    Some(number.round() as i32)
+}
+
+/// Returns the JSON after the colon of the first field with the name
+fn json_field<'a>(json: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("\"{name}\"");
+    let start = json.find(&key)? + key.len();
+    let rest = json[start..].trim_start().strip_prefix(':')?;
+    Some(rest.trim_start())
+}
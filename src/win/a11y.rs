@@ -0,0 +1,95 @@
+use log::error;
+use windows::Win32::{
+    System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_MULTITHREADED,
+    },
+    UI::Accessibility::{
+        CUIAutomation, IUIAutomation, UIA_DocumentControlTypeId, UIA_EditControlTypeId,
+    },
+};
+
+use crate::{a11y::FocusedElement, InputError, InputResult};
+
+/// Programmatic names of the control types. The index is the offset of the id
+/// from `UIA_ButtonControlTypeId` (50000)
+const CONTROL_TYPES: [&str; 41] = [
+    "Button",
+    "Calendar",
+    "CheckBox",
+    "ComboBox",
+    "Edit",
+    "Hyperlink",
+    "Image",
+    "ListItem",
+    "List",
+    "Menu",
+    "MenuBar",
+    "MenuItem",
+    "ProgressBar",
+    "RadioButton",
+    "ScrollBar",
+    "Slider",
+    "Spinner",
+    "StatusBar",
+    "Tab",
+    "TabItem",
+    "Text",
+    "ToolBar",
+    "ToolTip",
+    "Tree",
+    "TreeItem",
+    "Custom",
+    "Group",
+    "Thumb",
+    "DataGrid",
+    "DataItem",
+    "Document",
+    "SplitButton",
+    "Window",
+    "Pane",
+    "Header",
+    "HeaderItem",
+    "Table",
+    "TitleBar",
+    "Separator",
+    "SemanticZoom",
+    "AppBar",
+];
+
+pub(crate) fn focused_element() -> InputResult<FocusedElement> {
+    // COM might already be initialized on this thread. It only has to be
+    // uninitialized if this call initialized it
+    let initialized = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.is_ok();
+    let res = query_focused_element();
+    if initialized {
+        unsafe { CoUninitialize() };
+    }
+    res
+}
+
+fn query_focused_element() -> InputResult<FocusedElement> {
+    let automation: IUIAutomation =
+        unsafe { CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER) }.map_err(|e| {
+            error!("{e}");
+            InputError::Simulate("unable to create the UI Automation client")
+        })?;
+    let element = unsafe { automation.GetFocusedElement() }
+        .map_err(|_| InputError::Simulate("no element has the focus"))?;
+    let control_type = unsafe { element.CurrentControlType() }
+        .map_err(|_| InputError::Simulate("unable to get the control type"))?;
+    let role = usize::try_from(control_type.0 - 50000)
+        .ok()
+        .and_then(|idx| CONTROL_TYPES.get(idx))
+        .map_or_else(|| control_type.0.to_string(), |name| (*name).to_string());
+    let name = unsafe { element.CurrentName() }
+        .ok()
+        .map(|name| name.to_string())
+        .filter(|name| !name.is_empty());
+    Ok(FocusedElement {
+        role,
+        name,
+        editable: control_type == UIA_EditControlTypeId
+            || control_type == UIA_DocumentControlTypeId,
+    })
+}
@@ -0,0 +1,107 @@
+use log::debug;
+use windows::core::PWSTR;
+use windows::Win32::Foundation::{ERROR_ACCESS_DENIED, HANDLE};
+use windows::Win32::System::RemoteDesktop::{
+    WTSActive, WTSFreeMemory, WTSQuerySessionInformationW, WTSSessionInfoEx, WTSINFOEXW,
+    WTS_CURRENT_SERVER_HANDLE, WTS_CURRENT_SESSION, WTS_SESSIONSTATE_LOCK,
+};
+use windows::Win32::System::StationsAndDesktops::{
+    CloseDesktop, GetUserObjectInformationW, OpenInputDesktop, DESKTOP_CONTROL_FLAGS,
+    DESKTOP_READOBJECTS, UOI_NAME,
+};
+
+use super::win_impl::foreground_blocked_by_uipi;
+use crate::health::HealthIssue;
+
+/// Name of the desktop of the interactive user. All other desktops (e.g.
+/// "Winlogon" or "Screen-saver") are secure desktops
+const DEFAULT_DESKTOP: &str = "Default";
+
+/// Name of the desktop that receives the input. Returns `Err(true)` if the
+/// access to it was denied, which happens if it is a secure desktop
+fn input_desktop_name() -> Result<String, bool> {
+    let desktop = match unsafe {
+        OpenInputDesktop(DESKTOP_CONTROL_FLAGS::default(), false, DESKTOP_READOBJECTS)
+    } {
+        Ok(desktop) => desktop,
+        Err(e) => {
+            debug!("unable to open the input desktop: {e}");
+            return Err(e.code() == ERROR_ACCESS_DENIED.to_hresult());
+        }
+    };
+    let mut name = [0u16; 256];
+    let mut len = 0;
+    let size = u32::try_from(std::mem::size_of_val(&name)).unwrap_or(u32::MAX);
+    let res = unsafe {
+        GetUserObjectInformationW(
+            HANDLE(desktop.0),
+            UOI_NAME,
+            Some(name.as_mut_ptr().cast()),
+            size,
+            Some(&raw mut len),
+        )
+    };
+    let _ = unsafe { CloseDesktop(desktop) };
+    if let Err(e) = res {
+        debug!("unable to get the name of the input desktop: {e}");
+        return Err(false);
+    }
+    let end = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    Ok(String::from_utf16_lossy(&name[..end]))
+}
+
+/// Returns if the current session is locked and if it is the active session
+/// (connected to the console or a remote desktop client)
+fn session_state() -> Option<(bool, bool)> {
+    let mut buffer = PWSTR::null();
+    let mut len = 0;
+    if let Err(e) = unsafe {
+        WTSQuerySessionInformationW(
+            WTS_CURRENT_SERVER_HANDLE,
+            WTS_CURRENT_SESSION,
+            WTSSessionInfoEx,
+            &raw mut buffer,
+            &raw mut len,
+        )
+    } {
+        debug!("unable to get the state of the session: {e}");
+        return None;
+    }
+    if buffer.is_null() {
+        return None;
+    }
+    // The buffer is allocated by the OS, so it is not guaranteed to be aligned
+    let info = unsafe { buffer.0.cast::<WTSINFOEXW>().read_unaligned() };
+    unsafe { WTSFreeMemory(buffer.0.cast()) };
+    if info.Level != 1 {
+        return None;
+    }
+    let level1 = unsafe { info.Data.WTSInfoExLevel1 };
+    let locked = u32::try_from(level1.SessionFlags).is_ok_and(|f| f == WTS_SESSIONSTATE_LOCK);
+    Some((locked, level1.SessionState == WTSActive))
+}
+
+pub(crate) fn issues() -> Vec<HealthIssue> {
+    let mut issues = Vec::new();
+    match input_desktop_name() {
+        Ok(name) if name.eq_ignore_ascii_case(DEFAULT_DESKTOP) => {}
+        Ok(name) => {
+            debug!("the input desktop is {name}");
+            issues.push(HealthIssue::SecureDesktop);
+        }
+        Err(true) => issues.push(HealthIssue::SecureDesktop),
+        Err(false) => {}
+    }
+    if let Some((locked, active)) = session_state() {
+        if locked {
+            issues.push(HealthIssue::SessionLocked);
+        }
+        if !active {
+            issues.push(HealthIssue::SessionInactive);
+        }
+    }
+    if foreground_blocked_by_uipi() {
+        issues.push(HealthIssue::BlockedByUipi);
+    }
+    issues
+}
@@ -0,0 +1,68 @@
+use std::{
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+};
+
+use log::{debug, error};
+use windows::Win32::System::Power::{
+    SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+};
+
+use crate::{InputError, InputResult};
+
+/// The execution state belongs to a thread, so a thread of its own holds it
+/// until the inhibitor is dropped. That way it does not matter which thread
+/// drops it
+#[derive(Debug)]
+pub(crate) struct Inhibitor {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Inhibitor {
+    pub(crate) fn new(reason: &str) -> InputResult<Self> {
+        debug!("keep the system awake because of: {reason}");
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let (started_tx, started_rx) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("enigo-keep-awake".to_string())
+            .spawn(move || {
+                let previous = unsafe {
+                    SetThreadExecutionState(
+                        ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED,
+                    )
+                };
+                let success = previous.0 != 0;
+                let _ = started_tx.send(success);
+                if !success {
+                    return;
+                }
+                // Returns an error once the sender was dropped
+                let _ = stop_rx.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            })
+            .map_err(|_| InputError::Simulate("unable to spawn a thread"))?;
+
+        if started_rx.recv() != Ok(true) {
+            let _ = thread.join();
+            return Err(InputError::Simulate(
+                "unable to set the execution state of the thread",
+            ));
+        }
+        Ok(Self {
+            stop: Some(stop_tx),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("the thread keeping the system awake panicked");
+            }
+        }
+    }
+}
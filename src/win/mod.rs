@@ -1,2 +1,7 @@
+#[cfg(feature = "a11y")]
+pub(crate) mod a11y;
+pub(crate) mod health;
+pub(crate) mod keep_awake;
+pub(crate) mod system;
 mod win_impl;
-pub use win_impl::{set_dpi_awareness, Enigo, EXT};
+pub use win_impl::{set_dpi_awareness, Enigo, ForegroundTarget, MouseAcceleration, Tagged, EXT};
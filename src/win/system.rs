@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use windows::Win32::{
+    System::SystemInformation::GetTickCount,
+    UI::{
+        HiDpi::GetDpiForSystem,
+        Input::KeyboardAndMouse::{GetDoubleClickTime, GetLastInputInfo, LASTINPUTINFO},
+    },
+};
+
+use super::Enigo;
+use crate::{InputError, InputResult};
+
+pub(crate) fn mouse_speed() -> InputResult<f64> {
+    Ok(Enigo::mouse_acceleration()?.speed.into())
+}
+
+pub(crate) fn mouse_acceleration_enabled() -> InputResult<bool> {
+    Ok(Enigo::mouse_acceleration()?.level != 0)
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn double_click_interval() -> InputResult<Duration> {
+    Ok(Duration::from_millis(
+        unsafe { GetDoubleClickTime() }.into(),
+    ))
+}
+
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn screen_dpi() -> InputResult<f64> {
+    Ok(unsafe { GetDpiForSystem() }.into())
+}
+
+pub(crate) fn idle_time() -> InputResult<Duration> {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&raw mut info) }.as_bool() {
+        return Err(InputError::Simulate(
+            "unable to get the time of the last input",
+        ));
+    }
+    // Both are ticks since the start of the system that wrap around after 49.7 days
+    let idle = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+    Ok(Duration::from_millis(idle.into()))
+}
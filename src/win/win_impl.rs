@@ -1,44 +1,430 @@
-use std::mem::size_of;
+use std::{
+    cell::Cell,
+    mem::size_of,
+    sync::{Arc, Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
 
 use log::{debug, error, info, warn};
-use windows::Win32::Foundation::POINT;
+use windows::core::{PCSTR, PCWSTR};
+use windows::Win32::Foundation::{CloseHandle, BOOL, HANDLE, HWND, LPARAM, POINT, RECT, WPARAM};
+use windows::Win32::Globalization::{
+    GetLocaleInfoEx, LCIDToLocaleName, WideCharToMultiByte, CP_ACP, LOCALE_ALLOW_NEUTRAL_NAMES,
+    LOCALE_SLOCALIZEDDISPLAYNAME, WC_NO_BEST_FIT_CHARS,
+};
+use windows::Win32::Security::{
+    GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation, TokenIntegrityLevel,
+    TokenUIAccess, TOKEN_MANDATORY_LABEL, TOKEN_QUERY,
+};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+};
 use windows::Win32::UI::{
+    Input::Ime::{ImmGetDefaultIMEWnd, IMC_SETOPENSTATUS},
     Input::KeyboardAndMouse::{
-        GetKeyboardLayout, MapVirtualKeyExW, SendInput, HKL, INPUT, INPUT_0, INPUT_KEYBOARD,
-        INPUT_MOUSE, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP,
-        KEYEVENTF_SCANCODE, KEYEVENTF_UNICODE, MAPVK_VK_TO_VSC_EX, MAPVK_VSC_TO_VK_EX,
+        ActivateKeyboardLayout, GetAsyncKeyState, GetDoubleClickTime, GetKeyboardLayout,
+        GetKeyboardLayoutNameW, LoadKeyboardLayoutW, MapVirtualKeyExW, SendInput,
+        ACTIVATE_KEYBOARD_LAYOUT_FLAGS, HKL, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE,
+        KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_SCANCODE,
+        KEYEVENTF_UNICODE, KLF_NOTELLSHELL, MAPVK_VK_TO_VSC_EX, MAPVK_VSC_TO_VK_EX,
         MAP_VIRTUAL_KEY_TYPE, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_HWHEEL, MOUSEEVENTF_LEFTDOWN,
         MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE,
         MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_WHEEL, MOUSEEVENTF_XDOWN,
-        MOUSEEVENTF_XUP, MOUSEINPUT, MOUSE_EVENT_FLAGS, VIRTUAL_KEY,
+        MOUSEEVENTF_XUP, MOUSEINPUT, MOUSE_EVENT_FLAGS, VIRTUAL_KEY, VK_CONTROL, VK_MENU,
     },
     WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId},
 };
 
 use windows::Win32::UI::WindowsAndMessaging::{
-    GetCursorPos, GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN, WHEEL_DELTA,
+    ClipCursor, CreateCursor, SetSystemCursor, OCR_APPSTARTING, OCR_CROSS, OCR_HAND, OCR_IBEAM,
+    OCR_NO, OCR_NORMAL, OCR_SIZEALL, OCR_SIZENESW, OCR_SIZENS, OCR_SIZENWSE, OCR_SIZEWE, OCR_UP,
+    OCR_WAIT, SPI_SETCURSORS, SYSTEM_CURSOR_ID,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetCursorPos, GetSystemMetrics, SetCursorPos, SystemParametersInfoW, SM_CXSCREEN, SM_CYSCREEN,
+    SM_SWAPBUTTON, SPI_GETMOUSE, SPI_GETMOUSESPEED, SPI_GETWHEELSCROLLCHARS,
+    SPI_GETWHEELSCROLLLINES, SPI_SETMOUSE, SPI_SETMOUSESPEED, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    WHEEL_DELTA,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetGUIThreadInfo, PostMessageW, SendMessageTimeoutW, GUITHREADINFO, SMTO_ABORTIFHUNG,
+    WM_IME_CHAR, WM_IME_CONTROL,
 };
 
 use crate::{
-    Axis, Button, Coordinate, Direction, InputError, InputResult, Key, Keyboard, Mouse,
-    NewConError, Settings,
+    diagnostics::{self, DiagnosticsReport},
+    metrics::MetricsHook,
+    origin_to_top_left, origin_to_top_left_f, pixels_to_notches, round_coordinates,
+    settings::{warn_unchangeable, SettingsError},
+    throttle::Throttle,
+    AbsoluteMode, Axis, Button, Coordinate, Direction, ImeStrategy, InputError, InputResult, Key,
+    Keyboard, LayoutInfo, Mouse, NewConError, PointerMode, Rect, RelativeMode, ScrollUnit,
+    Settings, Subpixel, TextEntryMethod, UnicodeKeyMode, LINE_HEIGHT,
 };
 
 type ScanCode = u16;
 pub const EXT: u16 = 0xFF00;
 
+/// Value of the wheel settings if a notch scrolls a whole page. It is missing
+/// in the windows crate
+const WHEEL_PAGESCROLL: u32 = u32::MAX;
+/// Interval in which it is checked if the expected window became the foreground
+/// window
+const FOREGROUND_POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Approximate width of a character in pixels, used to convert the horizontal
+/// wheel setting
+const CHAR_WIDTH: i32 = 8;
+
+/// Command of `WM_IME_CONTROL` to get the open status of the IME. It is missing
+/// in the windows crate
+const IMC_GETOPENSTATUS: usize = 5;
+/// Time to wait for the foreground window to process the simulated text before
+/// the IME is opened again. Sent messages are processed before input
+/// messages, so it would otherwise be opened before the text arrives
+const IME_RESTORE_DELAY: Duration = Duration::from_millis(50);
+
+thread_local! {
+    /// Layout that is used to translate keys instead of the layout of the
+    /// foreground window while a key is injected
+    static LAYOUT_OVERRIDE: Cell<Option<HKL>> = const { Cell::new(None) };
+}
+
+/// Activates a keyboard layout for the current thread and uses it to translate
+/// keys until it is dropped
+struct LayoutGuard {
+    previous_override: Option<HKL>,
+    previous_layout: Option<HKL>,
+}
+
+impl LayoutGuard {
+    fn activate(layout: usize) -> Self {
+        let layout = HKL(layout as *mut _);
+        let previous_override = LAYOUT_OVERRIDE.replace(Some(layout));
+        let previous_layout =
+            unsafe { ActivateKeyboardLayout(layout, ACTIVATE_KEYBOARD_LAYOUT_FLAGS(0)) }.ok();
+        if previous_layout.is_none() {
+            warn!("unable to activate the keyboard layout {layout:?}");
+        }
+        Self {
+            previous_override,
+            previous_layout,
+        }
+    }
+}
+
+impl Drop for LayoutGuard {
+    fn drop(&mut self) {
+        LAYOUT_OVERRIDE.set(self.previous_override);
+        if let Some(previous) = self.previous_layout {
+            if unsafe { ActivateKeyboardLayout(previous, ACTIVATE_KEYBOARD_LAYOUT_FLAGS(0)) }
+                .is_err()
+            {
+                warn!("unable to restore the keyboard layout {previous:?}");
+            }
+        }
+    }
+}
+
+/// Mouse speed and acceleration settings of Windows. They are applied to
+/// relative mouse moves with `RelativeMode::Raw` and to the movement of
+/// physical mice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseAcceleration {
+    /// The two thresholds of the distance of a movement. If the distance is
+    /// greater than the first (second) threshold and the acceleration level is
+    /// at least one (two), the distance is doubled
+    pub thresholds: (i32, i32),
+    /// Acceleration level from 0 (disabled) to 2
+    pub level: i32,
+    /// Mouse speed from 1 (slowest) to 20 (fastest). The speed 10 does not
+    /// modify the distance
+    pub speed: u32,
+}
+
+impl MouseAcceleration {
+    /// Settings that move the cursor by exactly the distance of the movement
+    pub const DISABLED: Self = Self {
+        thresholds: (0, 0),
+        level: 0,
+        speed: 10,
+    };
+}
+
+/// Window that is expected to be the foreground window. Have a look at
+/// `Enigo::when_foreground`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ForegroundTarget {
+    /// Any window of the process with the id
+    Process(u32),
+    /// The window with the handle (`HWND`)
+    Window(isize),
+}
+
+impl ForegroundTarget {
+    /// Returns true if the current foreground window is the target
+    fn is_foreground(self) -> bool {
+        let foreground = unsafe { GetForegroundWindow() };
+        if foreground.is_invalid() {
+            return false;
+        }
+        match self {
+            Self::Process(pid) => {
+                let mut foreground_pid = 0;
+                unsafe { GetWindowThreadProcessId(foreground, Some(&raw mut foreground_pid)) };
+                foreground_pid == pid
+            }
+            Self::Window(hwnd) => foreground.0 as isize == hwnd,
+        }
+    }
+}
+
+/// Restores the mouse acceleration settings when it is dropped
+struct AccelerationGuard(MouseAcceleration);
+
+impl Drop for AccelerationGuard {
+    fn drop(&mut self) {
+        if Enigo::set_mouse_acceleration(&self.0).is_err() {
+            warn!("unable to restore the mouse acceleration {:?}", self.0);
+        }
+    }
+}
+
+/// Load the keyboard layout with the KLID (e.g. "00000407")
+fn load_keyboard_layout(klid: &str) -> Option<usize> {
+    let klid: Vec<u16> = klid.encode_utf16().chain(Some(0)).collect();
+    match unsafe { LoadKeyboardLayoutW(PCWSTR(klid.as_ptr()), KLF_NOTELLSHELL) } {
+        Ok(layout) if !layout.is_invalid() => Some(layout.0 as usize),
+        _ => {
+            error!("{}", std::io::Error::last_os_error());
+            None
+        }
+    }
+}
+
 /// The main struct for handling the event emitting
+#[allow(clippy::struct_excessive_bools)]
 pub struct Enigo {
     held: (Vec<Key>, Vec<ScanCode>), // Currently held keys
+    latched: Vec<Key>,               // Modifiers that are pressed with the next key
+    implicit_modifiers: Vec<Key>, // Break and SysRq keys that were pressed together with their modifier
     release_keys_when_dropped: bool,
     dw_extra_info: usize,
     windows_subject_to_mouse_speed_and_acceleration_level: bool,
+    unicode_key_mode: UnicodeKeyMode,
+    text_entry_method: TextEntryMethod,
+    ime_strategy: ImeStrategy,
+    relative_mode: RelativeMode,
+    absolute_mode: AbsoluteMode,
+    double_click_interval: Option<Duration>,
+    respect_button_swap: bool,
+    keyboard_layout: Option<usize>, // HKL as an integer so the struct is Send
+    metrics_hook: Option<Arc<dyn MetricsHook>>,
+    throttle: Option<Throttle>,
+    pub(crate) focus_guard: bool,
+    settings: Settings,  // Settings the struct was created with or updated to
+    cursor_hidden: bool, // The system cursors were replaced with a transparent one
+    cursor_disassociated: bool, // The cursor is confined to its location with ClipCursor
+    confinement: Option<Rect>, // The cursor is confined to the rectangle with ClipCursor
+    event_time: Option<Duration>, // Timestamp of the sent events instead of the time they are sent
+    pointer_mode: PointerMode,
+    subpixel: Subpixel, // Fractions of the relative movements that were not sent yet
+    tracked_position: Option<(i32, i32)>, // Target of the last movement
+}
+
+/// The system cursors that are replaced to hide the cursor
+const SYSTEM_CURSORS: [SYSTEM_CURSOR_ID; 13] = [
+    OCR_APPSTARTING,
+    OCR_NORMAL,
+    OCR_CROSS,
+    OCR_HAND,
+    OCR_IBEAM,
+    OCR_NO,
+    OCR_SIZEALL,
+    OCR_SIZENESW,
+    OCR_SIZENS,
+    OCR_SIZENWSE,
+    OCR_SIZEWE,
+    OCR_UP,
+    OCR_WAIT,
+];
+
+/// Hide the cursor by replacing all system cursors with a transparent one or
+/// show it by reloading the cursors of the user
+fn set_system_cursors_visible(visible: bool) -> InputResult<()> {
+    if visible {
+        return unsafe {
+            SystemParametersInfoW(
+                SPI_SETCURSORS,
+                0,
+                None,
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+        }
+        .map_err(|_| InputError::Simulate("unable to restore the system cursors"));
+    }
+
+    // The AND mask keeps the screen and the XOR mask does not change it
+    let and_mask = [0xFF_u8; 32 * 32 / 8];
+    let xor_mask = [0_u8; 32 * 32 / 8];
+    for id in SYSTEM_CURSORS {
+        // SetSystemCursor destroys the cursor, so each one needs its own
+        let cursor = unsafe {
+            CreateCursor(
+                None,
+                0,
+                0,
+                32,
+                32,
+                and_mask.as_ptr().cast(),
+                xor_mask.as_ptr().cast(),
+            )
+        }
+        .map_err(|_| InputError::Simulate("unable to create a transparent cursor"))?;
+        unsafe { SetSystemCursor(cursor, id) }
+            .map_err(|_| InputError::Simulate("unable to replace the system cursor"))?;
+    }
+    Ok(())
+}
+
+/// Confine the cursor to the rectangle or release it if it is `None`
+fn clip_cursor(rect: Option<RECT>) -> InputResult<()> {
+    debug!("confine the cursor to {rect:?}");
+    unsafe { ClipCursor(rect.as_ref().map(std::ptr::from_ref)) }
+        .map_err(|_| InputError::Simulate("unable to confine the cursor"))
+}
+
+/// Integrity level of the process and whether it has `UIAccess`. Fails if the
+/// token of the process could not be queried
+fn integrity_level(process: HANDLE) -> windows::core::Result<(u32, bool)> {
+    let mut token = HANDLE::default();
+    unsafe { OpenProcessToken(process, TOKEN_QUERY, &raw mut token) }?;
+    let level = token_integrity_level(token);
+    let ui_access = token_ui_access(token);
+    let _ = unsafe { CloseHandle(token) };
+    Ok((level?, ui_access))
+}
+
+/// Relative identifier of the mandatory label of the token (e.g.
+/// `SECURITY_MANDATORY_HIGH_RID` for elevated processes)
+fn token_integrity_level(token: HANDLE) -> windows::core::Result<u32> {
+    let mut len = 0;
+    // The first call fails and returns the required size of the buffer
+    let _ = unsafe { GetTokenInformation(token, TokenIntegrityLevel, None, 0, &raw mut len) };
+    // Use u64 so the buffer is aligned for the pointer in the label
+    let mut buffer = vec![0u64; (len as usize).div_ceil(size_of::<u64>())];
+    unsafe {
+        GetTokenInformation(
+            token,
+            TokenIntegrityLevel,
+            Some(buffer.as_mut_ptr().cast()),
+            len,
+            &raw mut len,
+        )
+    }?;
+    let label = unsafe { &*buffer.as_ptr().cast::<TOKEN_MANDATORY_LABEL>() };
+    let sid = label.Label.Sid;
+    let count = unsafe { *GetSidSubAuthorityCount(sid) };
+    let Some(last) = count.checked_sub(1) else {
+        return Err(windows::core::Error::from_win32());
+    };
+    Ok(unsafe { *GetSidSubAuthority(sid, u32::from(last)) })
+}
+
+/// Returns true if the token has `UIAccess`. Those processes are allowed to
+/// send input to processes with a higher integrity level
+fn token_ui_access(token: HANDLE) -> bool {
+    let mut ui_access = 0u32;
+    let mut len = 0;
+    let Ok(size) = u32::try_from(size_of::<u32>()) else {
+        return false;
+    };
+    unsafe {
+        GetTokenInformation(
+            token,
+            TokenUIAccess,
+            Some((&raw mut ui_access).cast()),
+            size,
+            &raw mut len,
+        )
+    }
+    .is_ok()
+        && ui_access != 0
+}
+
+/// Returns true if the foreground window belongs to a process with a higher
+/// integrity level than this one. User Interface Privilege Isolation (UIPI)
+/// silently discards the input that is sent to it and `SendInput` still
+/// reports success
+///
+/// The result is cached for the foreground window and its process, so the
+/// token is only queried when a different window becomes active. If the
+/// integrity level of the foreground process can't be determined (e.g.
+/// because it is protected or runs in another session), the input is not
+/// blocked.
+pub(crate) fn foreground_blocked_by_uipi() -> bool {
+    static OWN_LEVEL: OnceLock<Option<(u32, bool)>> = OnceLock::new();
+    // Foreground window, its process and if the input to it is blocked
+    static LAST_FOREGROUND: Mutex<Option<(usize, u32, bool)>> = Mutex::new(None);
+
+    let own = *OWN_LEVEL.get_or_init(|| integrity_level(unsafe { GetCurrentProcess() }).ok());
+    let Some((own_level, own_ui_access)) = own else {
+        return false;
+    };
+    if own_ui_access {
+        return false;
+    }
+
+    let foreground = unsafe { GetForegroundWindow() };
+    if foreground.is_invalid() {
+        return false;
+    }
+    let mut pid = 0;
+    unsafe { GetWindowThreadProcessId(foreground, Some(&raw mut pid)) };
+    if pid == 0 || pid == std::process::id() {
+        return false;
+    }
+
+    let window = foreground.0 as usize;
+    let mut last = LAST_FOREGROUND
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some((last_window, last_pid, blocked)) = *last {
+        if last_window == window && last_pid == pid {
+            return blocked;
+        }
+    }
+
+    let level = match unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) } {
+        Ok(process) => {
+            let level = integrity_level(process);
+            let _ = unsafe { CloseHandle(process) };
+            level
+        }
+        Err(e) => Err(e),
+    };
+    let blocked = match level {
+        Ok((level, _)) => level > own_level,
+        Err(e) => {
+            // Being denied access is no proof of a higher integrity level
+            debug!("unable to get the integrity level of the foreground process {pid}: {e}");
+            false
+        }
+    };
+    if blocked {
+        warn!("the foreground window (process {pid}) has a higher integrity level, UIPI blocks the input");
+    }
+    *last = Some((window, pid, blocked));
+    blocked
 }
 
 fn send_input(input: &[INPUT]) -> InputResult<()> {
     if input.is_empty() {
         return Ok(());
     }
+    if foreground_blocked_by_uipi() {
+        return Err(InputError::BlockedByUipi);
+    }
     let Ok(input_size): Result<i32, _> = size_of::<INPUT>().try_into() else {
         return Err(InputError::InvalidInput(
             "the size of the INPUT was so large, the size exceeded i32::MAX",
@@ -83,6 +469,26 @@ fn mouse_event(
     }
 }
 
+/// Returns the value of the character in the active ANSI codepage if it is
+/// part of it and encoded with a single byte
+fn ansi_code(character: char) -> Option<u8> {
+    let mut buffer = [0; 2];
+    let utf16 = character.encode_utf16(&mut buffer);
+    let mut bytes = [0; 2];
+    let mut used_default_char = BOOL(0);
+    let len = unsafe {
+        WideCharToMultiByte(
+            CP_ACP,
+            WC_NO_BEST_FIT_CHARS,
+            utf16,
+            Some(&mut bytes),
+            PCSTR::null(),
+            Some(&raw mut used_default_char),
+        )
+    };
+    (len == 1 && !used_default_char.as_bool()).then_some(bytes[0])
+}
+
 fn keybd_event(
     flags: KEYBD_EVENT_FLAGS,
     vk: VIRTUAL_KEY,
@@ -106,59 +512,489 @@ fn keybd_event(
 impl Mouse for Enigo {
     // Sends a button event to the X11 server via `XTest` extension
     fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mbutton(button: {button:?}, direction: {direction:?})\x1b[0m");
-        let mut input = vec![];
-        let button_no = match button {
-            Button::Back => 1,
-            Button::Forward => 2,
-            _ => 0,
-        };
-        if direction == Direction::Click || direction == Direction::Press {
-            let mouse_event_flag = match button {
-                Button::Left => MOUSEEVENTF_LEFTDOWN,
-                Button::Middle => MOUSEEVENTF_MIDDLEDOWN,
-                Button::Right => MOUSEEVENTF_RIGHTDOWN,
-                Button::Back | Button::Forward => MOUSEEVENTF_XDOWN,
-                Button::ScrollUp => return self.scroll(-1, Axis::Vertical),
-                Button::ScrollDown => return self.scroll(1, Axis::Vertical),
-                Button::ScrollLeft => return self.scroll(-1, Axis::Horizontal),
-                Button::ScrollRight => return self.scroll(1, Axis::Horizontal),
+        throttled!("button", self, [button, direction], || {
+            let mut input = vec![];
+            self.queue_button(&mut input, button, direction);
+            self.send_input(&input)
+        })
+    }
+
+    fn buttons(&mut self, buttons: &[(Button, Direction)]) -> InputResult<()> {
+        throttled!("buttons", self, [buttons], || {
+            let mut input = vec![];
+            for &(button, direction) in buttons {
+                if direction != Direction::Release {
+                    self.queue_button(&mut input, button, Direction::Press);
+                }
+            }
+            for &(button, direction) in buttons {
+                if direction != Direction::Press {
+                    self.queue_button(&mut input, button, Direction::Release);
+                }
+            }
+            self.send_input(&input)
+        })
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        throttled!("move_mouse", self, [x, y, coordinate], || {
+            let (x, y) = match coordinate {
+                Coordinate::Abs => origin_to_top_left(self, self.settings.origin, x, y)?,
+                Coordinate::Rel => (x, y),
             };
-            input.push(mouse_event(
-                mouse_event_flag,
-                button_no,
-                0,
-                0,
-                self.dw_extra_info,
-            ));
-        }
-        if direction == Direction::Click || direction == Direction::Release {
-            let mouse_event_flag = match button {
-                Button::Left => MOUSEEVENTF_LEFTUP,
-                Button::Middle => MOUSEEVENTF_MIDDLEUP,
-                Button::Right => MOUSEEVENTF_RIGHTUP,
-                Button::Back | Button::Forward => MOUSEEVENTF_XUP,
-                Button::ScrollUp
-                | Button::ScrollDown
-                | Button::ScrollLeft
-                | Button::ScrollRight => {
-                    info!("On Windows the mouse_up function has no effect when called with one of the Scroll buttons");
+            self.move_cursor(x, y, coordinate)
+        })
+    }
+
+    fn move_mouse_f(&mut self, x: f64, y: f64, coordinate: Coordinate) -> InputResult<()> {
+        throttled!("move_mouse_f", self, [x, y, coordinate], || {
+            let (x, y) = match coordinate {
+                Coordinate::Abs => origin_to_top_left_f(self, self.settings.origin, x, y)?,
+                Coordinate::Rel => (x, y),
+            };
+            let rounded = round_coordinates(x, y)?;
+            if coordinate == Coordinate::Rel {
+                let (dx, dy) = self.subpixel.take(x, y);
+                if dx == 0 && dy == 0 {
                     return Ok(());
                 }
+                return self.move_cursor(dx, dy, Coordinate::Rel);
+            }
+            if self.pointer_mode == PointerMode::RelativeOnly
+                || self.absolute_mode == AbsoluteMode::Warp
+            {
+                return self.move_cursor(rounded.0, rounded.1, Coordinate::Abs);
+            }
+            // The normalized coordinates have a higher resolution than the pixels
+            let (w, h) = self.main_display()?;
+            let normalize = |value: f64, size: i32| {
+                (value * 65535.0 / f64::from((size - 1).max(1))).round() as i32
             };
-            input.push(mouse_event(
-                mouse_event_flag,
-                button_no,
-                0,
+            let input = mouse_event(
+                MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE,
                 0,
+                normalize(x, w),
+                normalize(y, h),
                 self.dw_extra_info,
-            ));
+            );
+            self.send_input(&[input])?;
+            self.tracked_position = Some(rounded);
+            Ok(())
+        })
+    }
+
+    // Sends a scroll event to the X11 server via `XTest` extension
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        throttled!("scroll", self, [length, axis], || {
+            self.send_input(&[self.scroll_event(length, axis)])
+        })
+    }
+
+    fn scroll_2d(&mut self, dx: i32, dy: i32, unit: ScrollUnit) -> InputResult<()> {
+        throttled!("scroll_2d", self, [dx, dy, unit], || {
+            if unit != ScrollUnit::Line {
+                return Err(InputError::InvalidInput(
+                    "scrolling by pixels is not supported on Windows",
+                ));
+            }
+            // There is no event for both axes, but they are at least sent together
+            let mut input = Vec::with_capacity(2);
+            if dx != 0 {
+                input.push(self.scroll_event(dx, Axis::Horizontal));
+            }
+            if dy != 0 {
+                input.push(self.scroll_event(dy, Axis::Vertical));
+            }
+            self.send_input(&input)
+        })
+    }
+
+    fn scroll_by_pixels_approx(&mut self, target_px: i32, axis: Axis) -> InputResult<()> {
+        traced!("scroll_by_pixels_approx", self, [target_px, axis], || {
+            let px_per_notch = self.wheel_scroll_distance(axis)?;
+            debug!("a notch scrolls {px_per_notch} pixels");
+            self.scroll(pixels_to_notches(target_px, px_per_notch)?, axis)
+        })
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        traced!("main_display", self, [], || {
+            let w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
+            let h = unsafe { GetSystemMetrics(SM_CYSCREEN) };
+            if w == 0 || h == 0 {
+                // Last error does not contain information about why there was an issue so it is
+                // not used here
+                Err(InputError::Simulate(
+                    "could not get the dimensions of the screen",
+                ))
+            } else {
+                Ok((w, h))
+            }
+        })
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        traced!("location", self, [], || {
+            let (x, y) = Self::cursor_location()?;
+            origin_to_top_left(self, self.settings.origin, x, y)
+        })
+    }
+    fn show_cursor(&mut self, visible: bool) -> InputResult<()> {
+        traced!("show_cursor", self, [visible], || {
+            set_system_cursors_visible(visible)?;
+            self.cursor_hidden = !visible;
+            crate::cleanup::cursor_hidden(!visible);
+            Ok(())
+        })
+    }
+
+    fn associate_cursor(&mut self, associated: bool) -> InputResult<()> {
+        traced!("associate_cursor", self, [associated], || {
+            self.cursor_disassociated = !associated;
+            self.update_clip()
+        })
+    }
+
+    fn confine_to(&mut self, rect: Rect) -> InputResult<()> {
+        traced!("confine_to", self, [rect], || {
+            rect.check_not_empty()?;
+            self.confinement = Some(rect);
+            self.update_clip()
+        })
+    }
+
+    fn release_confinement(&mut self) -> InputResult<()> {
+        traced!("release_confinement", self, [], || {
+            self.confinement = None;
+            self.update_clip()
+        })
+    }
+}
+
+impl Keyboard for Enigo {
+    fn fast_text(&mut self, _text: &str) -> InputResult<Option<()>> {
+        Ok(None)
+    }
+
+    /// Enter the whole text string instead of entering individual keys
+    /// This is much faster if you type longer text at the cost of keyboard
+    /// shortcuts not getting recognized
+    fn text(&mut self, text: &str) -> InputResult<()> {
+        throttled!("text", self, [text], || {
+            if text.is_empty() {
+                return Ok(()); // Nothing to simulate.
+            }
+            let _layout = self.keyboard_layout.map(LayoutGuard::activate);
+            let ime_window = match self.ime_strategy {
+                ImeStrategy::Ignore => None,
+                _ => Self::open_ime_window(),
+            };
+            if let Some(ime_window) = ime_window {
+                match self.ime_strategy {
+                    ImeStrategy::Error => {
+                        return Err(InputError::Simulate(
+                            "an IME is active in the foreground window",
+                        ));
+                    }
+                    ImeStrategy::ImeChar => return Self::post_ime_chars(text),
+                    ImeStrategy::Disable => {
+                        debug!("closing the IME while the text is entered");
+                        Self::set_ime_open(ime_window, false);
+                        let res = self.send_text(text);
+                        thread::sleep(IME_RESTORE_DELAY);
+                        Self::set_ime_open(ime_window, true);
+                        return res;
+                    }
+                    ImeStrategy::Ignore => {}
+                }
+            }
+            self.send_text(text)
+        })
+    }
+
+    /// Sends a key event to the X11 server via `XTest` extension
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        // Press the latched modifiers around the next key that is not a modifier
+        if !self.latched.is_empty() && direction != Direction::Release && !key.is_modifier() {
+            let latched = std::mem::take(&mut self.latched);
+            for &modifier in &latched {
+                self.key(modifier, Direction::Press)?;
+            }
+            let res = self.key(key, direction);
+            for &modifier in latched.iter().rev() {
+                self.key(modifier, Direction::Release)?;
+            }
+            return res;
         }
-        send_input(&input)
+        throttled!("key", self, [key, direction], || {
+            let _layout = self.keyboard_layout.map(LayoutGuard::activate);
+            let mut input = Vec::with_capacity(2);
+
+            self.queue_key(&mut input, key, direction)?;
+            self.send_input(&input)?;
+
+            match direction {
+                Direction::Press => {
+                    debug!("added the key {key:?} to the held keys");
+                    crate::cleanup::key(key, direction);
+                    self.held.0.push(key);
+                    // TODO: Make it work that they can get released with the raw
+                    // function as well
+                }
+                Direction::Release => {
+                    debug!("removed the key {key:?} from the held keys");
+                    crate::cleanup::key(key, direction);
+                    self.held.0.retain(|&k| k != key);
+                    // TODO: Make it work that they can get released with the raw
+                    // function as well
+                }
+                Direction::Click => (),
+            }
+
+            Ok(())
+        })
     }
 
-    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
-        debug!("\x1b[93mmove_mouse(x: {x:?}, y: {y:?}, coordinate:{coordinate:?})\x1b[0m");
+    fn key_sequence(&mut self, keys: &[Key], direction: Direction) -> InputResult<()> {
+        // The latched modifiers are pressed around each key that is not a modifier
+        if !self.latched.is_empty() {
+            return crate::key_sequence_by_key(self, keys, direction);
+        }
+        throttled!("key_sequence", self, [keys, direction], || {
+            let _layout = self.keyboard_layout.map(LayoutGuard::activate);
+            let mut input = Vec::with_capacity(keys.len() * 2);
+
+            // Queue all events first, so nothing is sent if one of the keys can't be entered
+            if direction != Direction::Release {
+                for &key in keys {
+                    self.queue_key(&mut input, key, Direction::Press)?;
+                }
+            }
+            if direction != Direction::Press {
+                for &key in keys.iter().rev() {
+                    self.queue_key(&mut input, key, Direction::Release)?;
+                }
+            }
+            self.send_input(&input)?;
+
+            for &key in keys {
+                match direction {
+                    Direction::Press => {
+                        debug!("added the key {key:?} to the held keys");
+                        crate::cleanup::key(key, direction);
+                        self.held.0.push(key);
+                    }
+                    Direction::Release => {
+                        debug!("removed the key {key:?} from the held keys");
+                        crate::cleanup::key(key, direction);
+                        self.held.0.retain(|&k| k != key);
+                    }
+                    Direction::Click => (),
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn raw(&mut self, scan: u16, direction: Direction) -> InputResult<()> {
+        throttled!("raw", self, [scan, direction], || {
+            let _layout = self.keyboard_layout.map(LayoutGuard::activate);
+            let mut input = vec![];
+
+            let vk = VIRTUAL_KEY(Enigo::translate_key(scan, MAPVK_VSC_TO_VK_EX)?); // translate scan code to virtual key
+
+            let mut keyflags = KEYEVENTF_SCANCODE;
+            // TODO: Check if the first bytes need to be truncated if it is an extended key
+            // The prefix 0xE0 (e.g. from `Keyboard::physical`) marks an extended key. It is
+            // not part of the scan code that is sent
+            let prefixed = scan >> 8 == 0xE0;
+            if prefixed || Enigo::is_extended_key(vk) {
+                keyflags |= KEYEVENTF_EXTENDEDKEY;
+            }
+            let sent_scan = if prefixed { scan & 0xFF } else { scan };
+
+            if direction == Direction::Click || direction == Direction::Press {
+                input.push(keybd_event(keyflags, vk, sent_scan, self.dw_extra_info));
+            }
+            if direction == Direction::Click || direction == Direction::Release {
+                input.push(keybd_event(
+                    keyflags | KEYEVENTF_KEYUP,
+                    vk,
+                    sent_scan,
+                    self.dw_extra_info,
+                ));
+            }
+
+            self.send_input(&input)?;
+
+            match direction {
+                Direction::Press => {
+                    debug!("added the key {scan:?} to the held keys");
+                    crate::cleanup::raw(scan, direction);
+                    self.held.1.push(scan);
+                    // TODO: Make it work that they can get released with the key
+                    // function as well
+                }
+                Direction::Release => {
+                    debug!("removed the key {scan:?} from the held keys");
+                    crate::cleanup::raw(scan, direction);
+                    self.held.1.retain(|&k| k != scan);
+                    // TODO: Make it work that they can get released with the key
+                    // function as well
+                }
+                Direction::Click => (),
+            }
+
+            Ok(())
+        })
+    }
+
+    fn latch_modifier(&mut self, key: Key) -> InputResult<()> {
+        debug!("\x1b[93mlatch_modifier(key: {key:?})\x1b[0m");
+        if !key.is_modifier() {
+            return Err(InputError::InvalidInput("the key is not a modifier"));
+        }
+        if !self.latched.contains(&key) {
+            self.latched.push(key);
+        }
+        Ok(())
+    }
+}
+
+impl Enigo {
+    /// Create a new Enigo struct to establish the connection to simulate input
+    /// with the specified settings
+    ///
+    /// # Errors
+    /// Have a look at the documentation of `NewConError` to see under which
+    /// conditions an error will be returned.
+    pub fn new(settings: &Settings) -> Result<Self, NewConError> {
+        let Settings {
+            windows_dw_extra_info: dw_extra_info,
+            release_keys_when_dropped,
+            windows_subject_to_mouse_speed_and_acceleration_level,
+            unicode_key_mode,
+            windows_text_entry_method,
+            relative_mode,
+            double_click_interval,
+            respect_button_swap,
+            windows_keyboard_layout,
+            windows_ime_strategy,
+            ..
+        } = settings;
+
+        let keyboard_layout = match windows_keyboard_layout {
+            Some(klid) => Some(load_keyboard_layout(klid).ok_or(NewConError::EstablishCon(
+                "unable to load the keyboard layout",
+            ))?),
+            None => None,
+        };
+
+        let held = (vec![], vec![]);
+
+        if *relative_mode == RelativeMode::Raw
+            && !matches!(Self::relative_moves_transformed(), Ok(false))
+        {
+            warn!("the cursor will be moved by a different amount than the relative mouse moves because of the mouse speed and acceleration settings. Applications reading raw input will receive the exact deltas");
+        }
+
+        debug!("\x1b[93mconnection established on windows\x1b[0m");
+
+        Ok(Self {
+            held,
+            latched: Vec::new(),
+            implicit_modifiers: Vec::new(),
+            release_keys_when_dropped: *release_keys_when_dropped,
+            dw_extra_info: dw_extra_info.unwrap_or(crate::EVENT_MARKER as usize),
+            windows_subject_to_mouse_speed_and_acceleration_level:
+                *windows_subject_to_mouse_speed_and_acceleration_level,
+            unicode_key_mode: *unicode_key_mode,
+            text_entry_method: *windows_text_entry_method,
+            ime_strategy: *windows_ime_strategy,
+            relative_mode: *relative_mode,
+            absolute_mode: settings.absolute_mode,
+            double_click_interval: *double_click_interval,
+            respect_button_swap: *respect_button_swap,
+            keyboard_layout,
+            metrics_hook: None,
+            throttle: Throttle::new(settings),
+            focus_guard: settings.focus_guard,
+            settings: settings.clone(),
+            cursor_hidden: false,
+            cursor_disassociated: false,
+            confinement: None,
+            event_time: None,
+            pointer_mode: PointerMode::Normal,
+            subpixel: Subpixel::default(),
+            tracked_position: None,
+        })
+    }
+
+    /// Apply the settings without creating a new Enigo struct
+    ///
+    /// All options except `windows_keyboard_layout` are applied. Use
+    /// `Enigo::with_keyboard_layout` to simulate input with a different
+    /// layout. A warning is logged if it differs
+    ///
+    /// # Errors
+    /// Returns an error and changes nothing if the settings are invalid. Have
+    /// a look at `Settings::validate`
+    pub fn update_settings(&mut self, settings: &Settings) -> Result<(), SettingsError> {
+        debug!("\x1b[93mupdate_settings(settings: {settings:?})\x1b[0m");
+        settings.validate()?;
+        let old = std::mem::replace(&mut self.settings, settings.clone());
+        warn_unchangeable(&[(
+            "windows_keyboard_layout",
+            old.windows_keyboard_layout != settings.windows_keyboard_layout,
+        )]);
+        self.settings.windows_keyboard_layout = old.windows_keyboard_layout;
+
+        self.release_keys_when_dropped = settings.release_keys_when_dropped;
+        self.dw_extra_info = settings
+            .windows_dw_extra_info
+            .unwrap_or(crate::EVENT_MARKER as usize);
+        self.windows_subject_to_mouse_speed_and_acceleration_level =
+            settings.windows_subject_to_mouse_speed_and_acceleration_level;
+        self.unicode_key_mode = settings.unicode_key_mode;
+        self.text_entry_method = settings.windows_text_entry_method;
+        self.ime_strategy = settings.windows_ime_strategy;
+        self.relative_mode = settings.relative_mode;
+        self.absolute_mode = settings.absolute_mode;
+        self.double_click_interval = settings.double_click_interval;
+        self.respect_button_swap = settings.respect_button_swap;
+        // Keep the state of the rate limit if it did not change
+        if old.max_events_per_second != settings.max_events_per_second
+            || old.rate_limit_behavior != settings.rate_limit_behavior
+        {
+            self.throttle = Throttle::new(settings);
+        }
+        self.focus_guard = settings.focus_guard;
+        Ok(())
+    }
+
+    /// Returns true if the user swapped the meaning of the left and right
+    /// mouse buttons (left-handed use)
+    #[must_use]
+    pub fn buttons_swapped() -> bool {
+        unsafe { GetSystemMetrics(SM_SWAPBUTTON) != 0 }
+    }
+
+    /// Move the cursor without acquiring the throttle
+    fn move_cursor(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        if coordinate == Coordinate::Abs && self.pointer_mode == PointerMode::RelativeOnly {
+            return Err(InputError::InvalidInput(
+                "absolute movements are refused in PointerMode::RelativeOnly",
+            ));
+        }
+        if coordinate == Coordinate::Abs && self.absolute_mode == AbsoluteMode::Warp {
+            debug!("warping the cursor with SetCursorPos");
+            unsafe { SetCursorPos(x, y) }
+                .map_err(|_| InputError::Simulate("unable to warp the cursor"))?;
+            self.tracked_position = Some((x, y));
+            return Ok(());
+        }
+        let target = (x, y);
         let (flags, x, y) = if coordinate == Coordinate::Abs {
             // 0-screen width/height - 1 map to 0-65535
             // Add w/2 or h/2 to round off
@@ -172,7 +1008,10 @@ impl Mouse for Enigo {
             let y = (y * 65535 + h / 2 * y.signum()) / h;
             // TODO: Check if we should use MOUSEEVENTF_VIRTUALDESK too
             (MOUSEEVENTF_MOVE | MOUSEEVENTF_ABSOLUTE, x as i32, y as i32)
-        } else if self.windows_subject_to_mouse_speed_and_acceleration_level {
+        } else if self.windows_subject_to_mouse_speed_and_acceleration_level
+            || self.relative_mode == RelativeMode::Raw
+            || self.pointer_mode == PointerMode::RelativeOnly
+        {
             // Quote from documentation (http://web.archive.org/web/20241118235853/https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-mouse_event):
             // Relative mouse motion is subject to the settings for mouse speed and
             // acceleration level. An end user sets these values using the Mouse application
@@ -201,53 +1040,18 @@ impl Mouse for Enigo {
             // location and move it to the absolute location so it is not subject to mouse
             // speed and acceleration levels
             debug!("\x1b[93mRelative mouse move is NOT subject to mouse speed and acceleration level\x1b[0m");
-            let (current_x, current_y) = self.location()?;
-            return self.move_mouse(current_x + x, current_y + y, Coordinate::Abs);
+            let (current_x, current_y) = self.tracked_location()?;
+            return self.move_cursor(current_x + x, current_y + y, Coordinate::Abs);
         };
         let input = mouse_event(flags, 0, x, y, self.dw_extra_info);
-        send_input(&[input])
-    }
-
-    // Sends a scroll event to the X11 server via `XTest` extension
-    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
-        debug!("\x1b[93mscroll(length: {length:?}, axis: {axis:?})\x1b[0m");
-        let input = match axis {
-            Axis::Horizontal => mouse_event(
-                MOUSEEVENTF_HWHEEL,
-                length * (WHEEL_DELTA as i32),
-                0,
-                0,
-                self.dw_extra_info,
-            ),
-            Axis::Vertical => mouse_event(
-                MOUSEEVENTF_WHEEL,
-                -length * (WHEEL_DELTA as i32),
-                0,
-                0,
-                self.dw_extra_info,
-            ),
-        };
-        send_input(&[input])?;
+        self.send_input(&[input])?;
+        // The OS transforms relative movements, so their target is unknown
+        self.tracked_position = (coordinate == Coordinate::Abs).then_some(target);
         Ok(())
     }
 
-    fn main_display(&self) -> InputResult<(i32, i32)> {
-        debug!("\x1b[93mmain_display()\x1b[0m");
-        let w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
-        let h = unsafe { GetSystemMetrics(SM_CYSCREEN) };
-        if w == 0 || h == 0 {
-            // Last error does not contain information about why there was an issue so it is
-            // not used here
-            Err(InputError::Simulate(
-                "could not get the dimensions of the screen",
-            ))
-        } else {
-            Ok((w, h))
-        }
-    }
-
-    fn location(&self) -> InputResult<(i32, i32)> {
-        debug!("\x1b[93mlocation()\x1b[0m");
+    /// Location of the cursor with the top left origin
+    fn cursor_location() -> InputResult<(i32, i32)> {
         let mut point = POINT { x: 0, y: 0 };
         if unsafe { GetCursorPos(&mut point) }.is_ok() {
             Ok((point.x, point.y))
@@ -257,142 +1061,268 @@ impl Mouse for Enigo {
             ))
         }
     }
-}
-
-impl Keyboard for Enigo {
-    fn fast_text(&mut self, _text: &str) -> InputResult<Option<()>> {
-        Ok(None)
-    }
 
-    /// Enter the whole text string instead of entering individual keys
-    /// This is much faster if you type longer text at the cost of keyboard
-    /// shortcuts not getting recognized
-    fn text(&mut self, text: &str) -> InputResult<()> {
-        debug!("\x1b[93mtext(text: {text})\x1b[0m");
-        if text.is_empty() {
-            return Ok(()); // Nothing to simulate.
-        }
-        let mut buffer = [0; 2]; // A buffer of length 2 is large enough to encode any char in utf16
-
-        let mut input = Vec::with_capacity(2 * text.len()); // Each char needs at least one event to press and one to release it
-        for c in text.chars() {
-            // Enter special characters as keys
-            match c {
-                '\n' => self.queue_key(&mut input, Key::Return, Direction::Click)?,
-                '\r' => { // TODO: What is the correct key to type here?
-                }
-                '\t' => self.queue_key(&mut input, Key::Tab, Direction::Click)?,
-                '\0' => Err(InputError::InvalidInput("the text contained a null byte"))?,
-                _ => (),
+    /// Location to continue a relative movement from. The normalized
+    /// coordinates of an absolute movement can land a pixel next to the
+    /// requested one. Continuing from there would add up the rounding errors,
+    /// so the target of the last movement is used as long as the cursor is
+    /// within a pixel of it. Otherwise something else moved the cursor
+    fn tracked_location(&self) -> InputResult<(i32, i32)> {
+        let location = Self::cursor_location()?;
+        Ok(match self.tracked_position {
+            Some(tracked)
+                if (tracked.0 - location.0).abs() <= 1 && (tracked.1 - location.1).abs() <= 1 =>
+            {
+                tracked
             }
+            _ => location,
+        })
+    }
 
-            self.queue_char(&mut input, c, &mut buffer);
-        }
-        send_input(&input)
+    /// Position the Enigo struct believes the cursor is at after the last
+    /// simulated movement. Consecutive relative movements continue from it, so
+    /// the rounding errors of the absolute coordinates Windows uses don't add
+    /// up. It is `None` if the last movement was transformed by the mouse
+    /// speed and acceleration (see `RelativeMode::Raw`)
+    #[must_use]
+    pub fn tracked_position(&self) -> Option<(i32, i32)> {
+        self.tracked_position
     }
 
-    /// Sends a key event to the X11 server via `XTest` extension
-    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mkey(key: {key:?}, direction: {direction:?})\x1b[0m");
-        let mut input = Vec::with_capacity(2);
+    /// Forget the tracked position and the fractions of a pixel of relative
+    /// movements with `Mouse::move_mouse_f` that were not sent yet. The next
+    /// relative movement starts at the location of the cursor
+    pub fn reset_position_tracking(&mut self) {
+        debug!("\x1b[93mreset_position_tracking()\x1b[0m");
+        self.tracked_position = None;
+        self.subpixel = Subpixel::default();
+    }
 
-        self.queue_key(&mut input, key, direction)?;
-        send_input(&input)?;
+    /// Set which mouse movements are simulated. Have a look at the
+    /// documentation of `PointerMode`
+    pub fn set_pointer_mode(&mut self, mode: PointerMode) {
+        debug!("\x1b[93mset_pointer_mode(mode: {mode:?})\x1b[0m");
+        self.pointer_mode = mode;
+    }
 
-        match direction {
-            Direction::Press => {
-                debug!("added the key {key:?} to the held keys");
-                self.held.0.push(key);
-                // TODO: Make it work that they can get released with the raw
-                // function as well
-            }
-            Direction::Release => {
-                debug!("removed the key {key:?} from the held keys");
-                self.held.0.retain(|&k| k != key);
-                // TODO: Make it work that they can get released with the raw
-                // function as well
-            }
-            Direction::Click => (),
-        }
+    /// Returns which mouse movements are simulated
+    #[must_use]
+    pub fn pointer_mode(&self) -> PointerMode {
+        self.pointer_mode
+    }
 
-        Ok(())
+    /// Set the hook that gets notified about each call of a function of the
+    /// `Keyboard` and `Mouse` traits. Have a look at the `metrics` module.
+    /// Pass `None` to remove it
+    pub fn set_metrics_hook(&mut self, hook: Option<Arc<dyn MetricsHook>>) {
+        self.metrics_hook = hook;
     }
 
-    fn raw(&mut self, scan: u16, direction: Direction) -> InputResult<()> {
-        debug!("\x1b[93mraw(scan: {scan:?}, direction: {direction:?})\x1b[0m");
-        let mut input = vec![];
+    /// Set the timestamp of the events that are sent from now on (e.g. to
+    /// replay recorded input with its original timing). The timestamp is the
+    /// time since the system started like `GetTickCount` returns it. Pass
+    /// `None` to let the system timestamp the events with the time they are
+    /// sent
+    pub fn set_event_time(&mut self, time: Option<Duration>) {
+        debug!("\x1b[93mset_event_time(time: {time:?})\x1b[0m");
+        self.event_time = time;
+    }
 
-        let vk = VIRTUAL_KEY(Enigo::translate_key(scan, MAPVK_VSC_TO_VK_EX)?); // translate scan code to virtual key
+    /// Returns the name of the API that is used to simulate input
+    #[allow(clippy::unused_self)]
+    fn backend(&self) -> &'static str {
+        "SendInput"
+    }
 
-        let mut keyflags = KEYEVENTF_SCANCODE;
-        // TODO: Check if the first bytes need to be truncated if it is an extended key
-        if Enigo::is_extended_key(vk) {
-            keyflags |= KEYEVENTF_EXTENDEDKEY;
-        }
+    /// Returns the maximum interval between two clicks so they count as a
+    /// double click. Unless it was overridden, the current setting of the OS
+    /// (`GetDoubleClickTime`) is returned
+    #[must_use]
+    pub fn double_click_interval(&self) -> Duration {
+        self.double_click_interval
+            .unwrap_or_else(|| Duration::from_millis(unsafe { GetDoubleClickTime() }.into()))
+    }
 
-        if direction == Direction::Click || direction == Direction::Press {
-            input.push(keybd_event(keyflags, vk, scan, self.dw_extra_info));
-        }
-        if direction == Direction::Click || direction == Direction::Release {
-            input.push(keybd_event(
-                keyflags | KEYEVENTF_KEYUP,
-                vk,
-                scan,
-                self.dw_extra_info,
-            ));
-        }
+    /// Override the maximum interval between two clicks so they count as a
+    /// double click. If it is `None`, the setting of the OS is used
+    pub fn set_double_click_interval(&mut self, interval: Option<Duration>) {
+        self.double_click_interval = interval;
+    }
 
-        send_input(&input)?;
+    /// Returns true if Windows applies the mouse speed or acceleration to
+    /// relative mouse moves that are not moved to the absolute location (see
+    /// `RelativeMode::Raw`). The cursor then moves by a different distance
+    /// than the specified one. Applications that read raw input are not
+    /// affected
+    ///
+    /// # Errors
+    /// Returns an error if the settings of the mouse could not be read
+    pub fn relative_moves_transformed() -> InputResult<bool> {
+        let acceleration = Self::mouse_acceleration()?;
+        // A speed of 10 results in no additional modification of the motion
+        Ok(acceleration.level != 0 || acceleration.speed != 10)
+    }
 
-        match direction {
-            Direction::Press => {
-                debug!("added the key {scan:?} to the held keys");
-                self.held.1.push(scan);
-                // TODO: Make it work that they can get released with the key
-                // function as well
-            }
-            Direction::Release => {
-                debug!("removed the key {scan:?} from the held keys");
-                self.held.1.retain(|&k| k != scan);
-                // TODO: Make it work that they can get released with the key
-                // function as well
-            }
-            Direction::Click => (),
+    /// Get the current mouse speed and acceleration settings of the system
+    ///
+    /// # Errors
+    /// Returns an error if the settings of the mouse could not be read
+    pub fn mouse_acceleration() -> InputResult<MouseAcceleration> {
+        // The first two values are the thresholds and the third one is the
+        // acceleration level
+        let mut mouse = [0i32; 3];
+        let mut speed = 0u32;
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETMOUSE,
+                0,
+                Some(mouse.as_mut_ptr().cast()),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+            .map_err(|_| InputError::Simulate("unable to get the mouse acceleration"))?;
+            SystemParametersInfoW(
+                SPI_GETMOUSESPEED,
+                0,
+                Some((&raw mut speed).cast()),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+            .map_err(|_| InputError::Simulate("unable to get the mouse speed"))?;
         }
+        Ok(MouseAcceleration {
+            thresholds: (mouse[0], mouse[1]),
+            level: mouse[2],
+            speed,
+        })
+    }
 
+    /// Change the mouse speed and acceleration settings of the system. They
+    /// apply to all applications until they are changed again or the user logs
+    /// off, but are not written to the profile of the user
+    ///
+    /// # Errors
+    /// Returns an error if the settings of the mouse could not be changed
+    pub fn set_mouse_acceleration(acceleration: &MouseAcceleration) -> InputResult<()> {
+        debug!("\x1b[93mset_mouse_acceleration(acceleration: {acceleration:?})\x1b[0m");
+        let mut mouse = [
+            acceleration.thresholds.0,
+            acceleration.thresholds.1,
+            acceleration.level,
+        ];
+        unsafe {
+            SystemParametersInfoW(
+                SPI_SETMOUSE,
+                0,
+                Some(mouse.as_mut_ptr().cast()),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+            .map_err(|_| InputError::Simulate("unable to set the mouse acceleration"))?;
+            // The speed is passed as the value of the pointer
+            SystemParametersInfoW(
+                SPI_SETMOUSESPEED,
+                0,
+                Some(acceleration.speed as usize as *mut _),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+            .map_err(|_| InputError::Simulate("unable to set the mouse speed"))?;
+        }
         Ok(())
     }
-}
 
-impl Enigo {
-    /// Create a new Enigo struct to establish the connection to simulate input
-    /// with the specified settings
+    /// Disable the mouse acceleration and use the default mouse speed while
+    /// `f` runs, so relative mouse moves move the cursor by exactly the
+    /// specified distance. The previous settings are restored afterwards, even
+    /// if `f` panics
+    ///
+    /// ```no_run
+    /// use enigo::{Coordinate::Rel, Enigo, Mouse, Settings};
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// Enigo::with_mouse_acceleration_disabled(|| enigo.move_mouse(100, 0, Rel))
+    ///     .unwrap()
+    ///     .unwrap();
+    /// ```
     ///
     /// # Errors
-    /// Have a look at the documentation of `NewConError` to see under which
-    /// conditions an error will be returned.
-    pub fn new(settings: &Settings) -> Result<Self, NewConError> {
-        let Settings {
-            windows_dw_extra_info: dw_extra_info,
-            release_keys_when_dropped,
-            windows_subject_to_mouse_speed_and_acceleration_level,
-            ..
-        } = settings;
+    /// Returns an error if the settings of the mouse could not be read or
+    /// changed
+    pub fn with_mouse_acceleration_disabled<T>(f: impl FnOnce() -> T) -> InputResult<T> {
+        let previous = Self::mouse_acceleration()?;
+        debug!("previous mouse acceleration: {previous:?}");
+        let _guard = AccelerationGuard(previous);
+        Self::set_mouse_acceleration(&MouseAcceleration::DISABLED)?;
+        Ok(f())
+    }
 
-        let held = (vec![], vec![]);
+    /// Set the timestamp of the events if one was set with
+    /// `Enigo::set_event_time` and send them
+    fn send_input(&self, input: &[INPUT]) -> InputResult<()> {
+        let Some(time) = self.event_time else {
+            return send_input(input);
+        };
+        // The timestamps are milliseconds of GetTickCount, which wraps around
+        // after 49.7 days
+        let time = (time.as_millis() % (u128::from(u32::MAX) + 1)) as u32;
+        let mut input = input.to_vec();
+        for input in &mut input {
+            if input.r#type == INPUT_KEYBOARD {
+                input.Anonymous.ki.time = time;
+            } else if input.r#type == INPUT_MOUSE {
+                input.Anonymous.mi.time = time;
+            }
+        }
+        send_input(&input)
+    }
 
-        debug!("\x1b[93mconnection established on windows\x1b[0m");
+    /// Clip the cursor to its location if it is disassociated from the
+    /// mouse, to the rectangle it is confined to or release it
+    fn update_clip(&self) -> InputResult<()> {
+        let rect = if self.cursor_disassociated {
+            let (x, y) = Self::cursor_location()?;
+            Some(Rect::new(x, y, 1, 1))
+        } else {
+            self.confinement
+        };
+        clip_cursor(rect.map(|rect| RECT {
+            left: rect.x,
+            top: rect.y,
+            right: rect.x.saturating_add(rect.width),
+            bottom: rect.y.saturating_add(rect.height),
+        }))
+    }
 
-        Ok(Self {
-            held,
-            release_keys_when_dropped: *release_keys_when_dropped,
-            dw_extra_info: dw_extra_info.unwrap_or(crate::EVENT_MARKER as usize),
-            windows_subject_to_mouse_speed_and_acceleration_level:
-                *windows_subject_to_mouse_speed_and_acceleration_level,
-        })
+    /// Approximate number of pixels a notch of the mouse wheel scrolls on the
+    /// axis according to the wheel settings of the system
+    fn wheel_scroll_distance(&self, axis: Axis) -> InputResult<i32> {
+        let (action, unit) = match axis {
+            Axis::Horizontal => (SPI_GETWHEELSCROLLCHARS, CHAR_WIDTH),
+            Axis::Vertical => (SPI_GETWHEELSCROLLLINES, LINE_HEIGHT),
+        };
+        let mut amount = 0u32;
+        unsafe {
+            SystemParametersInfoW(
+                action,
+                0,
+                Some((&raw mut amount).cast()),
+                SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+            )
+        }
+        .map_err(|_| InputError::Simulate("unable to get the wheel settings"))?;
+        if amount == WHEEL_PAGESCROLL {
+            // A notch scrolls a whole page
+            let (width, height) = self.main_display()?;
+            return Ok(match axis {
+                Axis::Horizontal => width,
+                Axis::Vertical => height,
+            });
+        }
+        Ok(i32::try_from(amount)
+            .unwrap_or(i32::MAX)
+            .saturating_mul(unit))
     }
 
     pub(crate) fn get_keyboard_layout() -> HKL {
+        if let Some(layout) = LAYOUT_OVERRIDE.get() {
+            return layout;
+        }
         let current_window_thread_id =
             unsafe { GetWindowThreadProcessId(GetForegroundWindow(), None) };
         unsafe { GetKeyboardLayout(current_window_thread_id) }
@@ -417,6 +1347,94 @@ impl Enigo {
         }
     }
 
+    fn queue_button(&self, input_queue: &mut Vec<INPUT>, button: Button, direction: Direction) {
+        crate::cleanup::button(button, direction);
+        // Windows applies the swap of the buttons to the simulated events, so
+        // they need to be swapped again to press the primary button
+        let button = match button {
+            Button::Left if self.respect_button_swap && Self::buttons_swapped() => Button::Right,
+            Button::Right if self.respect_button_swap && Self::buttons_swapped() => Button::Left,
+            button => button,
+        };
+        let button_no = match button {
+            Button::Back => 1,
+            Button::Forward => 2,
+            _ => 0,
+        };
+        if direction == Direction::Click || direction == Direction::Press {
+            let mouse_event_flag = match button {
+                Button::Left => MOUSEEVENTF_LEFTDOWN,
+                Button::Middle => MOUSEEVENTF_MIDDLEDOWN,
+                Button::Right => MOUSEEVENTF_RIGHTDOWN,
+                Button::Back | Button::Forward => MOUSEEVENTF_XDOWN,
+                Button::ScrollUp => {
+                    input_queue.push(self.scroll_event(-1, Axis::Vertical));
+                    return;
+                }
+                Button::ScrollDown => {
+                    input_queue.push(self.scroll_event(1, Axis::Vertical));
+                    return;
+                }
+                Button::ScrollLeft => {
+                    input_queue.push(self.scroll_event(-1, Axis::Horizontal));
+                    return;
+                }
+                Button::ScrollRight => {
+                    input_queue.push(self.scroll_event(1, Axis::Horizontal));
+                    return;
+                }
+            };
+            input_queue.push(mouse_event(
+                mouse_event_flag,
+                button_no,
+                0,
+                0,
+                self.dw_extra_info,
+            ));
+        }
+        if direction == Direction::Click || direction == Direction::Release {
+            let mouse_event_flag = match button {
+                Button::Left => MOUSEEVENTF_LEFTUP,
+                Button::Middle => MOUSEEVENTF_MIDDLEUP,
+                Button::Right => MOUSEEVENTF_RIGHTUP,
+                Button::Back | Button::Forward => MOUSEEVENTF_XUP,
+                Button::ScrollUp
+                | Button::ScrollDown
+                | Button::ScrollLeft
+                | Button::ScrollRight => {
+                    info!("On Windows the mouse_up function has no effect when called with one of the Scroll buttons");
+                    return;
+                }
+            };
+            input_queue.push(mouse_event(
+                mouse_event_flag,
+                button_no,
+                0,
+                0,
+                self.dw_extra_info,
+            ));
+        }
+    }
+
+    fn scroll_event(&self, length: i32, axis: Axis) -> INPUT {
+        match axis {
+            Axis::Horizontal => mouse_event(
+                MOUSEEVENTF_HWHEEL,
+                length * (WHEEL_DELTA as i32),
+                0,
+                0,
+                self.dw_extra_info,
+            ),
+            Axis::Vertical => mouse_event(
+                MOUSEEVENTF_WHEEL,
+                -length * (WHEEL_DELTA as i32),
+                0,
+                0,
+                self.dw_extra_info,
+            ),
+        }
+    }
+
     fn queue_key(
         &mut self,
         input_queue: &mut Vec<INPUT>,
@@ -425,17 +1443,35 @@ impl Enigo {
     ) -> InputResult<()> {
         let Ok(vk) = VIRTUAL_KEY::try_from(key) else {
             if let Key::Unicode(c) = key {
+                if self.unicode_key_mode == UnicodeKeyMode::Layout {
+                    return Err(InputError::Mapping(format!(
+                        "no key of the active layout produces {c:?}"
+                    )));
+                }
                 warn!("Unable to enter the key as a virtual key.");
                 warn!("Falling back to entering it as text.");
                 let mut buffer = [0; 2]; // A buffer of length 2 is large enough to encode any char in utf16
-                self.queue_char(input_queue, c, &mut buffer);
-                return Ok(());
+                return self.queue_char(input_queue, c, &mut buffer);
+            }
+            if matches!(key, Key::Function | Key::Globe) {
+                return Err(InputError::InvalidInput(
+                    "the fn key is handled by the keyboard and cannot be simulated on Windows",
+                ));
             }
             return Err(InputError::Mapping(
                 "This should never happen. There is a bug in the implementation".to_string(),
             ));
         };
-        let scan = Enigo::translate_key(vk.0, MAPVK_VK_TO_VSC_EX)?; // Translate virtual key to scan code
+        let scan = match key {
+            // The keyboard sends the sequence E1 1D 45 for Pause, but MapVirtualKeyExW only
+            // returns the first part of it (0xE11D). Windows itself uses 0x45
+            Key::Pause => 0x45,
+            // Break is Ctrl+Pause and the keyboard sends E0 46 for it
+            Key::Break => 0xE046,
+            // SysRq is Alt+PrintScreen and the keyboard sends 54 for it
+            Key::SysReq => 0x54,
+            _ => Enigo::translate_key(vk.0, MAPVK_VK_TO_VSC_EX)?, // Translate virtual key to scan code
+        };
 
         let mut keyflags = KEYBD_EVENT_FLAGS::default();
 
@@ -445,11 +1481,35 @@ impl Enigo {
             keyflags |= KEYEVENTF_SCANCODE;
         };
 
-        if Enigo::is_extended_key(vk) {
+        // The prefix 0xE0 of a scan code marks an extended key. It is not part of
+        // the scan code that is sent
+        let extended = match key {
+            Key::Pause | Key::SysReq => false,
+            _ => scan >> 8 == 0xE0 || Enigo::is_extended_key(vk),
+        };
+        if extended {
             keyflags |= KEYEVENTF_EXTENDEDKEY;
         }
+        let scan = scan & 0xFF;
+
+        // Break and SysRq are only sent while Ctrl or Alt are held. The modifier
+        // is only pressed if it is not held already (e.g. by the user) and only
+        // a modifier that was pressed here is released again
+        let modifier = match key {
+            Key::Break => Some((Key::LControl, VK_CONTROL)),
+            Key::SysReq => Some((Key::LMenu, VK_MENU)),
+            _ => None,
+        };
 
         if direction == Direction::Click || direction == Direction::Press {
+            if let Some((modifier, vk)) = modifier {
+                // The most significant bit is set if either of the keys is down
+                let held = unsafe { GetAsyncKeyState(i32::from(vk.0)) } < 0;
+                if !held && !self.implicit_modifiers.contains(&key) {
+                    self.queue_key(input_queue, modifier, Direction::Press)?;
+                    self.implicit_modifiers.push(key);
+                }
+            }
             input_queue.push(keybd_event(keyflags, vk, scan, self.dw_extra_info));
         }
         if direction == Direction::Click || direction == Direction::Release {
@@ -459,12 +1519,120 @@ impl Enigo {
                 scan,
                 self.dw_extra_info,
             ));
+            if let Some((modifier, _)) = modifier {
+                if let Some(idx) = self.implicit_modifiers.iter().position(|k| *k == key) {
+                    self.implicit_modifiers.remove(idx);
+                    self.queue_key(input_queue, modifier, Direction::Release)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Queue the events to enter the text and send them
+    fn send_text(&mut self, text: &str) -> InputResult<()> {
+        let mut buffer = [0; 2]; // A buffer of length 2 is large enough to encode any char in utf16
+
+        let mut input = Vec::with_capacity(2 * text.len()); // Each char needs at least one event to press and one to release it
+        for c in text.chars() {
+            // Enter special characters as keys
+            match c {
+                '\n' => self.queue_key(&mut input, Key::Return, Direction::Click)?,
+                '\r' => { // TODO: What is the correct key to type here?
+                }
+                '\t' => self.queue_key(&mut input, Key::Tab, Direction::Click)?,
+                '\0' => Err(InputError::InvalidInput("the text contained a null byte"))?,
+                _ => (),
+            }
+
+            self.queue_char(&mut input, c, &mut buffer)?;
+        }
+        self.send_input(&input)
+    }
+
+    /// Returns true if the IME of the foreground window is open, so simulated
+    /// text is handled by it
+    #[must_use]
+    pub fn ime_active() -> bool {
+        Self::open_ime_window().is_some()
+    }
+
+    /// Returns the IME window of the foreground window if the IME is open
+    fn open_ime_window() -> Option<HWND> {
+        let ime_window = unsafe { ImmGetDefaultIMEWnd(GetForegroundWindow()) };
+        if ime_window.is_invalid() {
+            return None;
+        }
+        let mut open = 0;
+        let res = unsafe {
+            SendMessageTimeoutW(
+                ime_window,
+                WM_IME_CONTROL,
+                WPARAM(IMC_GETOPENSTATUS),
+                LPARAM(0),
+                SMTO_ABORTIFHUNG,
+                100,
+                Some(&raw mut open),
+            )
+        };
+        (res.0 != 0 && open != 0).then_some(ime_window)
+    }
+
+    fn set_ime_open(ime_window: HWND, open: bool) {
+        let res = unsafe {
+            SendMessageTimeoutW(
+                ime_window,
+                WM_IME_CONTROL,
+                WPARAM(IMC_SETOPENSTATUS as usize),
+                LPARAM(open.into()),
+                SMTO_ABORTIFHUNG,
+                100,
+                None,
+            )
+        };
+        if res.0 == 0 {
+            warn!("unable to set the open status of the IME to {open}");
         }
+    }
 
+    /// Post the characters as `WM_IME_CHAR` messages to the focused window of
+    /// the foreground thread
+    fn post_ime_chars(text: &str) -> InputResult<()> {
+        // Validate the whole text first so it is not entered partially
+        if text.contains('\0') {
+            return Err(InputError::InvalidInput("the text contained a null byte"));
+        }
+        let thread_id = unsafe { GetWindowThreadProcessId(GetForegroundWindow(), None) };
+        let mut info = GUITHREADINFO {
+            cbSize: size_of::<GUITHREADINFO>() as u32,
+            ..Default::default()
+        };
+        if unsafe { GetGUIThreadInfo(thread_id, &raw mut info) }.is_err()
+            || info.hwndFocus.is_invalid()
+        {
+            return Err(InputError::Simulate("unable to get the focused window"));
+        }
+        for unit in text.encode_utf16() {
+            unsafe { PostMessageW(info.hwndFocus, WM_IME_CHAR, WPARAM(unit.into()), LPARAM(1)) }
+                .map_err(|_| InputError::Simulate("unable to post the WM_IME_CHAR message"))?;
+        }
         Ok(())
     }
 
-    fn queue_char(&mut self, input_queue: &mut Vec<INPUT>, character: char, buffer: &mut [u16; 2]) {
+    fn queue_char(
+        &mut self,
+        input_queue: &mut Vec<INPUT>,
+        character: char,
+        buffer: &mut [u16; 2],
+    ) -> InputResult<()> {
+        if self.text_entry_method == TextEntryMethod::AltNumpad {
+            if let Some(code) = ansi_code(character) {
+                return self.queue_alt_code(input_queue, code);
+            }
+            debug!("{character:?} is not in the active codepage. Entering it as unicode");
+        }
+
         // Windows uses uft-16 encoding. We need to check
         // for variable length characters. As such some
         // characters can be 32 bit long and those are
@@ -494,6 +1662,32 @@ impl Enigo {
                 self.dw_extra_info,
             ));
         }
+        Ok(())
+    }
+
+    /// Enter the character with the value `code` in the active ANSI codepage
+    /// by holding Alt and typing "0" followed by the decimal value on the
+    /// numpad
+    fn queue_alt_code(&mut self, input_queue: &mut Vec<INPUT>, code: u8) -> InputResult<()> {
+        const NUMPAD: [Key; 10] = [
+            Key::Numpad0,
+            Key::Numpad1,
+            Key::Numpad2,
+            Key::Numpad3,
+            Key::Numpad4,
+            Key::Numpad5,
+            Key::Numpad6,
+            Key::Numpad7,
+            Key::Numpad8,
+            Key::Numpad9,
+        ];
+
+        self.queue_key(input_queue, Key::Alt, Direction::Press)?;
+        // The leading zero selects the ANSI codepage instead of the OEM codepage
+        for digit in [0, code / 100, code / 10 % 10, code % 10] {
+            self.queue_key(input_queue, NUMPAD[usize::from(digit)], Direction::Click)?;
+        }
+        self.queue_key(input_queue, Key::Alt, Direction::Release)
     }
 
     /// Returns a list of all currently pressed keys
@@ -507,6 +1701,198 @@ impl Enigo {
         self.dw_extra_info
     }
 
+    /// Mark the events that are simulated through the returned guard with the
+    /// tag instead of the value of `Settings::windows_dw_extra_info`. The previous
+    /// value is restored when the guard is dropped. This allows e.g. a
+    /// remote-control server with several clients to attribute the injected
+    /// events to a client in its own hooks, where the tag is the `dwExtraInfo` of
+    /// the event
+    ///
+    /// ```no_run
+    /// use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+    ///
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// let client_id = 42;
+    /// enigo.tagged(client_id).key(Key::Unicode('a'), Direction::Click).unwrap();
+    /// assert_ne!(enigo.get_marker_value(), client_id);
+    /// ```
+    #[must_use]
+    pub fn tagged(&mut self, tag: usize) -> Tagged<'_> {
+        debug!("\x1b[93mtagged(tag: {tag:?})\x1b[0m");
+        let previous = std::mem::replace(&mut self.dw_extra_info, tag);
+        Tagged {
+            enigo: self,
+            previous,
+        }
+    }
+
+    /// Returns information about the keyboard layout of the foreground window
+    ///
+    /// The language is the locale name of the input locale (e.g. "de-DE") and
+    /// the variant is the KLID of the layout (e.g. "00000407"), which can be
+    /// passed to `Enigo::with_keyboard_layout`. The variant is `None` if the
+    /// KLID could not be looked up
+    ///
+    /// # Errors
+    /// Returns an error if the locale of the layout could not be looked up
+    pub fn keyboard_layout(&self) -> InputResult<LayoutInfo> {
+        debug!("\x1b[93mkeyboard_layout()\x1b[0m");
+        let layout = Enigo::get_keyboard_layout();
+        // The low word of the HKL is the language identifier
+        let hkl = layout.0 as usize;
+        let lang_id = (hkl & 0xFFFF) as u32;
+
+        let mut locale_name = [0u16; 85]; // LOCALE_NAME_MAX_LENGTH
+        let len = unsafe {
+            LCIDToLocaleName(lang_id, Some(&mut locale_name), LOCALE_ALLOW_NEUTRAL_NAMES)
+        };
+        if len == 0 {
+            error!("LCIDToLocaleName failed for the language identifier {lang_id:#06X}");
+            return Err(InputError::Simulate(
+                "unable to get the locale of the keyboard layout",
+            ));
+        }
+
+        let mut display_name = [0u16; 256];
+        let display_len = unsafe {
+            GetLocaleInfoEx(
+                PCWSTR(locale_name.as_ptr()),
+                LOCALE_SLOCALIZEDDISPLAYNAME,
+                Some(&mut display_name),
+            )
+        };
+        if display_len == 0 {
+            error!("GetLocaleInfoEx failed for the language identifier {lang_id:#06X}");
+            return Err(InputError::Simulate(
+                "unable to get the name of the keyboard layout",
+            ));
+        }
+
+        // The lengths include the terminating null character
+        let language = String::from_utf16_lossy(&locale_name[..len as usize - 1]);
+        let name = String::from_utf16_lossy(&display_name[..display_len as usize - 1]);
+        Ok(LayoutInfo {
+            name,
+            language: Some(language),
+            variant: Self::layout_klid(layout),
+        })
+    }
+
+    /// Returns the KLID of the layout. The HKL is not the KLID (e.g. the HKL
+    /// of the US Dvorak layout is F0020409 and its KLID is 00010409) and
+    /// Windows only returns the KLID of the layout of the calling thread, so
+    /// the layout is activated on this thread while the KLID is read
+    fn layout_klid(layout: HKL) -> Option<String> {
+        let guard = LayoutGuard::activate(layout.0 as usize);
+        guard.previous_layout?;
+        let mut klid = [0u16; 9]; // KL_NAMELENGTH
+        if unsafe { GetKeyboardLayoutNameW(&mut klid) }.is_err() {
+            warn!("unable to get the KLID of the keyboard layout {layout:?}");
+            return None;
+        }
+        Some(String::from_utf16_lossy(&klid[..8]))
+    }
+
+    /// Collect information about the environment to attach it to bug reports
+    ///
+    /// Have a look at [`DiagnosticsReport`] for the information it contains
+    #[must_use]
+    pub fn diagnostics(&self) -> DiagnosticsReport {
+        debug!("\x1b[93mdiagnostics()\x1b[0m");
+        diagnostics::report(
+            vec![self.backend().to_string()],
+            None,
+            self.keyboard_layout().ok().map(|layout| layout.name),
+            self.main_display().ok(),
+            &diagnostics::redact(&self.settings),
+        )
+    }
+
+    /// Use the keyboard layout with the KLID (e.g. "00000407" for German) to
+    /// simulate keys while `f` runs. It overrides
+    /// `Settings::windows_keyboard_layout`. The layout is loaded if needed and
+    /// activated for the calling thread around each injected key. Characters
+    /// and virtual keys are translated to the scan codes of the keys that
+    /// produce them on this layout instead of the layout of the foreground
+    /// window. Note that the foreground window still interprets the scan
+    /// codes with its own layout
+    ///
+    /// ```no_run
+    /// use enigo::{Direction::Click, Enigo, Key, Keyboard, Settings};
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// enigo
+    ///     .with_keyboard_layout("00000409", |enigo| enigo.key(Key::Unicode('z'), Click))
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the layout could not be loaded or the error of `f`
+    pub fn with_keyboard_layout<T>(
+        &mut self,
+        klid: &str,
+        f: impl FnOnce(&mut Self) -> InputResult<T>,
+    ) -> InputResult<T> {
+        debug!("\x1b[93mwith_keyboard_layout(klid: {klid:?})\x1b[0m");
+        let layout = load_keyboard_layout(klid).ok_or(InputError::InvalidInput(
+            "unable to load the keyboard layout",
+        ))?;
+        let previous = self.keyboard_layout.replace(layout);
+        let res = f(self);
+        self.keyboard_layout = previous;
+        res
+    }
+
+    /// Returns the handle (`HWND`) of the foreground window
+    ///
+    /// # Errors
+    /// Returns an error if there is no foreground window. This can happen
+    /// while the focus is switched
+    #[allow(clippy::unused_self)]
+    pub fn active_window(&self) -> InputResult<u64> {
+        let window = unsafe { GetForegroundWindow() };
+        if window.is_invalid() {
+            return Err(InputError::Simulate("there is no foreground window"));
+        }
+        Ok(window.0 as usize as u64)
+    }
+
+    /// Runs `f` only if the target is the foreground window, so the input is
+    /// not received by a different application (e.g. a password is not typed
+    /// into the wrong window). If it is not the foreground window, it is
+    /// waited for up to `wait` for it to become the foreground window
+    ///
+    /// ```no_run
+    /// use enigo::{Enigo, ForegroundTarget, Keyboard, Settings};
+    /// use std::time::Duration;
+    /// let mut enigo = Enigo::new(&Settings::default()).unwrap();
+    /// enigo
+    ///     .when_foreground(ForegroundTarget::Process(1234), Duration::from_secs(1), |enigo| {
+    ///         enigo.text("secret")
+    ///     })
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an [`InputError::WrongForegroundWindow`] if the target did not
+    /// become the foreground window in time or the error of `f`
+    pub fn when_foreground<T>(
+        &mut self,
+        target: ForegroundTarget,
+        wait: Duration,
+        f: impl FnOnce(&mut Self) -> InputResult<T>,
+    ) -> InputResult<T> {
+        debug!("\x1b[93mwhen_foreground(target: {target:?}, wait: {wait:?})\x1b[0m");
+        let start = Instant::now();
+        while !target.is_foreground() {
+            if start.elapsed() >= wait {
+                debug!("{target:?} is not the foreground window");
+                return Err(InputError::WrongForegroundWindow);
+            }
+            thread::sleep(FOREGROUND_POLL_INTERVAL);
+        }
+        f(self)
+    }
+
     /// Test if the virtual key is one of the keys that need the
     /// `KEYEVENTF_EXTENDEDKEY` flag to be set
     fn is_extended_key(vk: VIRTUAL_KEY) -> bool {
@@ -556,9 +1942,47 @@ pub fn set_dpi_awareness() -> Result<(), ()> {
     unsafe { SetProcessDpiAwareness(PROCESS_PER_MONITOR_DPI_AWARE) }.map_err(|_| ())
 }
 
+/// Guard that marks the simulated events with a tag until it is dropped. It
+/// dereferences to the [`Enigo`] struct. Have a look at [`Enigo::tagged`]
+#[derive(Debug)]
+pub struct Tagged<'a> {
+    enigo: &'a mut Enigo,
+    previous: usize,
+}
+
+impl std::ops::Deref for Tagged<'_> {
+    type Target = Enigo;
+
+    fn deref(&self) -> &Enigo {
+        self.enigo
+    }
+}
+
+impl std::ops::DerefMut for Tagged<'_> {
+    fn deref_mut(&mut self) -> &mut Enigo {
+        self.enigo
+    }
+}
+
+impl Drop for Tagged<'_> {
+    fn drop(&mut self) {
+        self.enigo.dw_extra_info = self.previous;
+    }
+}
+
 impl Drop for Enigo {
     // Release the held keys before the connection is dropped
     fn drop(&mut self) {
+        // The cursors stay hidden and confined after the process exited
+        if self.cursor_hidden {
+            if set_system_cursors_visible(true).is_err() {
+                error!("unable to show the cursor again");
+            }
+            crate::cleanup::cursor_hidden(false);
+        }
+        if (self.cursor_disassociated || self.confinement.is_some()) && clip_cursor(None).is_err() {
+            error!("unable to release the cursor");
+        }
         if !self.release_keys_when_dropped {
             return;
         }
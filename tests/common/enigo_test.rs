@@ -1,24 +1,28 @@
-use std::net::{TcpListener, TcpStream};
-
-use tungstenite::accept;
-
 use enigo::{
+    test_harness::{launch_firefox, BrowserEvent, BrowserEventStream, DEFAULT_ADDRESS},
     Axis, Coordinate,
     Direction::{self, Click, Press, Release},
     Enigo, Key, Keyboard, Mouse, Settings,
 };
 
-use super::browser_events::BrowserEvent;
-
 const TIMEOUT: u64 = 5; // Number of minutes the test is allowed to run before timing out
                         // This is needed, because some of the websocket functions are blocking and
                         // would run indefinitely without a timeout if they don't receive a message
 const INPUT_DELAY: u64 = 40; // Number of milliseconds to wait for the input to have an effect
 const SCROLL_STEP: (i32, i32) = (20, 114); // (horizontal, vertical)
 
+// Launch Firefox in kiosk mode (full screen and can't be closed with F11)
+//
+// n.b. static items do not call [`Drop`] on program termination, so this won't
+// be deallocated. this is fine, as the OS can deallocate the terminated program
+// faster than we can free memory but tools like valgrind might report "memory
+// leaks" as it isn't obvious this is intentional.
+static BROWSER_INSTANCE: std::sync::LazyLock<Option<std::process::Child>> =
+    std::sync::LazyLock::new(|| Some(launch_firefox().expect("Failed to start Firefox")));
+
 pub struct EnigoTest {
     enigo: Enigo,
-    websocket: tungstenite::WebSocket<TcpStream>,
+    events: BrowserEventStream,
 }
 
 impl EnigoTest {
@@ -26,46 +30,20 @@ impl EnigoTest {
         env_logger::try_init().ok();
         EnigoTest::start_timeout_thread();
         let enigo = Enigo::new(settings).unwrap();
-        let _ = &*super::browser::BROWSER_INSTANCE; // Launch Firefox
-        let websocket = Self::websocket();
-
-        std::thread::sleep(std::time::Duration::from_secs(10)); // Give Firefox some time to launch
-        Self { enigo, websocket }
-    }
-
-    fn websocket() -> tungstenite::WebSocket<TcpStream> {
-        let listener = TcpListener::bind("127.0.0.1:26541").unwrap();
-        println!("TcpListener was created");
-        let (stream, addr) = listener.accept().expect("Unable to accept the connection");
-        println!("New connection was made from {addr:?}");
-        let websocket = accept(stream).expect("Unable to accept connections on the websocket");
+        let _ = &*BROWSER_INSTANCE; // Launch Firefox
+        let mut events = BrowserEventStream::listen(DEFAULT_ADDRESS)
+            .expect("Unable to accept connections on the websocket");
+        // The timeout thread ends the test suite if an event is missing
+        events.set_timeout(None).unwrap();
         println!("WebSocket was successfully created");
-        websocket
-    }
 
-    fn send_message(&mut self, msg: &str) {
-        println!("Sending message: {msg}");
-        self.websocket
-            .send(tungstenite::Message::Text(tungstenite::Utf8Bytes::from(
-                msg,
-            )))
-            .expect("Unable to send the message");
-        println!("Sent message");
+        std::thread::sleep(std::time::Duration::from_secs(10)); // Give Firefox some time to launch
+        Self { enigo, events }
     }
 
     fn read_message(&mut self) -> BrowserEvent {
         println!("Waiting for message on Websocket");
-        let message = self.websocket.read().unwrap();
-        println!("Processing message");
-
-        let Ok(browser_event) = BrowserEvent::try_from(message) else {
-            panic!("Other text received");
-        };
-        assert!(
-            !(browser_event == BrowserEvent::Close),
-            "Received a Close event"
-        );
-        browser_event
+        self.events.expect_event()
     }
 
     fn start_timeout_thread() {
@@ -81,24 +59,11 @@ impl EnigoTest {
 impl Keyboard for EnigoTest {
     // This does not work for all text or the library does not work properly
     fn fast_text(&mut self, text: &str) -> enigo::InputResult<Option<()>> {
-        self.send_message("ClearText");
         println!("Attempt to clear the text");
-        assert_eq!(
-            BrowserEvent::ReadyForText,
-            self.read_message(),
-            "Failed to get ready for the text"
-        );
+        self.events.clear_text();
         let res = self.enigo.text(text);
         std::thread::sleep(std::time::Duration::from_millis(INPUT_DELAY)); // Wait for input to have an effect
-        self.send_message("GetText");
-
-        let ev = self.read_message();
-        if let BrowserEvent::Text(received_text) = ev {
-            println!("received text: {received_text}");
-            assert_eq!(text, received_text);
-        } else {
-            panic!("BrowserEvent was not a Text: {ev:?}");
-        }
+        self.events.expect_text(text);
 
         res.map(Some) // TODO: Check if this is always correct
     }
@@ -107,7 +72,7 @@ impl Keyboard for EnigoTest {
         let res = self.enigo.key(key, direction);
         if direction == Press || direction == Click {
             let ev = self.read_message();
-            if let BrowserEvent::KeyDown(name) = ev {
+            if let BrowserEvent::KeyDown(name, _) = ev {
                 println!("received pressed key: {name}");
                 let key_name = if let Key::Unicode(char) = key {
                     format!("{char}")
@@ -123,7 +88,7 @@ impl Keyboard for EnigoTest {
         if direction == Release || direction == Click {
             std::thread::sleep(std::time::Duration::from_millis(INPUT_DELAY)); // Wait for input to have an effect
             let ev = self.read_message();
-            if let BrowserEvent::KeyUp(name) = ev {
+            if let BrowserEvent::KeyUp(name, _) = ev {
                 println!("received released key: {name}");
                 let key_name = if let Key::Unicode(char) = key {
                     format!("{char}")
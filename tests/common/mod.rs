@@ -1,3 +1 @@
-mod browser;
-mod browser_events;
 pub mod enigo_test;